@@ -0,0 +1,163 @@
+//! Benchmarks of the `TopologyGraph` and of the endpoint trace over it, with the `sienna_files`
+//! and `devices_files` fixtures.
+//!
+//! `sienna_files` has no topology, only connections and services, so it measures the connection
+//! lookups; it is also replicated with fresh UUIDs to check the trace does not slow down as the
+//! context grows. `devices_files` adds the nodes and links.
+//!
+//! Run with `cargo bench -p actix_api --bench topology_graph`.
+use actix_api::logic::{
+    connection_builder::connection_vector_build, link_builder::link_vector_build,
+    node_builder::node_vector_building, schema_builder::build_schema,
+};
+use actix_api::models::{
+    connectivity_services::Service, topology_graph::TopologyGraph, vendor_profile::VendorProfile,
+};
+use actix_api::utils::{parse_tapi, parse_topologies};
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use serde_json::Value;
+use std::fs;
+use std::sync::Arc;
+use tapi_model::connectivity::{Connection, ConnectivityService};
+
+/// Times the `sienna_files` connections are replicated in the scaling benchmark.
+const REPLICAS: [usize; 3] = [1, 10, 50];
+
+/// Parsed fixtures of a directory.
+struct Fixture {
+    name: String,
+    graph: TopologyGraph,
+    services: Vec<Service>,
+}
+
+/// Reads a fixture holding a list, either as a JSON array or as an object with a single array member.
+fn read_list(path: &str) -> Vec<Value> {
+    let value: Value = serde_json::from_str(&fs::read_to_string(path).unwrap()).unwrap();
+    match value {
+        Value::Array(items) => items,
+        Value::Object(object) => object
+            .into_iter()
+            .find_map(|(_, member)| match member {
+                Value::Array(items) => Some(items),
+                _ => None,
+            })
+            .unwrap_or_default(),
+        _ => vec![],
+    }
+}
+
+/// Copies every UUID of the values, replacing their first group with the number of the copy, so
+/// the copies reference each other as the originals do.
+fn replicate(items: &[Value], copy: usize) -> Vec<Value> {
+    fn rewrite(value: &mut Value, copy: usize) {
+        match value {
+            Value::String(text) if text.len() == 36 && text.as_bytes()[8] == b'-' => {
+                text.replace_range(..8, &format!("{:08x}", copy));
+            }
+            Value::Array(items) => items.iter_mut().for_each(|item| rewrite(item, copy)),
+            Value::Object(object) => object.values_mut().for_each(|item| rewrite(item, copy)),
+            _ => {}
+        }
+    }
+
+    items
+        .iter()
+        .cloned()
+        .map(|mut item| {
+            rewrite(&mut item, copy);
+            item
+        })
+        .collect()
+}
+
+/// Loads the connections and services of a directory, with its topology if it has one.
+fn load(dir: &str, replicas: usize) -> Fixture {
+    let profile = VendorProfile::default();
+    let connection_items = read_list(&format!("{}/connections.json", dir));
+    let service_items = read_list(&format!("{}/connectivity_services.json", dir));
+    let topology: Value = fs::read_to_string(format!("{}/topology.json", dir))
+        .map(|topology| serde_json::from_str(&topology).unwrap())
+        .unwrap_or(Value::Null);
+
+    let mut replicated_connections = vec![];
+    for copy in 0..replicas {
+        replicated_connections.extend(replicate(&connection_items, copy));
+    }
+    let connections: Vec<Connection> = parse_tapi(&replicated_connections, "connection").unwrap();
+    let connectivity_services: Vec<ConnectivityService> =
+        parse_tapi(&service_items, "connectivity service").unwrap();
+    let topologies = parse_topologies(&topology).unwrap();
+
+    let connections = Arc::new(connection_vector_build(&connections, &profile));
+    let services = connectivity_services
+        .iter()
+        .map(|service| Service::connectivity_service_build(service, &connections, &profile))
+        .collect();
+    let graph = TopologyGraph::new(
+        Arc::new(node_vector_building(&topologies, &profile)),
+        Arc::new(link_vector_build(&topologies)),
+        connections,
+    );
+
+    Fixture {
+        name: dir.to_string(),
+        graph,
+        services,
+    }
+}
+
+fn graph_build(c: &mut Criterion) {
+    let mut group = c.benchmark_group("graph_build");
+    for fixture in [load("sienna_files", 1), load("devices_files", 1)] {
+        let nodes = Arc::new(fixture.graph.nodes().to_vec());
+        let links = Arc::new(fixture.graph.links().to_vec());
+        let connections = Arc::new(fixture.graph.connections().to_vec());
+        group.bench_function(&fixture.name, |b| {
+            b.iter(|| {
+                TopologyGraph::new(
+                    black_box(nodes.clone()),
+                    black_box(links.clone()),
+                    black_box(connections.clone()),
+                )
+            })
+        });
+    }
+    group.finish();
+}
+
+fn trace(c: &mut Criterion) {
+    let mut group = c.benchmark_group("trace");
+    for fixture in [load("sienna_files", 1), load("devices_files", 1)] {
+        group.bench_function(&fixture.name, |b| {
+            b.iter(|| {
+                for service in &fixture.services {
+                    black_box(build_schema(service, &fixture.graph).unwrap());
+                }
+            })
+        });
+    }
+    group.finish();
+}
+
+fn trace_scaling(c: &mut Criterion) {
+    let mut group = c.benchmark_group("trace_scaling");
+    for replicas in REPLICAS {
+        let fixture = load("sienna_files", replicas);
+        group.bench_with_input(
+            BenchmarkId::new("sienna_files", fixture.graph.connections().len()),
+            &fixture,
+            |b, fixture| {
+                b.iter(|| {
+                    for service in &fixture.services {
+                        black_box(build_schema(service, &fixture.graph).unwrap());
+                    }
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, graph_build, trace, trace_scaling);
+criterion_main!(benches);
@@ -0,0 +1,2 @@
+-- Add down migration script here
+DROP TABLE file_sets;
\ No newline at end of file
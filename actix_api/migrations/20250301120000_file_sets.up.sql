@@ -0,0 +1,10 @@
+-- Add up migration script here
+CREATE TABLE IF NOT EXISTS file_sets (
+    id VARCHAR PRIMARY KEY,
+    kind VARCHAR NOT NULL,
+    CONSTRAINT check_kind CHECK (kind IN ('complete', 'by_part')),
+    complete_context_path VARCHAR,
+    topology_path VARCHAR,
+    connections_path VARCHAR,
+    connectivity_services_path VARCHAR
+);
\ No newline at end of file
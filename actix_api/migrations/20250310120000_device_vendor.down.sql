@@ -0,0 +1,2 @@
+-- Add down migration script here
+ALTER TABLE devices DROP COLUMN IF EXISTS vendor;
@@ -0,0 +1,2 @@
+-- Add up migration script here
+ALTER TABLE devices ADD COLUMN IF NOT EXISTS vendor VARCHAR;
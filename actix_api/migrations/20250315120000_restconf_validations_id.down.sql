@@ -0,0 +1,4 @@
+-- Add down migration script here
+DROP INDEX IF EXISTS restconf_validations_device_ip;
+ALTER TABLE restconf_validations DROP COLUMN IF EXISTS id;
+ALTER TABLE restconf_validations ADD PRIMARY KEY (validation_datetime);
@@ -0,0 +1,4 @@
+-- Add up migration script here
+ALTER TABLE restconf_validations DROP CONSTRAINT IF EXISTS restconf_validations_pkey;
+ALTER TABLE restconf_validations ADD COLUMN IF NOT EXISTS id SERIAL PRIMARY KEY;
+CREATE INDEX IF NOT EXISTS restconf_validations_device_ip ON restconf_validations (device_ip, uri_id);
@@ -0,0 +1,4 @@
+-- Add down migration script here
+DELETE FROM devices WHERE auth_type = 'oauth2';
+ALTER TABLE devices DROP CONSTRAINT IF EXISTS check_auth_type;
+ALTER TABLE devices ADD CONSTRAINT check_auth_type CHECK (auth_type IN ('token', 'basic'));
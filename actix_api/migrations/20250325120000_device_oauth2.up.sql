@@ -0,0 +1,3 @@
+-- Add up migration script here
+ALTER TABLE devices DROP CONSTRAINT IF EXISTS check_auth_type;
+ALTER TABLE devices ADD CONSTRAINT check_auth_type CHECK (auth_type IN ('token', 'basic', 'oauth2'));
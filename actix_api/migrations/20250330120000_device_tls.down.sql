@@ -0,0 +1,7 @@
+-- Add down migration script here
+ALTER TABLE devices DROP CONSTRAINT IF EXISTS check_tls_scheme;
+ALTER TABLE devices DROP COLUMN IF EXISTS tls_insecure;
+ALTER TABLE devices DROP COLUMN IF EXISTS tls_client_key;
+ALTER TABLE devices DROP COLUMN IF EXISTS tls_client_certificate;
+ALTER TABLE devices DROP COLUMN IF EXISTS tls_ca_certificate;
+ALTER TABLE devices DROP COLUMN IF EXISTS tls_scheme;
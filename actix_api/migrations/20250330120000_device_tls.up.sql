@@ -0,0 +1,9 @@
+-- Add up migration script here
+ALTER TABLE devices ADD COLUMN IF NOT EXISTS tls_scheme VARCHAR NOT NULL DEFAULT 'https';
+ALTER TABLE devices ADD COLUMN IF NOT EXISTS tls_ca_certificate TEXT;
+ALTER TABLE devices ADD COLUMN IF NOT EXISTS tls_client_certificate TEXT;
+ALTER TABLE devices ADD COLUMN IF NOT EXISTS tls_client_key TEXT;
+ALTER TABLE devices ADD COLUMN IF NOT EXISTS tls_insecure BOOLEAN NOT NULL DEFAULT FALSE;
+-- Registered devices were always requested accepting any certificate
+UPDATE devices SET tls_insecure = TRUE;
+ALTER TABLE devices ADD CONSTRAINT check_tls_scheme CHECK (tls_scheme IN ('https', 'http'));
@@ -0,0 +1,14 @@
+-- Add up migration script here
+CREATE TABLE IF NOT EXISTS device_health (
+  id SERIAL PRIMARY KEY,
+  checked_at TIMESTAMP NOT NULL,
+  device_ip VARCHAR NOT NULL,
+  status VARCHAR NOT NULL CHECK (status IN ('healthy', 'degraded', 'unreachable')),
+  tcp_connect BOOLEAN NOT NULL,
+  tls_handshake BOOLEAN,
+  authenticated BOOLEAN,
+  restconf BOOLEAN,
+  latency_ms INTEGER,
+  error VARCHAR
+);
+CREATE INDEX IF NOT EXISTS device_health_device_ip_checked_at ON device_health (device_ip, checked_at DESC);
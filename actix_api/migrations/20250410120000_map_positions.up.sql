@@ -0,0 +1,5 @@
+-- Add up migration script here
+CREATE TABLE IF NOT EXISTS map_positions (
+  device_ip VARCHAR PRIMARY KEY,
+  positions JSONB NOT NULL
+);
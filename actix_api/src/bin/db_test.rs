@@ -1,30 +1,18 @@
 use std::env;
 
 use actix_api::{
-    handlers::{database::DatabaseHandler, http::HttpHandler},
+    handlers::{cipher::CipherHandler, database::DatabaseHandler, token::TokenCache},
     models::devices::{Auth, Device},
     utils::is_reachable,
     AppError,
 };
 use serde_json::{from_str, Value};
 
-use hmac::Hmac;
-use pbkdf2::pbkdf2;
-use sha2::Sha256;
-
 #[tokio::main]
 async fn main() -> Result<(), AppError> {
     dotenv::dotenv().ok();
-    let binding = env::var("CYPHER_KEY").unwrap();
-    let cyhper_key: &[u8] = binding.as_bytes();
-
-    let binding = env::var("SALT").unwrap();
-    let salt: &[u8] = binding.as_bytes();
-
-    let mut key = [0u8; 32];
-    pbkdf2::<Hmac<Sha256>>(cyhper_key, salt, 100_000, &mut key)
-        .expect("HMAC can be initialized with any key length");
-    println!("{:?}", key);
+    // Fails early if CYPHER_KEY or SALT are missing
+    CipherHandler::global()?;
 
     let database_url = env::var("DATABASE_URL").unwrap();
     let database_handler = DatabaseHandler::new(database_url).await?;
@@ -54,22 +42,18 @@ async fn main() -> Result<(), AppError> {
                 ret_ip = device.create_device(&database_handler).await?;
                 println!("{} device saved!", &ret_ip);
             }
-            Auth::Token(token_auth) => {
-                match HttpHandler::get_token(&device.get_full_auth_url(), &token_auth.auth_body)
-                    .await
-                {
-                    Ok(_) => {
-                        ret_ip = device.create_device(&database_handler).await?;
-                        println!("{} device saved!", &ret_ip);
-                    }
-                    Err(err) => {
-                        return Err(AppError::RequestError(format!(
-                            "Device cannot be added. {}",
-                            err
-                        )));
-                    }
+            Auth::Token(_) | Auth::OAuth2(_) => match TokenCache::global().token(&device).await {
+                Ok(_) => {
+                    ret_ip = device.create_device(&database_handler).await?;
+                    println!("{} device saved!", &ret_ip);
                 }
-            }
+                Err(err) => {
+                    return Err(AppError::RequestError(format!(
+                        "Device cannot be added. {}",
+                        err
+                    )));
+                }
+            },
         }
     } else {
         return Err(AppError::RequestError(format!(
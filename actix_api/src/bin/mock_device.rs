@@ -0,0 +1,76 @@
+use actix_api::mock::{MockDevice, MockDeviceConfig, MockFormat};
+use actix_api::models::devices::{Auth, BasicAuth, GrantType, OAuth2Auth, TokenAuth};
+use actix_web::main;
+use dotenv::dotenv;
+use serde_json::json;
+use std::env;
+use std::io::Result;
+
+/// Runs an offline RESTCONF/TAPI device serving fixture files, to try the device path without a real device.
+///
+/// Configured through environment variables:
+/// 1. **MOCK_FIXTURES_DIR:** Directory with the fixture files, `devices_files` by default.
+/// 2. **MOCK_HOST / MOCK_PORT:** Address to listen on, `0.0.0.0:18010` by default.
+/// 3. **MOCK_USERNAME / MOCK_PASSWORD:** Accepted credentials, `tapi`/`tapi` by default.
+/// 4. **MOCK_AUTH_URI:** When set, token authentication is used and the token is requested on this URI
+///    with `{"username": ..., "password": ...}` as body.
+/// 5. **MOCK_OAUTH2_TOKEN_URI:** When set, OAuth2 with the password grant is used instead, issuing
+///    tokens on this URI. **MOCK_CLIENT_ID / MOCK_CLIENT_SECRET** are required from the client when set.
+/// 6. **MOCK_FORMAT:** `json` (default) or `xml`.
+/// 7. **MOCK_CA_FILE:** When set, the CA certificate of the mock is written to this file, to be uploaded as
+///    the CA bundle of the device instead of registering it as insecure.
+///
+/// # Returns
+///
+/// * `Result<()>` - Runs until Ctrl+C is pressed, or returns an `io::Error` if the mock cannot start.
+#[main]
+async fn main() -> Result<()> {
+    dotenv().ok();
+    env_logger::init();
+
+    let fixtures_dir = env::var("MOCK_FIXTURES_DIR").unwrap_or("devices_files".to_string());
+    let host = env::var("MOCK_HOST").unwrap_or("0.0.0.0".to_string());
+    let port = env::var("MOCK_PORT").unwrap_or("18010".to_string());
+    let username = env::var("MOCK_USERNAME").unwrap_or("tapi".to_string());
+    let password = env::var("MOCK_PASSWORD").unwrap_or("tapi".to_string());
+
+    let auth = match (env::var("MOCK_OAUTH2_TOKEN_URI"), env::var("MOCK_AUTH_URI")) {
+        (Ok(token_uri), _) => Auth::OAuth2(OAuth2Auth {
+            grant_type: GrantType::Password,
+            token_uri,
+            client_id: env::var("MOCK_CLIENT_ID").ok(),
+            client_secret: env::var("MOCK_CLIENT_SECRET").ok(),
+            username: Some(username),
+            password: Some(password),
+            scope: None,
+        }),
+        (_, Ok(auth_uri)) => Auth::Token(TokenAuth {
+            auth_body: json!({ "username": username, "password": password }),
+            auth_uri,
+        }),
+        _ => Auth::Basic(BasicAuth { username, password }),
+    };
+
+    let mut config = MockDeviceConfig::from_fixtures(&fixtures_dir, auth)
+        .map_err(|err| std::io::Error::other(err.to_string()))?;
+    if env::var("MOCK_FORMAT").is_ok_and(|format| format.eq_ignore_ascii_case("xml")) {
+        config.format = MockFormat::Xml;
+    }
+
+    let mock_device = MockDevice::start(config, &format!("{}:{}", host, port))
+        .await
+        .map_err(|err| std::io::Error::other(err.to_string()))?;
+    if let Ok(ca_file) = env::var("MOCK_CA_FILE") {
+        std::fs::write(&ca_file, mock_device.ca_certificate())?;
+        log::info!("CA certificate written to {}", ca_file);
+    }
+    log::info!(
+        "Mock device serving {} on port {}",
+        fixtures_dir,
+        mock_device.port()
+    );
+
+    tokio::signal::ctrl_c().await?;
+    mock_device.stop().await;
+    Ok(())
+}
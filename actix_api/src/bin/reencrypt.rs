@@ -0,0 +1,40 @@
+use std::env;
+
+use actix_api::{
+    handlers::{cipher::CipherHandler, database::DatabaseHandler},
+    models::devices::Device,
+    AppError,
+};
+
+/// Re-encrypts the credentials of every stored device.
+///
+/// 1. **Old key:** `OLD_CYPHER_KEY`/`OLD_SALT`, the key the credentials are currently encrypted with.
+///    When they are not set the current key is used, which only encrypts rows still stored in plain text.
+/// 2. **New key:** `CYPHER_KEY`/`SALT`, the key the server will use from now on.
+///
+/// # Usage
+///
+/// ```text
+/// OLD_CYPHER_KEY=... OLD_SALT=... CYPHER_KEY=... SALT=... cargo run --bin reencrypt
+/// ```
+#[tokio::main]
+async fn main() -> Result<(), AppError> {
+    dotenv::dotenv().ok();
+
+    let new_cipher = CipherHandler::from_env()?;
+    let old_cipher = if env::var("OLD_CYPHER_KEY").is_ok() {
+        CipherHandler::from_env_vars("OLD_CYPHER_KEY", "OLD_SALT")?
+    } else {
+        new_cipher.clone()
+    };
+
+    let database_url = env::var("DATABASE_URL")
+        .map_err(|_| AppError::database_error("DATABASE_URL is not set"))?;
+    let database_handler = DatabaseHandler::new(database_url).await?;
+    database_handler.run_migrations().await?;
+
+    let updated = Device::reencrypt_all(&database_handler, &old_cipher, &new_cipher).await?;
+    println!("{} devices re-encrypted.", updated);
+
+    Ok(())
+}
@@ -1,20 +1,32 @@
-use actix_api::handlers::requester::DataSource;
-//use actix_api::handlers::database::DatabaseHandler;
+use actix_api::handlers::cipher::CipherHandler;
+use actix_api::handlers::database::DatabaseHandler;
+use actix_api::handlers::health::HealthMonitor;
+use actix_api::handlers::notifications::NotificationHub;
+use actix_api::handlers::provisioning::Provisioner;
+use actix_api::handlers::repository::DataSourceRepository;
+use actix_api::handlers::snapshot::SnapshotCache;
 use actix_cors::Cors;
 use actix_web::{main, web, App, HttpServer};
 use dotenv::dotenv;
-use std::collections::HashMap;
 use std::env;
 use std::io::Result;
-use std::sync::Arc;
-use tokio::sync::Mutex;
 
 /// Main entry point for the Actix web server.
 ///
 /// This function sets up and runs the Actix web server with the following configurations:
 /// 1. **CORS Configuration:** Permissive CORS setup to allow requests from any origin.
-/// 2. **Data Sharing:** Uses `web::Data` to share the `host_dictionary` across the application.
-/// 3. **Routes:** Registers various routes for handling different types of HTTP requests.
+/// 2. **Persistence:** Connects to `DATABASE_URL`, runs the migrations and removes uploaded files
+///    that no longer belong to any registered file set. Without `DATABASE_URL` hosts are kept in memory.
+///    Device credentials are encrypted with the key derived from `CYPHER_KEY` and `SALT`.
+/// 3. **Data Sharing:** Uses `web::Data` to share the `DataSourceRepository` and the `SnapshotCache` across the
+///    application. Topology snapshots expire after `SNAPSHOT_TTL_SECS` seconds, 300 by default.
+/// 4. **Health Monitoring:** Checks every registered device each `HEALTH_CHECK_INTERVAL_SECS` seconds, 60 by
+///    default, storing the results exposed by `/hosts/{id}/health`. `0` disables the checks.
+/// 5. **Live Updates:** Follows the notification stream of each device shown in a browser, applying the
+///    changes to its snapshot and forwarding them on `/events/{id}`.
+/// 6. **Provisioning:** Creates and deletes connectivity services on devices, waiting up to
+///    `PROVISIONING_TIMEOUT_SECS` seconds, 60 by default, for the device to apply them.
+/// 7. **Routes:** Registers various routes for handling different types of HTTP requests.
 ///
 /// # Returns
 ///
@@ -33,17 +45,46 @@ async fn main() -> Result<()> {
 
     let host: String = env::var("API_HOST").unwrap_or("0.0.0.0".to_string());
 
-    //let database_url: String = env::var("DATABASE_URL").unwrap_or("postgres://postgres:testing01!@db:5432/app?sslmode=disable".to_string());
-    //let database_handler = DatabaseHandler::new(database_url).await.map_err(|_| std::io::ErrorKind::NetworkUnreachable)?;
+    let repository = match env::var("DATABASE_URL") {
+        Ok(database_url) => {
+            // Device credentials are encrypted with CYPHER_KEY and SALT
+            CipherHandler::global().map_err(|err| std::io::Error::other(err.to_string()))?;
 
-    //sqlx::migrate!("./migrations")
-    //    .run(&database_handler.connection)
-    //     .await
-    //    .map_err(|err| std::io::ErrorKind::NetworkUnreachable)?;
+            let database_handler = DatabaseHandler::new(database_url)
+                .await
+                .map_err(|err| std::io::Error::other(err.to_string()))?;
 
-    // Create a shared, thread-safe dictionary to hold host parameters
-    let host_dictionary: Arc<Mutex<HashMap<String, DataSource>>> =
-        Arc::new(Mutex::new(HashMap::new()));
+            database_handler
+                .run_migrations()
+                .await
+                .map_err(|err| std::io::Error::other(err.to_string()))?;
+
+            DataSourceRepository::new(database_handler)
+        }
+        Err(_) => {
+            log::warn!("DATABASE_URL is not set, registered hosts will be lost on restart");
+            DataSourceRepository::in_memory()
+        }
+    };
+
+    // Files left in `data/` by file sets that are no longer registered
+    let removed_files = repository
+        .remove_orphaned_files("data")
+        .await
+        .map_err(|err| std::io::Error::other(err.to_string()))?;
+    for removed_file in removed_files {
+        log::info!("Removed orphaned file {}", removed_file.display());
+    }
+
+    // Background reachability, TLS, authentication and RESTCONF checks of every device
+    if let Some(health_monitor) = HealthMonitor::from_env(repository.clone()) {
+        health_monitor.spawn();
+    }
+
+    // Parsed topologies shared by every worker
+    let cache = SnapshotCache::from_env();
+    let hub = NotificationHub::new(cache.clone());
+    let provisioner = Provisioner::from_env();
 
     // Start the HTTP server
     HttpServer::new(move || {
@@ -52,15 +93,29 @@ async fn main() -> Result<()> {
 
         App::new()
             .wrap(cors) // Apply CORS configuration
-            .app_data(web::Data::new(host_dictionary.clone())) // Share `host_dictionary` with application
-            //.app_data(web::Data::new(database_handler.clone()))
+            .app_data(web::Data::new(repository.clone())) // Share `repository` with application
+            .app_data(web::Data::new(cache.clone())) // Share `cache` with application
+            .app_data(web::Data::new(hub.clone())) // Share `hub` with application
+            .app_data(web::Data::new(provisioner.clone())) // Share `provisioner` with application
             //.service(actix_api::routes::add_device::add_device)
             .service(actix_api::routes::get_services::connectivity_services)
             .service(actix_api::routes::get_schema::schema_by_service)
+            .service(actix_api::routes::get_topology::topology_by_id)
             .service(actix_api::routes::get_hosts::get_hosts)
             .service(actix_api::routes::add_host::add_host)
             .service(actix_api::routes::delete_host::delete_host)
             .service(actix_api::routes::by_files::upload_services)
+            .service(actix_api::routes::refresh::refresh)
+            .service(actix_api::routes::probe::probe)
+            .service(actix_api::routes::get_validations::get_validations)
+            .service(actix_api::routes::get_health::get_health)
+            .service(actix_api::routes::positions::get_positions)
+            .service(actix_api::routes::positions::set_positions)
+            .service(actix_api::routes::events::events)
+            .service(actix_api::routes::provisioning::get_sips)
+            .service(actix_api::routes::provisioning::create_service)
+            .service(actix_api::routes::provisioning::delete_service)
+            .service(actix_api::routes::path_computation::compute_paths)
             .service(actix_api::routes::home::home)
     })
     .bind((host.as_str(), port))? // Bind the server
@@ -73,12 +128,11 @@ async fn main() -> Result<()> {
 async fn test_server() {
     use actix_web::test;
 
-    let host_dictionary: Arc<Mutex<HashMap<String, DataSource>>> =
-        Arc::new(Mutex::new(HashMap::new()));
+    let repository = DataSourceRepository::in_memory();
 
     let app = test::init_service(
         App::new()
-            .app_data(web::Data::new(host_dictionary.clone()))
+            .app_data(web::Data::new(repository.clone()))
             .service(actix_api::routes::get_hosts::get_hosts),
     )
     .await;
@@ -0,0 +1,159 @@
+use crate::AppError;
+
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use hmac::Hmac;
+use pbkdf2::pbkdf2;
+use sha2::Sha256;
+use std::env;
+use std::sync::OnceLock;
+
+/// Prefix of every ciphertext produced by `CipherHandler`, used to tell it apart from plain text.
+const CIPHERTEXT_PREFIX: &str = "enc:v1:";
+
+/// Number of PBKDF2 iterations used to derive the key from `CYPHER_KEY` and `SALT`.
+const PBKDF2_ROUNDS: u32 = 100_000;
+
+/// Length in bytes of the AES-GCM nonce stored in front of each ciphertext.
+const NONCE_LENGTH: usize = 12;
+
+static GLOBAL_CIPHER: OnceLock<CipherHandler> = OnceLock::new();
+
+/// Authenticated encryption (AES-256-GCM) for secrets stored in the database.
+///
+/// Ciphertexts are stored as `enc:v1:<base64(nonce || ciphertext)>`.
+#[derive(Clone)]
+pub struct CipherHandler {
+    cipher: Aes256Gcm,
+}
+
+impl std::fmt::Debug for CipherHandler {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CipherHandler").finish_non_exhaustive()
+    }
+}
+
+impl CipherHandler {
+    /// Derives a 32-byte key from a passphrase and a salt with PBKDF2-HMAC-SHA256.
+    pub fn new(cypher_key: impl AsRef<[u8]>, salt: impl AsRef<[u8]>) -> Result<Self, AppError> {
+        let mut key = [0u8; 32];
+        pbkdf2::<Hmac<Sha256>>(cypher_key.as_ref(), salt.as_ref(), PBKDF2_ROUNDS, &mut key)
+            .map_err(|err| AppError::encryption_error(err.to_string()))?;
+
+        Ok(Self {
+            cipher: Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key)),
+        })
+    }
+
+    /// Builds the handler from the `CYPHER_KEY` and `SALT` environment variables.
+    pub fn from_env() -> Result<Self, AppError> {
+        Self::from_env_vars("CYPHER_KEY", "SALT")
+    }
+
+    /// Builds the handler from the given environment variables, e.g. the old key during a rotation.
+    pub fn from_env_vars(key_var: &str, salt_var: &str) -> Result<Self, AppError> {
+        let cypher_key = env::var(key_var)
+            .map_err(|_| AppError::encryption_error(format!("{} is not set", key_var)))?;
+        let salt = env::var(salt_var)
+            .map_err(|_| AppError::encryption_error(format!("{} is not set", salt_var)))?;
+
+        Self::new(cypher_key, salt)
+    }
+
+    /// Shared handler used when reading and writing devices, built from the environment on first use.
+    pub fn global() -> Result<&'static Self, AppError> {
+        if let Some(cipher_handler) = GLOBAL_CIPHER.get() {
+            return Ok(cipher_handler);
+        }
+        let cipher_handler = Self::from_env()?;
+        Ok(GLOBAL_CIPHER.get_or_init(|| cipher_handler))
+    }
+
+    /// Returns `true` if the value was produced by `encrypt`.
+    pub fn is_ciphertext(value: &str) -> bool {
+        value.starts_with(CIPHERTEXT_PREFIX)
+    }
+
+    /// Encrypts a plain text with a fresh random nonce.
+    pub fn encrypt(&self, plaintext: &str) -> Result<String, AppError> {
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let ciphertext = self
+            .cipher
+            .encrypt(&nonce, plaintext.as_bytes())
+            .map_err(|err| AppError::encryption_error(err.to_string()))?;
+
+        let mut payload = nonce.to_vec();
+        payload.extend(ciphertext);
+
+        Ok(format!("{}{}", CIPHERTEXT_PREFIX, STANDARD.encode(payload)))
+    }
+
+    /// Decrypts a value produced by `encrypt`, failing if it was tampered with or encrypted with another key.
+    pub fn decrypt(&self, value: &str) -> Result<String, AppError> {
+        let encoded = value.strip_prefix(CIPHERTEXT_PREFIX).ok_or_else(|| {
+            AppError::encryption_error("Value is not encrypted, run the reencrypt command")
+        })?;
+        let payload = STANDARD
+            .decode(encoded)
+            .map_err(|err| AppError::encryption_error(err.to_string()))?;
+
+        if payload.len() < NONCE_LENGTH {
+            return Err(AppError::encryption_error("Ciphertext is too short"));
+        }
+        let (nonce, ciphertext) = payload.split_at(NONCE_LENGTH);
+
+        let plaintext = self
+            .cipher
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map_err(|_| {
+                AppError::encryption_error("Ciphertext cannot be decrypted with this key")
+            })?;
+
+        String::from_utf8(plaintext).map_err(|err| AppError::encryption_error(err.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CipherHandler;
+
+    #[test]
+    fn test_encrypt_decrypt_roundtrip() {
+        let cipher_handler = CipherHandler::new("cypher_key", "salt").unwrap();
+        let ciphertext = cipher_handler
+            .encrypt(r#"{"password":"Zte_2025"}"#)
+            .unwrap();
+
+        assert!(CipherHandler::is_ciphertext(&ciphertext));
+        assert!(!ciphertext.contains("Zte_2025"));
+        assert_eq!(
+            cipher_handler.decrypt(&ciphertext).unwrap(),
+            r#"{"password":"Zte_2025"}"#
+        );
+    }
+
+    #[test]
+    fn test_decrypt_with_other_key() {
+        let ciphertext = CipherHandler::new("cypher_key", "salt")
+            .unwrap()
+            .encrypt("secret")
+            .unwrap();
+
+        assert!(CipherHandler::new("other_key", "salt")
+            .unwrap()
+            .decrypt(&ciphertext)
+            .is_err());
+    }
+
+    #[test]
+    fn test_decrypt_tampered_and_plain_values() {
+        let cipher_handler = CipherHandler::new("cypher_key", "salt").unwrap();
+        let mut ciphertext = cipher_handler.encrypt("secret").unwrap();
+        let last = ciphertext.pop().unwrap();
+        ciphertext.push(if last == 'A' { 'B' } else { 'A' });
+
+        assert!(cipher_handler.decrypt(&ciphertext).is_err());
+        assert!(cipher_handler.decrypt(r#"{"password":"secret"}"#).is_err());
+    }
+}
@@ -10,6 +10,7 @@ pub struct DatabaseHandler {
 // 📌 Enum para permitir diferentes tipos en `bind()`
 pub enum SqlxBindValue {
     Int(i32),
+    Bool(bool),
     Str(String),
     Json(serde_json::Value),
     Null,
@@ -36,6 +37,7 @@ impl DatabaseHandler {
         for param in params {
             query_builder = match param {
                 SqlxBindValue::Int(value) => query_builder.bind(value),
+                SqlxBindValue::Bool(value) => query_builder.bind(value),
                 SqlxBindValue::Str(value) => query_builder.bind(value),
                 SqlxBindValue::Json(value) => query_builder.bind(sqlx::types::Json(value)),
                 SqlxBindValue::Null => query_builder.bind(None::<String>),
@@ -64,6 +66,7 @@ impl DatabaseHandler {
         for param in params {
             query_builder = match param {
                 SqlxBindValue::Int(value) => query_builder.bind(value),
+                SqlxBindValue::Bool(value) => query_builder.bind(value),
                 SqlxBindValue::Str(value) => query_builder.bind(value),
                 SqlxBindValue::Json(value) => query_builder.bind(sqlx::types::Json(value)),
                 SqlxBindValue::Null => query_builder.bind(None::<String>),
@@ -77,4 +80,41 @@ impl DatabaseHandler {
 
         Ok(result)
     }
+
+    pub async fn fetch_optional<T>(
+        &self,
+        query: &str,
+        params: Vec<SqlxBindValue>,
+    ) -> Result<Option<T>, AppError>
+    where
+        T: for<'r> FromRow<'r, sqlx::postgres::PgRow> + Send + Unpin,
+    {
+        let mut query_builder = sqlx::query_as::<_, T>(query);
+
+        // 📌 Bind dinámico sin errores de vida útil
+        for param in params {
+            query_builder = match param {
+                SqlxBindValue::Int(value) => query_builder.bind(value),
+                SqlxBindValue::Bool(value) => query_builder.bind(value),
+                SqlxBindValue::Str(value) => query_builder.bind(value),
+                SqlxBindValue::Json(value) => query_builder.bind(sqlx::types::Json(value)),
+                SqlxBindValue::Null => query_builder.bind(None::<String>),
+            };
+        }
+
+        let result = query_builder
+            .fetch_optional(&self.connection)
+            .await
+            .map_err(|err| AppError::DatabaseError(err.to_string()))?;
+
+        Ok(result)
+    }
+
+    /// Applies every pending migration embedded from `./migrations`.
+    pub async fn run_migrations(&self) -> Result<(), AppError> {
+        sqlx::migrate!("./migrations")
+            .run(&self.connection)
+            .await
+            .map_err(|err| AppError::DatabaseError(err.to_string()))
+    }
 }
@@ -0,0 +1,221 @@
+use crate::handlers::http::{HttpConfig, HttpHandler};
+use crate::handlers::repository::DataSourceRepository;
+use crate::handlers::requester::{DataSource, DeviceHandler};
+use crate::models::device_health::{DeviceHealth, HealthStatus};
+use crate::models::devices::{Device, Scheme};
+use crate::AppError;
+
+use chrono::Utc;
+use futures_util::{stream, StreamExt};
+use std::env;
+use std::time::{Duration, Instant};
+use tokio::net::TcpStream;
+use tokio::task::JoinHandle;
+use tokio::time::{self, MissedTickBehavior};
+
+/// Time between two checks of every device when `HEALTH_CHECK_INTERVAL_SECS` is not set.
+const DEFAULT_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Time allowed to open the TCP connection to a device.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Periodically checks the health of every registered device and stores the results in the
+/// `DataSourceRepository`.
+#[derive(Clone, Debug)]
+pub struct HealthMonitor {
+    repository: DataSourceRepository,
+    interval: Duration,
+}
+
+impl HealthMonitor {
+    /// Creates a monitor checking the devices of `repository` every `interval`.
+    pub fn new(repository: DataSourceRepository, interval: Duration) -> Self {
+        Self {
+            repository,
+            interval,
+        }
+    }
+
+    /// Creates a monitor with the interval in seconds from `HEALTH_CHECK_INTERVAL_SECS`, 60 by
+    /// default.
+    ///
+    /// # Returns
+    /// The monitor, or `None` if the interval is `0`, which disables the checks.
+    pub fn from_env(repository: DataSourceRepository) -> Option<Self> {
+        let interval = env::var("HEALTH_CHECK_INTERVAL_SECS")
+            .ok()
+            .and_then(|value| value.parse().ok())
+            .map(Duration::from_secs)
+            .unwrap_or(DEFAULT_INTERVAL);
+        (!interval.is_zero()).then(|| Self::new(repository, interval))
+    }
+
+    /// Runs `check_all` in the background every `interval`, starting right away.
+    pub fn spawn(self) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut interval = time::interval(self.interval);
+            interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+            loop {
+                interval.tick().await;
+                if let Err(err) = self.check_all().await {
+                    log::error!("Health check failed: {}", err);
+                }
+            }
+        })
+    }
+
+    /// Checks every registered device concurrently, with at most `HttpConfig::max_concurrency`
+    /// in flight, and stores the results. A result that cannot be stored is logged and skipped.
+    ///
+    /// # Returns
+    /// The ip and the result of each device, or an `Error` if the devices cannot be listed.
+    pub async fn check_all(&self) -> Result<Vec<(String, DeviceHealth)>, AppError> {
+        let devices: Vec<Device> = self
+            .repository
+            .list()
+            .await?
+            .into_iter()
+            .filter_map(|data_source| match data_source {
+                DataSource::Device(device) => Some(device),
+                DataSource::FilesEnum(_) => None,
+            })
+            .collect();
+
+        let results: Vec<(String, DeviceHealth)> = stream::iter(devices)
+            .map(|device| async move {
+                let health = Self::check(&device).await;
+                (device.ip, health)
+            })
+            .buffer_unordered(HttpConfig::global().max_concurrency)
+            .collect()
+            .await;
+
+        for (ip, health) in &results {
+            if health.status != HealthStatus::Healthy {
+                log::warn!(
+                    "{} is {}: {}",
+                    ip,
+                    health.status,
+                    health.error.as_deref().unwrap_or_default()
+                );
+            }
+            // A failed insert does not keep the results of the other devices from being stored
+            if let Err(err) = self.repository.insert_health(ip, health).await {
+                log::error!("Cannot store the health of {}: {}", ip, err);
+            }
+        }
+
+        Ok(results)
+    }
+
+    /// Checks a device step by step, stopping at the first failure.
+    ///
+    /// 1. **TCP:** Connects to the RESTCONF port, the default one of the scheme if the device has none.
+    /// 2. **TLS:** Sends a request without credentials to complete a handshake with the `TlsConfig`
+    ///    of the device. Skipped for plain HTTP.
+    /// 3. **Authentication:** Obtains a token and discovers the RESTCONF root. Basic credentials
+    ///    are checked by the requests themselves.
+    /// 4. **RESTCONF:** Requests `tapi-common:context?fields=name;uuid`, timing the answer.
+    ///
+    /// # Returns
+    /// A `DeviceHealth` that is `Unreachable` if the first two steps fail, `Degraded` if the last
+    /// two do and `Healthy` otherwise.
+    pub async fn check(device: &Device) -> DeviceHealth {
+        let mut health = DeviceHealth {
+            status: HealthStatus::Unreachable,
+            tcp_connect: false,
+            tls_handshake: None,
+            authenticated: None,
+            restconf: None,
+            latency_ms: None,
+            error: None,
+            checked_at: Utc::now().naive_utc(),
+        };
+
+        let port = device
+            .port
+            .and_then(|port| u16::try_from(port).ok())
+            .unwrap_or(device.tls.scheme.default_port());
+        match time::timeout(
+            CONNECT_TIMEOUT,
+            TcpStream::connect((device.ip.as_str(), port)),
+        )
+        .await
+        {
+            Ok(Ok(_)) => health.tcp_connect = true,
+            Ok(Err(err)) => {
+                health.error = Some(format!("TCP connection to port {} failed: {}", port, err));
+                return health;
+            }
+            Err(_) => {
+                health.error = Some(format!("TCP connection to port {} timed out", port));
+                return health;
+            }
+        }
+
+        let device_url = DeviceHandler::device_url(device);
+        if device.tls.scheme == Scheme::Https {
+            let handshake = HttpHandler::handshake(&device_url, &device.tls).await;
+            health.tls_handshake = Some(handshake.is_ok());
+            if let Err(err) = handshake {
+                health.error = Some(format!("TLS handshake failed: {}", err));
+                return health;
+            }
+        }
+
+        health.status = HealthStatus::Degraded;
+        let token = match DeviceHandler::token(device).await {
+            Ok(token) => token,
+            Err(err) => {
+                health.authenticated = Some(false);
+                health.error = Some(format!("No token was granted: {}", err));
+                return health;
+            }
+        };
+        let restconf_url = match DeviceHandler::base_url(device, &token).await {
+            Ok(restconf_url) => restconf_url,
+            Err(err @ AppError::AuthenticationError(_)) => {
+                health.authenticated = Some(false);
+                health.error = Some(err.to_string());
+                return health;
+            }
+            Err(err) => {
+                health.error = Some(format!("RESTCONF root cannot be discovered: {}", err));
+                return health;
+            }
+        };
+
+        let context_url = format!("{}/data/tapi-common:context?fields=name;uuid", restconf_url);
+        let started = Instant::now();
+        let response = DeviceHandler::probe(device, &context_url, &token).await;
+        health.latency_ms = Some(started.elapsed().as_millis().try_into().unwrap_or(i32::MAX));
+        match response {
+            Ok(response) if response.status == reqwest::StatusCode::UNAUTHORIZED => {
+                health.authenticated = Some(false);
+                health.error = Some(format!("{} rejected the credentials", context_url));
+            }
+            Ok(response) => {
+                health.authenticated = Some(true);
+                health.restconf = Some(response.status.is_success() && response.body.is_ok());
+                match response.body {
+                    _ if !response.status.is_success() => {
+                        health.error =
+                            Some(format!("{} answered {}", context_url, response.status));
+                    }
+                    Err(err) => health.error = Some(err.to_string()),
+                    Ok(_) => health.status = HealthStatus::Healthy,
+                }
+            }
+            Err(err @ AppError::AuthenticationError(_)) => {
+                health.authenticated = Some(false);
+                health.error = Some(err.to_string());
+            }
+            Err(err) => {
+                health.restconf = Some(false);
+                health.error = Some(err.to_string());
+            }
+        }
+
+        health
+    }
+}
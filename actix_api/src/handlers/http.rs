@@ -1,8 +1,127 @@
+use crate::models::devices::TlsConfig;
 use crate::utils::{find_token_key, xml_to_json};
 use crate::AppError;
-use reqwest::{Client, RequestBuilder};
+use reqwest::{
+    Certificate, Client, Identity, Method, Request, RequestBuilder, Response, StatusCode,
+};
 
 use serde_json::Value;
+use std::collections::HashMap;
+use std::env;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+static HTTP_CONFIG: OnceLock<HttpConfig> = OnceLock::new();
+static HTTP_CLIENTS: OnceLock<Mutex<HashMap<TlsConfig, Client>>> = OnceLock::new();
+static RATE_LIMITER: OnceLock<RateLimiter> = OnceLock::new();
+
+/// Longest time an event stream is kept open, after which it has to be opened again.
+const STREAM_TIMEOUT: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Limits applied to the requests sent to devices.
+#[derive(Clone, Debug)]
+pub struct HttpConfig {
+    /// Maximum number of requests in flight per fan-out, e.g. when fetching every node of a topology.
+    pub max_concurrency: usize,
+    /// Number of times a GET is retried after a 5xx, a 429 or a timeout.
+    pub max_retries: u32,
+    /// Wait before the first retry, doubled on each following one.
+    pub retry_backoff: Duration,
+    /// Timeout of a single request.
+    pub timeout: Duration,
+    /// Maximum number of requests per second sent to the same device, `0` disables the limit.
+    pub rate_limit: u32,
+}
+
+impl Default for HttpConfig {
+    fn default() -> Self {
+        Self {
+            max_concurrency: 8,
+            max_retries: 3,
+            retry_backoff: Duration::from_millis(200),
+            timeout: Duration::from_secs(60),
+            rate_limit: 0,
+        }
+    }
+}
+
+impl HttpConfig {
+    /// Builds the configuration from `DEVICE_MAX_CONCURRENCY`, `DEVICE_MAX_RETRIES`, `DEVICE_RETRY_BACKOFF_MS`,
+    /// `DEVICE_REQUEST_TIMEOUT_SECS` and `DEVICE_RATE_LIMIT`, using the defaults for the missing ones.
+    pub fn from_env() -> Self {
+        fn parse<T: std::str::FromStr>(name: &str) -> Option<T> {
+            env::var(name).ok().and_then(|value| value.parse().ok())
+        }
+
+        let default = Self::default();
+        Self {
+            max_concurrency: parse::<usize>("DEVICE_MAX_CONCURRENCY")
+                .filter(|value| *value > 0)
+                .unwrap_or(default.max_concurrency),
+            max_retries: parse("DEVICE_MAX_RETRIES").unwrap_or(default.max_retries),
+            retry_backoff: parse("DEVICE_RETRY_BACKOFF_MS")
+                .map(Duration::from_millis)
+                .unwrap_or(default.retry_backoff),
+            timeout: parse("DEVICE_REQUEST_TIMEOUT_SECS")
+                .map(Duration::from_secs)
+                .unwrap_or(default.timeout),
+            rate_limit: parse("DEVICE_RATE_LIMIT").unwrap_or(default.rate_limit),
+        }
+    }
+
+    /// Shared configuration, read from the environment on first use.
+    pub fn global() -> &'static Self {
+        HTTP_CONFIG.get_or_init(Self::from_env)
+    }
+}
+
+/// Spaces out the requests sent to each device so no more than `rate_limit` are sent per second.
+#[derive(Debug)]
+pub struct RateLimiter {
+    interval: Duration,
+    next_slots: Mutex<HashMap<String, Instant>>,
+}
+
+impl RateLimiter {
+    /// Creates a limiter allowing `rate_limit` requests per second per device, `0` means unlimited.
+    pub fn new(rate_limit: u32) -> Self {
+        let interval = if rate_limit == 0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs(1) / rate_limit
+        };
+
+        Self {
+            interval,
+            next_slots: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Waits until a request can be sent to the given device.
+    pub async fn acquire(&self, device: &str) {
+        if self.interval.is_zero() {
+            return;
+        }
+
+        let wait = {
+            let mut next_slots = self
+                .next_slots
+                .lock()
+                .unwrap_or_else(|err| err.into_inner());
+            let now = Instant::now();
+            let slot = next_slots
+                .get(device)
+                .map(|next_slot| (*next_slot).max(now))
+                .unwrap_or(now);
+            next_slots.insert(device.to_string(), slot + self.interval);
+            slot - now
+        };
+
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
 
 #[derive(Debug)]
 pub enum ContentType {
@@ -24,32 +143,286 @@ impl ContentType {
     }
 }
 
+/// Response of a request sent to check what a device supports, kept whatever its status.
+#[derive(Debug)]
+pub struct ProbeResponse {
+    pub status: StatusCode,
+    /// Media type of the `Content-Type` header, without parameters.
+    pub content_type: Option<String>,
+    /// The parsed body, or the `Error` found parsing it.
+    pub body: Result<Value, AppError>,
+}
+
 pub struct HttpHandler;
 
 impl HttpHandler {
+    /// Shared client for a TLS configuration, so connections to the same device are pooled and
+    /// reused between requests.
+    ///
+    /// # Returns
+    /// The client, or an `Error` if the certificates or the key of `tls` cannot be read.
+    ///
+    /// # Notes
+    /// - Invalid certificates are only accepted when `tls.insecure` is set.
+    /// - Gzip, Brotli, and Deflate encodings are enabled for the request.
+    fn client(tls: &TlsConfig) -> Result<Client, AppError> {
+        let mut clients = HTTP_CLIENTS
+            .get_or_init(|| Mutex::new(HashMap::new()))
+            .lock()
+            .unwrap_or_else(|err| err.into_inner());
+        if let Some(client) = clients.get(tls) {
+            return Ok(client.clone());
+        }
+
+        let config = HttpConfig::global();
+        let mut builder = Client::builder()
+            .danger_accept_invalid_certs(tls.insecure) // Accept invalid certificates on opt-in.
+            .gzip(true) // Enable gzip encoding.
+            .brotli(true) // Enable brotli encoding.
+            .deflate(true) // Enable deflate encoding.
+            .timeout(config.timeout)
+            .pool_max_idle_per_host(config.max_concurrency);
+        if let Some(ca_certificate) = &tls.ca_certificate {
+            let certificates = Certificate::from_pem_bundle(ca_certificate.as_bytes())
+                .map_err(|err| AppError::validation_error(format!("Invalid CA bundle: {}", err)))?;
+            if certificates.is_empty() {
+                return Err(AppError::validation_error(
+                    "The CA bundle holds no PEM certificate",
+                ));
+            }
+            for certificate in certificates {
+                builder = builder.add_root_certificate(certificate);
+            }
+        }
+        match (&tls.client_certificate, &tls.client_key) {
+            (Some(client_certificate), Some(client_key)) => {
+                let identity =
+                    Identity::from_pkcs8_pem(client_certificate.as_bytes(), client_key.as_bytes())
+                        .map_err(|err| {
+                            AppError::validation_error(format!(
+                                "Invalid client certificate: {}",
+                                err
+                            ))
+                        })?;
+                builder = builder.identity(identity);
+            }
+            (None, None) => {}
+            _ => {
+                return Err(AppError::validation_error(
+                    "The client certificate and key must be given together",
+                ))
+            }
+        }
+
+        let client = builder
+            .build()
+            .map_err(|err| AppError::validation_error(err.to_string()))?;
+        clients.insert(tls.clone(), client.clone());
+        Ok(client)
+    }
+
+    /// Checks that a client can be built for the TLS configuration of a device.
+    ///
+    /// # Returns
+    /// `Ok(())`, or an `Error` describing the invalid certificate or key.
+    pub fn check_tls(tls: &TlsConfig) -> Result<(), AppError> {
+        Self::client(tls).map(|_| ())
+    }
+
+    /// Sends a HEAD request without credentials, to check that a connection can be established
+    /// with the TLS configuration of a device.
+    ///
+    /// # Returns
+    /// `Ok(())` if the device answered with any status, or an `Error` with the cause of the
+    /// failure, e.g. an untrusted certificate.
+    pub async fn handshake(url: &String, tls: &TlsConfig) -> Result<(), AppError> {
+        Self::client(tls)?
+            .head(url)
+            .send()
+            .await
+            .map(|_| ())
+            .map_err(|err| {
+                let mut message = err.to_string();
+                let mut source = std::error::Error::source(&err);
+                while let Some(cause) = source {
+                    message = format!("{}: {}", message, cause);
+                    source = cause.source();
+                }
+                AppError::request_error(message)
+            })
+    }
+
     /// Builds a `RequestBuilder` for a GET request with specific configurations.
     ///
     /// # Arguments
     /// - `url`: A reference to the URL for the GET request.
+    /// - `tls`: The TLS configuration of the device.
     ///
     /// # Returns
     /// A `RequestBuilder` configured with the specified URL and headers.
     ///
     /// # Notes
-    /// - The client accepts invalid SSL certificates.
-    /// - Gzip, Brotli, and Deflate encodings are enabled for the request.
     /// - Adds headers for `Accept` and `Accept-Encoding` to handle JSON data and compression.
-    fn client_get_builder(url: &String) -> RequestBuilder {
-        Client::builder()
-            .danger_accept_invalid_certs(true) // Accept invalid certificates.
-            .gzip(true) // Enable gzip encoding.
-            .brotli(true) // Enable brotli encoding.
-            .deflate(true) // Enable deflate encoding.
-            .build()
-            .unwrap() // Handle client build error.
+    fn client_get_builder(url: &String, tls: &TlsConfig) -> Result<RequestBuilder, AppError> {
+        Ok(Self::client(tls)?
             .get(url) // Set up the GET request.
             .header("Accept", "*/*")
-            .header("Accept-Encoding", "gzip, deflate, br")
+            .header("Accept-Encoding", "gzip, deflate, br"))
+    }
+
+    /// Sends a request respecting the per-device rate limit.
+    ///
+    /// GET requests are retried with exponential backoff after a 5xx, a 429, a timeout or a
+    /// connection error, up to `HttpConfig::max_retries` times.
+    ///
+    /// # Returns
+    /// The successful `Response`, or an `Error` with the last failure. A `401` is returned as
+    /// `AppError::AuthenticationError`, so the token can be renewed.
+    async fn execute(request: RequestBuilder) -> Result<Response, AppError> {
+        let config = HttpConfig::global();
+        let (client, request) = request.build_split();
+        let request: Request = request.map_err(|err| AppError::request_error(err.to_string()))?;
+        let device = format!(
+            "{}:{}",
+            request.url().host_str().unwrap_or_default(),
+            request.url().port_or_known_default().unwrap_or_default()
+        );
+        let max_retries = if request.method() == Method::GET {
+            config.max_retries
+        } else {
+            0
+        };
+
+        let mut attempt = 0;
+        loop {
+            let attempt_request = request
+                .try_clone()
+                .ok_or_else(|| AppError::request_error("Request cannot be cloned"))?;
+            RATE_LIMITER
+                .get_or_init(|| RateLimiter::new(config.rate_limit))
+                .acquire(&device)
+                .await;
+
+            let failure = match client.execute(attempt_request).await {
+                Ok(response)
+                    if response.status().is_server_error()
+                        || response.status() == StatusCode::TOO_MANY_REQUESTS =>
+                {
+                    if attempt >= max_retries {
+                        return response
+                            .error_for_status()
+                            .map_err(|err| AppError::request_error(err.to_string()));
+                    }
+                    response.status().to_string()
+                }
+                Ok(response) if response.status() == StatusCode::UNAUTHORIZED => {
+                    return Err(AppError::authentication_error(format!(
+                        "{} rejected the credentials",
+                        request.url()
+                    )))
+                }
+                Ok(response) => {
+                    return response
+                        .error_for_status()
+                        .map_err(|err| AppError::request_error(err.to_string()))
+                }
+                Err(err) if (err.is_timeout() || err.is_connect()) && attempt < max_retries => {
+                    err.to_string()
+                }
+                Err(err) => return Err(AppError::request_error(err.to_string())),
+            };
+
+            log::warn!(
+                "Retrying {} after {} ({}/{})",
+                request.url(),
+                failure,
+                attempt + 1,
+                max_retries
+            );
+            tokio::time::sleep(config.retry_backoff * 2u32.pow(attempt)).await;
+            attempt += 1;
+        }
+    }
+
+    /// Sends a single GET request respecting the per-device rate limit, without retries.
+    ///
+    /// # Returns
+    /// A `ProbeResponse` for any status, or an `Error` if no response was received.
+    async fn probe(request: RequestBuilder) -> Result<ProbeResponse, AppError> {
+        let (client, request) = request.build_split();
+        let request: Request = request.map_err(|err| AppError::request_error(err.to_string()))?;
+        let device = format!(
+            "{}:{}",
+            request.url().host_str().unwrap_or_default(),
+            request.url().port_or_known_default().unwrap_or_default()
+        );
+        RATE_LIMITER
+            .get_or_init(|| RateLimiter::new(HttpConfig::global().rate_limit))
+            .acquire(&device)
+            .await;
+
+        let response = client
+            .execute(request)
+            .await
+            .map_err(|err| AppError::request_error(err.to_string()))?;
+        let status = response.status();
+        let content_type = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|content_type| content_type.to_str().ok())
+            .and_then(|content_type| content_type.split(';').next())
+            .map(|media_type| media_type.trim().to_string());
+
+        Ok(ProbeResponse {
+            status,
+            content_type,
+            body: Self::handle_content_type(response).await,
+        })
+    }
+
+    /// Opens a server-sent events stream, read with `Response::chunk` until the device closes it.
+    ///
+    /// # Returns
+    /// The `Response` of a successful request, or an `Error`. A `401` is returned as
+    /// `AppError::AuthenticationError`, so the token can be renewed.
+    async fn event_stream(request: RequestBuilder) -> Result<Response, AppError> {
+        let response = request
+            .header("Accept", "text/event-stream")
+            .timeout(STREAM_TIMEOUT)
+            .send()
+            .await
+            .map_err(|err| AppError::request_error(err.to_string()))?;
+        if response.status() == StatusCode::UNAUTHORIZED {
+            return Err(AppError::authentication_error(format!(
+                "{} rejected the credentials",
+                response.url()
+            )));
+        }
+        response
+            .error_for_status()
+            .map_err(|err| AppError::request_error(err.to_string()))
+    }
+
+    /// Sends a request changing the datastore of the device, with a YANG JSON body when given.
+    ///
+    /// # Returns
+    /// The body of the response, `Value::Null` when the device answers without one (`201`, `204`),
+    /// or an `Error`. A `401` is returned as `AppError::AuthenticationError`, so the token can be
+    /// renewed.
+    async fn edit(request: RequestBuilder, body: Option<&Value>) -> Result<Value, AppError> {
+        let request = match body {
+            Some(body) => request
+                .header("Content-Type", "application/yang-data+json")
+                .body(body.to_string()),
+            None => request,
+        };
+        let response =
+            Self::execute(request.header("Accept", "application/yang-data+json")).await?;
+        if response.content_length() == Some(0) || response.status() == StatusCode::NO_CONTENT {
+            return Ok(Value::Null);
+        }
+
+        Self::handle_content_type(response).await
     }
 
     async fn handle_content_type(response: reqwest::Response) -> Result<Value, AppError> {
@@ -85,25 +458,20 @@ impl HttpHandler {
     /// # Arguments
     /// - `url`: A reference to the URL for the POST request.
     /// - `json`: A reference to a `HashMap` containing the JSON payload for the POST request.
+    /// - `tls`: The TLS configuration of the device.
     ///
     /// # Returns
     /// A `Result` containing the deserialized JSON response as a `Value`, or an `Error`.
     ///
     /// # Notes
-    /// - The client accepts invalid SSL certificates.
     /// - If the request or response parsing fails, appropriate errors are returned.
-    pub async fn custom_post_request(url: &String, json: &Value) -> Result<Value, AppError> {
-        let response = Client::builder()
-            .danger_accept_invalid_certs(true) // Accept invalid certificates.
-            .build()
-            .unwrap() // Handle client build error.
-            .post(url) // Set up the GET request.
-            .json(&json)
-            .send()
-            .await
-            .map_err(|err| AppError::request_error(err.to_string()))?
-            .error_for_status()
-            .map_err(|err| AppError::request_error(err.to_string()))?;
+    /// - The request is not retried, but it counts for the rate limit of the device.
+    pub async fn custom_post_request(
+        url: &String,
+        json: &Value,
+        tls: &TlsConfig,
+    ) -> Result<Value, AppError> {
+        let response = Self::execute(Self::client(tls)?.post(url).json(&json)).await?;
 
         Self::handle_content_type(response).await
     }
@@ -113,25 +481,20 @@ impl HttpHandler {
     /// # Arguments
     /// - `url`: A reference to the URL for the POST request.
     /// - `json`: A reference to a `HashMap` containing the JSON payload for the POST request.
+    /// - `tls`: The TLS configuration of the device.
     ///
     /// # Returns
     /// A `Result` containing the deserialized JSON response as a `Value`, or an `Error`.
     ///
     /// # Notes
-    /// - The client accepts invalid SSL certificates.
     /// - If the request or response parsing fails, appropriate errors are returned.
-    pub async fn custom_put_request(url: &String, json: &Value) -> Result<Value, AppError> {
-        let response = Client::builder()
-            .danger_accept_invalid_certs(true) // Accept invalid certificates.
-            .build()
-            .unwrap() // Handle client build error.
-            .put(url) // Set up the GET request.
-            .json(&json)
-            .send()
-            .await
-            .map_err(|err| AppError::request_error(err.to_string()))?
-            .error_for_status()
-            .map_err(|err| AppError::request_error(err.to_string()))?;
+    /// - The request is not retried, but it counts for the rate limit of the device.
+    pub async fn custom_put_request(
+        url: &String,
+        json: &Value,
+        tls: &TlsConfig,
+    ) -> Result<Value, AppError> {
+        let response = Self::execute(Self::client(tls)?.put(url).json(&json)).await?;
 
         Self::handle_content_type(response).await
     }
@@ -143,35 +506,44 @@ impl HttpHandler {
     /// # Arguments
     /// * `url` - A reference to a string containing the request URL.
     /// * `json` - A reference to a hashmap containing the JSON request body.
+    /// * `tls` - The TLS configuration of the device.
     ///
     /// # Returns
     /// * `Ok(String)` - The token if found.
     /// * `Err(Error)` - An error if both requests fail or if the token cannot be found.
-    pub async fn get_token(url: &String, json: &Value) -> Result<String, AppError> {
-        let response = Self::custom_post_request(url, json).await;
-
-        if let Ok(res) = response { match find_token_key(&res) {
-            Some(token_key) => {
-                return Ok(res
-                    .get(token_key)
-                    .ok_or_else(|| {
-                        AppError::validation_error("Cannot find Token key in POST response")
-                    })?
-                    .as_str()
-                    .unwrap()
-                    .to_string());
-            }
-            None => {
-                return Err(AppError::validation_error(
-                    "Cannot find Token key in POST response",
-                ));
+    pub async fn get_token(
+        url: &String,
+        json: &Value,
+        tls: &TlsConfig,
+    ) -> Result<String, AppError> {
+        let response = Self::custom_post_request(url, json, tls).await;
+
+        if let Ok(res) = response {
+            match find_token_key(&res) {
+                Some(token_key) => {
+                    return Ok(res
+                        .get(token_key)
+                        .ok_or_else(|| {
+                            AppError::validation_error("Cannot find Token key in POST response")
+                        })?
+                        .as_str()
+                        .unwrap()
+                        .to_string());
+                }
+                None => {
+                    return Err(AppError::validation_error(
+                        "Cannot find Token key in POST response",
+                    ));
+                }
             }
-        } }
+        }
 
         // If POST fails, try with PUT
-        let response = Self::custom_put_request(url, json).await.map_err(|_| {
-            AppError::validation_error("Device has not response to POST/PUT request")
-        })?;
+        let response = Self::custom_put_request(url, json, tls)
+            .await
+            .map_err(|_| {
+                AppError::validation_error("Device has not response to POST/PUT request")
+            })?;
 
         match find_token_key(&response) {
             Some(token_key) => Ok(response
@@ -186,30 +558,58 @@ impl HttpHandler {
         }
     }
 
+    /// Requests an OAuth2 access token with a form-encoded POST (RFC 6749, section 4).
+    ///
+    /// # Arguments
+    /// - `url`: The URL of the token endpoint.
+    /// - `form`: The parameters of the grant, e.g. `grant_type` and `refresh_token`.
+    /// - `client_id`: Client authenticated with HTTP basic authentication, if any.
+    /// - `client_secret`: Secret of the client.
+    /// - `tls`: The TLS configuration of the device.
+    ///
+    /// # Returns
+    /// The token response as a `Value`, or an `Error` if the grant is rejected.
+    pub async fn oauth2_token_request(
+        url: &String,
+        form: &[(&str, &str)],
+        client_id: Option<&str>,
+        client_secret: Option<&str>,
+        tls: &TlsConfig,
+    ) -> Result<Value, AppError> {
+        let mut request = Self::client(tls)?
+            .post(url)
+            .header("Accept", "application/json")
+            .form(form);
+        if let Some(client_id) = client_id {
+            request = request.basic_auth(client_id, client_secret);
+        }
+        let response = Self::execute(request).await?;
+
+        Self::handle_content_type(response).await
+    }
+
     /// Sends a GET request with basic authentication and returns the response as a `Value`.
     ///
     /// # Arguments
     /// - `url`: A reference to the URL for the GET request.
     /// - `username`: The username for basic authentication.
     /// - `password`: An optional password for basic authentication.
+    /// - `tls`: The TLS configuration of the device.
     ///
     /// # Returns
     /// A `Result` containing the deserialized JSON response as a `Value`, or an `Error`.
     ///
     /// # Errors
-    /// - Returns an error if the request fails or the response cannot be parsed as JSON.
+    /// - Returns an error if the request fails after the retries or the response cannot be parsed as JSON.
     pub async fn basic_request(
         url: &String,
         username: String,
         password: Option<String>,
+        tls: &TlsConfig,
     ) -> Result<Value, AppError> {
-        let response = Self::client_get_builder(url)
-            .basic_auth(username, password)
-            .send()
-            .await
-            .map_err(|err| AppError::request_error(err.to_string()))?
-            .error_for_status()
-            .map_err(|err| AppError::request_error(err.to_string()))?;
+        let response =
+            Self::execute(Self::client_get_builder(url, tls)?.basic_auth(username, password))
+                .await?;
 
         Self::handle_content_type(response).await
     }
@@ -219,21 +619,182 @@ impl HttpHandler {
     /// # Arguments
     /// - `url`: A reference to the URL for the GET request.
     /// - `token`: The bearer token for authentication.
+    /// - `tls`: The TLS configuration of the device.
     ///
     /// # Returns
     /// A `Result` containing the deserialized JSON response as a `Value`, or an `Error`.
     ///
     /// # Errors
-    /// - Returns an error if the request fails or the response cannot be parsed as JSON.
-    pub async fn token_request(url: &String, token: &str) -> Result<Value, AppError> {
-        let response = Self::client_get_builder(url)
-            .bearer_auth(token)
-            .send()
-            .await
-            .map_err(|err| AppError::request_error(err.to_string()))?
-            .error_for_status()
-            .map_err(|err| AppError::request_error(err.to_string()))?;
+    /// - Returns an error if the request fails after the retries or the response cannot be parsed as JSON.
+    pub async fn token_request(
+        url: &String,
+        token: &str,
+        tls: &TlsConfig,
+    ) -> Result<Value, AppError> {
+        let response =
+            Self::execute(Self::client_get_builder(url, tls)?.bearer_auth(token)).await?;
 
         Self::handle_content_type(response).await
     }
+
+    /// Sends a GET request with basic authentication and returns the response whatever its status.
+    ///
+    /// # Arguments
+    /// - `url`: A reference to the URL for the GET request.
+    /// - `username`: The username for basic authentication.
+    /// - `password`: An optional password for basic authentication.
+    /// - `tls`: The TLS configuration of the device.
+    ///
+    /// # Returns
+    /// A `Result` containing the `ProbeResponse`, or an `Error` if the device did not answer.
+    pub async fn basic_probe(
+        url: &String,
+        username: String,
+        password: Option<String>,
+        tls: &TlsConfig,
+    ) -> Result<ProbeResponse, AppError> {
+        Self::probe(Self::client_get_builder(url, tls)?.basic_auth(username, password)).await
+    }
+
+    /// Sends a GET request with bearer token authentication and returns the response whatever its
+    /// status.
+    ///
+    /// # Arguments
+    /// - `url`: A reference to the URL for the GET request.
+    /// - `token`: The bearer token for authentication.
+    /// - `tls`: The TLS configuration of the device.
+    ///
+    /// # Returns
+    /// A `Result` containing the `ProbeResponse`, or an `Error` if the device did not answer.
+    pub async fn token_probe(
+        url: &String,
+        token: &str,
+        tls: &TlsConfig,
+    ) -> Result<ProbeResponse, AppError> {
+        Self::probe(Self::client_get_builder(url, tls)?.bearer_auth(token)).await
+    }
+
+    /// Sends a POST, PUT, PATCH or DELETE request with basic authentication.
+    ///
+    /// # Arguments
+    /// - `method`: The method of the request.
+    /// - `url`: A reference to the URL of the data resource.
+    /// - `username`: The username for basic authentication.
+    /// - `password`: An optional password for basic authentication.
+    /// - `body`: The YANG JSON body, if any.
+    /// - `tls`: The TLS configuration of the device.
+    ///
+    /// # Returns
+    /// A `Result` containing the body of the response, `Value::Null` if it has none, or an `Error`.
+    pub async fn basic_edit(
+        method: Method,
+        url: &String,
+        username: String,
+        password: Option<String>,
+        body: Option<&Value>,
+        tls: &TlsConfig,
+    ) -> Result<Value, AppError> {
+        Self::edit(
+            Self::client(tls)?
+                .request(method, url)
+                .basic_auth(username, password),
+            body,
+        )
+        .await
+    }
+
+    /// Sends a POST, PUT, PATCH or DELETE request with bearer token authentication.
+    ///
+    /// # Arguments
+    /// - `method`: The method of the request.
+    /// - `url`: A reference to the URL of the data resource.
+    /// - `token`: The bearer token for authentication.
+    /// - `body`: The YANG JSON body, if any.
+    /// - `tls`: The TLS configuration of the device.
+    ///
+    /// # Returns
+    /// A `Result` containing the body of the response, `Value::Null` if it has none, or an `Error`.
+    pub async fn token_edit(
+        method: Method,
+        url: &String,
+        token: &str,
+        body: Option<&Value>,
+        tls: &TlsConfig,
+    ) -> Result<Value, AppError> {
+        Self::edit(
+            Self::client(tls)?.request(method, url).bearer_auth(token),
+            body,
+        )
+        .await
+    }
+
+    /// Opens a server-sent events stream with basic authentication.
+    ///
+    /// # Arguments
+    /// - `url`: A reference to the URL of the stream.
+    /// - `username`: The username for basic authentication.
+    /// - `password`: An optional password for basic authentication.
+    /// - `tls`: The TLS configuration of the device.
+    ///
+    /// # Returns
+    /// A `Result` containing the open `Response`, or an `Error` if the device refused the stream.
+    pub async fn basic_event_stream(
+        url: &String,
+        username: String,
+        password: Option<String>,
+        tls: &TlsConfig,
+    ) -> Result<Response, AppError> {
+        Self::event_stream(Self::client(tls)?.get(url).basic_auth(username, password)).await
+    }
+
+    /// Opens a server-sent events stream with bearer token authentication.
+    ///
+    /// # Arguments
+    /// - `url`: A reference to the URL of the stream.
+    /// - `token`: The bearer token for authentication.
+    /// - `tls`: The TLS configuration of the device.
+    ///
+    /// # Returns
+    /// A `Result` containing the open `Response`, or an `Error` if the device refused the stream.
+    pub async fn token_event_stream(
+        url: &String,
+        token: &str,
+        tls: &TlsConfig,
+    ) -> Result<Response, AppError> {
+        Self::event_stream(Self::client(tls)?.get(url).bearer_auth(token)).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RateLimiter;
+    use std::time::{Duration, Instant};
+
+    #[tokio::test]
+    async fn test_rate_limiter_spaces_requests_per_device() {
+        let rate_limiter = RateLimiter::new(20);
+        let start = Instant::now();
+
+        for _ in 0..5 {
+            rate_limiter.acquire("10.95.87.21:18010").await;
+        }
+        // 5 requests at 20 per second need at least 4 intervals of 50ms
+        assert!(start.elapsed() >= Duration::from_millis(200));
+
+        // Other devices are not delayed by the first one
+        let start = Instant::now();
+        rate_limiter.acquire("10.95.86.185:443").await;
+        assert!(start.elapsed() < Duration::from_millis(50));
+    }
+
+    #[tokio::test]
+    async fn test_rate_limiter_disabled() {
+        let rate_limiter = RateLimiter::new(0);
+        let start = Instant::now();
+
+        for _ in 0..100 {
+            rate_limiter.acquire("10.95.87.21:18010").await;
+        }
+        assert!(start.elapsed() < Duration::from_millis(50));
+    }
 }
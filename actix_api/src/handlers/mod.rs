@@ -1,3 +1,13 @@
+pub mod cipher;
 pub mod database;
+pub mod health;
 pub mod http;
+pub mod notifications;
+pub mod path_computation;
+pub mod prober;
+pub mod provisioning;
+pub mod repository;
 pub mod requester;
+pub mod snapshot;
+pub mod token;
+pub mod vendor;
@@ -0,0 +1,262 @@
+use crate::handlers::requester::{DataSource, DeviceHandler};
+use crate::handlers::snapshot::SnapshotCache;
+use crate::models::devices::Device;
+use crate::models::notification::{TapiNotification, TopologyEvent};
+use crate::AppError;
+
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{broadcast, Mutex};
+use tokio::task::JoinHandle;
+use tokio::time;
+
+/// Events kept for each device; browsers falling further behind skip the oldest ones.
+const CHANNEL_CAPACITY: usize = 64;
+
+/// Time waited before opening again a stream that failed or was closed by the device.
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+/// Time between two checks that a device is still followed while its stream is silent.
+const IDLE_CHECK: Duration = Duration::from_secs(30);
+
+/// Notification stream of a device, read while a browser follows it.
+#[derive(Debug)]
+struct Subscription {
+    /// Device the stream was opened for, to open it again when the device is replaced.
+    device: Device,
+    sender: broadcast::Sender<TopologyEvent>,
+    task: JoinHandle<()>,
+}
+
+/// Follows the notification streams of the devices shown in the browsers.
+///
+/// Each notification is applied to the cached `TopologySnapshot` of its device and then sent to
+/// every browser following the device. A stream is opened with the first follower and closed, with
+/// its subscription, once the last one leaves.
+#[derive(Clone, Debug)]
+pub struct NotificationHub {
+    cache: SnapshotCache,
+    subscriptions: Arc<Mutex<HashMap<String, Subscription>>>,
+}
+
+impl NotificationHub {
+    /// Creates a hub applying the notifications to the snapshots of `cache`.
+    pub fn new(cache: SnapshotCache) -> Self {
+        Self {
+            cache,
+            subscriptions: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Follows the notifications of a device, opening its stream if nobody follows it yet.
+    ///
+    /// # Returns
+    /// A receiver of the `TopologyEvent` of the device, closed when the device is unsubscribed.
+    pub async fn subscribe(&self, device: &Device) -> broadcast::Receiver<TopologyEvent> {
+        let mut subscriptions = self.subscriptions.lock().await;
+        if let Some(subscription) = subscriptions.get(&device.ip) {
+            if !subscription.task.is_finished() && subscription.device == *device {
+                return subscription.sender.subscribe();
+            }
+            subscription.task.abort();
+        }
+
+        let (sender, receiver) = broadcast::channel(CHANNEL_CAPACITY);
+        let task = tokio::spawn(Self::follow(
+            device.clone(),
+            self.cache.clone(),
+            sender.clone(),
+            self.subscriptions.clone(),
+        ));
+        subscriptions.insert(
+            device.ip.clone(),
+            Subscription {
+                device: device.clone(),
+                sender,
+                task,
+            },
+        );
+        receiver
+    }
+
+    /// Whether the stream of a device is open.
+    pub async fn is_followed(&self, id: &str) -> bool {
+        self.subscriptions.lock().await.contains_key(id)
+    }
+
+    /// Closes the stream of a device, e.g. when it is deleted, ending the events of its followers.
+    pub async fn unsubscribe(&self, id: &str) {
+        if let Some(subscription) = self.subscriptions.lock().await.remove(id) {
+            subscription.task.abort();
+        }
+    }
+
+    /// Reads the stream of a device while it has followers, opening it again after
+    /// `RECONNECT_DELAY` when it fails or is closed, then removes the subscription of the device.
+    async fn follow(
+        device: Device,
+        cache: SnapshotCache,
+        sender: broadcast::Sender<TopologyEvent>,
+        subscriptions: Arc<Mutex<HashMap<String, Subscription>>>,
+    ) {
+        let data_source = DataSource::Device(device.clone());
+        loop {
+            while sender.receiver_count() > 0 {
+                match Self::read_stream(&data_source, &device, &cache, &sender).await {
+                    Ok(()) => log::info!("The notification stream of {} was closed", device.ip),
+                    Err(err) => log::warn!(
+                        "Cannot read the notification stream of {}: {}",
+                        device.ip,
+                        err
+                    ),
+                }
+                if sender.receiver_count() == 0 {
+                    break;
+                }
+                time::sleep(RECONNECT_DELAY).await;
+            }
+
+            // Followers only join under the lock, so none can join a stream being removed
+            let mut subscriptions = subscriptions.lock().await;
+            if sender.receiver_count() > 0 {
+                continue;
+            }
+            // The device may have been subscribed again with another stream meanwhile
+            if subscriptions
+                .get(&device.ip)
+                .is_some_and(|subscription| subscription.sender.same_channel(&sender))
+            {
+                subscriptions.remove(&device.ip);
+            }
+            break;
+        }
+        log::info!("{} is no longer followed", device.ip);
+    }
+
+    /// Opens the notification stream of a device and handles its events until it is closed or
+    /// nobody follows the device.
+    ///
+    /// # Returns
+    /// `Ok(())` when the stream ends, or an `Error` if it cannot be opened or read.
+    async fn read_stream(
+        data_source: &DataSource,
+        device: &Device,
+        cache: &SnapshotCache,
+        sender: &broadcast::Sender<TopologyEvent>,
+    ) -> Result<(), AppError> {
+        let token = DeviceHandler::token(device).await?;
+        let url = DeviceHandler::notification_stream_url(device, &token).await?;
+        let mut response = DeviceHandler::event_stream(device, &url, &token).await?;
+        log::info!("Following the notifications of {} on {}", device.ip, url);
+
+        let mut parser = SseParser::default();
+        loop {
+            let chunk = tokio::select! {
+                chunk = response.chunk() => {
+                    chunk.map_err(|err| AppError::request_error(err.to_string()))?
+                }
+                _ = time::sleep(IDLE_CHECK) => {
+                    if sender.receiver_count() == 0 {
+                        return Ok(());
+                    }
+                    continue;
+                }
+            };
+            let Some(chunk) = chunk else {
+                return Ok(());
+            };
+
+            for data in parser.feed(&chunk) {
+                let notification = match serde_json::from_str::<Value>(&data)
+                    .map_err(|err| AppError::validation_error(err.to_string()))
+                    .and_then(|event| TapiNotification::from_event(&event))
+                {
+                    Ok(notification) => notification,
+                    Err(err) => {
+                        log::warn!("Ignoring an event of {}: {}", device.ip, err);
+                        continue;
+                    }
+                };
+                let snapshot_timestamp = match cache.apply(data_source, &notification).await {
+                    Ok(snapshot) => snapshot.map(|snapshot| snapshot.timestamp()),
+                    Err(err) => {
+                        log::warn!("Cannot update the snapshot of {}: {}", device.ip, err);
+                        cache.invalidate(data_source.id()).await;
+                        None
+                    }
+                };
+                // Nobody may be left to receive it, which the loop checks below
+                let _ = sender.send(TopologyEvent {
+                    notification,
+                    snapshot_timestamp,
+                });
+            }
+            if sender.receiver_count() == 0 {
+                return Ok(());
+            }
+        }
+    }
+}
+
+/// Splits a server-sent events stream into the `data` of its events (HTML Living Standard, section 9.2).
+#[derive(Debug, Default)]
+struct SseParser {
+    /// Bytes received after the last complete line.
+    pending: Vec<u8>,
+    /// `data` lines of the event being received.
+    data: Vec<String>,
+}
+
+impl SseParser {
+    /// Reads a chunk of the stream.
+    ///
+    /// # Returns
+    /// The `data` of every event completed by the chunk, its lines joined with `\n`.
+    fn feed(&mut self, chunk: &[u8]) -> Vec<String> {
+        self.pending.extend_from_slice(chunk);
+        let mut events = Vec::new();
+
+        while let Some(end) = self.pending.iter().position(|byte| *byte == b'\n') {
+            let line: Vec<u8> = self.pending.drain(..=end).collect();
+            let line = String::from_utf8_lossy(&line);
+            let line = line.trim_end_matches(['\n', '\r']);
+
+            if line.is_empty() {
+                if !self.data.is_empty() {
+                    events.push(self.data.join("\n"));
+                    self.data.clear();
+                }
+            } else if let Some(value) = line.strip_prefix("data") {
+                if value.is_empty() || value.starts_with(':') {
+                    let value = value.strip_prefix(':').unwrap_or(value);
+                    self.data
+                        .push(value.strip_prefix(' ').unwrap_or(value).to_string());
+                }
+            }
+            // Comments and the `event`, `id` and `retry` fields are not used
+        }
+
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SseParser;
+
+    #[test]
+    fn test_sse_parser_joins_chunks_and_lines() {
+        let mut parser = SseParser::default();
+
+        assert!(parser.feed(b": subscribed\n\ndata: {\"a\":").is_empty());
+        assert_eq!(parser.feed(b" 1}\r\n\r\n"), vec!["{\"a\": 1}"]);
+        assert_eq!(
+            parser.feed(b"event: notification\ndata:first\ndata: second\n\ndata: third\n\n"),
+            vec!["first\nsecond", "third"]
+        );
+        // `database` is not a `data` field
+        assert!(parser.feed(b"database: x\n\n").is_empty());
+    }
+}
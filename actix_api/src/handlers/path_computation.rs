@@ -0,0 +1,99 @@
+use crate::handlers::requester::DeviceHandler;
+use crate::models::devices::Device;
+use crate::models::path_computation::{
+    context_paths, path_comp_services, ComputedPath, PathCompService, PathRequest, PathSource,
+    COMPUTE_P2P_PATH_OPERATION, PATH_COMPUTATION_CONTEXT_PATH,
+};
+use crate::AppError;
+
+use reqwest::Method;
+use serde_json::Value;
+
+/// Finds paths between service interface points with the TAPI path computation service of a device.
+pub struct PathComputer;
+
+impl PathComputer {
+    /// Asks the device for the paths joining the service interface points of a request.
+    ///
+    /// The `compute-p-2-p-path` RPC is sent first. Devices that do not implement it, or fail to
+    /// compute a path, are read instead for a `path-comp-service` already joining the service
+    /// interface points. Paths only referenced by the service are read from the path computation
+    /// context.
+    ///
+    /// # Arguments
+    /// - `request`: The path request, to find an existing `path-comp-service`.
+    /// - `input`: The RPC input built by `PathRequest::input`.
+    ///
+    /// # Returns
+    /// Where the paths come from, the `path-comp-service` and its paths in the order given by the
+    /// device, or an `Error` if the device has no path between the service interface points.
+    pub async fn paths(
+        device: &Device,
+        request: &PathRequest,
+        input: &Value,
+    ) -> Result<(PathSource, PathCompService, Vec<ComputedPath>), AppError> {
+        let token = DeviceHandler::token(device).await?;
+        let restconf_url = DeviceHandler::base_url(device, &token).await?;
+        let context_url = format!("{}{}", restconf_url, PATH_COMPUTATION_CONTEXT_PATH);
+        let operation_url = format!("{}{}", restconf_url, COMPUTE_P2P_PATH_OPERATION);
+
+        let computed =
+            match DeviceHandler::edit(device, Method::POST, &operation_url, Some(input), &token)
+                .await
+            {
+                Ok(output) => PathCompService::from_output(&output)
+                    .filter(|service| !service.path_uuids.is_empty())
+                    .ok_or_else(|| AppError::validation_error("The device computed no path")),
+                Err(err @ AppError::AuthenticationError(_)) => return Err(err),
+                Err(err) => Err(err),
+            };
+
+        let (source, service, mut context) = match computed {
+            Ok(service) => (PathSource::Computed, service, None),
+            Err(err) => {
+                log::warn!(
+                    "compute-p-2-p-path failed on {}, reading existing paths: {}",
+                    device.ip,
+                    err
+                );
+                // Without a readable context the failure of the RPC is the one to report
+                let Ok(context) = DeviceHandler::request(device, &context_url, &token).await else {
+                    return Err(err);
+                };
+                let service = path_comp_services(&context)
+                    .into_iter()
+                    .filter(|service| request.matches(service))
+                    .filter_map(PathCompService::from_value)
+                    .find(|service| !service.path_uuids.is_empty())
+                    .ok_or_else(|| {
+                        AppError::validation_error(format!(
+                            "No path between {}",
+                            request.service_interface_points.join(" and ")
+                        ))
+                    })?;
+                (PathSource::Existing, service, Some(context))
+            }
+        };
+
+        let mut paths = vec![];
+        for path_uuid in &service.path_uuids {
+            if let Some(path) = service.paths.iter().find(|path| &path.uuid == path_uuid) {
+                paths.push(path.clone());
+                continue;
+            }
+            if context.is_none() {
+                context = Some(DeviceHandler::request(device, &context_url, &token).await?);
+            }
+            let path = context
+                .as_ref()
+                .map(context_paths)
+                .unwrap_or_default()
+                .into_iter()
+                .find(|path| &path.uuid == path_uuid)
+                .ok_or_else(|| AppError::request_error(format!("Path {} Not Found", path_uuid)))?;
+            paths.push(path);
+        }
+
+        Ok((source, service, paths))
+    }
+}
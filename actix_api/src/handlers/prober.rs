@@ -0,0 +1,177 @@
+use crate::handlers::requester::DeviceHandler;
+use crate::models::devices::Device;
+use crate::models::restconf_validations::RestconfValidation;
+use crate::models::tapi_uris::TapiUri;
+use crate::AppError;
+
+use chrono::Utc;
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+
+/// Placeholder of the `tapi_uris` replaced by the UUIDs of the dependency chain.
+const UUID_PLACEHOLDER: &str = "{uuid}";
+
+/// Checks which of the `tapi_uris` a device answers, to know the retrieval strategies it supports.
+pub struct RestconfProber;
+
+impl RestconfProber {
+    /// Requests every URI to the device following their dependency chains.
+    ///
+    /// URIs without dependency are requested first. The `{uuid}` placeholders of the others are
+    /// filled with the UUIDs found by the `uuid-list` topic they depend on, which in turn used
+    /// the UUIDs of its own dependency, e.g. `node.by-parts` uses a topology and a node UUID.
+    /// Only the first entry of each list is followed, so each URI is requested once.
+    ///
+    /// # Arguments
+    /// - `device`: The device to probe.
+    /// - `uris`: The URIs to request, e.g. from `TapiUri::read_all`.
+    ///
+    /// # Returns
+    /// A `RestconfValidation` per URI in the order of `uris`, or an `Error` if no token or
+    /// RESTCONF root can be obtained. URIs whose placeholders cannot be filled are returned
+    /// without status code.
+    pub async fn probe(
+        device: &Device,
+        uris: &[TapiUri],
+    ) -> Result<Vec<RestconfValidation>, AppError> {
+        let token = DeviceHandler::token(device).await?;
+        let device_url = DeviceHandler::device_url(device);
+        let restconf_url = DeviceHandler::base_url(device, &token).await?;
+
+        // UUIDs found by each `uuid-list` topic, preceded by the ones of its dependency
+        let mut found_uuids: HashMap<&str, Vec<String>> = HashMap::new();
+        let mut results: HashMap<i32, RestconfValidation> = HashMap::new();
+        let mut pending: Vec<&TapiUri> = uris.iter().collect();
+        let mut probed: HashSet<&str> = HashSet::new();
+
+        loop {
+            let (ready, waiting): (Vec<&TapiUri>, Vec<&TapiUri>) =
+                pending.into_iter().partition(|tapi_uri| {
+                    tapi_uri
+                        .dependency
+                        .as_deref()
+                        .map(|dependency| probed.contains(dependency))
+                        .unwrap_or(true)
+                });
+            pending = waiting;
+            if ready.is_empty() {
+                break;
+            }
+
+            for tapi_uri in ready {
+                let parent_uuids = tapi_uri
+                    .dependency
+                    .as_deref()
+                    .and_then(|dependency| found_uuids.get(dependency))
+                    .cloned()
+                    .unwrap_or_default();
+                let mut validation = RestconfValidation {
+                    uri_id: tapi_uri.id,
+                    topic: tapi_uri.topic.clone(),
+                    uri: tapi_uri.uri.clone(),
+                    status_code: None,
+                    response_type: None,
+                    success: false,
+                    validation_datetime: Utc::now().naive_utc(),
+                };
+
+                if let Some(url) = resolve_url(tapi_uri, &parent_uuids, &device_url, &restconf_url)
+                {
+                    match DeviceHandler::probe(device, &url, &token).await {
+                        Ok(response) => {
+                            validation.status_code = Some(response.status.as_u16() as i32);
+                            validation.response_type = response.content_type;
+                            validation.success =
+                                response.status.is_success() && response.body.is_ok();
+                            if let (true, Ok(body)) = (validation.success, &response.body) {
+                                if let Some(uuid) = first_list_key(&tapi_uri.uri, body) {
+                                    let mut uuids = parent_uuids;
+                                    uuids.push(uuid);
+                                    found_uuids.insert(&tapi_uri.topic, uuids);
+                                }
+                            }
+                        }
+                        Err(err) => log::warn!("Probing {} failed: {}", url, err),
+                    }
+                }
+
+                probed.insert(&tapi_uri.topic);
+                results.insert(tapi_uri.id, validation);
+            }
+        }
+
+        // URIs depending on a topic that is not probed are never requested
+        Ok(uris
+            .iter()
+            .map(|tapi_uri| {
+                results
+                    .remove(&tapi_uri.id)
+                    .unwrap_or_else(|| RestconfValidation {
+                        uri_id: tapi_uri.id,
+                        topic: tapi_uri.topic.clone(),
+                        uri: tapi_uri.uri.clone(),
+                        status_code: None,
+                        response_type: None,
+                        success: false,
+                        validation_datetime: Utc::now().naive_utc(),
+                    })
+            })
+            .collect())
+    }
+}
+
+/// Builds the URL of a URI, filling its placeholders with the UUIDs of its dependency chain.
+///
+/// Data resources such as `/tapi-common:context` are requested under `{restconf_url}/data`, and
+/// any other URI, e.g. `/.well-known/host-meta`, from the root of the device.
+///
+/// # Returns
+/// The URL, or `None` if there are fewer UUIDs than placeholders.
+fn resolve_url(
+    tapi_uri: &TapiUri,
+    uuids: &[String],
+    device_url: &str,
+    restconf_url: &str,
+) -> Option<String> {
+    let mut uri = tapi_uri.uri.clone();
+    let mut uuids = uuids.iter();
+    while uri.contains(UUID_PLACEHOLDER) {
+        uri = uri.replacen(UUID_PLACEHOLDER, uuids.next()?, 1);
+    }
+
+    let is_data_resource = uri.starts_with("/tapi-") || uri.starts_with("/ietf-");
+    Some(if is_data_resource {
+        format!("{}/data{}", restconf_url, uri)
+    } else {
+        format!("{}{}", device_url, uri)
+    })
+}
+
+/// Key of the first entry of the list selected by a `fields` URI, e.g. the `uuid` of the first
+/// node for `?fields=node(uuid)` or the `connection-uuid` of the first connection for
+/// `?fields=connection(connection-uuid)`.
+fn first_list_key(uri: &str, body: &Value) -> Option<String> {
+    let (_, fields) = uri.split_once("fields=")?;
+    let (list, key) = fields.split_once('(')?;
+    let key = key.split([')', ';']).next()?;
+
+    find_list(list, body)?
+        .iter()
+        .find_map(|entry| entry.get(key).and_then(Value::as_str))
+        .map(str::to_string)
+}
+
+/// Finds a non-empty list by name, with or without module prefix, anywhere in a value.
+fn find_list<'a>(list: &str, value: &'a Value) -> Option<&'a Vec<Value>> {
+    match value {
+        Value::Object(object) => object.iter().find_map(|(key, child)| {
+            let local_name = key.rsplit(':').next().unwrap_or(key);
+            match child {
+                Value::Array(entries) if local_name == list && !entries.is_empty() => Some(entries),
+                _ => find_list(list, child),
+            }
+        }),
+        Value::Array(entries) => entries.iter().find_map(|entry| find_list(list, entry)),
+        _ => None,
+    }
+}
@@ -0,0 +1,147 @@
+use crate::handlers::requester::DeviceHandler;
+use crate::models::devices::Device;
+use crate::models::provisioning::{
+    service_path, ProvisioningResult, ServiceState, CONNECTIVITY_CONTEXT_PATH,
+};
+use crate::AppError;
+use tapi_model::Uuid;
+
+use reqwest::{Method, StatusCode};
+use serde_json::Value;
+use std::env;
+use std::time::Duration;
+use tokio::time::{self, Instant};
+
+/// Time waited for a service to be installed or removed when `PROVISIONING_TIMEOUT_SECS` is not set.
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Time between two reads of the state of a service while waiting for it.
+const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Creates and deletes connectivity services on devices, waiting for the device to apply them.
+#[derive(Clone, Debug)]
+pub struct Provisioner {
+    poll_interval: Duration,
+    timeout: Duration,
+}
+
+impl Provisioner {
+    /// Creates a provisioner reading the state of the services every `poll_interval`, for at most
+    /// `timeout`.
+    pub fn new(poll_interval: Duration, timeout: Duration) -> Self {
+        Self {
+            poll_interval,
+            timeout,
+        }
+    }
+
+    /// Creates a provisioner waiting `PROVISIONING_TIMEOUT_SECS` seconds, 60 by default, for each
+    /// service.
+    pub fn from_env() -> Self {
+        let timeout = env::var("PROVISIONING_TIMEOUT_SECS")
+            .ok()
+            .and_then(|value| value.parse().ok())
+            .map(Duration::from_secs)
+            .unwrap_or(DEFAULT_TIMEOUT);
+        Self::new(DEFAULT_POLL_INTERVAL, timeout)
+    }
+
+    /// POSTs a connectivity service to the device and polls it until it is installed.
+    ///
+    /// # Arguments
+    /// - `uuid`: UUID of the service, as set in `payload`.
+    /// - `payload`: The body built by `ServiceRequest::payload`.
+    ///
+    /// # Returns
+    /// A `ProvisioningResult` with the last state read, `completed` once the service is installed,
+    /// or an `Error` if the device rejects the service.
+    pub async fn create(
+        &self,
+        device: &Device,
+        uuid: Uuid,
+        payload: Value,
+    ) -> Result<ProvisioningResult, AppError> {
+        let token = DeviceHandler::token(device).await?;
+        let restconf_url = DeviceHandler::base_url(device, &token).await?;
+        let url = format!("{}{}", restconf_url, CONNECTIVITY_CONTEXT_PATH);
+        DeviceHandler::edit(device, Method::POST, &url, Some(&payload), &token).await?;
+        log::info!("Service {} requested on {}", uuid, device.ip);
+
+        let service_url = format!("{}{}", restconf_url, service_path(uuid));
+        let deadline = Instant::now() + self.timeout;
+        let mut state = None;
+        loop {
+            let response = DeviceHandler::probe(device, &service_url, &token).await?;
+            if let (true, Ok(body)) = (response.status.is_success(), &response.body) {
+                let current = ServiceState::from_response(body);
+                let is_installed = current.is_installed();
+                state = Some(current);
+                if is_installed {
+                    break;
+                }
+            }
+            if Instant::now() + self.poll_interval > deadline {
+                log::warn!("Service {} is not installed on {} yet", uuid, device.ip);
+                break;
+            }
+            time::sleep(self.poll_interval).await;
+        }
+
+        Ok(ProvisioningResult {
+            uuid,
+            dry_run: false,
+            method: Method::POST.to_string(),
+            path: CONNECTIVITY_CONTEXT_PATH.to_string(),
+            completed: state.as_ref().is_some_and(ServiceState::is_installed),
+            payload: Some(payload),
+            state,
+        })
+    }
+
+    /// DELETEs a connectivity service from the device and polls it until it is no longer found.
+    ///
+    /// # Returns
+    /// A `ProvisioningResult` with the last state read while the service remained, `completed`
+    /// once the device answers `404`, or an `Error` if the device rejects the deletion.
+    pub async fn delete(
+        &self,
+        device: &Device,
+        uuid: Uuid,
+    ) -> Result<ProvisioningResult, AppError> {
+        let token = DeviceHandler::token(device).await?;
+        let restconf_url = DeviceHandler::base_url(device, &token).await?;
+        let service_url = format!("{}{}", restconf_url, service_path(uuid));
+        DeviceHandler::edit(device, Method::DELETE, &service_url, None, &token).await?;
+        log::info!("Service {} deleted on {}", uuid, device.ip);
+
+        let deadline = Instant::now() + self.timeout;
+        let mut state = None;
+        let mut completed = false;
+        loop {
+            let response = DeviceHandler::probe(device, &service_url, &token).await?;
+            if response.status == StatusCode::NOT_FOUND {
+                completed = true;
+                state = None;
+                break;
+            }
+            if let Ok(body) = &response.body {
+                state = Some(ServiceState::from_response(body));
+            }
+            if Instant::now() + self.poll_interval > deadline {
+                log::warn!("Service {} is still on {}", uuid, device.ip);
+                break;
+            }
+            time::sleep(self.poll_interval).await;
+        }
+
+        Ok(ProvisioningResult {
+            uuid,
+            dry_run: false,
+            method: Method::DELETE.to_string(),
+            path: service_path(uuid),
+            payload: None,
+            state,
+            completed,
+        })
+    }
+}
@@ -0,0 +1,394 @@
+use crate::handlers::database::DatabaseHandler;
+use crate::handlers::requester::DataSource;
+use crate::logic::context_index::ContextIndex;
+use crate::models::device_health::DeviceHealth;
+use crate::models::devices::{Device, RetrievalStrategy};
+use crate::models::files_model::FilesEnum;
+use crate::models::map_positions::MapPositions;
+use crate::models::restconf_validations::RestconfValidation;
+use crate::models::tapi_uris::TapiUri;
+use crate::AppError;
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+/// Health checks kept per device, a day of checks at the default interval.
+const HEALTH_CHECKS: usize = 1440;
+
+/// Storage used by the repository.
+#[derive(Clone, Debug)]
+enum Backend {
+    /// Devices and file sets are persisted in Postgres.
+    Database(DatabaseHandler),
+    /// Everything is kept in memory and lost on restart. Used when no database is configured.
+    Memory {
+        data_sources: Arc<Mutex<HashMap<String, DataSource>>>,
+        /// Last probe results of each device, by ip.
+        validations: Arc<Mutex<HashMap<String, Vec<RestconfValidation>>>>,
+        /// Last health checks of each device by ip, the most recent first.
+        health: Arc<Mutex<HashMap<String, VecDeque<DeviceHealth>>>>,
+        /// Positions of the nodes moved on the network map of each host, by id.
+        positions: Arc<Mutex<HashMap<String, MapPositions>>>,
+    },
+}
+
+/// Keeps track of every registered `DataSource`, devices by ip and file sets by id.
+#[derive(Clone, Debug)]
+pub struct DataSourceRepository {
+    backend: Backend,
+}
+
+impl DataSourceRepository {
+    /// Creates a repository persisted through the given `DatabaseHandler`.
+    pub fn new(database_handler: DatabaseHandler) -> Self {
+        Self {
+            backend: Backend::Database(database_handler),
+        }
+    }
+
+    /// Creates a repository that only lives in memory.
+    pub fn in_memory() -> Self {
+        Self {
+            backend: Backend::Memory {
+                data_sources: Arc::new(Mutex::new(HashMap::new())),
+                validations: Arc::new(Mutex::new(HashMap::new())),
+                health: Arc::new(Mutex::new(HashMap::new())),
+                positions: Arc::new(Mutex::new(HashMap::new())),
+            },
+        }
+    }
+
+    /// Stores a data source, replacing the one registered with the same id.
+    ///
+    /// When a file set replaces another one, the files of the old set that are not reused are removed from disk.
+    ///
+    /// # Returns
+    /// The id the data source was stored with.
+    pub async fn insert(&self, data_source: DataSource) -> Result<String, AppError> {
+        let previous = self.get(data_source.id()).await?;
+
+        let id = match &self.backend {
+            Backend::Database(database_handler) => match &data_source {
+                DataSource::Device(device) => device.create_device(database_handler).await?,
+                DataSource::FilesEnum(files_enum) => {
+                    files_enum.create_files(database_handler).await?
+                }
+            },
+            Backend::Memory { data_sources, .. } => {
+                let id = data_source.id().to_string();
+                data_sources
+                    .lock()
+                    .await
+                    .insert(id.clone(), data_source.clone());
+                id
+            }
+        };
+
+        if let Some(DataSource::FilesEnum(previous_files)) = previous {
+            let kept: HashSet<&str> = match &data_source {
+                DataSource::FilesEnum(files_enum) => files_enum.paths().into_iter().collect(),
+                DataSource::Device(_) => HashSet::new(),
+            };
+            for path in previous_files.paths() {
+                if !kept.contains(path) && Path::new(path).exists() {
+                    fs::remove_file(path).map_err(|err| AppError::io_error(err.to_string()))?;
+                }
+            }
+        }
+
+        Ok(id)
+    }
+
+    /// Retrieves the data source registered with the given id.
+    pub async fn get(&self, id: &str) -> Result<Option<DataSource>, AppError> {
+        match &self.backend {
+            Backend::Database(database_handler) => {
+                if let Some(device) = Device::read_optional_by_ip(database_handler, id).await? {
+                    return Ok(Some(DataSource::Device(device)));
+                }
+                Ok(FilesEnum::read_optional_by_id(database_handler, id)
+                    .await?
+                    .map(DataSource::FilesEnum))
+            }
+            Backend::Memory { data_sources, .. } => Ok(data_sources.lock().await.get(id).cloned()),
+        }
+    }
+
+    /// Lists every registered data source, devices first.
+    pub async fn list(&self) -> Result<Vec<DataSource>, AppError> {
+        match &self.backend {
+            Backend::Database(database_handler) => {
+                let mut data_sources: Vec<DataSource> = Device::read_all(database_handler)
+                    .await?
+                    .into_iter()
+                    .map(DataSource::Device)
+                    .collect();
+                data_sources.extend(
+                    FilesEnum::read_all(database_handler)
+                        .await?
+                        .into_iter()
+                        .map(DataSource::FilesEnum),
+                );
+                Ok(data_sources)
+            }
+            Backend::Memory { data_sources, .. } => {
+                Ok(data_sources.lock().await.values().cloned().collect())
+            }
+        }
+    }
+
+    /// Removes the data source registered with the given id, deleting its files from disk, the
+    /// positions of its network map and the probe results and health checks of devices.
+    ///
+    /// # Returns
+    /// The removed data source, or `None` if nothing was registered with that id.
+    pub async fn remove(&self, id: &str) -> Result<Option<DataSource>, AppError> {
+        let Some(data_source) = self.get(id).await? else {
+            return Ok(None);
+        };
+
+        match &self.backend {
+            Backend::Database(database_handler) => {
+                MapPositions::delete_by_device_ip(database_handler, id).await?;
+                match &data_source {
+                    DataSource::Device(device) => {
+                        RestconfValidation::delete_by_device_ip(database_handler, &device.ip)
+                            .await?;
+                        DeviceHealth::delete_by_device_ip(database_handler, &device.ip).await?;
+                        Device::delete_device(database_handler, &device.ip).await?;
+                    }
+                    DataSource::FilesEnum(files_enum) => {
+                        FilesEnum::delete_files(database_handler, files_enum.id()).await?;
+                    }
+                }
+            }
+            Backend::Memory {
+                data_sources,
+                validations,
+                health,
+                positions,
+            } => {
+                data_sources.lock().await.remove(id);
+                validations.lock().await.remove(id);
+                health.lock().await.remove(id);
+                positions.lock().await.remove(id);
+            }
+        }
+
+        if let DataSource::FilesEnum(files_enum) = &data_source {
+            files_enum.remove_files()?;
+        }
+
+        Ok(Some(data_source))
+    }
+
+    /// Remembers the strategy a device is read with, when it is a device and the strategy changed.
+    pub async fn remember_strategy(
+        &self,
+        data_source: &DataSource,
+        strategy: Option<RetrievalStrategy>,
+    ) -> Result<(), AppError> {
+        let (DataSource::Device(device), Some(strategy)) = (data_source, strategy) else {
+            return Ok(());
+        };
+        if device.retrieval_strategy == Some(strategy) {
+            return Ok(());
+        }
+
+        match &self.backend {
+            Backend::Database(database_handler) => {
+                Device::update_retrieval_strategy(database_handler, &device.ip, strategy).await?;
+            }
+            Backend::Memory { data_sources, .. } => {
+                if let Some(DataSource::Device(stored)) =
+                    data_sources.lock().await.get_mut(&device.ip)
+                {
+                    stored.retrieval_strategy = Some(strategy);
+                }
+            }
+        }
+        log::info!("{} is read with the {} strategy", device.ip, strategy);
+        Ok(())
+    }
+
+    /// Lists the TAPI RESTCONF URIs a device can be probed with.
+    ///
+    /// Without a database the URIs seeded by the `tapi_uris` migration are used.
+    pub async fn tapi_uris(&self) -> Result<Vec<TapiUri>, AppError> {
+        match &self.backend {
+            Backend::Database(database_handler) => TapiUri::read_all(database_handler).await,
+            Backend::Memory { .. } => Ok(TapiUri::seeded()),
+        }
+    }
+
+    /// Stores the results of probing a device, keeping the previous ones in the database.
+    pub async fn insert_validations(
+        &self,
+        device_ip: &str,
+        results: &[RestconfValidation],
+    ) -> Result<(), AppError> {
+        match &self.backend {
+            Backend::Database(database_handler) => {
+                for result in results {
+                    result
+                        .create_validation(database_handler, device_ip)
+                        .await?;
+                }
+            }
+            Backend::Memory { validations, .. } => {
+                let mut validations = validations.lock().await;
+                let stored = validations.entry(device_ip.to_string()).or_default();
+                for result in results {
+                    stored.retain(|validation| validation.uri_id != result.uri_id);
+                    stored.push(result.clone());
+                }
+                stored.sort_by_key(|validation| validation.uri_id);
+            }
+        }
+        Ok(())
+    }
+
+    /// Retrieves the last probe result of each URI for the device with the given ip.
+    pub async fn validations(&self, device_ip: &str) -> Result<Vec<RestconfValidation>, AppError> {
+        match &self.backend {
+            Backend::Database(database_handler) => {
+                RestconfValidation::read_latest_by_device_ip(database_handler, device_ip).await
+            }
+            Backend::Memory { validations, .. } => Ok(validations
+                .lock()
+                .await
+                .get(device_ip)
+                .cloned()
+                .unwrap_or_default()),
+        }
+    }
+
+    /// Stores the result of a health check of a device, keeping the last `HEALTH_CHECKS` ones.
+    pub async fn insert_health(
+        &self,
+        device_ip: &str,
+        result: &DeviceHealth,
+    ) -> Result<(), AppError> {
+        match &self.backend {
+            Backend::Database(database_handler) => {
+                result.create_health(database_handler, device_ip).await?;
+                DeviceHealth::delete_older_by_device_ip(
+                    database_handler,
+                    device_ip,
+                    HEALTH_CHECKS as i32,
+                )
+                .await?;
+            }
+            Backend::Memory { health, .. } => {
+                let mut health = health.lock().await;
+                let stored = health.entry(device_ip.to_string()).or_default();
+                stored.push_front(result.clone());
+                stored.truncate(HEALTH_CHECKS);
+            }
+        }
+        Ok(())
+    }
+
+    /// Retrieves the last `limit` health checks of the device with the given ip, the most recent
+    /// first.
+    pub async fn health(
+        &self,
+        device_ip: &str,
+        limit: usize,
+    ) -> Result<Vec<DeviceHealth>, AppError> {
+        match &self.backend {
+            Backend::Database(database_handler) => {
+                let limit = i32::try_from(limit).unwrap_or(i32::MAX);
+                DeviceHealth::read_latest_by_device_ip(database_handler, device_ip, limit).await
+            }
+            Backend::Memory { health, .. } => Ok(health
+                .lock()
+                .await
+                .get(device_ip)
+                .map(|stored| stored.iter().take(limit).cloned().collect())
+                .unwrap_or_default()),
+        }
+    }
+
+    /// Retrieves the positions of the nodes moved on the network map of the host with the given id,
+    /// empty if none were stored.
+    pub async fn positions(&self, id: &str) -> Result<MapPositions, AppError> {
+        match &self.backend {
+            Backend::Database(database_handler) => {
+                MapPositions::read_by_device_ip(database_handler, id).await
+            }
+            Backend::Memory { positions, .. } => {
+                Ok(positions.lock().await.get(id).cloned().unwrap_or_default())
+            }
+        }
+    }
+
+    /// Stores the positions of the nodes moved on the network map of the host with the given id,
+    /// replacing the previous ones.
+    pub async fn set_positions(
+        &self,
+        id: &str,
+        map_positions: &MapPositions,
+    ) -> Result<(), AppError> {
+        match &self.backend {
+            Backend::Database(database_handler) => {
+                map_positions.upsert_positions(database_handler, id).await
+            }
+            Backend::Memory { positions, .. } => {
+                positions
+                    .lock()
+                    .await
+                    .insert(id.to_string(), map_positions.clone());
+                Ok(())
+            }
+        }
+    }
+
+    /// Deletes the files in `data_dir` that do not belong to any registered file set.
+    ///
+    /// The index stored next to each registered file is kept with it. Hidden files such as
+    /// `.gitkeep` are left untouched.
+    ///
+    /// # Returns
+    /// The paths of the removed files.
+    pub async fn remove_orphaned_files(
+        &self,
+        data_dir: impl AsRef<Path>,
+    ) -> Result<Vec<PathBuf>, AppError> {
+        let referenced: HashSet<PathBuf> = self
+            .list()
+            .await?
+            .iter()
+            .filter_map(|data_source| match data_source {
+                DataSource::FilesEnum(files_enum) => Some(files_enum.paths()),
+                DataSource::Device(_) => None,
+            })
+            .flatten()
+            .flat_map(|path| [path.to_string(), ContextIndex::index_path(path)])
+            .map(|path| Path::new(&path).components().collect())
+            .collect();
+
+        let entries = match fs::read_dir(data_dir.as_ref()) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(vec![]),
+            Err(err) => return Err(AppError::io_error(err.to_string())),
+        };
+
+        let mut removed = Vec::new();
+        for entry in entries {
+            let entry = entry.map_err(|err| AppError::io_error(err.to_string()))?;
+            let path = entry.path();
+            let is_hidden = entry.file_name().to_string_lossy().starts_with('.');
+
+            if path.is_file() && !is_hidden && !referenced.contains(&path) {
+                fs::remove_file(&path).map_err(|err| AppError::io_error(err.to_string()))?;
+                removed.push(path);
+            }
+        }
+
+        Ok(removed)
+    }
+}
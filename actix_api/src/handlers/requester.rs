@@ -1,15 +1,26 @@
-use crate::handlers::http::HttpHandler;
-use crate::models::devices::{Auth, Device};
+use crate::handlers::http::{HttpConfig, HttpHandler, ProbeResponse};
+use crate::handlers::token::TokenCache;
+use crate::logic::context_index::{read_file, read_span, ContextIndex, IndexKind};
+use crate::models::devices::{Auth, Device, RetrievalStrategy};
 use crate::models::files_model::FilesEnum;
-use crate::utils::find_key_recursively;
+use crate::models::notification::NOTIFICATION_CONTEXT_PATH;
+use crate::utils::{collect_modules, find_key_recursively};
 use crate::AppError;
 
+use futures_util::stream::{self, StreamExt, TryStreamExt};
+use reqwest::{Method, StatusCode};
 use serde::{Deserialize, Serialize};
-use serde_json::{Map, Value};
-use std::fs::File;
-use std::io::BufReader;
+use serde_json::{json, Value};
+use std::future::Future;
+use std::time::Instant;
+use tapi_model::Uuid;
+
+/// Data nodes of each topology read by the `FieldsFiltered` strategy.
+const TOPOLOGY_FIELDS: &str = "topology(uuid;name;layer-protocol-name;node;link)";
 
 /// Enum representing the source of data, either from a device or from a set of files.
+// Only a handful of data sources are held at once, so `Device` is not boxed.
+#[allow(clippy::large_enum_variant)]
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum DataSource {
@@ -17,6 +28,24 @@ pub enum DataSource {
     FilesEnum(FilesEnum),
 }
 
+impl DataSource {
+    /// Identifier of the data source: the ip for devices and the upload id for files.
+    pub fn id(&self) -> &str {
+        match self {
+            DataSource::Device(device) => &device.ip,
+            DataSource::FilesEnum(files_enum) => files_enum.id(),
+        }
+    }
+
+    /// Name of the `VendorProfile` chosen for the data source, if any.
+    pub fn vendor(&self) -> Option<&str> {
+        match self {
+            DataSource::Device(device) => device.vendor.as_deref(),
+            DataSource::FilesEnum(_) => None,
+        }
+    }
+}
+
 /// Struct representing the service context, including connectivity services, connections, and topology.
 pub struct Context {
     pub connectivity_service: Value,
@@ -24,6 +53,31 @@ pub struct Context {
     pub topology: Value,
 }
 
+/// Struct representing the complete context of a data source: every connectivity service, every
+/// connection and the topologies, independent of any service.
+#[derive(Clone, Debug)]
+pub struct FullContext {
+    pub connectivity_services: Vec<Value>,
+    pub connections: Vec<Value>,
+    pub topology: Value,
+    /// YANG modules announced by the device in `ietf-yang-library`, empty for files.
+    pub yang_modules: Vec<String>,
+    /// Strategy the context of the device was read with, `None` for files.
+    pub strategy: Option<RetrievalStrategy>,
+}
+
+impl FullContext {
+    /// YANG modules of the data source: the announced ones followed by the ones used in the data.
+    pub fn modules(&self) -> Vec<String> {
+        let mut modules = self.yang_modules.clone();
+        collect_modules(&self.topology, &mut modules);
+        for item in self.connections.iter().chain(&self.connectivity_services) {
+            collect_modules(item, &mut modules);
+        }
+        modules
+    }
+}
+
 /// Handles operations related to retrieving services and service contexts from various data sources.
 pub struct Requester;
 
@@ -34,11 +88,18 @@ impl Requester {
     /// - `data_source`: The data source, which could be a `Device` or a `FilesEnum`.
     ///
     /// # Returns
-    /// A `Result` containing a vector of `Value` objects representing the services, or an `Error`.
-    pub async fn get_services(data_source: &DataSource) -> Result<Vec<Value>, AppError> {
+    /// A `Result` containing a vector of `Value` objects representing the services, with the
+    /// strategy a device was read with, or an `Error`.
+    pub async fn get_services(
+        data_source: &DataSource,
+    ) -> Result<(Vec<Value>, Option<RetrievalStrategy>), AppError> {
         match data_source {
-            DataSource::Device(device) => DeviceHandler::get_services(device).await,
-            DataSource::FilesEnum(file_enum) => FilesHandler::get_services(file_enum).await,
+            DataSource::Device(device) => DeviceHandler::get_services(device)
+                .await
+                .map(|(services, strategy)| (services, Some(strategy))),
+            DataSource::FilesEnum(file_enum) => FilesHandler::get_services(file_enum)
+                .await
+                .map(|services| (services, None)),
         }
     }
 
@@ -52,7 +113,7 @@ impl Requester {
     /// A `Result` containing a `Context` object or an `Error`.
     pub async fn get_service_context(
         data_source: &DataSource,
-        service_uuid: &String,
+        service_uuid: &str,
     ) -> Result<Context, AppError> {
         match data_source {
             DataSource::Device(device) => {
@@ -63,12 +124,48 @@ impl Requester {
             }
         }
     }
+
+    /// Retrieve the complete context of the data source, used to build topology snapshots.
+    ///
+    /// # Arguments
+    /// - `data_source`: The data source, which could be a `Device` or a `FilesEnum`.
+    ///
+    /// # Returns
+    /// A `Result` containing a `FullContext` object or an `Error`.
+    pub async fn get_full_context(data_source: &DataSource) -> Result<FullContext, AppError> {
+        match data_source {
+            DataSource::Device(device) => DeviceHandler::get_full_context(device).await,
+            DataSource::FilesEnum(file_enum) => FilesHandler::get_full_context(file_enum).await,
+        }
+    }
 }
 
 /// Handles operations related to retrieving data from files.
 pub struct FilesHandler;
 
 impl FilesHandler {
+    /// Reads a file holding a list, an empty one if the file holds any other value.
+    fn read_list(path: &str) -> Result<Vec<Value>, AppError> {
+        match read_file(path)? {
+            Value::Array(items) => Ok(items),
+            _ => Ok(Vec::new()),
+        }
+    }
+
+    /// Index of the file holding the connectivity services of a file set.
+    fn services_index(file_enum: &FilesEnum) -> Result<(&str, ContextIndex), AppError> {
+        match file_enum {
+            FilesEnum::ByPart(by_part_paths) => {
+                let path = by_part_paths.connectivity_services_path.as_str();
+                Ok((path, ContextIndex::load(path, IndexKind::Services)?))
+            }
+            FilesEnum::Complete(complete_path) => {
+                let path = complete_path.complete_context_path.as_str();
+                Ok((path, ContextIndex::load(path, IndexKind::Complete)?))
+            }
+        }
+    }
+
     /// Retrieve services from files based on the provided `FilesEnum`.
     ///
     /// # Arguments
@@ -77,42 +174,19 @@ impl FilesHandler {
     /// # Returns
     /// A `Result` containing a vector of `Value` objects representing the services, or an `Error`.
     pub async fn get_services(file_enum: &FilesEnum) -> Result<Vec<Value>, AppError> {
-        match file_enum {
-            FilesEnum::ByPart(by_part_paths) => {
-                let connectivity_services_file =
-                    File::open(&by_part_paths.connectivity_services_path)
-                        .map_err(|err| AppError::database_error(err.to_string()))?;
-                File::open(&by_part_paths.connectivity_services_path)
-                    .map_err(|err| AppError::database_error(err.to_string()))?;
-                let connectivity_services_reader = BufReader::new(connectivity_services_file);
-                let connectivity_services_value: Value =
-                    serde_json::from_reader(connectivity_services_reader)
-                        .map_err(|err| AppError::validation_error(err.to_string()))?;
-                let connectivity_services = connectivity_services_value
-                    .as_array()
-                    .cloned()
-                    .unwrap_or_default();
-
-                Ok(connectivity_services)
-            }
-            FilesEnum::Complete(complete_path) => {
-                let file = File::open(&complete_path.complete_context_path)
-                    .map_err(|err| AppError::database_error(err.to_string()))?;
-                let reader = BufReader::new(file);
-                let json_value: Value = serde_json::from_reader(reader)
-                    .map_err(|err| AppError::validation_error(err.to_string()))?;
-                let connectivity_services = json_value
-                                                            .pointer("/tapi-common:context/tapi-connectivity:connectivity-context/connectivity-service")
-                                                            .and_then(Value::as_array)
-                                                            .ok_or_else(|| AppError::database_error("Cannot find connectivity-context"))?
-                                                            .clone();
-                Ok(connectivity_services)
-            }
-        }
+        let (path, index) = Self::services_index(file_enum)?;
+        index
+            .services()?
+            .iter()
+            .map(|service| read_span(path, service.span))
+            .collect()
     }
 
     /// Retrieve the context of a specific service from files.
     ///
+    /// Only the requested service is read from the services list, located through the
+    /// `ContextIndex` of the file.
+    ///
     /// # Arguments
     /// - `file_enum`: The file representation, either split into parts or complete.
     /// - `service_uuid`: The UUID of the service to retrieve context for.
@@ -123,63 +197,76 @@ impl FilesHandler {
         file_enum: &FilesEnum,
         service_uuid: &str,
     ) -> Result<Context, AppError> {
-        match file_enum {
-            FilesEnum::ByPart(by_part_paths) => {
-                let topology_file = File::open(&by_part_paths.topology_path)
-                    .map_err(|err| AppError::database_error(err.to_string()))?;
-                let topology_reader = BufReader::new(topology_file);
-                let topology: Value = serde_json::from_reader(topology_reader)
-                    .map_err(|err| AppError::validation_error(err.to_string()))?;
+        let (path, index) = Self::services_index(file_enum)?;
+        let connectivity_service = read_span(
+            path,
+            index.service(service_uuid).ok_or_else(|| {
+                AppError::validation_error("There is not any Service with that id")
+            })?,
+        )?;
 
-                let connections_file = File::open(&by_part_paths.connections_path)
-                    .map_err(|err| AppError::database_error(err.to_string()))?;
-                let connections_reader = BufReader::new(connections_file);
-                let connections_value: Value = serde_json::from_reader(connections_reader)
-                    .map_err(|err| AppError::validation_error(err.to_string()))?;
-                let connections = connections_value.as_array().cloned().unwrap_or_default();
-
-                let connectivity_services_file =
-                    File::open(&by_part_paths.connectivity_services_path)
-                        .map_err(|err| AppError::database_error(err.to_string()))?;
-                File::open(&by_part_paths.connectivity_services_path)
-                    .map_err(|err| AppError::database_error(err.to_string()))?;
-                let connectivity_services_reader = BufReader::new(connectivity_services_file);
-                let connectivity_services_value: Value =
-                    serde_json::from_reader(connectivity_services_reader)
-                        .map_err(|err| AppError::validation_error(err.to_string()))?;
-                let connectivity_services = connectivity_services_value
-                    .as_array()
-                    .cloned()
-                    .unwrap_or_default();
+        match file_enum {
+            FilesEnum::ByPart(by_part_paths) => Ok(Context {
+                connectivity_service,
+                connections: Self::read_list(&by_part_paths.connections_path)?,
+                topology: read_file(&by_part_paths.topology_path)?,
+            }),
+            FilesEnum::Complete(_) => Ok(Context {
+                connectivity_service,
+                connections: read_span(
+                    path,
+                    index.connections.ok_or_else(|| {
+                        AppError::validation_error("Cannot find connections-context")
+                    })?,
+                )?,
+                topology: read_span(
+                    path,
+                    index.topology.ok_or_else(|| {
+                        AppError::validation_error("Cannot find topology-context")
+                    })?,
+                )?,
+            }),
+        }
+    }
 
-                let connectivity_service = connectivity_services
-                    .iter()
-                    .find(|service| {
-                        service
-                            .get("uuid")
-                            .and_then(|uuid| uuid.as_str())
-                            .map(|uuid_str| uuid_str == service_uuid)
-                            .unwrap_or(false)
-                    })
-                    .ok_or_else(|| {
-                        AppError::validation_error("There is not any Service with that id")
-                    })?
-                    .clone();
-
-                Ok(Context {
-                    connectivity_service,
-                    connections,
-                    topology,
-                })
-            }
+    /// Retrieve the complete context from files.
+    ///
+    /// # Arguments
+    /// - `file_enum`: The file representation, either split into parts or complete.
+    ///
+    /// # Returns
+    /// A `Result` containing a `FullContext` object or an `Error`.
+    pub async fn get_full_context(file_enum: &FilesEnum) -> Result<FullContext, AppError> {
+        match file_enum {
+            FilesEnum::ByPart(by_part_paths) => Ok(FullContext {
+                connectivity_services: Self::read_list(&by_part_paths.connectivity_services_path)?,
+                connections: Self::read_list(&by_part_paths.connections_path)?,
+                topology: read_file(&by_part_paths.topology_path)?,
+                yang_modules: Vec::new(),
+                strategy: None,
+            }),
             FilesEnum::Complete(complete_path) => {
-                let file = File::open(&complete_path.complete_context_path)
-                    .map_err(|err| AppError::database_error(err.to_string()))?;
-                let reader = BufReader::new(file);
-                let json_value: Value = serde_json::from_reader(reader)
-                    .map_err(|err| AppError::validation_error(err.to_string()))?;
-
-                context_by_context_json(json_value, service_uuid)
+                let path = complete_path.complete_context_path.as_str();
+                let index = ContextIndex::load(path, IndexKind::Complete)?;
+                Ok(FullContext {
+                    connectivity_services: index
+                        .services()?
+                        .iter()
+                        .map(|service| read_span(path, service.span))
+                        .collect::<Result<_, _>>()?,
+                    connections: match index.connections {
+                        Some(span) => read_span(path, span)?,
+                        None => Vec::new(),
+                    },
+                    topology: read_span(
+                        path,
+                        index.topology.ok_or_else(|| {
+                            AppError::validation_error("Cannot find topology-context")
+                        })?,
+                    )?,
+                    yang_modules: Vec::new(),
+                    strategy: None,
+                })
             }
         }
     }
@@ -189,616 +276,904 @@ impl FilesHandler {
 pub struct DeviceHandler;
 
 impl DeviceHandler {
-    /// Retrieve services from a device via API calls.
+    /// Sends an authenticated GET request to the device.
     ///
-    /// # Arguments
-    /// - `device`: A reference to the `Device` object containing connection details.
+    /// A token rejected with a `401` is renewed and the request sent once more.
     ///
-    /// # Returns
-    /// A `Result` containing a vector of `Value` objects representing the services, or an `Error`.
-    pub async fn get_services(device: &Device) -> Result<Vec<Value>, AppError> {
-        let mut token: String = String::default();
-
-        if let Auth::Token(token_auth) = &device.auth {
-            token = HttpHandler::get_token(&device.get_full_auth_url(), &token_auth.auth_body)
-                .await?;
-        }
-
-        let well_known_url = format!(
-            "https://{}{}/.well-known/host-meta/",
-            &device.ip,
-            &device.port.map(|s| format!(":{}", s)).unwrap_or_default()
-        );
-
-        let well_known_json = match &device.auth {
+    /// # Arguments
+    /// - `device`: The device, whose `Auth` decides between basic and bearer authentication.
+    /// - `url`: The URL to request.
+    /// - `token`: The token obtained with `token`, ignored for `Auth::Basic` devices.
+    pub(crate) async fn request(
+        device: &Device,
+        url: &String,
+        token: &str,
+    ) -> Result<Value, AppError> {
+        match &device.auth {
             Auth::Basic(basic_auth) => {
                 HttpHandler::basic_request(
-                    &well_known_url,
+                    url,
                     basic_auth.username.clone(),
                     Some(basic_auth.password.clone()),
+                    &device.tls,
                 )
-                .await?
+                .await
             }
-            Auth::Token(_) => HttpHandler::token_request(&well_known_url, token.as_str()).await?,
-        };
-
-        let rel_default_value = Value::String("restconf".to_string());
-        let rel_value = find_key_recursively("rel", &well_known_json)
-            .unwrap_or_else(|| rel_default_value.clone());
-        let rel = rel_value
-            .as_str()
-            .ok_or(AppError::validation_error("Invalid Rel Value"))?;
-
-        let base_url = format!(
-            "https://{}{}/{}",
-            &device.ip,
-            &device.port.map(|s| format!(":{}", s)).unwrap_or_default(),
-            rel
-        );
+            Auth::Token(_) | Auth::OAuth2(_) => {
+                match HttpHandler::token_request(url, token, &device.tls).await {
+                    Err(AppError::AuthenticationError(_)) => {
+                        let renewed = TokenCache::global().renew_rejected(device, token).await?;
+                        HttpHandler::token_request(url, &renewed, &device.tls).await
+                    }
+                    response => response,
+                }
+            }
+        }
+    }
 
-        let services_url = format!(
-            "{}/data/tapi-common:context/tapi-connectivity:connectivity-context?fields=connectivity-service(uuid)",
-            &base_url
-        );
+    /// Requests many per-UUID resources concurrently, with at most `HttpConfig::max_concurrency` in flight.
+    ///
+    /// # Arguments
+    /// - `urls`: The resources to request.
+    /// - `key`: The list holding the entries in each response, e.g. `tapi-topology:node`.
+    /// - `name`: Name of the entries used in the error messages.
+    ///
+    /// # Returns
+    /// The entries of every response in the order of `urls`, or the first `Error` found.
+    async fn fetch_all(
+        device: &Device,
+        token: &str,
+        urls: Vec<String>,
+        key: &str,
+        name: &str,
+    ) -> Result<Vec<Value>, AppError> {
+        let responses: Vec<Value> = stream::iter(urls)
+            .map(|url| async move { Self::request(device, &url, token).await })
+            .buffered(HttpConfig::global().max_concurrency)
+            .try_collect()
+            .await?;
+
+        let mut entries: Vec<Value> = vec![];
+        for response in responses {
+            let response_entries = response
+                .get(key)
+                .ok_or(AppError::validation_error(format!("{} Not Found", name)))?
+                .as_array()
+                .ok_or(AppError::validation_error(format!("Invalid {}", name)))?;
+            entries.extend(response_entries.iter().cloned());
+        }
 
-        let context_url = format!("{}/data/tapi-common:context", &base_url);
+        Ok(entries)
+    }
 
-        if let Ok(services_uuid_json) = match &device.auth {
+    /// Sends an authenticated GET request to the device and returns the response whatever its status.
+    ///
+    /// A token rejected with a `401` is renewed and the request sent once more.
+    ///
+    /// # Arguments
+    /// - `device`: The device, whose `Auth` decides between basic and bearer authentication.
+    /// - `url`: The URL to request.
+    /// - `token`: The token obtained with `token`, ignored for `Auth::Basic` devices.
+    pub(crate) async fn probe(
+        device: &Device,
+        url: &String,
+        token: &str,
+    ) -> Result<ProbeResponse, AppError> {
+        match &device.auth {
             Auth::Basic(basic_auth) => {
-                HttpHandler::basic_request(
-                    &services_url,
+                HttpHandler::basic_probe(
+                    url,
                     basic_auth.username.clone(),
                     Some(basic_auth.password.clone()),
+                    &device.tls,
                 )
                 .await
             }
-            Auth::Token(_) => HttpHandler::token_request(&services_url, token.as_str()).await,
-        } {
-            let connectivity_services = services_uuid_json
-                .pointer("/tapi-connectivity:connectivity-context/connectivity-service")
-                .ok_or(AppError::validation_error("Connectivity Context Not Found"))?
-                .as_array()
-                .ok_or(AppError::validation_error("Invalid Connectivity Service"))?
-                .clone();
-
-            let mut services_vector: Vec<Value> = vec![];
-            for service in connectivity_services {
-                let service_uuid = service
-                    .get("uuid")
-                    .ok_or(AppError::validation_error("UUID Not Found"))?
-                    .as_str()
-                    .ok_or(AppError::validation_error("Invalid UUID"))?;
-
-                let service_url = format!(
-                    "{}/data/tapi-common:context/tapi-connectivity:connectivity-context/connectivity-service={}",
-                    &base_url, service_uuid
-                );
-                let service_json = match &device.auth {
-                    Auth::Basic(basic_auth) => {
-                        HttpHandler::basic_request(
-                            &service_url,
-                            basic_auth.username.clone(),
-                            Some(basic_auth.password.clone()),
-                        )
-                        .await?
-                    }
-                    Auth::Token(_) => {
-                        HttpHandler::token_request(&service_url, token.as_str()).await?
-                    }
-                };
-                let service_data = &service_json
-                    .get("tapi-connectivity:connectivity-service")
-                    .ok_or(AppError::validation_error("Service Data Not Found"))?
-                    .as_array()
-                    .unwrap()[0];
-                services_vector.push(service_data.clone()); // Add the service data to the vector.
-            }
-            Ok(services_vector)
-        } else {
-            let json = match &device.auth {
-                Auth::Basic(basic_auth) => {
-                    HttpHandler::basic_request(
-                        &context_url,
-                        basic_auth.username.clone(),
-                        Some(basic_auth.password.clone()),
-                    )
-                    .await?
+            Auth::Token(_) | Auth::OAuth2(_) => {
+                let response = HttpHandler::token_probe(url, token, &device.tls).await?;
+                if response.status != StatusCode::UNAUTHORIZED {
+                    return Ok(response);
                 }
-                Auth::Token(_) => HttpHandler::token_request(&context_url, token.as_str()).await?,
-            };
-            let connectivity_services = json
-                .pointer("/tapi-common:context/tapi-connectivity:connectivity-context/connectivity-service")
-                .and_then(Value::as_array)
-                .ok_or_else(|| AppError::validation_error("Cannot find connectivity-context"))?
-                .clone();
-
-            Ok(connectivity_services)
+                let renewed = TokenCache::global().renew_rejected(device, token).await?;
+                HttpHandler::token_probe(url, &renewed, &device.tls).await
+            }
         }
     }
 
-    /// Retrieve the context of a specific service from a device.
+    /// Sends an authenticated request changing the datastore of the device.
     ///
-    /// # Arguments
-    /// - `device`: A reference to the `Device` object containing connection details.
-    /// - `service_uuid`: The UUID of the service to retrieve context for.
+    /// A token rejected with a `401` is renewed and the request sent once more.
     ///
-    /// # Returns
-    /// A `Result` containing a `Context` object or an `Error`.
-    pub async fn get_service_context(
+    /// # Arguments
+    /// - `device`: The device, whose `Auth` decides between basic and bearer authentication.
+    /// - `method`: The method of the request, e.g. `POST` or `DELETE`.
+    /// - `url`: The URL of the data resource.
+    /// - `body`: The YANG JSON body, if any.
+    /// - `token`: The token obtained with `token`, ignored for `Auth::Basic` devices.
+    pub(crate) async fn edit(
         device: &Device,
-        service_uuid: &String,
-    ) -> Result<Context, AppError> {
-        let mut token: String = String::default();
-
-        if let Auth::Token(token_auth) = &device.auth {
-            token = HttpHandler::get_token(&device.get_full_auth_url(), &token_auth.auth_body)
-                .await?;
-        }
-
-        let well_known_url = format!(
-            "https://{}{}/.well-known/host-meta",
-            &device.ip,
-            &device.port.map(|s| format!(":{}", s)).unwrap_or_default()
-        );
-
-        let well_known_json = match &device.auth {
+        method: Method,
+        url: &String,
+        body: Option<&Value>,
+        token: &str,
+    ) -> Result<Value, AppError> {
+        match &device.auth {
             Auth::Basic(basic_auth) => {
-                HttpHandler::basic_request(
-                    &well_known_url,
+                HttpHandler::basic_edit(
+                    method,
+                    url,
                     basic_auth.username.clone(),
                     Some(basic_auth.password.clone()),
+                    body,
+                    &device.tls,
                 )
-                .await?
+                .await
             }
-            Auth::Token(_) => HttpHandler::token_request(&well_known_url, token.as_str()).await?,
-        };
+            Auth::Token(_) | Auth::OAuth2(_) => {
+                match HttpHandler::token_edit(method.clone(), url, token, body, &device.tls).await {
+                    Err(AppError::AuthenticationError(_)) => {
+                        let renewed = TokenCache::global().renew_rejected(device, token).await?;
+                        HttpHandler::token_edit(method, url, &renewed, body, &device.tls).await
+                    }
+                    response => response,
+                }
+            }
+        }
+    }
+
+    /// Returns the cached token of token and OAuth2 devices, renewed when it expires, or an empty
+    /// one for `Auth::Basic` devices.
+    pub(crate) async fn token(device: &Device) -> Result<String, AppError> {
+        TokenCache::global().token(device).await
+    }
+
+    /// Root of the device with the scheme of its `TlsConfig`, e.g. `https://10.95.87.21:18010`.
+    pub(crate) fn device_url(device: &Device) -> String {
+        device.get_device_base_url()
+    }
+
+    /// Discovers the RESTCONF root through `/.well-known/host-meta`, defaulting to `restconf`.
+    ///
+    /// # Returns
+    /// The base URL of the RESTCONF API, e.g. `https://10.95.87.21:18010/restconf`.
+    pub(crate) async fn base_url(device: &Device, token: &str) -> Result<String, AppError> {
+        let device_url = Self::device_url(device);
+        let well_known_json =
+            Self::request(device, &format!("{}/.well-known/host-meta", device_url), token).await?;
 
-        let rel_default_value = Value::String("restconf".to_string());
         let rel_value = find_key_recursively("rel", &well_known_json)
-            .unwrap_or_else(|| rel_default_value.clone());
+            .unwrap_or_else(|| Value::String("restconf".to_string()));
         let rel = rel_value
             .as_str()
             .ok_or(AppError::validation_error("Invalid Rel Value"))?;
 
-        let base_url = format!(
-            "https://{}{}/{}",
-            &device.ip,
-            &device.port.map(|s| format!(":{}", s)).unwrap_or_default(),
-            rel
-        );
-
-        let topology_by_uuid_url = format!(
-            "{}/data/tapi-common:context/tapi-topology:topology-context?fields=topology(uuid)",
-            &base_url
-        );
-
-        let connections_url = format!(
-            "{}/data/tapi-common:context/tapi-connectivity:connectivity-context",
-            base_url
-        );
-
-        let context_url = format!("{}/data/tapi-common:context", base_url);
-
-        match async {
-            // Obtain the JSON of topologies based on the authentication method.
-            let topology_uuids_json = match &device.auth {
-                Auth::Basic(basic_auth) => HttpHandler::basic_request(&topology_by_uuid_url, basic_auth.username.clone(), Some(basic_auth.password.clone())).await?,
-                Auth::Token(_) => HttpHandler::token_request(&topology_by_uuid_url, token.as_str()).await?,
-            };
-
-            // Parse the context and the topology UUID.
-            let topologies = topology_uuids_json
-                .pointer("/tapi-topology:topology-context/topology")
-                .ok_or(AppError::validation_error("Topology Context Not Found"))?
-                .as_array()
-                .ok_or(AppError::validation_error("Invalid Topology"))?;
+        Ok(format!("{}/{}", device_url, rel))
+    }
 
-            // Verify that there is exactly one topology UUID.
-            if topologies.len() != 1 {
-                return Err(AppError::validation_error("There is more or less than one topology uuid"));
+    /// Opens an authenticated server-sent events stream of the device.
+    ///
+    /// A token rejected with a `401` is renewed and the stream opened once more.
+    ///
+    /// # Arguments
+    /// - `device`: The device, whose `Auth` decides between basic and bearer authentication.
+    /// - `url`: The URL of the stream, e.g. from `notification_stream_url`.
+    /// - `token`: The token obtained with `token`, ignored for `Auth::Basic` devices.
+    pub(crate) async fn event_stream(
+        device: &Device,
+        url: &String,
+        token: &str,
+    ) -> Result<reqwest::Response, AppError> {
+        match &device.auth {
+            Auth::Basic(basic_auth) => {
+                HttpHandler::basic_event_stream(
+                    url,
+                    basic_auth.username.clone(),
+                    Some(basic_auth.password.clone()),
+                    &device.tls,
+                )
+                .await
             }
+            Auth::Token(_) | Auth::OAuth2(_) => {
+                match HttpHandler::token_event_stream(url, token, &device.tls).await {
+                    Err(AppError::AuthenticationError(_)) => {
+                        let renewed = TokenCache::global().renew_rejected(device, token).await?;
+                        HttpHandler::token_event_stream(url, &renewed, &device.tls).await
+                    }
+                    response => response,
+                }
+            }
+        }
+    }
 
-            let topology_uuid = topologies[0]
-                        .get("uuid")
-                        .ok_or(AppError::validation_error("Topology UUID Not Found"))?
-                        .as_str()
-                        .ok_or(AppError::validation_error("Invalid str UUID"))?;
-
-            let topology_fields_url = format!(
-                "{}/data/tapi-common:context/tapi-topology:topology-context/topology={topology_uuid}?fields=uuid;name;layer-protocol-name",
-                &base_url
-            );
-
-            let topology_fields_json = match &device.auth {
-                Auth::Basic(basic_auth) => HttpHandler::basic_request(&topology_fields_url, basic_auth.username.clone(), Some(basic_auth.password.clone())).await?,
-                Auth::Token(_) => HttpHandler::token_request(&topology_fields_url, token.as_str()).await?,
-            };
-
-            let topology_fields_array = topology_fields_json
-                .pointer("/tapi-topology:topology")
-                .ok_or(AppError::validation_error("Topology Context Not Found"))?
-                .as_array()
-                .ok_or(AppError::validation_error("Invalid Topology"))?;
+    /// Finds the stream the device sends its notifications on, looking in order at:
+    /// 1. The `connection-address` of the `tapi-streaming` available streams.
+    /// 2. The `stream-address` of the notification channel of the existing `tapi-notification`
+    ///    subscriptions.
+    /// 3. The `location` of the RESTCONF streams (RFC 8040, section 9.3), preferring JSON encoding.
+    ///
+    /// Devices announcing none of them get a `tapi-notification:notif-subscription`, whose
+    /// notification channel is used.
+    ///
+    /// # Returns
+    /// The URL of the first stream found, with relative addresses resolved against the device, or
+    /// an `Error` if the device announces none and cannot be subscribed to.
+    pub(crate) async fn notification_stream_url(
+        device: &Device,
+        token: &str,
+    ) -> Result<String, AppError> {
+        let restconf_url = Self::base_url(device, token).await?;
+        let subscriptions = (
+            "tapi-common:context/tapi-notification:notification-context/notif-subscription",
+            "stream-address",
+        );
+        let sources = [
+            (
+                "tapi-common:context/tapi-streaming:stream-context/available-stream",
+                "connection-address",
+            ),
+            subscriptions,
+            (
+                "ietf-restconf-monitoring:restconf-state/streams",
+                "location",
+            ),
+        ];
+
+        if let Some(url) = Self::stream_url(device, token, &restconf_url, &sources).await? {
+            return Ok(url);
+        }
 
-            // Verify that there is exactly one topology UUID.
-            if topology_fields_array.len() != 1 {
-                return Err(AppError::validation_error("There is more or less than one topology uuid"));
-            }
+        log::info!("Subscribing to the notifications of {}", device.ip);
+        let url = format!("{}{}", restconf_url, NOTIFICATION_CONTEXT_PATH);
+        let subscription = json!({
+            "tapi-notification:notif-subscription": [{
+                "uuid": Uuid::new_v4().to_string(),
+                "name": [{ "value-name": "SUBSCRIPTION_NAME", "value": "tapi-network-schema" }],
+                "subscription-state": "ACTIVE",
+            }]
+        });
+        Self::edit(device, Method::POST, &url, Some(&subscription), token).await?;
+
+        Self::stream_url(device, token, &restconf_url, &[subscriptions])
+            .await?
+            .ok_or_else(|| {
+                AppError::validation_error(format!(
+                    "{} announces no notification stream",
+                    device.ip
+                ))
+            })
+    }
 
-            let topology_fields = topology_fields_array[0]
-                .as_object()
-                .ok_or(AppError::validation_error("Invalid Map Topology"))?;
-
-            let nodes_uuid_url = format!(
-                "{}/data/tapi-common:context/tapi-topology:topology-context/topology={topology_uuid}?fields=node(uuid)",
-                &base_url
-            );
-            // Obtain the JSON of nodes uuids based on the authentication method.
-            let node_uuids_json = match &device.auth {
-                Auth::Basic(basic_auth) => HttpHandler::basic_request(&nodes_uuid_url, basic_auth.username.clone(), Some(basic_auth.password.clone())).await?,
-                Auth::Token(_) => HttpHandler::token_request(&nodes_uuid_url, token.as_str()).await?,
-            };
-            // Parse the context and the nodes UUIDs.
-            let node_uuids = {
-                let node_array = node_uuids_json
-                    .pointer("/tapi-topology:topology")
-                    .ok_or(AppError::validation_error("Node Context Not Found"))?
-                    .as_array()
-                    .ok_or(AppError::validation_error("Node Context Not Found"))?;
-
-                if node_array.len() > 1 {
-                    log::warn!("More than one topology found, using the first one.");
+    /// Reads the given data resources of a device until one holds a stream address.
+    ///
+    /// # Arguments
+    /// - `sources`: The data resources, relative to `{restconf_url}/data`, with the member holding
+    ///   the address in each of them.
+    ///
+    /// # Returns
+    /// The first address found, resolved against the device, or `None` if there is none.
+    async fn stream_url(
+        device: &Device,
+        token: &str,
+        restconf_url: &str,
+        sources: &[(&str, &str)],
+    ) -> Result<Option<String>, AppError> {
+        for (path, address_key) in sources {
+            let url = format!("{}/data/{}", restconf_url, path);
+            let response = match Self::request(device, &url, token).await {
+                Ok(response) => response,
+                Err(err) => {
+                    log::debug!("{} has no stream in {}: {}", device.ip, path, err);
+                    continue;
                 }
-
-                node_array[0]
-                    .pointer("/node")
-                    .ok_or(AppError::validation_error("Node Context Not Found"))?
-                    .as_array()
-                    .ok_or(AppError::validation_error("Invalid Node"))?
             };
+            if let Some(address) = stream_address(&response, address_key) {
+                let device_url = reqwest::Url::parse(&Self::device_url(device))
+                    .map_err(|err| AppError::validation_error(err.to_string()))?;
+                return device_url
+                    .join(&address)
+                    .map(|url| Some(String::from(url)))
+                    .map_err(|err| AppError::validation_error(err.to_string()));
+            }
+        }
+        Ok(None)
+    }
 
-            let mut nodes: Vec<Value> = vec![];
-            //Iterate over each node_uuid
-            for node_uuid_value in node_uuids {
-                let node_uuid = node_uuid_value
+    /// Lists the UUIDs of a list with a `fields` request and fetches every entry concurrently.
+    ///
+    /// # Arguments
+    /// - `uuids_url`: The `fields` request, e.g. `.../topology={uuid}?fields=node(uuid)`.
+    /// - `container_pointer`: Pointer to the object holding the list in the `fields` response.
+    /// - `list`: Name of the list, e.g. `node`. A missing list is treated as empty.
+    /// - `entry_url`: Builds the URL of an entry from its UUID.
+    /// - `key`: The list holding the entry in each entry response, e.g. `tapi-topology:node`.
+    ///
+    /// # Returns
+    /// The entries, or an `Error` if the container is missing or any request fails.
+    #[allow(clippy::too_many_arguments)]
+    async fn fetch_list(
+        device: &Device,
+        token: &str,
+        uuids_url: String,
+        container_pointer: &str,
+        list: &str,
+        entry_url: impl Fn(&str) -> String,
+        key: &str,
+        name: &str,
+    ) -> Result<Vec<Value>, AppError> {
+        let uuids_json = Self::request(device, &uuids_url, token).await?;
+        let container = uuids_json
+            .pointer(container_pointer)
+            .ok_or(AppError::validation_error(format!("{} Context Not Found", name)))?;
+
+        let entry_urls = container
+            .get(list)
+            .and_then(Value::as_array)
+            .cloned()
+            .unwrap_or_default()
+            .iter()
+            .map(|entry| {
+                entry
                     .get("uuid")
-                    .ok_or(AppError::validation_error("Node UUID Not Found"))?
-                    .as_str()
-                    .ok_or(AppError::validation_error("Invalid str UUID"))?;
-
-                let node_by_uuid_url = format!(
-                    "{}/data/tapi-common:context/tapi-topology:topology-context/topology={topology_uuid}/node={node_uuid}",
-                    &base_url
-                );
-
-                let node_value = match &device.auth {
-                    Auth::Basic(basic_auth) => HttpHandler::basic_request(&node_by_uuid_url, basic_auth.username.clone(), Some(basic_auth.password.clone())).await?,
-                    Auth::Token(_) => HttpHandler::token_request(&node_by_uuid_url, token.as_str()).await?,
-                };
+                    .and_then(Value::as_str)
+                    .map(&entry_url)
+                    .ok_or(AppError::validation_error(format!("{} UUID Not Found", name)))
+            })
+            .collect::<Result<Vec<String>, AppError>>()?;
 
-                let node_array = node_value
-                    .get("tapi-topology:node")
-                    .ok_or(AppError::validation_error("Node Not Found"))?
-                    .as_array()
-                    .ok_or(AppError::validation_error("Invalid Node"))?;
+        Self::fetch_all(device, token, entry_urls, key, name).await
+    }
 
-                for node in node_array {
-                    nodes.push(node.clone());
+    /// Lists the UUID of every topology of the device.
+    ///
+    /// Topologies referenced by the `nw-topology-service` that are not in the topology list are added
+    /// at the end. Devices without `nw-topology-service` only return the topology list.
+    ///
+    /// # Arguments
+    /// - `topology_context_url`: The URL of `tapi-topology:topology-context`.
+    async fn topology_uuids(
+        device: &Device,
+        token: &str,
+        topology_context_url: &str,
+    ) -> Result<Vec<String>, AppError> {
+        let topology_uuids_json = Self::request(
+            device,
+            &format!("{}?fields=topology(uuid)", topology_context_url),
+            token,
+        )
+        .await?;
+        let mut topology_uuids = topology_uuids_json
+            .pointer("/tapi-topology:topology-context/topology")
+            .and_then(Value::as_array)
+            .ok_or(AppError::validation_error("Topology Context Not Found"))?
+            .iter()
+            .map(|topology| {
+                topology
+                    .get("uuid")
+                    .and_then(Value::as_str)
+                    .map(str::to_string)
+                    .ok_or(AppError::validation_error("Topology UUID Not Found"))
+            })
+            .collect::<Result<Vec<String>, AppError>>()?;
+
+        let nw_topology_service_url = format!("{}/nw-topology-service", topology_context_url);
+        match Self::request(device, &nw_topology_service_url, token).await {
+            Ok(nw_topology_service_json) => {
+                let references = nw_topology_service_json
+                    .as_object()
+                    .and_then(|object| object.values().next())
+                    .and_then(|service| service.get("topology"))
+                    .and_then(Value::as_array)
+                    .cloned()
+                    .unwrap_or_default();
+                for reference in references {
+                    if let Some(topology_uuid) = reference.get("topology-uuid").and_then(Value::as_str) {
+                        if !topology_uuids.iter().any(|uuid| uuid == topology_uuid) {
+                            topology_uuids.push(topology_uuid.to_string());
+                        }
+                    }
                 }
             }
+            Err(err) => log::debug!("{} has no nw-topology-service: {}", device.ip, err),
+        }
 
-            let links_uuid_url = format!(
-                "{}/data/tapi-common:context/tapi-topology:topology-context/topology={topology_uuid}?fields=link(uuid)",
-                &base_url
-            );
-            // Obtain the JSON of links uuids based on the authentication method.
-            let link_uuids_json = match &device.auth {
-                Auth::Basic(basic_auth) => HttpHandler::basic_request(&links_uuid_url, basic_auth.username.clone(), Some(basic_auth.password.clone())).await?,
-                Auth::Token(_) => HttpHandler::token_request(&links_uuid_url, token.as_str()).await?,
-            };
-            // Parse the context and the links UUIDs.
-            let link_uuids = {
-                let link_array = link_uuids_json
-                    .pointer("/tapi-topology:topology")
-                    .ok_or(AppError::validation_error("Link Context Not Found"))?
-                    .as_array()
-                    .ok_or(AppError::validation_error("Link Context Not Found"))?;
-
-                if link_array.len() > 1 {
-                    log::warn!("More than one topology found, using the first one.");
-                }
-
-                link_array[0]
-                    .pointer("/link")
-                    .ok_or(AppError::validation_error("Link Context Not Found"))?
-                    .as_array()
-                    .ok_or(AppError::validation_error("Invalid Link"))?
-            };
-
-            let mut links: Vec<Value> = vec![];
-
-            //Iterate over each link_uuid
-            for link_uuid_value in link_uuids {
-                let link_uuid = link_uuid_value
-                    .get("uuid")
-                    .ok_or(AppError::validation_error("Link UUID Not Found"))?
-                    .as_str()
-                    .ok_or(AppError::validation_error("Invalid str UUID"))?;
+        Ok(topology_uuids)
+    }
 
-                let link_by_uuid_url = format!(
-                    "{}/data/tapi-common:context/tapi-topology:topology-context/topology={topology_uuid}/link={link_uuid}",
-                    &base_url
-                );
+    /// Retrieve every topology of a device, with its nodes and links fetched by UUID concurrently.
+    ///
+    /// # Arguments
+    /// - `topology_context_url`: The URL of `tapi-topology:topology-context`.
+    ///
+    /// # Returns
+    /// The topologies with their `uuid`, `name`, `layer-protocol-name`, `node` and `link`.
+    async fn get_topologies(
+        device: &Device,
+        token: &str,
+        topology_context_url: &str,
+    ) -> Result<Vec<Value>, AppError> {
+        let mut topologies: Vec<Value> = vec![];
+        for topology_uuid in Self::topology_uuids(device, token, topology_context_url).await? {
+            let topology_url = format!("{}/topology={}", topology_context_url, topology_uuid);
+
+            let topology_fields_json = Self::request(
+                device,
+                &format!("{}?fields=uuid;name;layer-protocol-name", topology_url),
+                token,
+            )
+            .await?;
+            let mut topology = topology_fields_json
+                .pointer("/tapi-topology:topology/0")
+                .and_then(Value::as_object)
+                .ok_or(AppError::validation_error("Topology Not Found"))?
+                .clone();
 
-                let link_value = match &device.auth {
-                    Auth::Basic(basic_auth) => HttpHandler::basic_request(&link_by_uuid_url, basic_auth.username.clone(), Some(basic_auth.password.clone())).await?,
-                    Auth::Token(_) => HttpHandler::token_request(&link_by_uuid_url, token.as_str()).await?,
-                };
+            let nodes = Self::fetch_list(
+                device,
+                token,
+                format!("{}?fields=node(uuid)", topology_url),
+                "/tapi-topology:topology/0",
+                "node",
+                |node_uuid| format!("{}/node={}", topology_url, node_uuid),
+                "tapi-topology:node",
+                "Node",
+            )
+            .await?;
+            let links = Self::fetch_list(
+                device,
+                token,
+                format!("{}?fields=link(uuid)", topology_url),
+                "/tapi-topology:topology/0",
+                "link",
+                |link_uuid| format!("{}/link={}", topology_url, link_uuid),
+                "tapi-topology:link",
+                "Link",
+            )
+            .await?;
+
+            topology.insert("node".to_string(), Value::Array(nodes));
+            topology.insert("link".to_string(), Value::Array(links));
+            topologies.push(Value::Object(topology));
+        }
 
-                let link_array = link_value
-                    .get("tapi-topology:link")
-                    .ok_or(AppError::validation_error("Link Not Found"))?
-                    .as_array()
-                    .ok_or(AppError::validation_error("Invalid Link"))?;
+        Ok(topologies)
+    }
 
-                for link in link_array {
-                    links.push(link.clone());
+    /// Reads data from a device with the strategy remembered for it, falling back to the others in
+    /// the order of `RetrievalStrategy::ALL`.
+    ///
+    /// Devices without remembered strategy are read with one strategy at a time, from the fewest
+    /// requests to the most, and the first one to succeed is kept.
+    ///
+    /// # Arguments
+    /// - `retrieve`: Reads the data with the given strategy.
+    ///
+    /// # Returns
+    /// The data with the strategy it was read with, or the last `Error` if every strategy fails.
+    async fn retrieve_adaptively<T, F, Fut>(
+        device: &Device,
+        retrieve: F,
+    ) -> Result<(T, RetrievalStrategy), AppError>
+    where
+        F: Fn(RetrievalStrategy) -> Fut,
+        Fut: Future<Output = Result<T, AppError>>,
+    {
+        let timed = |strategy: RetrievalStrategy| {
+            let retrieval = retrieve(strategy);
+            async move {
+                let started = Instant::now();
+                let retrieved = retrieval.await;
+                match &retrieved {
+                    Ok(_) => log::debug!(
+                        "{} read with the {} strategy in {:?}",
+                        device.ip,
+                        strategy,
+                        started.elapsed()
+                    ),
+                    Err(err) => log::warn!(
+                        "{} cannot be read with the {} strategy: {}",
+                        device.ip,
+                        strategy,
+                        err
+                    ),
                 }
+                retrieved.map(|data| (data, strategy))
+            }
+        };
 
+        let remembered = device.retrieval_strategy;
+        let mut last_error = None;
+        for strategy in remembered.into_iter().chain(
+            RetrievalStrategy::ALL
+                .into_iter()
+                .filter(|strategy| Some(*strategy) != remembered),
+        ) {
+            match timed(strategy).await {
+                Ok(retrieved) => return Ok(retrieved),
+                Err(err) => last_error = Some(err),
             }
+        }
+        Err(last_error.unwrap_or_else(|| AppError::request_error("No retrieval strategy left")))
+    }
 
-            let mut connectivity_services: Vec<Value> = vec![];
-            let mut connections: Vec<Value> = vec![];
+    /// Retrieve every topology of a device with the given strategy.
+    ///
+    /// # Arguments
+    /// - `context_url`: The URL of `tapi-common:context`.
+    ///
+    /// # Returns
+    /// The topologies, complete except with `PerUuid` and `FieldsFiltered`, which only read their
+    /// `uuid`, `name`, `layer-protocol-name`, `node` and `link`.
+    async fn topologies_with(
+        device: &Device,
+        token: &str,
+        context_url: &str,
+        strategy: RetrievalStrategy,
+    ) -> Result<Vec<Value>, AppError> {
+        let topology_context_url = format!("{}/tapi-topology:topology-context", context_url);
+        let topology_list = |json: Value| {
+            json.pointer("/tapi-topology:topology-context/topology")
+                .and_then(Value::as_array)
+                .cloned()
+                .ok_or(AppError::validation_error("Cannot find topology-context"))
+        };
 
-            let connectivity_service_by_uuid_url =format!(
-                "{}/data/tapi-common:context/tapi-connectivity:connectivity-context/connectivity-service={service_uuid}",
-                &base_url
-            );
+        match strategy {
+            RetrievalStrategy::FullContext => {
+                topology_list(Self::request(device, &topology_context_url, token).await?)
+            }
+            RetrievalStrategy::FieldsFiltered => topology_list(
+                Self::request(
+                    device,
+                    &format!("{}?fields={}", topology_context_url, TOPOLOGY_FIELDS),
+                    token,
+                )
+                .await?,
+            ),
+            RetrievalStrategy::DepthLimited => {
+                // Only the leaves of each topology are returned at depth 3
+                let topology_urls = topology_list(
+                    Self::request(device, &format!("{}?depth=3", topology_context_url), token)
+                        .await?,
+                )?
+                .iter()
+                .map(|topology| {
+                    topology
+                        .get("uuid")
+                        .and_then(Value::as_str)
+                        .map(|topology_uuid| {
+                            format!("{}/topology={}", topology_context_url, topology_uuid)
+                        })
+                        .ok_or(AppError::validation_error("Topology UUID Not Found"))
+                })
+                .collect::<Result<Vec<String>, AppError>>()?;
+                Self::fetch_all(
+                    device,
+                    token,
+                    topology_urls,
+                    "tapi-topology:topology",
+                    "Topology",
+                )
+                .await
+            }
+            RetrievalStrategy::PerUuid => {
+                Self::get_topologies(device, token, &topology_context_url).await
+            }
+        }
+    }
 
-            let connectivity_service_value = match &device.auth {
-                Auth::Basic(basic_auth) => HttpHandler::basic_request(&connectivity_service_by_uuid_url, basic_auth.username.clone(), Some(basic_auth.password.clone())).await?,
-                Auth::Token(_) => HttpHandler::token_request(&connectivity_service_by_uuid_url, token.as_str()).await?,
-            };
+    /// Retrieve every connection and connectivity service of a device with the given strategy.
+    ///
+    /// # Arguments
+    /// - `context_url`: The URL of `tapi-common:context`.
+    ///
+    /// # Returns
+    /// The connections, empty if the device has none, and the connectivity services.
+    async fn connectivity_with(
+        device: &Device,
+        token: &str,
+        context_url: &str,
+        strategy: RetrievalStrategy,
+    ) -> Result<(Vec<Value>, Vec<Value>), AppError> {
+        let connectivity_context_url =
+            format!("{}/tapi-connectivity:connectivity-context", context_url);
+
+        let json = match strategy {
+            RetrievalStrategy::PerUuid => {
+                let connections = Self::fetch_list(
+                    device,
+                    token,
+                    format!("{}?fields=connection(uuid)", connectivity_context_url),
+                    "/tapi-connectivity:connectivity-context",
+                    "connection",
+                    |connection_uuid| {
+                        format!("{}/connection={}", connectivity_context_url, connection_uuid)
+                    },
+                    "tapi-connectivity:connection",
+                    "Connection",
+                )
+                .await?;
+                let connectivity_services =
+                    Self::services_with(device, token, context_url, strategy).await?;
+                return Ok((connections, connectivity_services));
+            }
+            RetrievalStrategy::FieldsFiltered => {
+                let fields_url = format!(
+                    "{}?fields=connection;connectivity-service",
+                    connectivity_context_url
+                );
+                Self::request(device, &fields_url, token).await?
+            }
+            RetrievalStrategy::FullContext | RetrievalStrategy::DepthLimited => {
+                Self::request(device, &connectivity_context_url, token).await?
+            }
+        };
 
-            let connectivity_service_array = connectivity_service_value
-                .get("tapi-connectivity:connectivity-service")
-                .ok_or(AppError::validation_error("Connectivity Service Not Found"))?
-                .as_array()
-                .ok_or(AppError::validation_error("Invalid Connectivity Service"))?;
+        let connections = json
+            .pointer("/tapi-connectivity:connectivity-context/connection")
+            .and_then(Value::as_array)
+            .cloned()
+            .unwrap_or_default();
+        let connectivity_services = json
+            .pointer("/tapi-connectivity:connectivity-context/connectivity-service")
+            .and_then(Value::as_array)
+            .cloned()
+            .ok_or(AppError::validation_error("Cannot find connectivity-context"))?;
+        Ok((connections, connectivity_services))
+    }
 
-            for connectivity_service in connectivity_service_array {
-                connectivity_services.push(connectivity_service.clone());
+    /// Retrieve every connectivity service of a device with the given strategy.
+    ///
+    /// # Arguments
+    /// - `context_url`: The URL of `tapi-common:context`.
+    async fn services_with(
+        device: &Device,
+        token: &str,
+        context_url: &str,
+        strategy: RetrievalStrategy,
+    ) -> Result<Vec<Value>, AppError> {
+        let connectivity_context_url =
+            format!("{}/tapi-connectivity:connectivity-context", context_url);
+
+        let (url, pointer) = match strategy {
+            RetrievalStrategy::PerUuid => {
+                return Self::fetch_list(
+                    device,
+                    token,
+                    format!("{}?fields=connectivity-service(uuid)", connectivity_context_url),
+                    "/tapi-connectivity:connectivity-context",
+                    "connectivity-service",
+                    |service_uuid| {
+                        format!("{}/connectivity-service={}", connectivity_context_url, service_uuid)
+                    },
+                    "tapi-connectivity:connectivity-service",
+                    "Service Data",
+                )
+                .await
             }
+            RetrievalStrategy::FieldsFiltered => (
+                format!("{}?fields=connectivity-service", connectivity_context_url),
+                "/tapi-connectivity:connectivity-context/connectivity-service",
+            ),
+            RetrievalStrategy::DepthLimited => (
+                connectivity_context_url.clone(),
+                "/tapi-connectivity:connectivity-context/connectivity-service",
+            ),
+            RetrievalStrategy::FullContext => (
+                context_url.to_string(),
+                "/tapi-common:context/tapi-connectivity:connectivity-context/connectivity-service",
+            ),
+        };
 
-            let connections_uuids_url =format!(
-                "{}/data/tapi-common:context/tapi-connectivity:connectivity-context/connectivity-service={service_uuid}?fields=connection(connection-uuid)",
-                &base_url
-            );
+        Self::request(device, &url, token)
+            .await?
+            .pointer(pointer)
+            .and_then(Value::as_array)
+            .cloned()
+            .ok_or(AppError::validation_error("Cannot find connectivity-context"))
+    }
 
-            // Obtain the JSON of connections uuids based on the authentication method.
-            let connections_uuids_json = match &device.auth {
-                Auth::Basic(basic_auth) => HttpHandler::basic_request(&connections_uuids_url, basic_auth.username.clone(), Some(basic_auth.password.clone())).await?,
-                Auth::Token(_) => HttpHandler::token_request(&connections_uuids_url, token.as_str()).await?,
-            };
+    /// Retrieve the complete context of a device with the given strategy.
+    ///
+    /// # Arguments
+    /// - `context_url`: The URL of `tapi-common:context`.
+    async fn full_context_with(
+        device: &Device,
+        token: &str,
+        context_url: &str,
+        strategy: RetrievalStrategy,
+    ) -> Result<FullContext, AppError> {
+        if strategy == RetrievalStrategy::FullContext {
+            let json = Self::request(device, &context_url.to_string(), token).await?;
+            return full_context_by_context_json(json);
+        }
 
-            // Parse the context and the connectivity_services UUID.
-            let connections_uuids = {
-                let connections_array = connections_uuids_json
-                    .pointer("/tapi-connectivity:connectivity-service")
-                    .ok_or(AppError::validation_error("Connections Context Not Found"))?
-                    .as_array()
-                    .ok_or(AppError::validation_error("Connections Context Not Found"))?;
+        let topologies = Self::topologies_with(device, token, context_url, strategy).await?;
+        let (connections, connectivity_services) =
+            Self::connectivity_with(device, token, context_url, strategy).await?;
+        Ok(FullContext {
+            connectivity_services,
+            connections,
+            topology: Value::Array(topologies),
+            yang_modules: Vec::new(),
+            strategy: None,
+        })
+    }
 
-                if connections_array.len() > 1 {
-                    log::warn!("More than one Connections found, using the first one.");
-                }
+    /// Retrieve the complete context of a device.
+    ///
+    /// The context is read with the `RetrievalStrategy` remembered for the device, or with the
+    /// first one that succeeds when the device has none, see `retrieve_adaptively`.
+    ///
+    /// # Arguments
+    /// - `device`: A reference to the `Device` object containing connection details.
+    ///
+    /// # Returns
+    /// A `Result` containing a `FullContext` object, with the strategy it was read with, or an `Error`.
+    pub async fn get_full_context(device: &Device) -> Result<FullContext, AppError> {
+        let token = Self::token(device).await?;
+        let base_url = Self::base_url(device, &token).await?;
+        let context_url = format!("{}/data/tapi-common:context", base_url);
 
-                connections_array[0]
-                    .pointer("/connection")
-                    .ok_or(AppError::validation_error("Connections Context Not Found"))?
-                    .as_array()
-                    .ok_or(AppError::validation_error("Invalid Connections"))?
-            };
+        let (mut full_context, strategy) = Self::retrieve_adaptively(device, |strategy| {
+            Self::full_context_with(device, &token, &context_url, strategy)
+        })
+        .await?;
+        full_context.strategy = Some(strategy);
+        full_context.yang_modules = Self::yang_modules(device, &token, &base_url).await;
 
-            for connection_uuid_value in connections_uuids {
-                let connection_uuid = connection_uuid_value
-                    .get("connection-uuid")
-                    .ok_or(AppError::validation_error("Connection UUID Not Found"))?
-                    .as_str()
-                    .ok_or(AppError::validation_error("Invalid str UUID"))?;
+        Ok(full_context)
+    }
 
-                let connection_by_uuid_url =format!(
-                    "{}/data/tapi-common:context/tapi-connectivity:connectivity-context/connection={connection_uuid}",
-                    &base_url
-                );
+    /// Lists the YANG modules announced by the device, trying `ietf-yang-library:modules-state`
+    /// (RFC 7895) and then `ietf-yang-library:yang-library` (RFC 8525).
+    ///
+    /// # Returns
+    /// The module names, or an empty list if the device does not expose the YANG library.
+    async fn yang_modules(device: &Device, token: &str, base_url: &str) -> Vec<String> {
+        let module_names = |modules: Option<&Value>| -> Vec<String> {
+            modules
+                .and_then(Value::as_array)
+                .map(|modules| {
+                    modules
+                        .iter()
+                        .filter_map(|module| module.get("name").and_then(Value::as_str))
+                        .map(str::to_string)
+                        .collect()
+                })
+                .unwrap_or_default()
+        };
 
-                let connections_value = match &device.auth {
-                    Auth::Basic(basic_auth) => HttpHandler::basic_request(&connection_by_uuid_url, basic_auth.username.clone(), Some(basic_auth.password.clone())).await?,
-                    Auth::Token(_) => HttpHandler::token_request(&connection_by_uuid_url, token.as_str()).await?,
-                };
-                let connections_array = connections_value
-                    .get("tapi-connectivity:connection")
-                    .ok_or(AppError::validation_error("Connection Not Found"))?
-                    .as_array()
-                    .ok_or(AppError::validation_error("Invalid Connection UUID"))?;
-                for connection in connections_array {
-                    connections.push(connection.clone());
-                }
+        let modules_state_url = format!("{}/data/ietf-yang-library:modules-state", base_url);
+        if let Ok(modules_state) = Self::request(device, &modules_state_url, token).await {
+            let modules =
+                module_names(modules_state.pointer("/ietf-yang-library:modules-state/module"));
+            if !modules.is_empty() {
+                return modules;
+            }
+        }
 
+        let yang_library_url = format!("{}/data/ietf-yang-library:yang-library", base_url);
+        match Self::request(device, &yang_library_url, token).await {
+            Ok(yang_library) => yang_library
+                .pointer("/ietf-yang-library:yang-library/module-set")
+                .and_then(Value::as_array)
+                .cloned()
+                .unwrap_or_default()
+                .iter()
+                .flat_map(|module_set| module_names(module_set.get("module")))
+                .collect(),
+            Err(err) => {
+                log::debug!("{} does not expose the YANG library: {}", device.ip, err);
+                Vec::new()
             }
+        }
+    }
 
-            // Construct the topology hashmap.
-            let mut topology_hashmap: Map<String, Value> = Map::new();
-            topology_hashmap.insert(
-                "link".to_string(),
-                Value::Array(links),
-            );
-            topology_hashmap.insert(
-                "node".to_string(),
-                Value::Array(nodes),
-            );
-
-            topology_hashmap.extend(topology_fields.clone());
-
-            let topology_object: Value = Value::Object(topology_hashmap);
-            let topology: Value = Value::Array(vec![topology_object]);
-
-            Ok(Context {
-                connectivity_service: Value::Array(connectivity_services),
-                connections,
-                topology,
-            })
+    /// Retrieve services from a device via API calls.
+    ///
+    /// The services are read with the `RetrievalStrategy` remembered for the device, or with the
+    /// first one that succeeds when the device has none, see `retrieve_adaptively`.
+    ///
+    /// # Arguments
+    /// - `device`: A reference to the `Device` object containing connection details.
+    ///
+    /// # Returns
+    /// A `Result` containing a vector of `Value` objects representing the services, with the
+    /// strategy they were read with, or an `Error`.
+    pub async fn get_services(
+        device: &Device,
+    ) -> Result<(Vec<Value>, RetrievalStrategy), AppError> {
+        let token = Self::token(device).await?;
+        let base_url = Self::base_url(device, &token).await?;
+        let context_url = format!("{}/data/tapi-common:context", base_url);
 
-        } .await {
-            Ok(context) => Ok(context),
-            Err(_) => {
-                match async {
-                    // Obtain the JSON of topologies based on the authentication method.
-                    let topology_uuids_json = match &device.auth {
-                        Auth::Basic(basic_auth) => HttpHandler::basic_request(&topology_by_uuid_url, basic_auth.username.clone(), Some(basic_auth.password.clone())).await?,
-                        Auth::Token(_) => HttpHandler::token_request(&topology_by_uuid_url, token.as_str()).await?,
-                    };
-                    // Parse the context and the topology UUID.
-                    let topologies = topology_uuids_json
-                        .pointer("/tapi-topology:topology-context/topology")
-                        .ok_or(AppError::validation_error("Topology Context Not Found"))?
-                        .as_array()
-                        .ok_or(AppError::validation_error("Invalid Topology"))?;
-                    // Verify that there is exactly one topology UUID.
-                    if topologies.len() != 1 {
-                        return Err(AppError::validation_error("There is more or less than one topology uuid"));
-                    }
-                    let topology_uuid = topologies[0]
-                        .get("uuid")
-                        .ok_or(AppError::validation_error("Topology UUID Not Found"))?
-                        .as_str()
-                        .ok_or(AppError::validation_error("Invalid str UUID"))?;
-                    // Construct the URLs for links and nodes.
-                    let link_url = format!(
-                        "https://{}{}/tapi/data/tapi-common:context/tapi-topology:topology-context/topology={}/link",
-                        &device.ip, &device.port.map(|s| format!(":{}", s)).unwrap_or_default(), topology_uuid
-                    );
-                    let nodes_url = format!(
-                        "https://{}{}/tapi/data/tapi-common:context/tapi-topology:topology-context/topology={}/node",
-                        &device.ip, &device.port.map(|s| format!(":{}", s)).unwrap_or_default(), topology_uuid
-                    );
-
-                    // Retrieve the data for links and nodes.
-                    let link_json = match &device.auth {
-                        Auth::Basic(basic_auth) => HttpHandler::basic_request(&link_url, basic_auth.username.clone(), Some(basic_auth.password.clone())).await?,
-                        Auth::Token(_) => HttpHandler::token_request(&link_url, token.as_str()).await?,
-                    };
-
-                    let node_json = match &device.auth {
-                        Auth::Basic(basic_auth) => HttpHandler::basic_request(&nodes_url, basic_auth.username.clone(), Some(basic_auth.password.clone())).await?,
-                        Auth::Token(_) => HttpHandler::token_request(&nodes_url, token.as_str()).await?,
-                    };
-
-                    // Construct the topology hashmap.
-                    let mut topology_hashmap: Map<String, Value> = Map::new();
-                    topology_hashmap.insert(
-                        "link".to_string(),
-                        link_json.get("tapi-topology:link").ok_or(AppError::validation_error("tapi-topology:link Not Found"))?.clone(),
-                    );
-                    topology_hashmap.insert(
-                        "node".to_string(),
-                        node_json.get("tapi-topology:node").ok_or(AppError::validation_error("tapi-topology:node Not Found"))?.clone(),
-                    );
-
-                    let topology_object: Value = Value::Object(topology_hashmap);
-                    let topology: Value = Value::Array(vec![topology_object]);
-
-                    // Retrieve the connections.
-                    let connections = match &device.auth {
-                        Auth::Basic(basic_auth) => HttpHandler::basic_request(&connections_url, basic_auth.username.clone(), Some(basic_auth.password.clone())).await?,
-                        Auth::Token(_) => HttpHandler::token_request(&connections_url, token.as_str()).await?,
-                    }
-                    .as_array()
-                    .ok_or(AppError::validation_error("Connections cannot convert into array"))?
-                    .clone();
-
-                    // Construct the JSON for services.
-                    let service_url = format!(
-                        "{}/data/tapi-common:context/tapi-connectivity:connectivity-context/connectivity-service={}",
-                        &base_url, service_uuid
-                    );
-
-                    let service_json = match &device.auth {
-                        Auth::Basic(basic_auth) => HttpHandler::basic_request(&service_url, basic_auth.username.clone(), Some(basic_auth.password.clone())).await?,
-                        Auth::Token(_) => HttpHandler::token_request(&service_url, token.as_str()).await?,
-                    };
-
-                    let connectivity_service = service_json
-                        .get("tapi-connectivity:connectivity-service")
-                        .ok_or(AppError::validation_error("Service Data Not Found"))?;
-
-
-                    // Return the constructed context.
-                    Ok(Context {
-                        connectivity_service: connectivity_service.clone(),
-                        connections,
-                        topology,
-                    })
-                }
-                .await
-                {
-                    // If the `async` block executes successfully, continue normally.
-                    Ok(context) => Ok(context),
-                    Err(_) => {
-                        // If an error occurs, execute the `else` block.
-                        let json = match &device.auth {
-                            Auth::Basic(basic_auth) => HttpHandler::basic_request(&context_url, basic_auth.username.clone(), Some(basic_auth.password.clone())).await?,
-                            Auth::Token(_) => HttpHandler::token_request(&context_url, token.as_str()).await?,
-                        };
-
-                        context_by_context_json(json, service_uuid)
-                    }
+        Self::retrieve_adaptively(device, |strategy| {
+            Self::services_with(device, &token, &context_url, strategy)
+        })
+        .await
+    }
+
+    /// Retrieve the context of a specific service from a device.
+    ///
+    /// The context is read like the complete one, see `get_full_context`.
+    ///
+    /// # Arguments
+    /// - `device`: A reference to the `Device` object containing connection details.
+    /// - `service_uuid`: The UUID of the service to retrieve context for.
+    ///
+    /// # Returns
+    /// A `Result` containing a `Context` object or an `Error`.
+    pub async fn get_service_context(
+        device: &Device,
+        service_uuid: &str,
+    ) -> Result<Context, AppError> {
+        let full_context = Self::get_full_context(device).await?;
+        let connectivity_service = full_context
+            .connectivity_services
+            .into_iter()
+            .find(|service| service.get("uuid").and_then(Value::as_str) == Some(service_uuid))
+            .ok_or_else(|| AppError::validation_error("There is not any Service with that id"))?;
+
+        Ok(Context {
+            connectivity_service,
+            connections: full_context.connections,
+            topology: full_context.topology,
+        })
+    }
+}
+
+/// Finds the address of a stream under `key`, preferring the ones whose `encoding` is JSON or unset.
+fn stream_address(value: &Value, key: &str) -> Option<String> {
+    fn collect<'a>(value: &'a Value, key: &str, found: &mut Vec<(&'a str, Option<&'a str>)>) {
+        match value {
+            Value::Object(object) => {
+                if let Some(address) = object.get(key).and_then(Value::as_str) {
+                    found.push((address, object.get("encoding").and_then(Value::as_str)));
                 }
+                object.values().for_each(|child| collect(child, key, found));
             }
+            Value::Array(items) => items.iter().for_each(|item| collect(item, key, found)),
+            _ => {}
         }
     }
+
+    let mut found = Vec::new();
+    collect(value, key, &mut found);
+    found
+        .iter()
+        .find(|(_, encoding)| encoding.is_none_or(|encoding| encoding.contains("json")))
+        .or(found.first())
+        .map(|(address, _)| address.to_string())
 }
 
-/// Helper function to construct a `Context` from a JSON structure.
+/// Helper function to construct a `FullContext` from a complete `tapi-common:context` JSON structure.
 ///
 /// # Arguments
-/// - `json`: The JSON `Value` containing service, connections, and topology data.
-/// - `service_uuid`: The UUID of the service to construct the context for.
+/// - `json`: The JSON `Value` containing services, connections, and topology data.
 ///
 /// # Returns
-/// A `Result` containing a `Context` object or an `Error`.
-fn context_by_context_json(json: Value, service_uuid: &str) -> Result<Context, AppError> {
+/// A `Result` containing a `FullContext` object or an `Error`.
+fn full_context_by_context_json(json: Value) -> Result<FullContext, AppError> {
     let connectivity_services = json
         .pointer("/tapi-common:context/tapi-connectivity:connectivity-context/connectivity-service")
         .and_then(Value::as_array)
         .ok_or_else(|| AppError::validation_error("Cannot find connectivity-context"))?
         .clone();
-
-    let connectivity_service = connectivity_services
-        .iter()
-        .find(|service| {
-            service
-                .get("uuid")
-                .and_then(|uuid| uuid.as_str())
-                .map(|uuid_str| uuid_str == service_uuid)
-                .unwrap_or(false)
-        })
-        .ok_or_else(|| AppError::validation_error("There is not any Service with that id"))?
-        .clone();
-
     let connections = json
         .pointer("/tapi-common:context/tapi-connectivity:connectivity-context/connection")
         .and_then(Value::as_array)
-        .ok_or_else(|| AppError::validation_error("Cannot find connections-context"))?
-        .clone();
+        .cloned()
+        .unwrap_or_default();
     let topology = json
         .pointer("/tapi-common:context/tapi-topology:topology-context/topology")
-        .ok_or_else(|| AppError::validation_error("Cannot find connections-context"))?
+        .ok_or_else(|| AppError::validation_error("Cannot find topology-context"))?
         .clone();
 
-    Ok(Context {
-        connectivity_service,
+    Ok(FullContext {
+        connectivity_services,
         connections,
         topology,
+        yang_modules: Vec::new(),
+        strategy: None,
     })
 }
@@ -0,0 +1,456 @@
+use crate::handlers::requester::{DataSource, FullContext, Requester};
+use crate::handlers::vendor::VendorProfiles;
+use crate::logic::{
+    connection_builder::connection_vector_build, link_builder::link_vector_build,
+    node_builder::node_vector_building,
+};
+use crate::models::{
+    connections::Connection,
+    devices::RetrievalStrategy,
+    links::Link,
+    nodes::Node,
+    notification::{ChangedAttribute, NotificationType, ObjectType, TapiNotification},
+    provisioning::AvailableSip,
+    topology_graph::TopologyGraph,
+    vendor_profile::VendorProfile,
+};
+use crate::utils::{parse_tapi, parse_topologies};
+use crate::AppError;
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde_json::{Map, Value};
+use std::collections::{HashMap, HashSet};
+use std::env;
+use std::sync::Arc;
+use std::time::Duration;
+use tapi_model::connectivity::ConnectivityService;
+use tapi_model::topology::Topology;
+use tapi_model::Uuid;
+use tokio::sync::Mutex;
+
+/// TTL used when `SNAPSHOT_TTL_SECS` is not set.
+const DEFAULT_TTL: Duration = Duration::from_secs(300);
+
+/// Last snapshot of a data source, locked while it is being taken.
+type Slot = Arc<Mutex<Option<Arc<TopologySnapshot>>>>;
+
+/// Attributes of the topology objects read into a snapshot. Changing any other attribute, e.g.
+/// `operational-state`, leaves the snapshot as it is.
+const PARSED_ATTRIBUTES: &[&str] = &[
+    "uuid",
+    "name",
+    "node",
+    "link",
+    "owned-node-edge-point",
+    "node-edge-point",
+    "cep-list",
+    "connection-end-point",
+    "client-node-edge-point",
+    "layer-protocol-name",
+    "layer-protocol-qualifier",
+    "media-channel-node-edge-point-spec",
+    "mapped-service-interface-point",
+    "lower-connection",
+];
+
+/// Parsed topology and connectivity of a data source at a point in time.
+///
+/// Cloning a snapshot shares its vectors and graph.
+#[derive(Clone, Debug)]
+pub struct TopologySnapshot {
+    pub nodes: Arc<Vec<Node>>,
+    pub links: Arc<Vec<Link>>,
+    pub connections: Arc<Vec<Connection>>,
+    /// Indexes over `nodes`, `links` and `connections`, sharing them, for the endpoint trace.
+    pub graph: Arc<TopologyGraph>,
+    pub connectivity_services: Arc<Vec<ConnectivityService>>,
+    /// Service interface points mapped to the edge points of the topology.
+    sips: Arc<Vec<AvailableSip>>,
+    /// Profile used to read the names and extensions of the data source.
+    pub profile: VendorProfile,
+    /// Strategy the context was read with, `None` for files.
+    pub strategy: Option<RetrievalStrategy>,
+    pub taken_at: DateTime<Utc>,
+}
+
+impl TopologySnapshot {
+    /// Retrieves the complete context of a data source and parses it with the profile chosen for the
+    /// data source, or detected from its YANG modules.
+    pub async fn take(data_source: &DataSource) -> Result<Self, AppError> {
+        let full_context = Requester::get_full_context(data_source).await?;
+        let profile =
+            VendorProfiles::global().select(data_source.vendor(), &full_context.modules());
+        log::debug!(
+            "Using vendor profile {} for {}",
+            profile.name,
+            data_source.id()
+        );
+        Self::build(full_context, profile)
+    }
+
+    /// Parses the nodes, links and connections of a complete context, merging every topology.
+    ///
+    /// # Returns
+    /// The `TopologySnapshot`, or an `Error` if the context has no topology or does not match the
+    /// TAPI model.
+    pub fn build(full_context: FullContext, profile: VendorProfile) -> Result<Self, AppError> {
+        let topologies = parse_topologies(&full_context.topology)?;
+        if topologies.is_empty() {
+            return Err(AppError::validation_error("Cannot find topology"));
+        }
+        let connections = parse_tapi(&full_context.connections, "connection")?;
+        let nodes = Arc::new(node_vector_building(&topologies, &profile));
+        let links = Arc::new(link_vector_build(&topologies));
+        let connections = Arc::new(connection_vector_build(&connections, &profile));
+
+        Ok(Self {
+            graph: Arc::new(TopologyGraph::new(
+                nodes.clone(),
+                links.clone(),
+                connections.clone(),
+            )),
+            nodes,
+            links,
+            connections,
+            connectivity_services: Arc::new(parse_tapi(
+                &full_context.connectivity_services,
+                "connectivity service",
+            )?),
+            sips: Arc::new(mapped_sips(&topologies)),
+            profile,
+            strategy: full_context.strategy,
+            taken_at: Utc::now(),
+        })
+    }
+
+    /// Applies a notification to the snapshot, changing only the vector holding its target.
+    ///
+    /// Deleted objects are removed from their vector, and the graph indexes that vector again.
+    /// Changed attributes are replaced on connectivity services, while on topology objects they only
+    /// matter when the snapshot reads them. The new snapshot shares the untouched vectors and keeps
+    /// `taken_at`, so it still expires after the TTL.
+    ///
+    /// # Returns
+    /// The updated snapshot, or `None` if the notification cannot be applied locally: created
+    /// objects are not carried by notifications, the target may be missing from the snapshot, and
+    /// deleted topologies or changed attributes read by the snapshot need the context again.
+    pub fn apply(&self, notification: &TapiNotification) -> Result<Option<Self>, AppError> {
+        let Ok(uuid) = Uuid::parse_str(&notification.target_object_identifier) else {
+            return Ok(None);
+        };
+        let object_type = notification.target_object_type;
+        match notification.notification_type {
+            NotificationType::ObjectCreation => Ok(None),
+            NotificationType::ObjectDeletion => Ok(self.remove(object_type, uuid)),
+            NotificationType::AttributeValueChange => {
+                self.change(object_type, uuid, &notification.changed_attributes)
+            }
+        }
+    }
+
+    /// The snapshot without the object with the given UUID, or `None` if it does not hold it.
+    fn remove(&self, object_type: ObjectType, uuid: Uuid) -> Option<Self> {
+        let mut snapshot = self.clone();
+        match object_type {
+            ObjectType::ConnectivityService => {
+                snapshot.connectivity_services =
+                    retained(&self.connectivity_services, |service| service.uuid != uuid)?;
+            }
+            ObjectType::Connection => {
+                snapshot.connections = retained(&self.connections, |connection| {
+                    connection.connection_uuid != uuid
+                })?;
+                snapshot.graph =
+                    Arc::new(self.graph.with_connections(snapshot.connections.clone()));
+            }
+            ObjectType::Link => {
+                snapshot.links = retained(&self.links, |link| link.link_uuid != uuid)?;
+                snapshot.graph = Arc::new(self.graph.with_links(snapshot.links.clone()));
+            }
+            ObjectType::Node => {
+                snapshot.nodes = retained(&self.nodes, |node| node.node_uuid != uuid)?;
+                snapshot.graph = Arc::new(self.graph.with_nodes(snapshot.nodes.clone()));
+                snapshot.sips = retained(&self.sips, |sip| sip.node_uuid != uuid)
+                    .unwrap_or_else(|| self.sips.clone());
+            }
+            ObjectType::NodeEdgePoint => {
+                self.graph.node_edge_point_owners(uuid).next()?;
+                let nodes = self.nodes.iter().map(|node| {
+                    let mut node = node.clone();
+                    node.owned_node_edge_points
+                        .retain(|edge_point| edge_point.node_edge_point_uuid != uuid);
+                    node
+                });
+                snapshot.nodes = Arc::new(nodes.collect());
+                snapshot.graph = Arc::new(self.graph.with_nodes(snapshot.nodes.clone()));
+                snapshot.sips = retained(&self.sips, |sip| sip.node_edge_point_uuid != uuid)
+                    .unwrap_or_else(|| self.sips.clone());
+            }
+            ObjectType::Topology | ObjectType::ServiceInterfacePoint | ObjectType::Other => {
+                return None
+            }
+        }
+        Some(snapshot)
+    }
+
+    /// The snapshot with the changed attributes of an object, or `None` if it does not hold the
+    /// object or reads one of the attributes from the context.
+    fn change(
+        &self,
+        object_type: ObjectType,
+        uuid: Uuid,
+        attributes: &[ChangedAttribute],
+    ) -> Result<Option<Self>, AppError> {
+        if object_type == ObjectType::ConnectivityService {
+            let Some(position) = self
+                .connectivity_services
+                .iter()
+                .position(|service| service.uuid == uuid)
+            else {
+                return Ok(None);
+            };
+            let mut object = serde_json::to_value(&self.connectivity_services[position])
+                .map_err(|err| AppError::model_error(err.to_string()))?;
+            let Some(fields) = object.as_object_mut() else {
+                return Ok(None);
+            };
+            change_attributes(fields, attributes);
+
+            let mut connectivity_services = (*self.connectivity_services).clone();
+            connectivity_services[position] =
+                parse_tapi(&[object], "connectivity service")?.remove(0);
+            let mut snapshot = self.clone();
+            snapshot.connectivity_services = Arc::new(connectivity_services);
+            return Ok(Some(snapshot));
+        }
+
+        let is_parsed = attributes.iter().any(|attribute| {
+            let name = local_name(&attribute.value_name);
+            PARSED_ATTRIBUTES.contains(&name)
+                || self
+                    .profile
+                    .extensions
+                    .iter()
+                    .any(|extension| local_name(&extension.key) == name)
+        });
+        if is_parsed || !self.holds(object_type, uuid) {
+            return Ok(None);
+        }
+        Ok(Some(self.clone()))
+    }
+
+    /// Returns `true` if the snapshot holds a topology object with the given UUID.
+    fn holds(&self, object_type: ObjectType, uuid: Uuid) -> bool {
+        match object_type {
+            ObjectType::Topology => self.nodes.iter().any(|node| node.topology_uuid == uuid),
+            ObjectType::Node => self.graph.nodes_by_uuid(uuid).next().is_some(),
+            ObjectType::NodeEdgePoint => self.graph.node_edge_point_owners(uuid).next().is_some(),
+            ObjectType::Link => self.links.iter().any(|link| link.link_uuid == uuid),
+            ObjectType::Connection => self.graph.connections_by_uuid(uuid).next().is_some(),
+            ObjectType::ServiceInterfacePoint => self.sips.iter().any(|sip| sip.uuid == uuid),
+            ObjectType::ConnectivityService => self.service(&uuid.to_string()).is_some(),
+            ObjectType::Other => false,
+        }
+    }
+
+    /// Finds a connectivity service by UUID.
+    pub fn service(&self, service_uuid: &str) -> Option<&ConnectivityService> {
+        let service_uuid = Uuid::parse_str(service_uuid).ok()?;
+        self.connectivity_services
+            .iter()
+            .find(|service| service.uuid == service_uuid)
+    }
+
+    /// Topology of each node, by node UUID.
+    pub fn node_topologies(&self) -> HashMap<String, String> {
+        self.nodes
+            .iter()
+            .map(|node| (node.node_uuid.to_string(), node.topology_uuid.to_string()))
+            .collect()
+    }
+
+    /// Service interface points mapped to the edge points of the topology, in topology order and
+    /// without duplicates.
+    pub fn service_interface_points(&self) -> &[AvailableSip] {
+        &self.sips
+    }
+
+    /// Moment the snapshot was taken, in RFC 3339 format.
+    pub fn timestamp(&self) -> String {
+        self.taken_at.to_rfc3339_opts(SecondsFormat::Secs, true)
+    }
+
+    /// Returns `true` if the snapshot is older than the given TTL.
+    fn is_expired(&self, ttl: Duration) -> bool {
+        (Utc::now() - self.taken_at)
+            .to_std()
+            .map(|age| age >= ttl)
+            .unwrap_or(false)
+    }
+}
+
+/// Cache of the last `TopologySnapshot` of each data source, by id.
+///
+/// Each data source has its own lock, so concurrent requests for the same data source wait for a single
+/// download while other data sources are served independently.
+#[derive(Clone, Debug)]
+pub struct SnapshotCache {
+    ttl: Duration,
+    slots: Arc<Mutex<HashMap<String, Slot>>>,
+}
+
+impl SnapshotCache {
+    /// Creates an empty cache whose snapshots expire after `ttl`.
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            slots: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Creates an empty cache with the TTL in seconds from `SNAPSHOT_TTL_SECS`, 300 by default.
+    pub fn from_env() -> Self {
+        let ttl = env::var("SNAPSHOT_TTL_SECS")
+            .ok()
+            .and_then(|value| value.parse().ok())
+            .map(Duration::from_secs)
+            .unwrap_or(DEFAULT_TTL);
+        Self::new(ttl)
+    }
+
+    /// Lock of the snapshot of a data source, created on first use.
+    async fn slot(&self, id: &str) -> Slot {
+        self.slots
+            .lock()
+            .await
+            .entry(id.to_string())
+            .or_default()
+            .clone()
+    }
+
+    /// Returns the cached snapshot of the data source, taking a new one if it is missing or expired.
+    pub async fn get(&self, data_source: &DataSource) -> Result<Arc<TopologySnapshot>, AppError> {
+        let slot = self.slot(data_source.id()).await;
+        let mut snapshot = slot.lock().await;
+
+        match snapshot.as_ref() {
+            Some(cached) if !cached.is_expired(self.ttl) => Ok(cached.clone()),
+            _ => {
+                let fresh = Arc::new(TopologySnapshot::take(data_source).await?);
+                *snapshot = Some(fresh.clone());
+                Ok(fresh)
+            }
+        }
+    }
+
+    /// Takes a new snapshot of the data source, replacing the cached one.
+    pub async fn refresh(
+        &self,
+        data_source: &DataSource,
+    ) -> Result<Arc<TopologySnapshot>, AppError> {
+        let slot = self.slot(data_source.id()).await;
+        let mut snapshot = slot.lock().await;
+
+        let fresh = Arc::new(TopologySnapshot::take(data_source).await?);
+        *snapshot = Some(fresh.clone());
+        Ok(fresh)
+    }
+
+    /// Applies a notification of the data source to its cached snapshot, taking a new snapshot when
+    /// the notification cannot be applied locally, e.g. for created objects.
+    ///
+    /// # Returns
+    /// The updated snapshot, or `None` if no snapshot is cached, as the next `get` takes a fresh one.
+    pub async fn apply(
+        &self,
+        data_source: &DataSource,
+        notification: &TapiNotification,
+    ) -> Result<Option<Arc<TopologySnapshot>>, AppError> {
+        let slot = self.slot(data_source.id()).await;
+        {
+            let mut snapshot = slot.lock().await;
+            let Some(cached) = snapshot.as_ref() else {
+                return Ok(None);
+            };
+            if let Some(updated) = cached.apply(notification)? {
+                let updated = Arc::new(updated);
+                *snapshot = Some(updated.clone());
+                return Ok(Some(updated));
+            }
+        }
+
+        // The cached snapshot is served while the new one is taken
+        let fresh = Arc::new(TopologySnapshot::take(data_source).await?);
+        *slot.lock().await = Some(fresh.clone());
+        Ok(Some(fresh))
+    }
+
+    /// Drops the snapshot of a data source, e.g. when it is replaced or deleted.
+    pub async fn invalidate(&self, id: &str) {
+        self.slots.lock().await.remove(id);
+    }
+}
+
+/// Name of a data node without its module, e.g. `node` for `tapi-topology:node`.
+fn local_name(key: &str) -> &str {
+    key.rsplit(':').next().unwrap_or(key)
+}
+
+/// The items kept by `keep`, or `None` if every item is kept.
+fn retained<T: Clone>(items: &[T], keep: impl Fn(&T) -> bool) -> Option<Arc<Vec<T>>> {
+    let kept: Vec<T> = items.iter().filter(|item| keep(item)).cloned().collect();
+    (kept.len() != items.len()).then(|| Arc::new(kept))
+}
+
+/// Replaces the changed attributes on an object, matching their names without module.
+fn change_attributes(object: &mut Map<String, Value>, attributes: &[ChangedAttribute]) {
+    for attribute in attributes {
+        let name = local_name(&attribute.value_name);
+        let key = object
+            .keys()
+            .find(|key| local_name(key) == name)
+            .cloned()
+            .unwrap_or(attribute.value_name.clone());
+        match &attribute.new_value {
+            Some(new_value) => object.insert(key, new_value.clone()),
+            None => object.remove(&key),
+        };
+    }
+}
+
+/// Service interface points mapped to the edge points of the topologies, in topology order and
+/// without duplicates.
+fn mapped_sips(topologies: &[Topology]) -> Vec<AvailableSip> {
+    let mut seen_sip_uuids: HashSet<Uuid> = HashSet::new();
+    let mut sips: Vec<AvailableSip> = vec![];
+    for topology in topologies {
+        for node in &topology.node {
+            let node_name = node
+                .name
+                .first()
+                .map(|name| name.value.clone())
+                .unwrap_or_default();
+            for edge_point in &node.owned_node_edge_point {
+                let layer_protocol_name = edge_point
+                    .layer_protocol_name
+                    .and_then(|layer| serde_json::to_value(layer).ok())
+                    .and_then(|layer| layer.as_str().map(str::to_string));
+                for sip in &edge_point.mapped_service_interface_point {
+                    let uuid = sip.service_interface_point_uuid;
+                    if !seen_sip_uuids.insert(uuid) {
+                        continue;
+                    }
+                    sips.push(AvailableSip {
+                        uuid,
+                        topology_uuid: topology.uuid,
+                        node_uuid: node.uuid,
+                        node_name: node_name.clone(),
+                        node_edge_point_uuid: edge_point.uuid,
+                        layer_protocol_name: layer_protocol_name.clone(),
+                    });
+                }
+            }
+        }
+    }
+    sips
+}
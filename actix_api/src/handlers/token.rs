@@ -0,0 +1,227 @@
+use crate::handlers::http::HttpHandler;
+use crate::models::devices::{Auth, Device, GrantType, OAuth2Auth, TlsConfig};
+use crate::AppError;
+
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+static TOKEN_CACHE: OnceLock<TokenCache> = OnceLock::new();
+
+/// Tokens are renewed this long before `expires_in`, so they do not expire while in flight.
+const EXPIRY_MARGIN: Duration = Duration::from_secs(10);
+
+/// Last token of a device, locked while it is being renewed.
+type Slot = Arc<Mutex<Option<CachedToken>>>;
+
+/// Token obtained from the token endpoint of a device.
+#[derive(Clone, Debug)]
+struct CachedToken {
+    access_token: String,
+    /// Moment the token is renewed, `None` if the device does not tell when it expires.
+    renew_at: Option<Instant>,
+    refresh_token: Option<String>,
+}
+
+impl CachedToken {
+    /// Reads an OAuth2 token response, keeping the previous refresh token if no new one is issued.
+    fn from_oauth2_response(
+        response: &Value,
+        previous_refresh_token: Option<String>,
+    ) -> Result<Self, AppError> {
+        let access_token = response
+            .get("access_token")
+            .and_then(Value::as_str)
+            .ok_or(AppError::validation_error(
+                "Cannot find access_token in the token response",
+            ))?
+            .to_string();
+        // Some servers send `expires_in` as a string
+        let expires_in = response.get("expires_in").and_then(|expires_in| {
+            expires_in
+                .as_u64()
+                .or_else(|| expires_in.as_str().and_then(|value| value.parse().ok()))
+        });
+        let refresh_token = response
+            .get("refresh_token")
+            .and_then(Value::as_str)
+            .map(str::to_string)
+            .or(previous_refresh_token);
+
+        Ok(Self {
+            access_token,
+            renew_at: expires_in.map(|expires_in| {
+                Instant::now() + Duration::from_secs(expires_in).saturating_sub(EXPIRY_MARGIN)
+            }),
+            refresh_token,
+        })
+    }
+
+    fn is_fresh(&self) -> bool {
+        self.renew_at
+            .map(|renew_at| Instant::now() < renew_at)
+            .unwrap_or(true)
+    }
+}
+
+/// Cache of the access token of each token-authenticated device, by token endpoint.
+///
+/// Tokens are reused until they expire or the device rejects them. OAuth2 tokens are then renewed
+/// with their refresh token, falling back to a new grant when the refresh token is rejected too.
+/// Each device has its own lock, so concurrent requests wait for a single renewal.
+#[derive(Debug, Default)]
+pub struct TokenCache {
+    slots: Mutex<HashMap<String, Slot>>,
+}
+
+impl TokenCache {
+    /// Shared cache used by every `DeviceHandler`.
+    pub fn global() -> &'static Self {
+        TOKEN_CACHE.get_or_init(Self::default)
+    }
+
+    /// Lock of the token of a device, created on first use.
+    async fn slot(&self, device: &Device) -> Slot {
+        self.slots
+            .lock()
+            .await
+            .entry(device.get_full_auth_url())
+            .or_default()
+            .clone()
+    }
+
+    /// Returns a valid token for the device, requesting a new one if it is missing or expired.
+    ///
+    /// # Returns
+    /// The access token, an empty one for `Auth::Basic` devices, or an `Error` if the device does
+    /// not grant any.
+    pub async fn token(&self, device: &Device) -> Result<String, AppError> {
+        if let Auth::Basic(_) = device.auth {
+            return Ok(String::default());
+        }
+        let slot = self.slot(device).await;
+        let mut cached = slot.lock().await;
+
+        match cached.as_ref() {
+            Some(token) if token.is_fresh() => Ok(token.access_token.clone()),
+            _ => {
+                let renewed = Self::renew(device, cached.take()).await?;
+                let access_token = renewed.access_token.clone();
+                *cached = Some(renewed);
+                Ok(access_token)
+            }
+        }
+    }
+
+    /// Replaces a token the device rejected with a `401`.
+    ///
+    /// # Arguments
+    /// - `rejected`: The token sent in the rejected request.
+    ///
+    /// # Returns
+    /// A new token, or the cached one if another request already replaced the rejected token.
+    pub async fn renew_rejected(
+        &self,
+        device: &Device,
+        rejected: &str,
+    ) -> Result<String, AppError> {
+        let slot = self.slot(device).await;
+        let mut cached = slot.lock().await;
+
+        if let Some(token) = cached.as_ref() {
+            if token.access_token != rejected {
+                return Ok(token.access_token.clone());
+            }
+        }
+        let renewed = Self::renew(device, cached.take()).await?;
+        let access_token = renewed.access_token.clone();
+        *cached = Some(renewed);
+        Ok(access_token)
+    }
+
+    /// Drops the token of a device, e.g. when its credentials are replaced or it is deleted.
+    pub async fn invalidate(&self, device: &Device) {
+        self.slots.lock().await.remove(&device.get_full_auth_url());
+    }
+
+    /// Requests a new token, through the refresh token of the previous one when there is one.
+    async fn renew(
+        device: &Device,
+        previous: Option<CachedToken>,
+    ) -> Result<CachedToken, AppError> {
+        let url = device.get_full_auth_url();
+        match &device.auth {
+            Auth::OAuth2(oauth2_auth) => {
+                if let Some(refresh_token) = previous.and_then(|token| token.refresh_token) {
+                    let form = [
+                        ("grant_type", "refresh_token"),
+                        ("refresh_token", &refresh_token),
+                    ];
+                    match Self::oauth2_request(&url, oauth2_auth, &form, &device.tls).await {
+                        Ok(response) => {
+                            return CachedToken::from_oauth2_response(
+                                &response,
+                                Some(refresh_token),
+                            )
+                        }
+                        Err(err) => log::debug!(
+                            "{} rejected the refresh token ({}), requesting a new one",
+                            device.ip,
+                            err
+                        ),
+                    }
+                }
+
+                let mut form = vec![];
+                match oauth2_auth.grant_type {
+                    GrantType::ClientCredentials => form.push(("grant_type", "client_credentials")),
+                    GrantType::Password => {
+                        form.push(("grant_type", "password"));
+                        form.push((
+                            "username",
+                            oauth2_auth.username.as_deref().unwrap_or_default(),
+                        ));
+                        form.push((
+                            "password",
+                            oauth2_auth.password.as_deref().unwrap_or_default(),
+                        ));
+                    }
+                }
+                let response = Self::oauth2_request(&url, oauth2_auth, &form, &device.tls).await?;
+                CachedToken::from_oauth2_response(&response, None)
+            }
+            Auth::Token(token_auth) => Ok(CachedToken {
+                access_token: HttpHandler::get_token(&url, &token_auth.auth_body, &device.tls)
+                    .await?,
+                renew_at: None,
+                refresh_token: None,
+            }),
+            Auth::Basic(_) => Err(AppError::validation_error(
+                "Devices with basic authentication have no token",
+            )),
+        }
+    }
+
+    /// Sends a grant to the token endpoint, adding the scope and the client credentials.
+    async fn oauth2_request(
+        url: &String,
+        oauth2_auth: &OAuth2Auth,
+        form: &[(&str, &str)],
+        tls: &TlsConfig,
+    ) -> Result<Value, AppError> {
+        let mut form = form.to_vec();
+        if let Some(scope) = &oauth2_auth.scope {
+            form.push(("scope", scope));
+        }
+        HttpHandler::oauth2_token_request(
+            url,
+            &form,
+            oauth2_auth.client_id.as_deref(),
+            oauth2_auth.client_secret.as_deref(),
+            tls,
+        )
+        .await
+    }
+}
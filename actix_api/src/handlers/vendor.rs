@@ -0,0 +1,216 @@
+use crate::models::vendor_profile::VendorProfile;
+use crate::AppError;
+
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::sync::OnceLock;
+
+static VENDOR_PROFILES: OnceLock<VendorProfiles> = OnceLock::new();
+
+/// Profiles shipped with the binary, as `(file name, contents)`.
+const BUILTIN_PROFILES: [(&str, &str); 3] = [
+    ("generic.toml", include_str!("../../profiles/generic.toml")),
+    ("zte.toml", include_str!("../../profiles/zte.toml")),
+    ("ciena.toml", include_str!("../../profiles/ciena.toml")),
+];
+
+/// Directory read when `VENDOR_PROFILES_DIR` is not set.
+const DEFAULT_PROFILES_DIR: &str = "profiles";
+
+/// Name of the profile used when no other profile matches.
+pub const GENERIC_PROFILE: &str = "generic";
+
+/// Known `VendorProfile`s, by name.
+#[derive(Clone, Debug)]
+pub struct VendorProfiles {
+    profiles: Vec<VendorProfile>,
+}
+
+impl VendorProfiles {
+    /// Parses a profile written in TOML (`.toml`) or YAML (`.yaml`, `.yml`).
+    pub fn parse(file_name: &str, contents: &str) -> Result<VendorProfile, AppError> {
+        let extension = Path::new(file_name)
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .unwrap_or_default();
+
+        match extension {
+            "toml" => toml::from_str(contents)
+                .map_err(|err| AppError::validation_error(format!("{}: {}", file_name, err))),
+            "yaml" | "yml" => serde_yaml::from_str(contents)
+                .map_err(|err| AppError::validation_error(format!("{}: {}", file_name, err))),
+            _ => Err(AppError::validation_error(format!(
+                "{}: vendor profiles must be TOML or YAML",
+                file_name
+            ))),
+        }
+    }
+
+    /// Loads the profiles shipped with the binary.
+    pub fn builtin() -> Self {
+        let profiles = BUILTIN_PROFILES
+            .iter()
+            .map(|(file_name, contents)| {
+                Self::parse(file_name, contents).expect("Built-in vendor profiles are valid")
+            })
+            .collect();
+        Self { profiles }
+    }
+
+    /// Adds every profile of a directory, replacing the known profile with the same name.
+    pub fn load_dir(&mut self, dir: impl AsRef<Path>) -> Result<(), AppError> {
+        let entries = fs::read_dir(dir.as_ref())
+            .map_err(|err| AppError::validation_error(err.to_string()))?;
+
+        let mut paths = entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| {
+                matches!(
+                    path.extension().and_then(|extension| extension.to_str()),
+                    Some("toml" | "yaml" | "yml")
+                )
+            })
+            .collect::<Vec<_>>();
+        paths.sort();
+
+        for path in paths {
+            let contents = fs::read_to_string(&path)
+                .map_err(|err| AppError::validation_error(err.to_string()))?;
+            self.insert(Self::parse(&path.display().to_string(), &contents)?);
+        }
+        Ok(())
+    }
+
+    /// Built-in profiles plus the ones in `VENDOR_PROFILES_DIR`, `profiles` by default, if it exists.
+    pub fn from_env() -> Result<Self, AppError> {
+        let mut vendor_profiles = Self::builtin();
+        let dir = env::var("VENDOR_PROFILES_DIR").unwrap_or(DEFAULT_PROFILES_DIR.to_string());
+        if Path::new(&dir).is_dir() {
+            vendor_profiles.load_dir(&dir)?;
+        }
+        Ok(vendor_profiles)
+    }
+
+    /// Shared profiles, loaded from the environment on first use.
+    ///
+    /// Falls back to the built-in profiles if the directory cannot be read.
+    pub fn global() -> &'static Self {
+        VENDOR_PROFILES.get_or_init(|| {
+            Self::from_env().unwrap_or_else(|err| {
+                log::error!(
+                    "Vendor profiles cannot be loaded, using the built-in ones: {}",
+                    err
+                );
+                Self::builtin()
+            })
+        })
+    }
+
+    /// Adds a profile, replacing the known profile with the same name.
+    pub fn insert(&mut self, profile: VendorProfile) {
+        match self
+            .profiles
+            .iter_mut()
+            .find(|known| known.name == profile.name)
+        {
+            Some(known) => *known = profile,
+            None => self.profiles.push(profile),
+        }
+    }
+
+    /// Finds a profile by name.
+    pub fn get(&self, name: &str) -> Option<&VendorProfile> {
+        self.profiles.iter().find(|profile| profile.name == name)
+    }
+
+    /// Names of the known profiles.
+    pub fn names(&self) -> Vec<&str> {
+        self.profiles
+            .iter()
+            .map(|profile| profile.name.as_str())
+            .collect()
+    }
+
+    /// Finds the first profile whose modules are among the YANG modules, or the generic one.
+    pub fn detect(&self, modules: &[String]) -> VendorProfile {
+        self.profiles
+            .iter()
+            .find(|profile| profile.matches(modules))
+            .or_else(|| self.get(GENERIC_PROFILE))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Selects the profile named by the data source, detecting it from the YANG modules otherwise.
+    pub fn select(&self, vendor: Option<&str>, modules: &[String]) -> VendorProfile {
+        match vendor {
+            Some(name) => match self.get(name) {
+                Some(profile) => profile.clone(),
+                None => {
+                    log::warn!("Unknown vendor profile {}, detecting it", name);
+                    self.detect(modules)
+                }
+            },
+            None => self.detect(modules),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{VendorProfiles, GENERIC_PROFILE};
+    use crate::models::vendor_profile::{ExtensionEntity, VendorProfile};
+    use serde_json::json;
+
+    #[test]
+    fn test_builtin_profiles() {
+        let profiles = VendorProfiles::builtin();
+        assert_eq!(profiles.names(), vec!["generic", "zte", "ciena"]);
+        assert_eq!(
+            profiles.get(GENERIC_PROFILE).unwrap().names,
+            VendorProfile::default().names
+        );
+    }
+
+    #[test]
+    fn test_parse_yaml_profile() {
+        let profile = VendorProfiles::parse(
+            "acme.yaml",
+            "name: acme\nmodules: [acme-]\nnames:\n  node: [HOSTNAME]\nextensions:\n  - entity: node_edge_point\n    key: acme:port-speed\n    name: port_speed\n",
+        )
+        .unwrap();
+
+        assert_eq!(profile.names.node, vec!["HOSTNAME".to_string()]);
+        assert_eq!(profile.names.service, vec!["SERVICE_NAME".to_string()]);
+        assert_eq!(
+            profile.extract_extensions(
+                ExtensionEntity::NodeEdgePoint,
+                json!({"acme:port-speed": "100G", "other": 1})
+                    .as_object()
+                    .unwrap()
+            ),
+            [("port_speed".to_string(), json!("100G"))].into()
+        );
+
+        assert!(VendorProfiles::parse("acme.json", "{}").is_err());
+        assert!(VendorProfiles::parse("acme.toml", "modules = 1").is_err());
+    }
+
+    #[test]
+    fn test_select_profile() {
+        let profiles = VendorProfiles::builtin();
+        let modules = vec![
+            "tapi-common".to_string(),
+            "tapi-ciena-connection-extensions".to_string(),
+        ];
+
+        assert_eq!(profiles.detect(&modules).name, "ciena");
+        assert_eq!(
+            profiles.detect(&["tapi-common".to_string()]).name,
+            "generic"
+        );
+        assert_eq!(profiles.select(Some("zte"), &modules).name, "zte");
+        assert_eq!(profiles.select(Some("unknown"), &modules).name, "ciena");
+    }
+}
@@ -0,0 +1,130 @@
+use crate::{
+    handlers::database::{DatabaseHandler, SqlxBindValue},
+    models::device_health::{DeviceHealth, HealthStatus},
+    AppError,
+};
+use sqlx::{postgres::PgRow, FromRow, Row};
+
+impl<'r> FromRow<'r, PgRow> for DeviceHealth {
+    fn from_row(row: &'r PgRow) -> Result<Self, sqlx::Error> {
+        let status: String = row.try_get("status")?;
+        let status = status
+            .parse::<HealthStatus>()
+            .map_err(|err| sqlx::Error::ColumnDecode {
+                index: "status".into(),
+                source: Box::new(std::io::Error::other(err)),
+            })?;
+
+        Ok(Self {
+            status,
+            tcp_connect: row.try_get("tcp_connect")?,
+            tls_handshake: row.try_get("tls_handshake")?,
+            authenticated: row.try_get("authenticated")?,
+            restconf: row.try_get("restconf")?,
+            latency_ms: row.try_get("latency_ms")?,
+            error: row.try_get("error")?,
+            checked_at: row.try_get("checked_at")?,
+        })
+    }
+}
+
+impl DeviceHealth {
+    /// Inserts the result of checking the device with the given ip.
+    pub async fn create_health(
+        &self,
+        database_handler: &DatabaseHandler,
+        device_ip: impl Into<String>,
+    ) -> Result<i32, AppError> {
+        let optional_bool = |value: Option<bool>| match value {
+            Some(value) => SqlxBindValue::Bool(value),
+            None => SqlxBindValue::Null,
+        };
+        let latency_ms = match self.latency_ms {
+            Some(latency_ms) => SqlxBindValue::Int(latency_ms),
+            None => SqlxBindValue::Null,
+        };
+        let error = match &self.error {
+            Some(error) => SqlxBindValue::Str(error.clone()),
+            None => SqlxBindValue::Null,
+        };
+        let (id,): (i32,) = database_handler
+            .fetch_one(
+                r#"INSERT INTO device_health (checked_at, device_ip, status, tcp_connect, tls_handshake, authenticated, restconf, latency_ms, error)
+                   VALUES (clock_timestamp(), $1, $2, $3, $4::boolean, $5::boolean, $6::boolean, $7::integer, $8)
+                   RETURNING id"#,
+                vec![
+                    SqlxBindValue::Str(device_ip.into()),
+                    SqlxBindValue::Str(self.status.as_str().to_string()),
+                    SqlxBindValue::Bool(self.tcp_connect),
+                    optional_bool(self.tls_handshake),
+                    optional_bool(self.authenticated),
+                    optional_bool(self.restconf),
+                    latency_ms,
+                    error,
+                ],
+            )
+            .await?;
+        Ok(id)
+    }
+
+    /// Reads the last `limit` checks of the device, the most recent first.
+    pub async fn read_latest_by_device_ip(
+        database_handler: &DatabaseHandler,
+        device_ip: impl Into<String>,
+        limit: i32,
+    ) -> Result<Vec<Self>, AppError> {
+        database_handler
+            .fetch_all::<Self>(
+                r#"SELECT status, tcp_connect, tls_handshake, authenticated, restconf, latency_ms, error, checked_at
+                   FROM device_health
+                   WHERE device_ip = $1
+                   ORDER BY checked_at DESC
+                   LIMIT $2"#,
+                vec![
+                    SqlxBindValue::Str(device_ip.into()),
+                    SqlxBindValue::Int(limit),
+                ],
+            )
+            .await
+    }
+
+    /// Deletes the checks of the device older than its last `keep` ones.
+    pub async fn delete_older_by_device_ip(
+        database_handler: &DatabaseHandler,
+        device_ip: impl Into<String>,
+        keep: i32,
+    ) -> Result<usize, AppError> {
+        let ids: Vec<(i32,)> = database_handler
+            .fetch_all(
+                r#"DELETE FROM device_health
+                   WHERE device_ip = $1
+                   AND id NOT IN (
+                       SELECT id FROM device_health
+                       WHERE device_ip = $1
+                       ORDER BY checked_at DESC
+                       LIMIT $2
+                   )
+                   RETURNING id"#,
+                vec![
+                    SqlxBindValue::Str(device_ip.into()),
+                    SqlxBindValue::Int(keep),
+                ],
+            )
+            .await?;
+        Ok(ids.len())
+    }
+
+    /// Deletes every check stored for the device.
+    pub async fn delete_by_device_ip(
+        database_handler: &DatabaseHandler,
+        device_ip: impl Into<String>,
+    ) -> Result<usize, AppError> {
+        let ids: Vec<(i32,)> = database_handler
+            .fetch_all(
+                r#"DELETE FROM device_health WHERE device_ip = $1 RETURNING id"#,
+                vec![SqlxBindValue::Str(device_ip.into())],
+            )
+            .await?;
+        Ok(ids.len())
+    }
+}
@@ -1,6 +1,11 @@
 use crate::{
-    handlers::database::{DatabaseHandler, SqlxBindValue},
-    models::devices::{Auth, BasicAuth, Device, TokenAuth},
+    handlers::{
+        cipher::CipherHandler,
+        database::{DatabaseHandler, SqlxBindValue},
+    },
+    models::devices::{
+        Auth, BasicAuth, Device, OAuth2Auth, RetrievalStrategy, Scheme, TlsConfig, TokenAuth,
+    },
     AppError,
 };
 use sqlx::{postgres::PgRow, FromRow, Row};
@@ -13,7 +18,19 @@ impl<'r> FromRow<'r, PgRow> for Device {
         let port = port.map(|p| p as i64);
 
         // Obtener los campos relacionados a la autenticación
-        let auth_body: sqlx::types::Json<serde_json::Value> = row.try_get("auth_body")?;
+        // La columna auth_body solo guarda el JSON cifrado
+        let auth_body: String = row.try_get("auth_body")?;
+        let auth_body = CipherHandler::global()
+            .and_then(|cipher_handler| cipher_handler.decrypt(&auth_body))
+            .map_err(|err| sqlx::Error::ColumnDecode {
+                index: "auth_body".into(),
+                source: Box::new(err),
+            })?;
+        let auth_body: serde_json::Value =
+            serde_json::from_str(&auth_body).map_err(|err| sqlx::Error::ColumnDecode {
+                index: "auth_body".into(),
+                source: Box::new(err),
+            })?;
         let auth_type: String = row.try_get("auth_type")?;
         let auth_uri: Option<String> = row.try_get("auth_uri")?;
 
@@ -22,26 +39,22 @@ impl<'r> FromRow<'r, PgRow> for Device {
             "basic" => {
                 // Se espera que auth_body contenga un objeto JSON con "username" y "password"
                 let username = auth_body
-                    .0
                     .get("username")
                     .and_then(|v| v.as_str())
                     .ok_or_else(|| sqlx::Error::ColumnDecode {
                         index: "auth_body.username".into(),
-                        source: Box::new(std::io::Error::new(
-                            std::io::ErrorKind::Other,
+                        source: Box::new(std::io::Error::other(
                             "Falta el campo username en auth_body",
                         )),
                     })?
                     .to_string();
 
                 let password = auth_body
-                    .0
                     .get("password")
                     .and_then(|v| v.as_str())
                     .ok_or_else(|| sqlx::Error::ColumnDecode {
                         index: "auth_body.password".into(),
-                        source: Box::new(std::io::Error::new(
-                            std::io::ErrorKind::Other,
+                        source: Box::new(std::io::Error::other(
                             "Falta el campo password en auth_body",
                         )),
                     })?
@@ -53,35 +66,90 @@ impl<'r> FromRow<'r, PgRow> for Device {
                 // Se espera que auth_uri esté presente para autenticación de tipo token
                 let auth_uri = auth_uri.ok_or_else(|| sqlx::Error::ColumnDecode {
                     index: "auth_uri".into(),
-                    source: Box::new(std::io::Error::new(
-                        std::io::ErrorKind::Other,
+                    source: Box::new(std::io::Error::other(
                         "Falta auth_uri para autenticación token",
                     )),
                 })?;
                 Auth::Token(TokenAuth {
-                    auth_body: auth_body.0,
+                    auth_body,
                     auth_uri,
                 })
             }
+            "oauth2" => {
+                // auth_body guarda la configuración OAuth2 completa, incluido el token_uri
+                let oauth2_auth: OAuth2Auth =
+                    serde_json::from_value(auth_body).map_err(|err| sqlx::Error::ColumnDecode {
+                        index: "auth_body".into(),
+                        source: Box::new(err),
+                    })?;
+                Auth::OAuth2(oauth2_auth)
+            }
             other => {
                 return Err(sqlx::Error::ColumnDecode {
                     index: "auth_type".into(),
-                    source: Box::new(std::io::Error::new(
-                        std::io::ErrorKind::Other,
-                        format!("Tipo de autenticación desconocido: {}", other),
-                    )),
+                    source: Box::new(std::io::Error::other(format!(
+                        "Tipo de autenticación desconocido: {}",
+                        other
+                    ))),
                 });
             }
         };
 
-        Ok(Device { ip, port, auth })
+        let vendor: Option<String> = row.try_get("vendor")?;
+        let retrieval_strategy: Option<String> = row.try_get("retrieval_strategy")?;
+        let retrieval_strategy = retrieval_strategy
+            .map(|retrieval_strategy| retrieval_strategy.parse::<RetrievalStrategy>())
+            .transpose()
+            .map_err(|err| sqlx::Error::ColumnDecode {
+                index: "retrieval_strategy".into(),
+                source: Box::new(std::io::Error::other(err)),
+            })?;
+
+        // Configuración TLS; la clave privada del cliente se guarda cifrada
+        let scheme: String = row.try_get("tls_scheme")?;
+        let scheme = scheme
+            .parse::<Scheme>()
+            .map_err(|err| sqlx::Error::ColumnDecode {
+                index: "tls_scheme".into(),
+                source: Box::new(std::io::Error::other(err)),
+            })?;
+        let client_key: Option<String> = row.try_get("tls_client_key")?;
+        let client_key = client_key
+            .map(|client_key| {
+                CipherHandler::global()
+                    .and_then(|cipher_handler| cipher_handler.decrypt(&client_key))
+            })
+            .transpose()
+            .map_err(|err| sqlx::Error::ColumnDecode {
+                index: "tls_client_key".into(),
+                source: Box::new(err),
+            })?;
+        let tls = TlsConfig {
+            scheme,
+            ca_certificate: row.try_get("tls_ca_certificate")?,
+            client_certificate: row.try_get("tls_client_certificate")?,
+            client_key,
+            insecure: row.try_get("tls_insecure")?,
+        };
+
+        Ok(Device {
+            ip,
+            port,
+            auth,
+            vendor,
+            retrieval_strategy,
+            tls,
+        })
     }
 }
 
 impl Device {
     /// Convierte la instancia de Device en un vector de SqlxBindValue para usar en bind dinámico.
-    /// El orden de los valores es: ip, port, auth_body, auth_type, auth_uri.
-    pub fn to_bind_values(&self) -> Vec<SqlxBindValue> {
+    /// El orden de los valores es: ip, port, auth_type, auth_body, auth_uri, vendor, retrieval_strategy,
+    /// tls_scheme, tls_ca_certificate, tls_client_certificate, tls_client_key, tls_insecure.
+    /// El auth_body y la tls_client_key se cifran con el `CipherHandler` global.
+    pub fn to_bind_values(&self) -> Result<Vec<SqlxBindValue>, AppError> {
+        let cipher_handler = CipherHandler::global()?;
         let mut binds = Vec::new();
 
         // ip: siempre se envía como String.
@@ -93,7 +161,7 @@ impl Device {
             None => binds.push(SqlxBindValue::Null),
         }
 
-        // Para auth, diferenciamos entre Basic y Token.
+        // Para auth, diferenciamos entre Basic, Token y OAuth2.
         match &self.auth {
             Auth::Basic(basic_auth) => {
                 binds.push(SqlxBindValue::Str("basic".to_string()));
@@ -102,19 +170,57 @@ impl Device {
                     "username": basic_auth.username,
                     "password": basic_auth.password
                 });
-                binds.push(SqlxBindValue::Json(auth_body));
+                binds.push(SqlxBindValue::Str(
+                    cipher_handler.encrypt(&auth_body.to_string())?,
+                ));
                 // Para basic, no se utiliza auth_uri.
                 binds.push(SqlxBindValue::Null);
             }
             Auth::Token(token_auth) => {
-                // Para token, el auth_body se envía tal cual.
+                // Para token, el auth_body se cifra tal cual.
                 binds.push(SqlxBindValue::Str("token".to_string()));
-                binds.push(SqlxBindValue::Json(token_auth.auth_body.clone()));
+                binds.push(SqlxBindValue::Str(
+                    cipher_handler.encrypt(&token_auth.auth_body.to_string())?,
+                ));
                 binds.push(SqlxBindValue::Str(token_auth.auth_uri.clone()));
             }
+            Auth::OAuth2(oauth2_auth) => {
+                // Para oauth2, se cifra la configuración completa y el token_uri va en auth_uri.
+                binds.push(SqlxBindValue::Str("oauth2".to_string()));
+                let auth_body = serde_json::to_string(oauth2_auth)
+                    .map_err(|err| AppError::encryption_error(err.to_string()))?;
+                binds.push(SqlxBindValue::Str(cipher_handler.encrypt(&auth_body)?));
+                binds.push(SqlxBindValue::Str(oauth2_auth.token_uri.clone()));
+            }
         }
 
-        binds
+        // vendor: perfil elegido para el dispositivo (o Null para detectarlo).
+        match &self.vendor {
+            Some(vendor) => binds.push(SqlxBindValue::Str(vendor.clone())),
+            None => binds.push(SqlxBindValue::Null),
+        }
+
+        // retrieval_strategy: estrategia elegida para leer el contexto (o Null para medirla).
+        match &self.retrieval_strategy {
+            Some(strategy) => binds.push(SqlxBindValue::Str(strategy.as_str().to_string())),
+            None => binds.push(SqlxBindValue::Null),
+        }
+
+        // tls: esquema, certificados en PEM (o Null) y la clave del cliente cifrada.
+        let optional = |value: &Option<String>| match value {
+            Some(value) => SqlxBindValue::Str(value.clone()),
+            None => SqlxBindValue::Null,
+        };
+        binds.push(SqlxBindValue::Str(self.tls.scheme.as_str().to_string()));
+        binds.push(optional(&self.tls.ca_certificate));
+        binds.push(optional(&self.tls.client_certificate));
+        match &self.tls.client_key {
+            Some(client_key) => binds.push(SqlxBindValue::Str(cipher_handler.encrypt(client_key)?)),
+            None => binds.push(SqlxBindValue::Null),
+        }
+        binds.push(SqlxBindValue::Bool(self.tls.insecure));
+
+        Ok(binds)
     }
 
     pub async fn read_one_by_ip(
@@ -123,24 +229,66 @@ impl Device {
     ) -> Result<Self, AppError> {
         database_handler
             .fetch_one::<Self>(
-                r#"SELECT ip, port, auth_type, auth_body, auth_uri FROM devices WHERE ip = $1"#,
+                r#"SELECT ip, port, auth_type, auth_body, auth_uri, vendor, retrieval_strategy, tls_scheme, tls_ca_certificate, tls_client_certificate, tls_client_key, tls_insecure FROM devices WHERE ip = $1"#,
+                vec![SqlxBindValue::Str(ip.into())],
+            )
+            .await
+    }
+
+    pub async fn read_optional_by_ip(
+        database_handler: &DatabaseHandler,
+        ip: impl Into<String>,
+    ) -> Result<Option<Self>, AppError> {
+        database_handler
+            .fetch_optional::<Self>(
+                r#"SELECT ip, port, auth_type, auth_body, auth_uri, vendor, retrieval_strategy, tls_scheme, tls_ca_certificate, tls_client_certificate, tls_client_key, tls_insecure FROM devices WHERE ip = $1"#,
                 vec![SqlxBindValue::Str(ip.into())],
             )
             .await
     }
 
+    pub async fn read_all(database_handler: &DatabaseHandler) -> Result<Vec<Self>, AppError> {
+        database_handler
+            .fetch_all::<Self>(
+                r#"SELECT ip, port, auth_type, auth_body, auth_uri, vendor, retrieval_strategy, tls_scheme, tls_ca_certificate, tls_client_certificate, tls_client_key, tls_insecure FROM devices ORDER BY ip"#,
+                vec![],
+            )
+            .await
+    }
+
+    /// Inserts the device, replacing the stored one if the ip is already registered.
     pub async fn create_device(
         &self,
         database_handler: &DatabaseHandler,
     ) -> Result<String, AppError> {
-        let bind_values = self.to_bind_values();
+        let bind_values = self.to_bind_values()?;
         let (ip,): (String,) = database_handler.fetch_one(
-            r#"INSERT INTO devices (ip, port, auth_type, auth_body, auth_uri) VALUES ($1, $2, $3, $4, $5) RETURNING ip"#,
+            r#"INSERT INTO devices (ip, port, auth_type, auth_body, auth_uri, vendor, retrieval_strategy, tls_scheme, tls_ca_certificate, tls_client_certificate, tls_client_key, tls_insecure) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+               ON CONFLICT (ip) DO UPDATE SET port = EXCLUDED.port, auth_type = EXCLUDED.auth_type, auth_body = EXCLUDED.auth_body, auth_uri = EXCLUDED.auth_uri, vendor = EXCLUDED.vendor, retrieval_strategy = EXCLUDED.retrieval_strategy, tls_scheme = EXCLUDED.tls_scheme, tls_ca_certificate = EXCLUDED.tls_ca_certificate, tls_client_certificate = EXCLUDED.tls_client_certificate, tls_client_key = EXCLUDED.tls_client_key, tls_insecure = EXCLUDED.tls_insecure
+               RETURNING ip"#,
             bind_values,
         ).await?;
         Ok(ip)
     }
 
+    /// Remembers the strategy the context of the device is read with.
+    pub async fn update_retrieval_strategy(
+        database_handler: &DatabaseHandler,
+        ip: impl Into<String>,
+        retrieval_strategy: RetrievalStrategy,
+    ) -> Result<String, AppError> {
+        let (ip,): (String,) = database_handler
+            .fetch_one(
+                r#"UPDATE devices SET retrieval_strategy = $1 WHERE ip = $2 RETURNING ip"#,
+                vec![
+                    SqlxBindValue::Str(retrieval_strategy.as_str().to_string()),
+                    SqlxBindValue::Str(ip.into()),
+                ],
+            )
+            .await?;
+        Ok(ip)
+    }
+
     pub async fn delete_device(
         database_handler: &DatabaseHandler,
         ip: impl Into<String>,
@@ -153,4 +301,57 @@ impl Device {
             .await?;
         Ok(ip)
     }
+
+    /// Vuelve a cifrar el auth_body y la tls_client_key de todos los dispositivos con una nueva clave.
+    ///
+    /// Los valores que todavía están en texto plano se cifran directamente con `new_cipher`.
+    ///
+    /// # Returns
+    /// El número de dispositivos actualizados.
+    pub async fn reencrypt_all(
+        database_handler: &DatabaseHandler,
+        old_cipher: &CipherHandler,
+        new_cipher: &CipherHandler,
+    ) -> Result<usize, AppError> {
+        let rows: Vec<(String, String, Option<String>)> = database_handler
+            .fetch_all(
+                r#"SELECT ip, auth_body, tls_client_key FROM devices"#,
+                vec![],
+            )
+            .await?;
+
+        let reencrypt = |ip: &str, value: String| -> Result<String, AppError> {
+            let plaintext = if CipherHandler::is_ciphertext(&value) {
+                old_cipher
+                    .decrypt(&value)
+                    .map_err(|err| AppError::encryption_error(format!("Device {}: {}", ip, err)))?
+            } else {
+                value
+            };
+            new_cipher.encrypt(&plaintext)
+        };
+
+        let mut updated = 0;
+        for (ip, auth_body, client_key) in rows {
+            let auth_body = reencrypt(&ip, auth_body)?;
+            let client_key = match client_key {
+                Some(client_key) => SqlxBindValue::Str(reencrypt(&ip, client_key)?),
+                None => SqlxBindValue::Null,
+            };
+
+            let _: (String,) = database_handler
+                .fetch_one(
+                    r#"UPDATE devices SET auth_body = $1, tls_client_key = $2 WHERE ip = $3 RETURNING ip"#,
+                    vec![
+                        SqlxBindValue::Str(auth_body),
+                        client_key,
+                        SqlxBindValue::Str(ip),
+                    ],
+                )
+                .await?;
+            updated += 1;
+        }
+
+        Ok(updated)
+    }
 }
@@ -1,15 +1,55 @@
-use crate::logic::file_handler::get_file_path;
+use crate::handlers::database::{DatabaseHandler, SqlxBindValue};
+use crate::logic::context_index::{ContextIndex, IndexKind};
+use crate::logic::file_handler::{
+    extract_archive, get_file_path, promote_staged, FilePart, UploadFormat,
+};
 use crate::models::files_model::{ByPart, Complete, FilesEnum, UploadForm};
 use crate::AppError;
+use sqlx::{postgres::PgRow, FromRow, Row};
+use std::fs;
+use std::path::Path;
 
 impl UploadForm {
+    /// Form files required by the upload that were not sent: none when a complete context
+    /// file is present, otherwise every missing by-part file.
+    pub fn missing_files(&self) -> Vec<String> {
+        if self.complete_context_file.is_some() {
+            return Vec::new();
+        }
+        [
+            ("topology_file", &self.topology_file),
+            ("connections_file", &self.connections_file),
+            (
+                "connectivity_services_file",
+                &self.connectivity_services_file,
+            ),
+        ]
+        .into_iter()
+        .filter(|(_, file)| file.is_none())
+        .map(|(name, _)| name.to_string())
+        .collect()
+    }
+
     /// Converts the upload form into a `FilesEnum` based on the presence of files.
     ///
     /// # Returns
     /// - `FilesEnum::Complete` if a complete context file is provided.
     /// - `FilesEnum::ByPart` if topology, connections, and connectivity services files are provided.
+    /// - The `FilesEnum` of the parts found if the complete context file is a zip or tar.gz archive.
+    /// - A `ValidationError` naming the missing files otherwise.
     pub fn to_filesenum(&self) -> Result<FilesEnum, AppError> {
-        if self.complete_context_file.is_some() {
+        let missing_files = self.missing_files();
+        if !missing_files.is_empty() {
+            return Err(AppError::validation_error(format!(
+                "Missing {}",
+                missing_files.join(", ")
+            )));
+        }
+        if let Some(complete_context_file) = &self.complete_context_file {
+            if UploadFormat::detect(complete_context_file.file.as_file())?.is_archive() {
+                let parts = extract_archive(complete_context_file, &self.json.id)?;
+                return FilesEnum::from_parts(&self.json.id, parts);
+            }
             Ok(FilesEnum::Complete(Complete::from_uploadform(self)?))
         } else {
             Ok(FilesEnum::ByPart(ByPart::from_uploadform(self)?))
@@ -27,14 +67,14 @@ impl Complete {
     /// A `Complete` instance with the file path resolved.
     pub fn from_uploadform(form: &UploadForm) -> Result<Self, AppError> {
         let id = form.json.id.clone();
+        let complete_context_path =
+            get_file_path(form.complete_context_file.as_ref().unwrap(), &id, "context")?;
+        // Index the context now, so the first request does not scan the whole file
+        ContextIndex::load(&complete_context_path, IndexKind::Complete)?;
 
         Ok(Self {
             id: id.clone(),
-            complete_context_path: get_file_path(
-                form.complete_context_file.as_ref().unwrap(),
-                &id,
-                "context",
-            )?,
+            complete_context_path,
         })
     }
 }
@@ -49,6 +89,13 @@ impl ByPart {
     /// A `ByPart` instance with the paths of all part files resolved.
     pub fn from_uploadform(form: &UploadForm) -> Result<Self, AppError> {
         let id = form.json.id.clone();
+        let connectivity_services_path = get_file_path(
+            form.connectivity_services_file.as_ref().unwrap(),
+            &id,
+            "connectivity_services",
+        )?;
+        // Index the services now, so the first request does not scan the whole file
+        ContextIndex::load(&connectivity_services_path, IndexKind::Services)?;
 
         Ok(Self {
             id: id.clone(),
@@ -58,11 +105,246 @@ impl ByPart {
                 &id,
                 "connections",
             )?,
-            connectivity_services_path: get_file_path(
-                form.connectivity_services_file.as_ref().unwrap(),
-                &id,
-                "connectivity_services",
-            )?,
+            connectivity_services_path,
         })
     }
 }
+
+impl<'r> FromRow<'r, PgRow> for FilesEnum {
+    fn from_row(row: &'r PgRow) -> Result<Self, sqlx::Error> {
+        let id: String = row.try_get("id")?;
+        let kind: String = row.try_get("kind")?;
+
+        // Every path column is nullable, the kind decides which ones are required.
+        let required_path = |column: &str| -> Result<String, sqlx::Error> {
+            let path: Option<String> = row.try_get(column)?;
+            path.ok_or_else(|| sqlx::Error::ColumnDecode {
+                index: column.into(),
+                source: Box::new(std::io::Error::other(format!(
+                    "Missing {} for {} file set",
+                    column, kind
+                ))),
+            })
+        };
+
+        match kind.as_str() {
+            "complete" => Ok(FilesEnum::Complete(Complete {
+                complete_context_path: required_path("complete_context_path")?,
+                id,
+            })),
+            "by_part" => Ok(FilesEnum::ByPart(ByPart {
+                topology_path: required_path("topology_path")?,
+                connections_path: required_path("connections_path")?,
+                connectivity_services_path: required_path("connectivity_services_path")?,
+                id,
+            })),
+            other => Err(sqlx::Error::ColumnDecode {
+                index: "kind".into(),
+                source: Box::new(std::io::Error::other(format!(
+                    "Unknown file set kind: {}",
+                    other
+                ))),
+            }),
+        }
+    }
+}
+
+impl FilesEnum {
+    /// Creates the file set of the parts extracted from an archive: `Complete` if it holds a
+    /// complete context, `ByPart` if it holds the three parts.
+    ///
+    /// # Arguments
+    /// - `id`: Identifier of the upload.
+    /// - `parts`: Stored path of each part found in the archive.
+    ///
+    /// # Returns
+    /// The `FilesEnum`, or a `ValidationError` naming the missing parts. The stored files are
+    /// removed when no file set can be built from them.
+    pub fn from_parts(id: &str, parts: Vec<(FilePart, String)>) -> Result<Self, AppError> {
+        let path = |part: FilePart| {
+            parts
+                .iter()
+                .find(|(found, _)| *found == part)
+                .map(|(_, path)| path.clone())
+        };
+
+        let files_enum = match (
+            path(FilePart::Context),
+            path(FilePart::Topology),
+            path(FilePart::Connections),
+            path(FilePart::ConnectivityServices),
+        ) {
+            (Some(complete_context_path), _, _, _) => {
+                ContextIndex::load(&complete_context_path, IndexKind::Complete).map(|_| {
+                    FilesEnum::Complete(Complete {
+                        id: id.to_string(),
+                        complete_context_path,
+                    })
+                })
+            }
+            (
+                None,
+                Some(topology_path),
+                Some(connections_path),
+                Some(connectivity_services_path),
+            ) => ContextIndex::load(&connectivity_services_path, IndexKind::Services).map(|_| {
+                FilesEnum::ByPart(ByPart {
+                    id: id.to_string(),
+                    topology_path,
+                    connections_path,
+                    connectivity_services_path,
+                })
+            }),
+            _ => Err(AppError::validation_error(format!(
+                "Archive is missing {}",
+                [
+                    FilePart::Topology,
+                    FilePart::Connections,
+                    FilePart::ConnectivityServices
+                ]
+                .into_iter()
+                .filter(|part| path(*part).is_none())
+                .map(FilePart::file_name)
+                .collect::<Vec<_>>()
+                .join(", ")
+            ))),
+        };
+
+        // Parts not used by the file set are not kept on disk
+        let kept = files_enum
+            .as_ref()
+            .map(|files_enum| files_enum.paths())
+            .unwrap_or_default();
+        for (_, path) in &parts {
+            if !kept.contains(&path.as_str()) {
+                let _ = fs::remove_file(path);
+            }
+        }
+        files_enum
+    }
+
+    /// Identifier the file set was uploaded with.
+    pub fn id(&self) -> &str {
+        match self {
+            FilesEnum::Complete(complete) => &complete.id,
+            FilesEnum::ByPart(by_part) => &by_part.id,
+        }
+    }
+
+    /// Paths of every file on disk that belongs to this file set.
+    pub fn paths(&self) -> Vec<&str> {
+        match self {
+            FilesEnum::Complete(complete) => vec![complete.complete_context_path.as_str()],
+            FilesEnum::ByPart(by_part) => vec![
+                by_part.topology_path.as_str(),
+                by_part.connections_path.as_str(),
+                by_part.connectivity_services_path.as_str(),
+            ],
+        }
+    }
+
+    /// Moves the staged files of this set, and their indexes, to the paths they are registered
+    /// with, replacing the files of a previous upload with the same id.
+    ///
+    /// # Returns
+    /// The `FilesEnum` with the paths the files were moved to.
+    pub fn promote_staged(mut self) -> Result<Self, AppError> {
+        let paths = match &mut self {
+            FilesEnum::Complete(complete) => vec![&mut complete.complete_context_path],
+            FilesEnum::ByPart(by_part) => vec![
+                &mut by_part.topology_path,
+                &mut by_part.connections_path,
+                &mut by_part.connectivity_services_path,
+            ],
+        };
+        for path in paths {
+            *path = promote_staged(path)?;
+        }
+        Ok(self)
+    }
+
+    /// Removes the files of this set and their indexes from disk, ignoring the ones already gone.
+    pub fn remove_files(&self) -> Result<(), AppError> {
+        for path in self.paths() {
+            for path in [path.to_string(), ContextIndex::index_path(path)] {
+                if Path::new(&path).exists() {
+                    fs::remove_file(&path).map_err(|err| AppError::io_error(err.to_string()))?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Converts the file set into a vector of `SqlxBindValue`.
+    /// The order is: id, kind, complete_context_path, topology_path, connections_path, connectivity_services_path.
+    pub fn to_bind_values(&self) -> Vec<SqlxBindValue> {
+        match self {
+            FilesEnum::Complete(complete) => vec![
+                SqlxBindValue::Str(complete.id.clone()),
+                SqlxBindValue::Str("complete".to_string()),
+                SqlxBindValue::Str(complete.complete_context_path.clone()),
+                SqlxBindValue::Null,
+                SqlxBindValue::Null,
+                SqlxBindValue::Null,
+            ],
+            FilesEnum::ByPart(by_part) => vec![
+                SqlxBindValue::Str(by_part.id.clone()),
+                SqlxBindValue::Str("by_part".to_string()),
+                SqlxBindValue::Null,
+                SqlxBindValue::Str(by_part.topology_path.clone()),
+                SqlxBindValue::Str(by_part.connections_path.clone()),
+                SqlxBindValue::Str(by_part.connectivity_services_path.clone()),
+            ],
+        }
+    }
+
+    pub async fn read_optional_by_id(
+        database_handler: &DatabaseHandler,
+        id: impl Into<String>,
+    ) -> Result<Option<Self>, AppError> {
+        database_handler
+            .fetch_optional::<Self>(
+                r#"SELECT id, kind, complete_context_path, topology_path, connections_path, connectivity_services_path FROM file_sets WHERE id = $1"#,
+                vec![SqlxBindValue::Str(id.into())],
+            )
+            .await
+    }
+
+    pub async fn read_all(database_handler: &DatabaseHandler) -> Result<Vec<Self>, AppError> {
+        database_handler
+            .fetch_all::<Self>(
+                r#"SELECT id, kind, complete_context_path, topology_path, connections_path, connectivity_services_path FROM file_sets ORDER BY id"#,
+                vec![],
+            )
+            .await
+    }
+
+    /// Inserts the file set, replacing the stored one if the id is already registered.
+    pub async fn create_files(
+        &self,
+        database_handler: &DatabaseHandler,
+    ) -> Result<String, AppError> {
+        let (id,): (String,) = database_handler
+            .fetch_one(
+                r#"INSERT INTO file_sets (id, kind, complete_context_path, topology_path, connections_path, connectivity_services_path) VALUES ($1, $2, $3, $4, $5, $6)
+                   ON CONFLICT (id) DO UPDATE SET kind = EXCLUDED.kind, complete_context_path = EXCLUDED.complete_context_path, topology_path = EXCLUDED.topology_path, connections_path = EXCLUDED.connections_path, connectivity_services_path = EXCLUDED.connectivity_services_path
+                   RETURNING id"#,
+                self.to_bind_values(),
+            )
+            .await?;
+        Ok(id)
+    }
+
+    pub async fn delete_files(
+        database_handler: &DatabaseHandler,
+        id: impl Into<String>,
+    ) -> Result<String, AppError> {
+        let (id,): (String,) = database_handler
+            .fetch_one(
+                r#"DELETE FROM file_sets WHERE id = $1 RETURNING id"#,
+                vec![SqlxBindValue::Str(id.into())],
+            )
+            .await?;
+        Ok(id)
+    }
+}
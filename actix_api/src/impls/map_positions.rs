@@ -0,0 +1,61 @@
+use crate::{
+    handlers::database::{DatabaseHandler, SqlxBindValue},
+    models::map_positions::MapPositions,
+    AppError,
+};
+use sqlx::types::Json;
+
+impl MapPositions {
+    /// Reads the positions stored for the host with the given ip, empty if none were stored.
+    pub async fn read_by_device_ip(
+        database_handler: &DatabaseHandler,
+        device_ip: impl Into<String>,
+    ) -> Result<Self, AppError> {
+        let positions: Option<(Json<Self>,)> = database_handler
+            .fetch_optional(
+                r#"SELECT positions FROM map_positions WHERE device_ip = $1"#,
+                vec![SqlxBindValue::Str(device_ip.into())],
+            )
+            .await?;
+        Ok(positions
+            .map(|(Json(positions),)| positions)
+            .unwrap_or_default())
+    }
+
+    /// Stores the positions of the host with the given ip, replacing the previous ones.
+    pub async fn upsert_positions(
+        &self,
+        database_handler: &DatabaseHandler,
+        device_ip: impl Into<String>,
+    ) -> Result<(), AppError> {
+        let positions =
+            serde_json::to_value(self).map_err(|err| AppError::model_error(err.to_string()))?;
+        let _: (String,) = database_handler
+            .fetch_one(
+                r#"INSERT INTO map_positions (device_ip, positions)
+                   VALUES ($1, $2)
+                   ON CONFLICT (device_ip) DO UPDATE SET positions = EXCLUDED.positions
+                   RETURNING device_ip"#,
+                vec![
+                    SqlxBindValue::Str(device_ip.into()),
+                    SqlxBindValue::Json(positions),
+                ],
+            )
+            .await?;
+        Ok(())
+    }
+
+    /// Deletes the positions stored for the host.
+    pub async fn delete_by_device_ip(
+        database_handler: &DatabaseHandler,
+        device_ip: impl Into<String>,
+    ) -> Result<usize, AppError> {
+        let ips: Vec<(String,)> = database_handler
+            .fetch_all(
+                r#"DELETE FROM map_positions WHERE device_ip = $1 RETURNING device_ip"#,
+                vec![SqlxBindValue::Str(device_ip.into())],
+            )
+            .await?;
+        Ok(ips.len())
+    }
+}
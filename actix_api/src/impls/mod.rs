@@ -1,4 +1,7 @@
+pub mod device_health;
 pub mod devices;
 pub mod file_model;
+pub mod map_positions;
+pub mod restconf_validations;
 pub mod tapi_uris;
 pub mod user;
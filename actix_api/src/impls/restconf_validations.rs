@@ -0,0 +1,71 @@
+use crate::{
+    handlers::database::{DatabaseHandler, SqlxBindValue},
+    models::restconf_validations::RestconfValidation,
+    AppError,
+};
+
+impl RestconfValidation {
+    /// Inserts the result of probing a URI on the device with the given ip.
+    pub async fn create_validation(
+        &self,
+        database_handler: &DatabaseHandler,
+        device_ip: impl Into<String>,
+    ) -> Result<i32, AppError> {
+        let status_code = match self.status_code {
+            Some(status_code) => SqlxBindValue::Int(status_code),
+            None => SqlxBindValue::Null,
+        };
+        let response_type = match &self.response_type {
+            Some(response_type) => SqlxBindValue::Str(response_type.clone()),
+            None => SqlxBindValue::Null,
+        };
+        let (id,): (i32,) = database_handler
+            .fetch_one(
+                r#"INSERT INTO restconf_validations (validation_datetime, device_ip, uri_id, status_code, test_succesfull, response_type)
+                   VALUES (clock_timestamp(), $1, $2, $3::integer, $4, $5)
+                   RETURNING id"#,
+                vec![
+                    SqlxBindValue::Str(device_ip.into()),
+                    SqlxBindValue::Int(self.uri_id),
+                    status_code,
+                    SqlxBindValue::Bool(self.success),
+                    response_type,
+                ],
+            )
+            .await?;
+        Ok(id)
+    }
+
+    /// Reads the last result of each URI probed on the device, in the order of `tapi_uris`.
+    pub async fn read_latest_by_device_ip(
+        database_handler: &DatabaseHandler,
+        device_ip: impl Into<String>,
+    ) -> Result<Vec<Self>, AppError> {
+        database_handler
+            .fetch_all::<Self>(
+                r#"SELECT DISTINCT ON (tapi_uris.id) tapi_uris.id AS uri_id, tapi_uris.topic, tapi_uris.uri,
+                          restconf_validations.status_code, restconf_validations.response_type,
+                          COALESCE(restconf_validations.test_succesfull, FALSE) AS success,
+                          restconf_validations.validation_datetime
+                   FROM restconf_validations JOIN tapi_uris ON tapi_uris.id = restconf_validations.uri_id
+                   WHERE restconf_validations.device_ip = $1
+                   ORDER BY tapi_uris.id, restconf_validations.validation_datetime DESC"#,
+                vec![SqlxBindValue::Str(device_ip.into())],
+            )
+            .await
+    }
+
+    /// Deletes every result stored for the device.
+    pub async fn delete_by_device_ip(
+        database_handler: &DatabaseHandler,
+        device_ip: impl Into<String>,
+    ) -> Result<usize, AppError> {
+        let ids: Vec<(i32,)> = database_handler
+            .fetch_all(
+                r#"DELETE FROM restconf_validations WHERE device_ip = $1 RETURNING id"#,
+                vec![SqlxBindValue::Str(device_ip.into())],
+            )
+            .await?;
+        Ok(ids.len())
+    }
+}
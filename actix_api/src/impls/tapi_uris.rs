@@ -4,6 +4,9 @@ use crate::{
     AppError,
 };
 
+/// Migration seeding `tapi_uris`, also used to list the URIs when no database is configured.
+const TAPI_URIS_MIGRATION: &str = include_str!("../../migrations/20250206220245_tapi_uris.up.sql");
+
 impl TapiUri {
     pub async fn read_one_by_topic(
         database_handler: &DatabaseHandler,
@@ -17,9 +20,83 @@ impl TapiUri {
             .await
     }
 
+    /// Reads every URI except the ones whose dependency is still unknown (`?`).
     pub async fn read_all(database_handler: &DatabaseHandler) -> Result<Vec<Self>, AppError> {
         database_handler
-            .fetch_all::<Self>("SELECT * FROM tapi_uris WHERE NOT dependency = '?'", vec![])
+            .fetch_all::<Self>(
+                "SELECT * FROM tapi_uris WHERE dependency IS DISTINCT FROM '?' ORDER BY id",
+                vec![],
+            )
             .await
     }
+
+    /// URIs inserted by the `tapi_uris` migration, numbered in insertion order like the `SERIAL`
+    /// ids of a new database. As `read_all`, the ones with an unknown dependency are skipped.
+    pub fn seeded() -> Vec<Self> {
+        let rows = TAPI_URIS_MIGRATION
+            .lines()
+            .map(str::trim)
+            .filter(|line| line.starts_with("('"));
+
+        let mut uris = Vec::new();
+        for (index, row) in rows.enumerate() {
+            let values: Vec<Option<String>> = row
+                .trim_end_matches([',', ';'])
+                .trim_start_matches('(')
+                .trim_end_matches(')')
+                .split(',')
+                .map(str::trim)
+                .map(|value| {
+                    value
+                        .strip_prefix('\'')
+                        .and_then(|value| value.strip_suffix('\''))
+                        .map(str::to_string)
+                })
+                .collect();
+            let [Some(uri), Some(request_method), Some(topic), dependency] = values.as_slice()
+            else {
+                continue;
+            };
+            if dependency.as_deref() == Some("?") {
+                continue;
+            }
+
+            uris.push(Self {
+                id: index as i32 + 1,
+                uri: uri.clone(),
+                request_method: request_method.clone(),
+                topic: topic.clone(),
+                dependency: dependency.clone(),
+            });
+        }
+        uris
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::tapi_uris::TapiUri;
+
+    #[test]
+    fn test_seeded_tapi_uris() {
+        let uris = TapiUri::seeded();
+        assert_eq!(uris[0].topic, "host-meta");
+        assert_eq!(uris[0].id, 1);
+        assert!(uris
+            .iter()
+            .all(|uri| uri.dependency.as_deref() != Some("?")));
+
+        let node = uris
+            .iter()
+            .find(|uri| uri.topic == "node.by-parts")
+            .unwrap();
+        assert_eq!(
+            node.uri,
+            "/tapi-common:context/tapi-topology:topology-context/topology={uuid}/node={uuid}"
+        );
+        assert_eq!(node.dependency.as_deref(), Some("node.uuid-list"));
+        let last = uris.last().unwrap();
+        assert_eq!(last.topic, "stream-context.supported-stream-type");
+        assert_eq!(last.id, 52);
+    }
 }
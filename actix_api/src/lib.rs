@@ -1,6 +1,7 @@
 pub mod handlers;
 pub mod impls;
 pub mod logic; // Expose to bin
+pub mod mock; // Expose to tests and the mock_device bin
 pub mod models; // Expose to make tests
 pub mod routes; // Expose to bin
 pub mod utils; // Expose to make tests
@@ -29,6 +30,12 @@ pub enum AppError {
 
     ///Error on Encryption
     EncryptionError(String),
+
+    /// Error authenticating with a device: the credentials or the token were rejected
+    AuthenticationError(String),
+
+    /// Error reading or writing files on disk
+    IoError(String),
 }
 
 impl AppError {
@@ -73,6 +80,18 @@ impl AppError {
         let message = msg.into();
         AppError::EncryptionError(message)
     }
+
+    /// Constructor for AuthenticationError.
+    pub fn authentication_error(msg: impl Into<String>) -> Self {
+        let message = msg.into();
+        AppError::AuthenticationError(message)
+    }
+
+    /// Constructor for IoError.
+    pub fn io_error(msg: impl Into<String>) -> Self {
+        let message = msg.into();
+        AppError::IoError(message)
+    }
 }
 
 impl fmt::Display for AppError {
@@ -86,6 +105,8 @@ impl fmt::Display for AppError {
             AppError::ModelError(msg) => write!(f, "{}", msg),
             AppError::DatabaseError(msg) => write!(f, "{}", msg),
             AppError::EncryptionError(msg) => write!(f, "{}", msg),
+            AppError::AuthenticationError(msg) => write!(f, "{}", msg),
+            AppError::IoError(msg) => write!(f, "{}", msg),
         }
     }
 }
@@ -0,0 +1,395 @@
+use crate::AppError;
+
+use serde::de::{DeserializeOwned, IgnoredAny};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader, Bytes, Read, Seek, SeekFrom};
+use std::time::UNIX_EPOCH;
+
+/// Byte range of a JSON value inside a file.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Span {
+    pub start: u64,
+    pub end: u64,
+}
+
+/// Connectivity service found while indexing a file.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IndexedService {
+    pub uuid: Option<String>,
+    pub span: Span,
+}
+
+/// Layout of the file being indexed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IndexKind {
+    /// A complete `tapi-common:context` document.
+    Complete,
+    /// A list of connectivity services, as uploaded by parts.
+    Services,
+}
+
+/// Positions of the services, connections and topologies of an uploaded file, so they can be
+/// read on their own without parsing the whole file.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContextIndex {
+    /// Length of the file when it was indexed.
+    pub file_len: u64,
+    /// Modification time of the file when it was indexed, in seconds since the epoch.
+    pub modified: u64,
+    /// Services of the file, `None` when it has no list of connectivity services.
+    pub services: Option<Vec<IndexedService>>,
+    pub connections: Option<Span>,
+    pub topology: Option<Span>,
+}
+
+impl ContextIndex {
+    /// Path where the index of a file is stored.
+    pub fn index_path(path: &str) -> String {
+        format!("{}.index", path)
+    }
+
+    /// Loads the stored index of a file, building and storing it again when it is missing or
+    /// the file changed since it was taken.
+    ///
+    /// # Arguments
+    /// - `path`: Path of the indexed file.
+    /// - `kind`: Layout of the file.
+    ///
+    /// # Returns
+    /// The `ContextIndex` of the file or a `ValidationError` if it is not valid JSON.
+    pub fn load(path: &str, kind: IndexKind) -> Result<Self, AppError> {
+        let (file_len, modified) = file_version(path)?;
+        let stored = fs::read(Self::index_path(path))
+            .ok()
+            .and_then(|bytes| serde_json::from_slice::<Self>(&bytes).ok());
+        if let Some(index) = stored {
+            if index.file_len == file_len && index.modified == modified {
+                return Ok(index);
+            }
+        }
+
+        let index = Self::build(path, kind)?;
+        // A missing index is rebuilt on the next read, so failing to store it is not fatal.
+        match serde_json::to_vec(&index) {
+            Ok(bytes) => {
+                if let Err(err) = fs::write(Self::index_path(path), bytes) {
+                    log::warn!("Cannot store the index of {}: {}", path, err);
+                }
+            }
+            Err(err) => log::warn!("Cannot serialize the index of {}: {}", path, err),
+        }
+        Ok(index)
+    }
+
+    /// Scans a file once and records where its services, connections and topologies are.
+    ///
+    /// # Arguments
+    /// - `path`: Path of the file to index.
+    /// - `kind`: Layout of the file.
+    ///
+    /// # Returns
+    /// The `ContextIndex` of the file or a `ValidationError` if it is not valid JSON.
+    pub fn build(path: &str, kind: IndexKind) -> Result<Self, AppError> {
+        let (file_len, modified) = file_version(path)?;
+        let file = File::open(path).map_err(|err| AppError::io_error(err.to_string()))?;
+        let mut scanner = Scanner::new(BufReader::new(file));
+        let mut index = Self {
+            file_len,
+            modified,
+            services: None,
+            connections: None,
+            topology: None,
+        };
+
+        match kind {
+            IndexKind::Services => index.services = Some(scanner.services()?),
+            IndexKind::Complete => scanner.object(|scanner, key| match local_name(&key) {
+                "context" => scanner.object(|scanner, key| match local_name(&key) {
+                    "connectivity-context" => scanner.object(|scanner, key| {
+                        match local_name(&key) {
+                            "connectivity-service" => index.services = Some(scanner.services()?),
+                            "connection" => index.connections = Some(scanner.skip_value()?),
+                            _ => {
+                                scanner.skip_value()?;
+                            }
+                        }
+                        Ok(())
+                    }),
+                    "topology-context" => scanner.object(|scanner, key| {
+                        if local_name(&key) == "topology" {
+                            index.topology = Some(scanner.skip_value()?);
+                        } else {
+                            scanner.skip_value()?;
+                        }
+                        Ok(())
+                    }),
+                    _ => scanner.skip_value().map(|_| ()),
+                }),
+                _ => scanner.skip_value().map(|_| ()),
+            })?,
+        }
+        scanner.end()?;
+
+        Ok(index)
+    }
+
+    /// Services of the file or a `ValidationError` if it has no list of connectivity services.
+    pub fn services(&self) -> Result<&[IndexedService], AppError> {
+        self.services
+            .as_deref()
+            .ok_or_else(|| AppError::validation_error("Cannot find connectivity-context"))
+    }
+
+    /// Span of the connectivity service with the given UUID, if the file holds it.
+    pub fn service(&self, service_uuid: &str) -> Option<Span> {
+        self.services
+            .iter()
+            .flatten()
+            .find(|service| service.uuid.as_deref() == Some(service_uuid))
+            .map(|service| service.span)
+    }
+}
+
+/// Deserializes the JSON value found at `span` of a file, reading only that range.
+///
+/// # Arguments
+/// - `path`: Path of the indexed file.
+/// - `span`: Byte range of the value, as recorded in its `ContextIndex`.
+///
+/// # Returns
+/// The deserialized value or an `Error` if the range cannot be read or parsed.
+pub fn read_span<T: DeserializeOwned>(path: &str, span: Span) -> Result<T, AppError> {
+    let mut file = File::open(path).map_err(|err| AppError::io_error(err.to_string()))?;
+    file.seek(SeekFrom::Start(span.start))
+        .map_err(|err| AppError::io_error(err.to_string()))?;
+    serde_json::from_reader(BufReader::new(file.take(span.end - span.start)))
+        .map_err(|err| AppError::validation_error(err.to_string()))
+}
+
+/// Deserializes a whole JSON file straight from disk, without an intermediate string.
+pub fn read_file<T: DeserializeOwned>(path: &str) -> Result<T, AppError> {
+    let file = File::open(path).map_err(|err| AppError::io_error(err.to_string()))?;
+    serde_json::from_reader(BufReader::new(file))
+        .map_err(|err| AppError::validation_error(err.to_string()))
+}
+
+/// Checks that a file holds a single JSON document without keeping any of it in memory.
+pub fn validate_json<R: Read>(reader: R) -> Result<(), AppError> {
+    serde_json::from_reader::<_, IgnoredAny>(BufReader::new(reader))
+        .map(|_| ())
+        .map_err(|err| AppError::validation_error(err.to_string()))
+}
+
+/// Length and modification time of a file, used to notice stale indexes.
+fn file_version(path: &str) -> Result<(u64, u64), AppError> {
+    let metadata = fs::metadata(path).map_err(|err| AppError::io_error(err.to_string()))?;
+    let modified = metadata
+        .modified()
+        .ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default();
+    Ok((metadata.len(), modified))
+}
+
+/// Name of a key without its YANG module prefix.
+fn local_name(key: &str) -> &str {
+    key.rsplit(':').next().unwrap_or(key)
+}
+
+/// Minimal JSON reader that walks a document byte by byte, keeping track of the offset, and
+/// only materializes the keys and UUIDs it is asked for.
+struct Scanner<R: BufRead> {
+    bytes: Bytes<R>,
+    peeked: Option<u8>,
+    position: u64,
+}
+
+impl<R: BufRead> Scanner<R> {
+    fn new(reader: R) -> Self {
+        Self {
+            bytes: reader.bytes(),
+            peeked: None,
+            position: 0,
+        }
+    }
+
+    fn error(&self, message: &str) -> AppError {
+        AppError::validation_error(format!("{} at byte {}", message, self.position))
+    }
+
+    fn peek(&mut self) -> Result<Option<u8>, AppError> {
+        if self.peeked.is_none() {
+            self.peeked = self
+                .bytes
+                .next()
+                .transpose()
+                .map_err(|err| AppError::io_error(err.to_string()))?;
+        }
+        Ok(self.peeked)
+    }
+
+    fn next(&mut self) -> Result<u8, AppError> {
+        let byte = self
+            .peek()?
+            .ok_or_else(|| self.error("Unexpected end of file"))?;
+        self.peeked = None;
+        self.position += 1;
+        Ok(byte)
+    }
+
+    fn skip_whitespace(&mut self) -> Result<(), AppError> {
+        while let Some(b' ' | b'\n' | b'\r' | b'\t') = self.peek()? {
+            self.next()?;
+        }
+        Ok(())
+    }
+
+    fn expect(&mut self, expected: u8) -> Result<(), AppError> {
+        self.skip_whitespace()?;
+        if self.next()? != expected {
+            return Err(self.error(&format!("Expected '{}'", expected as char)));
+        }
+        Ok(())
+    }
+
+    /// Fails unless only whitespace is left.
+    fn end(&mut self) -> Result<(), AppError> {
+        self.skip_whitespace()?;
+        match self.peek()? {
+            Some(_) => Err(self.error("Trailing characters")),
+            None => Ok(()),
+        }
+    }
+
+    /// Reads the rest of a string whose opening quote was consumed, returning its raw bytes.
+    fn raw_string(&mut self) -> Result<Vec<u8>, AppError> {
+        let mut raw = vec![b'"'];
+        loop {
+            let byte = self.next()?;
+            raw.push(byte);
+            match byte {
+                b'"' => return Ok(raw),
+                b'\\' => raw.push(self.next()?),
+                _ => {}
+            }
+        }
+    }
+
+    fn string(&mut self) -> Result<String, AppError> {
+        self.expect(b'"')?;
+        let raw = self.raw_string()?;
+        serde_json::from_slice(&raw).map_err(|_| self.error("Invalid string"))
+    }
+
+    /// Skips the next value, returning its span.
+    fn skip_value(&mut self) -> Result<Span, AppError> {
+        self.skip_whitespace()?;
+        let start = self.position;
+        match self.next()? {
+            b'"' => {
+                self.raw_string()?;
+            }
+            b'{' | b'[' => {
+                let mut depth = 1;
+                while depth > 0 {
+                    match self.next()? {
+                        b'"' => {
+                            self.raw_string()?;
+                        }
+                        b'{' | b'[' => depth += 1,
+                        b'}' | b']' => depth -= 1,
+                        _ => {}
+                    }
+                }
+            }
+            b'}' | b']' | b',' | b':' => return Err(self.error("Expected a value")),
+            _ => {
+                while let Some(byte) = self.peek()? {
+                    if matches!(byte, b',' | b'}' | b']' | b' ' | b'\n' | b'\r' | b'\t') {
+                        break;
+                    }
+                    self.next()?;
+                }
+            }
+        }
+        Ok(Span {
+            start,
+            end: self.position,
+        })
+    }
+
+    /// Walks an object, calling `entry` with each key. `entry` must consume the value.
+    fn object<F>(&mut self, mut entry: F) -> Result<(), AppError>
+    where
+        F: FnMut(&mut Self, String) -> Result<(), AppError>,
+    {
+        self.expect(b'{')?;
+        self.skip_whitespace()?;
+        if self.peek()? == Some(b'}') {
+            self.next()?;
+            return Ok(());
+        }
+        loop {
+            let key = self.string()?;
+            self.expect(b':')?;
+            entry(self, key)?;
+            self.skip_whitespace()?;
+            match self.next()? {
+                b',' => continue,
+                b'}' => return Ok(()),
+                _ => return Err(self.error("Expected ',' or '}'")),
+            }
+        }
+    }
+
+    /// Walks an array, calling `element` for each item. `element` must consume the value.
+    fn array<F>(&mut self, mut element: F) -> Result<(), AppError>
+    where
+        F: FnMut(&mut Self) -> Result<(), AppError>,
+    {
+        self.expect(b'[')?;
+        self.skip_whitespace()?;
+        if self.peek()? == Some(b']') {
+            self.next()?;
+            return Ok(());
+        }
+        loop {
+            element(self)?;
+            self.skip_whitespace()?;
+            match self.next()? {
+                b',' => continue,
+                b']' => return Ok(()),
+                _ => return Err(self.error("Expected ',' or ']'")),
+            }
+        }
+    }
+
+    /// Records the span and UUID of every service of a list.
+    fn services(&mut self) -> Result<Vec<IndexedService>, AppError> {
+        let mut services = Vec::new();
+        self.array(|scanner| {
+            scanner.skip_whitespace()?;
+            let start = scanner.position;
+            let mut uuid = None;
+            scanner.object(|scanner, key| {
+                if key == "uuid" {
+                    uuid = Some(scanner.string()?);
+                } else {
+                    scanner.skip_value()?;
+                }
+                Ok(())
+            })?;
+            services.push(IndexedService {
+                uuid,
+                span: Span {
+                    start,
+                    end: scanner.position,
+                },
+            });
+            Ok(())
+        })?;
+        Ok(services)
+    }
+}
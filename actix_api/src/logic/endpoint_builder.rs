@@ -1,9 +1,11 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use tapi_model::Uuid;
+
 use crate::models::{
-    connections::Connection,
     connectivity_services::Service,
-    endpoint::{BaseEndpoint, Endpoint},
-    links::Link,
-    nodes::Node,
+    endpoint::{BaseEndpoint, Endpoint, HopDirection},
+    schema::{TraceWarning, TraceWarningKind},
+    topology_graph::TopologyGraph,
 };
 
 /// Builds a vector of `Endpoint` objects based on the provided service and related entities.
@@ -11,31 +13,28 @@ use crate::models::{
 /// # Arguments
 ///
 /// * `service` - A reference to the `Service` containing base endpoint data.
-/// * `link_vector` - A reference to a vector of `Link` objects.
-/// * `node_vector` - A reference to a vector of `Node` objects.
-/// * `connection_vector` - A reference to a vector of `Connection` objects.
+/// * `graph` - A reference to the `TopologyGraph` with the links, nodes and connections.
 ///
 /// # Returns
 ///
-/// A vector of `Endpoint` objects constructed from the provided data.
+/// A vector of `Endpoint` objects constructed from the provided data, in order along the route of
+/// the service, and the anomalies of the topology found on the way, once each.
 pub fn build_endpoint_vector(
     service: &Service,
-    link_vector: &Vec<Link>,
-    node_vector: &Vec<Node>,
-    connection_vector: &Vec<Connection>,
-) -> Vec<Endpoint> {
+    graph: &TopologyGraph,
+) -> (Vec<Endpoint>, Vec<TraceWarning>) {
     let mut endpoint_vector: Vec<Endpoint> = Vec::new();
-    let mut base_endpoint_vector: Vec<BaseEndpoint> = service.first_base_endpoint_vector();
-    let mut processed_node_edge_uuids: Vec<String> = Vec::new(); // It will use to keep track of processed UUID's.
+    let mut warnings: Vec<TraceWarning> = Vec::new();
+    let mut base_endpoint_vector: VecDeque<BaseEndpoint> =
+        service.first_base_endpoint_vector().into();
+    let mut processed_node_edge_uuids: HashSet<Uuid> = HashSet::new(); // It will use to keep track of processed UUID's.
 
-    while let Some(base_endpoint) = base_endpoint_vector.pop() {
+    while let Some(base_endpoint) = base_endpoint_vector.pop_back() {
         // Skip already processed UUIDs
-        if !processed_node_edge_uuids.contains(&base_endpoint.node_edge_point_uuid) {
-            processed_node_edge_uuids.push(base_endpoint.node_edge_point_uuid.clone()); // Añadir UUID a la lista de procesados
-            let (endpoint, extend_base_endpoint_vector) =
-                base_endpoint.build(link_vector, node_vector, connection_vector);
+        if processed_node_edge_uuids.insert(base_endpoint.node_edge_point_uuid) {
+            let (endpoint, extend_base_endpoint_vector) = base_endpoint.build(graph, &mut warnings);
 
-            if let Some(endpoint_connection_uuid) = endpoint.connection_uuid.clone() {
+            if let Some(endpoint_connection_uuid) = endpoint.connection_uuid {
                 // Verify if the connection belongs to the current service
                 if service
                     .connections
@@ -46,23 +45,150 @@ pub fn build_endpoint_vector(
                     })
                 {
                     endpoint_vector.push(endpoint);
-                    base_endpoint_vector.splice(0..0, extend_base_endpoint_vector);
+                    prepend(&mut base_endpoint_vector, extend_base_endpoint_vector);
                 } else {
-                    println!(
-                        "This connection belongs to other service: {}",
-                        endpoint_connection_uuid
+                    let mut warning = TraceWarning::new(
+                        TraceWarningKind::ForeignConnection,
+                        endpoint.node_uuid,
+                        endpoint.node_edge_point_uuid,
+                        format!(
+                            "Connection {} belongs to another service",
+                            endpoint_connection_uuid
+                        ),
                     );
+                    warning.connection_uuid = Some(endpoint_connection_uuid);
+                    warnings.push(warning);
                 }
             } else {
-                println!(
-                    "This endpoint node_edge_point_uuid dont have connection uuid: {}",
-                    endpoint.node_edge_point_uuid
-                );
+                // Edge points without connection, e.g. reached through a link, are traced as well
                 endpoint_vector.push(endpoint);
-                base_endpoint_vector.splice(0..0, extend_base_endpoint_vector);
+                prepend(&mut base_endpoint_vector, extend_base_endpoint_vector);
+            }
+        }
+    }
+
+    let mut seen_warnings: HashSet<TraceWarning> = HashSet::new();
+    let unique_warnings: Vec<TraceWarning> = warnings
+        .into_iter()
+        .filter(|warning| seen_warnings.insert(warning.clone()))
+        .collect();
+
+    (order_route(service, endpoint_vector), unique_warnings)
+}
+
+/// Numbers the endpoints of a service along its route, from its A-end, and sorts them in that order.
+///
+/// The route starts at the edge points of the first end point of the service and visits the
+/// endpoints breadth first: across the connections and links they share, and between layers through
+/// client edge points and lower connections. Neighbours are visited in edge point UUID order, so the
+/// numbering does not depend on the order the trace found the endpoints. Crossing a connection or a
+/// link switches between ingress and egress, changing layer keeps the direction. Endpoints the A-end
+/// does not reach are numbered afterwards, from the next end point of the service.
+fn order_route(service: &Service, endpoint_vector: Vec<Endpoint>) -> Vec<Endpoint> {
+    let mut by_node_edge_point: HashMap<Uuid, Vec<usize>> = HashMap::new();
+    let mut by_client: HashMap<Uuid, Vec<usize>> = HashMap::new();
+    let mut by_connection: HashMap<Uuid, Vec<usize>> = HashMap::new();
+    let mut by_lower_connection: HashMap<Uuid, Vec<usize>> = HashMap::new();
+    let mut by_link: HashMap<Uuid, Vec<usize>> = HashMap::new();
+    for (position, endpoint) in endpoint_vector.iter().enumerate() {
+        let indexes = [
+            (&mut by_node_edge_point, Some(endpoint.node_edge_point_uuid)),
+            (&mut by_client, endpoint.client_node_edge_point_uuid),
+            (&mut by_connection, endpoint.connection_uuid),
+            (&mut by_lower_connection, endpoint.lower_connection),
+            (&mut by_link, endpoint.link_uuid),
+        ];
+        for (index, uuid) in indexes {
+            if let Some(uuid) = uuid {
+                index.entry(uuid).or_default().push(position);
+            }
+        }
+    }
+    let related = |index: &HashMap<Uuid, Vec<usize>>, uuid: Option<Uuid>| {
+        uuid.and_then(|uuid| index.get(&uuid))
+            .into_iter()
+            .flatten()
+            .copied()
+            .collect::<Vec<usize>>()
+    };
+
+    // Neighbours of each endpoint, and whether reaching them crosses a connection or a link
+    let neighbours = |position: usize| {
+        let endpoint = &endpoint_vector[position];
+        let crossings = related(&by_connection, endpoint.connection_uuid)
+            .into_iter()
+            .chain(related(&by_link, endpoint.link_uuid))
+            .map(|neighbour| (neighbour, true));
+        let layers = related(&by_node_edge_point, endpoint.client_node_edge_point_uuid)
+            .into_iter()
+            .chain(related(&by_client, Some(endpoint.node_edge_point_uuid)))
+            .chain(related(&by_connection, endpoint.lower_connection))
+            .chain(related(&by_lower_connection, endpoint.connection_uuid))
+            .map(|neighbour| (neighbour, false));
+        let mut neighbours: Vec<(usize, bool)> = layers
+            .chain(crossings)
+            .filter(|(neighbour, _)| *neighbour != position)
+            .collect();
+        neighbours.sort_by_key(|&(neighbour, crossing)| {
+            (endpoint_vector[neighbour].node_edge_point_uuid, crossing)
+        });
+        neighbours.dedup_by_key(|(neighbour, _)| *neighbour);
+        neighbours
+    };
+
+    let starts = service
+        .end_points
+        .iter()
+        .flat_map(|end_point| &end_point.connection_end_points)
+        .flat_map(|connection_end_point| {
+            related(
+                &by_node_edge_point,
+                Some(connection_end_point.node_edge_point_uuid),
+            )
+        })
+        .chain(0..endpoint_vector.len());
+
+    let mut visited = vec![false; endpoint_vector.len()];
+    let mut route: Vec<(usize, HopDirection)> = Vec::with_capacity(endpoint_vector.len());
+    for start in starts {
+        if visited[start] {
+            continue;
+        }
+        visited[start] = true;
+        let mut queue = VecDeque::from([(start, HopDirection::Ingress)]);
+        while let Some((position, direction)) = queue.pop_front() {
+            route.push((position, direction));
+            for (neighbour, crossing) in neighbours(position) {
+                if !visited[neighbour] {
+                    visited[neighbour] = true;
+                    let direction = if crossing {
+                        direction.reverse()
+                    } else {
+                        direction
+                    };
+                    queue.push_back((neighbour, direction));
+                }
             }
         }
     }
 
-    endpoint_vector
+    let mut endpoint_vector: Vec<Option<Endpoint>> =
+        endpoint_vector.into_iter().map(Some).collect();
+    route
+        .into_iter()
+        .zip(1..)
+        .filter_map(|((position, direction), hop)| {
+            let mut endpoint = endpoint_vector[position].take()?;
+            endpoint.id = hop;
+            endpoint.direction = direction;
+            Some(endpoint)
+        })
+        .collect()
+}
+
+/// Queues base endpoints ahead of the pending ones, keeping their order.
+fn prepend(base_endpoint_vector: &mut VecDeque<BaseEndpoint>, base_endpoints: Vec<BaseEndpoint>) {
+    for base_endpoint in base_endpoints.into_iter().rev() {
+        base_endpoint_vector.push_front(base_endpoint);
+    }
 }
@@ -1,29 +1,170 @@
+use crate::logic::context_index::{validate_json, ContextIndex};
+use crate::utils::xml_to_tapi_json;
 use crate::AppError;
 use actix_multipart::form::tempfile::TempFile;
-use serde_json::Value;
-use std::fs::File;
-use std::io::{self, Read};
+use flate2::read::GzDecoder;
+use std::fs::{self, File};
+use std::io::{self, Read, Seek, SeekFrom};
 use std::path::Path;
 
-/// Reads a `TempFile` and deserializes its content into a JSON `Value`.
-///
-/// # Arguments
-///
-/// * `file` - Reference to the `TempFile` to be read.
-///
-/// # Returns
-///
-/// An `io::Result` containing the JSON `Value` or an I/O error.
-fn read_json_from_file(file: &TempFile) -> io::Result<Value> {
-    let mut file_content = String::new();
-    // Read file content into a string
-    file.file.as_file().read_to_string(&mut file_content)?;
-    // Deserialize the string as JSON
-    let json: Value = serde_json::from_str(&file_content)?;
-    Ok(json)
+/// Bytes read from a document to detect which part of a file set it holds.
+const DETECTION_HEAD: u64 = 64 * 1024;
+
+/// Suffix of the files of an upload until it is validated, so a rejected upload never replaces
+/// the files registered with the same id.
+pub const STAGED_SUFFIX: &str = ".staged";
+
+/// Part of a file set held by an uploaded JSON document.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FilePart {
+    Context,
+    Topology,
+    Connections,
+    ConnectivityServices,
+}
+
+impl FilePart {
+    /// Name of the stored file of the part, without id or extension.
+    pub fn file_name(self) -> &'static str {
+        match self {
+            FilePart::Context => "context",
+            FilePart::Topology => "topology",
+            FilePart::Connections => "connections",
+            FilePart::ConnectivityServices => "connectivity_services",
+        }
+    }
+
+    /// Detects the part from the first bytes of a document: a `tapi-common:context` object or
+    /// a list of topologies, connectivity services or connections.
+    fn from_content(head: &[u8]) -> Option<Self> {
+        let head = String::from_utf8_lossy(head);
+        match head.trim_start().chars().next()? {
+            '{' if head.contains("\"tapi-common:context\"") => Some(FilePart::Context),
+            '[' if ["\"node\"", "\"link\"", "\"owned-node-edge-point\""]
+                .iter()
+                .any(|key| head.contains(key)) =>
+            {
+                Some(FilePart::Topology)
+            }
+            '[' if head.contains("\"end-point\"") => Some(FilePart::ConnectivityServices),
+            '[' if head.contains("\"connection-end-point\"") => Some(FilePart::Connections),
+            _ => None,
+        }
+    }
+
+    /// Detects the part from the name of an archive member, e.g. `export/topology.json`.
+    fn from_name(name: &str) -> Option<Self> {
+        let name = name.rsplit('/').next().unwrap_or(name).to_lowercase();
+        if name.contains("service") {
+            Some(FilePart::ConnectivityServices)
+        } else if name.contains("connection") {
+            Some(FilePart::Connections)
+        } else if name.contains("topology") {
+            Some(FilePart::Topology)
+        } else if name.contains("context") {
+            Some(FilePart::Context)
+        } else {
+            None
+        }
+    }
+}
+
+/// Format of an uploaded file, detected from its first bytes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UploadFormat {
+    Json,
+    Gzip,
+    TarGz,
+    Zip,
+}
+
+impl UploadFormat {
+    /// Detects the format of a file and rewinds it.
+    pub fn detect(mut file: &File) -> Result<Self, AppError> {
+        let mut magic = [0u8; 4];
+        let read = file
+            .read(&mut magic)
+            .map_err(|err| AppError::io_error(err.to_string()))?;
+        let format = match &magic[..read] {
+            [0x50, 0x4b, 0x03, 0x04] => UploadFormat::Zip,
+            [0x1f, 0x8b, ..] => {
+                file.seek(SeekFrom::Start(0))
+                    .map_err(|err| AppError::io_error(err.to_string()))?;
+                // A tar header has the `ustar` magic at offset 257
+                let mut header = Vec::new();
+                GzDecoder::new(file)
+                    .take(262)
+                    .read_to_end(&mut header)
+                    .map_err(|err| AppError::validation_error(err.to_string()))?;
+                if header.get(257..262) == Some(b"ustar".as_slice()) {
+                    UploadFormat::TarGz
+                } else {
+                    UploadFormat::Gzip
+                }
+            }
+            _ => UploadFormat::Json,
+        };
+        file.seek(SeekFrom::Start(0))
+            .map_err(|err| AppError::io_error(err.to_string()))?;
+        Ok(format)
+    }
+
+    /// Whether the format bundles several documents.
+    pub fn is_archive(self) -> bool {
+        matches!(self, UploadFormat::TarGz | UploadFormat::Zip)
+    }
+}
+
+/// Writes a JSON or XML document to `data/{id}_{file_name}.json.staged`, removing it again if
+/// it is not valid. XML documents are converted with `xml_to_tapi_json`.
+fn store_json(mut reader: impl Read, id: &str, file_name: &str) -> Result<String, AppError> {
+    let base_path = Path::new("data");
+    // Construct the output file path
+    let file_path = base_path.join(format!("{}_{}.json{}", id, file_name, STAGED_SUFFIX));
+    // Create the output file
+    let mut file = File::create(&file_path).map_err(|err| AppError::io_error(err.to_string()))?;
+    // Copy the JSON data to the file
+    io::copy(&mut reader, &mut file).map_err(|err| AppError::validation_error(err.to_string()))?;
+
+    let result = convert_xml(&file_path).and_then(|_| {
+        File::open(&file_path)
+            .map_err(|err| AppError::io_error(err.to_string()))
+            .and_then(validate_json)
+    });
+    if let Err(err) = result {
+        let _ = fs::remove_file(&file_path);
+        return Err(AppError::validation_error(format!(
+            "{} file: {}",
+            file_name, err
+        )));
+    }
+
+    Ok(file_path.to_string_lossy().to_string())
+}
+
+/// Rewrites a stored document as JSON if it holds XML.
+fn convert_xml(path: &Path) -> Result<(), AppError> {
+    let mut head = Vec::new();
+    File::open(path)
+        .and_then(|file| file.take(DETECTION_HEAD).read_to_end(&mut head))
+        .map_err(|err| AppError::io_error(err.to_string()))?;
+    if head.trim_ascii_start().first() != Some(&b'<') {
+        return Ok(());
+    }
+
+    let xml =
+        fs::read_to_string(path).map_err(|err| AppError::validation_error(err.to_string()))?;
+    let json = xml_to_tapi_json(&xml)?;
+    let file = File::create(path).map_err(|err| AppError::io_error(err.to_string()))?;
+    serde_json::to_writer(io::BufWriter::new(file), &json)
+        .map_err(|err| AppError::io_error(err.to_string()))
 }
 
-/// Generates a file path, writes JSON data from a `TempFile` to a new file, and saves it.
+/// Generates a file path, copies a JSON `TempFile` to the new file and checks that it holds JSON.
+///
+/// Gzipped files are decompressed while they are copied, so JSON uploads are never held in
+/// memory. XML uploads are converted to JSON once stored. The file is staged until
+/// `promote_staged` moves it into place.
 ///
 /// # Arguments
 ///
@@ -36,23 +177,153 @@ fn read_json_from_file(file: &TempFile) -> io::Result<Value> {
 /// A `Result` containing the path to the written file or an error.
 pub fn get_file_path(
     complete_context_file: &TempFile,
-    id: &String,
+    id: &str,
     file_name: &str,
 ) -> Result<String, AppError> {
-    match read_json_from_file(complete_context_file) {
-        Ok(value) => {
-            let base_path = Path::new("data");
-            // Construct the output file path
-            let file_path = base_path.join(format!("{}_{}.json", id, file_name));
-            // Create the output file
-            let file = File::create(&file_path)
-                .map_err(|err| AppError::database_error(err.to_string()))?;
-            // Write the JSON data to the file
-            serde_json::to_writer_pretty(file, &value)
-                .map_err(|err| AppError::database_error(err.to_string()))?;
-
-            Ok(file_path.to_string_lossy().to_string())
-        }
-        Err(err) => Err(AppError::database_error(err.to_string())),
+    let upload = complete_context_file.file.as_file();
+    match UploadFormat::detect(upload)? {
+        UploadFormat::Json => store_json(upload, id, file_name),
+        UploadFormat::Gzip => store_json(GzDecoder::new(upload), id, file_name),
+        UploadFormat::TarGz | UploadFormat::Zip => Err(AppError::validation_error(format!(
+            "{} file: archives are only accepted as complete context",
+            file_name
+        ))),
+    }
+}
+
+/// Extracts the JSON members of a zip or tar.gz archive, detecting the part each one holds.
+///
+/// Only `.json` and `.xml` members, gzipped or not, are read. They are recognised by their content, or by
+/// their name when the content is ambiguous, and the unrecognised ones are skipped.
+///
+/// # Arguments
+///
+/// * `archive_file` - Reference to the uploaded archive.
+/// * `id` - Identifier of the upload, included in the stored file names.
+///
+/// # Returns
+///
+/// The staged path of each part found, or an error if a part appears twice.
+pub fn extract_archive(
+    archive_file: &TempFile,
+    id: &str,
+) -> Result<Vec<(FilePart, String)>, AppError> {
+    let upload = archive_file.file.as_file();
+    let mut parts: Vec<(FilePart, String)> = Vec::new();
+    let mut store_member = |name: &str, reader: &mut dyn Read| -> Result<(), AppError> {
+        let file_name = name.rsplit('/').next().unwrap_or(name);
+        let is_document = [".json", ".json.gz", ".xml", ".xml.gz"]
+            .iter()
+            .any(|extension| file_name.ends_with(extension));
+        if !is_document || file_name.starts_with('.') || name.starts_with("__MACOSX") {
+            return Ok(());
+        }
+        let member_path = if name.ends_with(".gz") {
+            store_json(GzDecoder::new(reader), id, "member")
+        } else {
+            store_json(reader, id, "member")
+        }
+        .map_err(|err| AppError::validation_error(format!("{}: {}", name, err)))?;
+
+        let mut head = Vec::new();
+        File::open(&member_path)
+            .and_then(|file| file.take(DETECTION_HEAD).read_to_end(&mut head))
+            .map_err(|err| AppError::io_error(err.to_string()))?;
+        let Some(part) = FilePart::from_content(&head).or_else(|| FilePart::from_name(name)) else {
+            log::warn!("Skipping archive member {} of {}", name, id);
+            return fs::remove_file(&member_path)
+                .map_err(|err| AppError::io_error(err.to_string()));
+        };
+        if parts.iter().any(|(found, _)| *found == part) {
+            let _ = fs::remove_file(&member_path);
+            return Err(AppError::validation_error(format!(
+                "Archive holds more than one {} file",
+                part.file_name()
+            )));
+        }
+
+        let part_path =
+            Path::new("data").join(format!("{}_{}.json{}", id, part.file_name(), STAGED_SUFFIX));
+        fs::rename(&member_path, &part_path).map_err(|err| AppError::io_error(err.to_string()))?;
+        parts.push((part, part_path.to_string_lossy().to_string()));
+        Ok(())
+    };
+
+    let result = match UploadFormat::detect(upload)? {
+        UploadFormat::Zip => {
+            let mut archive = zip::ZipArchive::new(upload)
+                .map_err(|err| AppError::validation_error(err.to_string()))?;
+            (0..archive.len()).try_for_each(|index| {
+                let mut member = archive
+                    .by_index(index)
+                    .map_err(|err| AppError::validation_error(err.to_string()))?;
+                if member.is_dir() {
+                    return Ok(());
+                }
+                let name = member.name().to_string();
+                store_member(&name, &mut member)
+            })
+        }
+        UploadFormat::TarGz => {
+            let mut archive = tar::Archive::new(GzDecoder::new(upload));
+            archive
+                .entries()
+                .map_err(|err| AppError::validation_error(err.to_string()))?
+                .try_for_each(|entry| {
+                    let mut entry =
+                        entry.map_err(|err| AppError::validation_error(err.to_string()))?;
+                    if !entry.header().entry_type().is_file() {
+                        return Ok(());
+                    }
+                    let name = entry
+                        .path()
+                        .map_err(|err| AppError::validation_error(err.to_string()))?
+                        .to_string_lossy()
+                        .to_string();
+                    store_member(&name, &mut entry)
+                })
+        }
+        UploadFormat::Json | UploadFormat::Gzip => Err(AppError::validation_error(
+            "The uploaded file is not a zip or tar.gz archive",
+        )),
+    };
+
+    if let Err(err) = result {
+        for (_, path) in &parts {
+            let _ = fs::remove_file(path);
+        }
+        return Err(err);
+    }
+    Ok(parts)
+}
+
+/// Moves a staged file, and its index if it has one, over the path it is registered with.
+///
+/// The index stays valid, as a rename keeps the length and modification time of the file. An
+/// index left by a previous upload is removed when the staged file has none.
+///
+/// # Arguments
+///
+/// * `staged_path` - Path of the staged file, ending with `STAGED_SUFFIX`.
+///
+/// # Returns
+///
+/// The path the file was moved to, or the same path if it was not staged.
+pub fn promote_staged(staged_path: &str) -> Result<String, AppError> {
+    let Some(path) = staged_path.strip_suffix(STAGED_SUFFIX) else {
+        return Ok(staged_path.to_string());
+    };
+    fs::rename(staged_path, path).map_err(|err| AppError::io_error(err.to_string()))?;
+
+    let staged_index_path = ContextIndex::index_path(staged_path);
+    let index_path = ContextIndex::index_path(path);
+    if Path::new(&staged_index_path).exists() {
+        fs::rename(&staged_index_path, &index_path)
+    } else if Path::new(&index_path).exists() {
+        fs::remove_file(&index_path)
+    } else {
+        Ok(())
     }
+    .map_err(|err| AppError::io_error(err.to_string()))?;
+    Ok(path.to_string())
 }
@@ -0,0 +1,133 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use tapi_model::Uuid;
+
+use crate::models::{
+    connections::Connection,
+    endpoint::Endpoint,
+    schema::{LayerConnection, LayerConnectionEndPoint, LayerResponse, TraceLayer},
+    topology_graph::TopologyGraph,
+};
+
+/// Builds the layers of the trace of a service: the connections of its endpoints and the lower
+/// connections carrying them, down to the lowest layer, each with its connection end points and the
+/// edge points serving them.
+///
+/// # Arguments
+/// - `endpoint_vector`: The endpoints of the service, numbered along the route.
+/// - `graph`: A reference to the `TopologyGraph` with the connections and nodes of the topology.
+///
+/// # Returns
+/// The layers with connections or links, from the client layer down.
+pub fn build_layers(endpoint_vector: &[Endpoint], graph: &TopologyGraph) -> Vec<LayerResponse> {
+    let hops: HashMap<Uuid, i32> = endpoint_vector
+        .iter()
+        .map(|endpoint| (endpoint.node_edge_point_uuid, endpoint.id))
+        .collect();
+
+    // Connections of the endpoints, then the lower connections of every connection found
+    let mut pending: Vec<Uuid> = endpoint_vector
+        .iter()
+        .flat_map(|endpoint| [endpoint.connection_uuid, endpoint.lower_connection])
+        .flatten()
+        .collect();
+    let mut seen: HashSet<Uuid> = HashSet::new();
+    let mut connections: Vec<&Connection> = Vec::new();
+    while let Some(connection_uuid) = pending.pop() {
+        if !seen.insert(connection_uuid) {
+            continue;
+        }
+        if let Some(connection) = graph.connections_by_uuid(connection_uuid).next() {
+            pending.extend(
+                connection
+                    .lower_connections
+                    .iter()
+                    .map(|lower_connection| lower_connection.connection_uuid),
+            );
+            connections.push(connection);
+        }
+    }
+
+    let mut layers: BTreeMap<TraceLayer, LayerResponse> = BTreeMap::new();
+    for connection in connections {
+        let layer = connection
+            .connection_end_points
+            .iter()
+            .find_map(|cep| graph.node_connection_end_point(cep.connection_end_point_uuid))
+            .map_or(TraceLayer::Unknown, |connection_end_point| {
+                TraceLayer::of(
+                    connection_end_point.layer_protocol_name,
+                    &connection_end_point.layer_protocol_qualifier,
+                )
+            });
+        let connection_end_points = connection
+            .connection_end_points
+            .iter()
+            .map(|cep| LayerConnectionEndPoint {
+                connection_end_point_uuid: cep.connection_end_point_uuid,
+                node_uuid: cep.node_uuid,
+                node_edge_point_uuid: cep.node_edge_point_uuid,
+                hop: hops.get(&cep.node_edge_point_uuid).copied(),
+                server_node_edge_points: graph
+                    .server_node_edge_points(cep.node_edge_point_uuid)
+                    .map(|(_, owned_node_edge_point)| owned_node_edge_point.node_edge_point_uuid)
+                    .collect(),
+            })
+            .collect();
+
+        layers
+            .entry(layer)
+            .or_insert_with(|| empty_layer(layer))
+            .connections
+            .push(LayerConnection {
+                connection_uuid: connection.connection_uuid,
+                connection_end_points,
+                lower_connections: connection
+                    .lower_connections
+                    .iter()
+                    .map(|lower_connection| lower_connection.connection_uuid)
+                    .collect(),
+            });
+    }
+
+    // Links are placed in the layer of the traced edge points at their ends
+    for endpoint in endpoint_vector {
+        if let Some(link_uuid) = endpoint.link_uuid {
+            let layer = TraceLayer::of(
+                endpoint.layer_protocol_name,
+                &endpoint.layer_protocol_qualifier,
+            );
+            let links = &mut layers
+                .entry(layer)
+                .or_insert_with(|| empty_layer(layer))
+                .links;
+            if !links.contains(&link_uuid) {
+                links.push(link_uuid);
+            }
+        }
+    }
+
+    layers
+        .into_values()
+        .map(|mut layer| {
+            layer.connections.sort_by_key(|connection| {
+                let first_hop = connection
+                    .connection_end_points
+                    .iter()
+                    .filter_map(|cep| cep.hop)
+                    .min()
+                    .unwrap_or(i32::MAX);
+                (first_hop, connection.connection_uuid)
+            });
+            layer
+        })
+        .collect()
+}
+
+/// A layer without connections or links yet.
+fn empty_layer(layer: TraceLayer) -> LayerResponse {
+    LayerResponse {
+        layer,
+        connections: Vec::new(),
+        links: Vec::new(),
+    }
+}
@@ -1,7 +1,12 @@
 pub mod connection_builder;
+pub mod context_index;
 pub mod endpoint_builder;
 pub mod file_handler;
+pub mod layer_builder;
 pub mod link_builder;
 pub mod node_builder;
+pub mod path_builder;
 pub mod schema_builder;
 pub mod services_builder;
+pub mod topology_builder;
+pub mod upload_validator;
@@ -1,179 +1,128 @@
-use serde_json::Value;
+use std::collections::HashSet;
+use tapi_model::topology::{Node as TapiNode, Topology};
+use tapi_model::Uuid;
 
-use crate::{
-    models::nodes::{
+use crate::models::{
+    nodes::{
         ClientNodeEdgePoint, FrecuencyPair, McPool, Node, NodeConnectionEndPoint,
         OwnedNodeEdgePoint,
     },
-    utils::find_name,
+    vendor_profile::{ExtensionEntity, VendorProfile},
 };
 
-/// Builds a vector of `Node` objects from the provided JSON topology.
+/// Builds a vector of `Node` objects from the provided topologies.
+///
+/// Every topology is read and merged. A node listed in several topologies is kept once, tagged with the
+/// first topology that contains it.
 ///
 /// # Arguments
 ///
-/// * `topology_json` - A reference to a JSON value representing the topology.
+/// * `topologies` - The topologies parsed with the TAPI model.
+/// * `profile` - The `VendorProfile` with the name keys and extensions of the vendor.
 ///
 /// # Returns
 ///
-/// A vector of `Node` objects constructed from the topology JSON.
-pub fn node_vector_building(topology_json: &Value) -> Vec<Node> {
-    // Determine the topology object, supporting both array and object JSON structures.
-    let topology_object = if let Some(topology_vec) = topology_json.as_array() {
-        topology_vec[0].as_object().unwrap()
-    } else {
-        topology_json.as_object().unwrap()
-    };
-
-    let mut node_key_name = String::default();
-
-    // Search for a key that contains "node" in the topology object.
-    'node_search: for (key, _) in topology_object {
-        if key.contains("node") {
-            node_key_name = key.clone();
-            break 'node_search;
+/// A vector of `Node` objects constructed from the topologies.
+pub fn node_vector_building(topologies: &[Topology], profile: &VendorProfile) -> Vec<Node> {
+    let mut node_uuids: HashSet<Uuid> = HashSet::new();
+    let mut node_section: Vec<(Uuid, &TapiNode)> = Vec::new();
+
+    for topology in topologies {
+        for node_item in &topology.node {
+            if node_uuids.insert(node_item.uuid) {
+                node_section.push((topology.uuid, node_item));
+            }
         }
     }
 
-    // Extract the node section as an array, defaulting to an empty array if missing.
-    let node_section = topology_object[&node_key_name]
-        .clone()
-        .as_array()
-        .unwrap_or(&Vec::default())
-        .clone();
-
     let mut node_vector: Vec<Node> = Vec::new();
 
-    // Process each node item in the node section.
-    for node_item in node_section {
+    // Process each node item of every topology.
+    for (topology_uuid, node_item) in node_section {
         let mut owned_node_edge_point_vector: Vec<OwnedNodeEdgePoint> = Vec::new();
 
-        if let Some(owned_node_edge_point_section) = node_item
-            .get("owned-node-edge-point")
-            .and_then(Value::as_array)
-        {
-            for owned_node_edge_point_item in owned_node_edge_point_section {
-                let mut connection_end_point_vector: Vec<NodeConnectionEndPoint> = Vec::new();
-
-                if let Some(connection_end_point_section) = owned_node_edge_point_item
-                    .pointer("/tapi-connectivity:cep-list/connection-end-point")
-                    .and_then(Value::as_array)
-                {
-                    for connection_end_point_item in connection_end_point_section {
-                        let mut client_node_edge_point_vector: Vec<ClientNodeEdgePoint> =
-                            Vec::new();
-
-                        if let Some(client_node_edge_point_section) = connection_end_point_item
-                            .get("client-node-edge-point")
-                            .and_then(Value::as_array)
-                        {
-                            for client_node_edge_point_item in client_node_edge_point_section {
-                                client_node_edge_point_vector.push(ClientNodeEdgePoint {
-                                    node_edge_point_uuid: client_node_edge_point_item
-                                        .get("node-edge-point-uuid")
-                                        .unwrap_or(&Value::default())
-                                        .to_string(),
-                                    node_uuid: client_node_edge_point_item
-                                        .get("node-uuid")
-                                        .unwrap_or(&Value::default())
-                                        .to_string(),
-                                });
-                            }
-                        }
-
-                        connection_end_point_vector.push(NodeConnectionEndPoint {
-                            connection_end_point_uuid: connection_end_point_item
-                                .get("uuid")
-                                .unwrap_or(&Value::default())
-                                .to_string(),
-                            layer_protocol_qualifier: connection_end_point_item
-                                .get("layer-protocol-qualifier")
-                                .unwrap_or(&Value::default())
-                                .to_string(),
-                            client_node_edge_points: client_node_edge_point_vector,
-                        });
-                    }
-                }
-
-                let mut mc_pool: Option<McPool> = Option::None;
-                if let Some(media_spec) = owned_node_edge_point_item
-                    .get("tapi-photonic-media:media-channel-node-edge-point-spec")
-                {
-                    if let Some(mc_pool_section) = media_spec.get("mc-pool") {
-                        let mut provisional_mc_pool: McPool = McPool::default();
-                        let mut mc_pool_exists: bool = false;
-                        if let Some(occupied_spectrum_array) = mc_pool_section
-                            .get("occupied-spectrum")
-                            .and_then(Value::as_array)
-                        {
-                            let mut occupied_spectrum = vec![];
-
-                            for item in occupied_spectrum_array {
-                                let upper = item.get("upper-frequency").and_then(Value::as_i64);
-                                let lower = item.get("lower-frequency").and_then(Value::as_i64);
-
-                                if let (Some(upper), Some(lower)) = (upper, lower) {
-                                    occupied_spectrum.push(FrecuencyPair {
-                                        upper_frequency: upper,
-                                        lower_frequency: lower,
-                                    });
-                                }
-                            }
-                            mc_pool_exists = true;
-                            provisional_mc_pool.occupied_spectrum = Some(occupied_spectrum);
-
-                            
-                        }
-
-                        if let Some(available_spectrum_array) = mc_pool_section
-                            .get("available-spectrum")
-                            .and_then(Value::as_array)
-                        {
-                            let mut available_spectrum = vec![];
-
-                            for item in available_spectrum_array {
-                                let upper = item.get("upper-frequency").and_then(Value::as_i64);
-                                let lower = item.get("lower-frequency").and_then(Value::as_i64);
-
-                                if let (Some(upper), Some(lower)) = (upper, lower) {
-                                    available_spectrum.push(FrecuencyPair {
-                                        upper_frequency: upper,
-                                        lower_frequency: lower,
-                                    });
-                                }
-                            }
-                            mc_pool_exists = true;
-                            provisional_mc_pool.available_spectrum = Some(available_spectrum);
-
-                            
-                        }
-                        if mc_pool_exists {
-                            mc_pool = Some(provisional_mc_pool);
-                        }
-                        
-                    }
+        for owned_node_edge_point_item in &node_item.owned_node_edge_point {
+            let mut connection_end_point_vector: Vec<NodeConnectionEndPoint> = Vec::new();
+
+            if let Some(cep_list) = &owned_node_edge_point_item.cep_list {
+                for connection_end_point_item in &cep_list.connection_end_point {
+                    let client_node_edge_point_vector: Vec<ClientNodeEdgePoint> =
+                        connection_end_point_item
+                            .client_node_edge_point
+                            .iter()
+                            .map(|client_node_edge_point_item| ClientNodeEdgePoint {
+                                node_edge_point_uuid: client_node_edge_point_item
+                                    .node_edge_point_uuid,
+                                node_uuid: client_node_edge_point_item.node_uuid,
+                            })
+                            .collect();
+
+                    connection_end_point_vector.push(NodeConnectionEndPoint {
+                        connection_end_point_uuid: connection_end_point_item.uuid,
+                        layer_protocol_name: connection_end_point_item
+                            .layer_protocol_name
+                            .or(owned_node_edge_point_item.layer_protocol_name),
+                        layer_protocol_qualifier: connection_end_point_item
+                            .layer_protocol_qualifier
+                            .as_ref()
+                            .map(|qualifier| qualifier.as_str().to_string())
+                            .unwrap_or_default(),
+                        client_node_edge_points: client_node_edge_point_vector,
+                    });
                 }
+            }
 
-                owned_node_edge_point_vector.push(OwnedNodeEdgePoint {
-                    node_edge_point_uuid: owned_node_edge_point_item
-                        .get("uuid")
-                        .unwrap_or(&Value::default())
-                        .to_string(),
-                    inventory_id: find_name(owned_node_edge_point_item, "INVENTORY_ID".to_string()),
-                    connection_end_points: connection_end_point_vector,
-                    mc_pool,
+            // Keep the spectrum lists that are present, even if they are empty.
+            let mc_pool = owned_node_edge_point_item
+                .media_channel_node_edge_point_spec
+                .as_ref()
+                .and_then(|media_spec| media_spec.mc_pool.as_ref())
+                .map(|mc_pool_section| McPool {
+                    occupied_spectrum: mc_pool_section
+                        .occupied_spectrum
+                        .as_ref()
+                        .map(|bands| frequency_pairs(bands)),
+                    available_spectrum: mc_pool_section
+                        .available_spectrum
+                        .as_ref()
+                        .map(|bands| frequency_pairs(bands)),
+                })
+                .filter(|mc_pool| {
+                    mc_pool.occupied_spectrum.is_some() || mc_pool.available_spectrum.is_some()
                 });
-            }
+
+            owned_node_edge_point_vector.push(OwnedNodeEdgePoint {
+                node_edge_point_uuid: owned_node_edge_point_item.uuid,
+                inventory_id: VendorProfile::find_name(
+                    &owned_node_edge_point_item.name,
+                    &profile.names.inventory,
+                ),
+                connection_end_points: connection_end_point_vector,
+                mc_pool,
+                extensions: profile.extract_extensions(
+                    ExtensionEntity::NodeEdgePoint,
+                    &owned_node_edge_point_item.extensions,
+                ),
+            });
         }
 
         node_vector.push(Node {
-            node_uuid: node_item
-                .get("uuid")
-                .unwrap_or(&Value::default())
-                .to_string(),
-            name: find_name(&node_item, "NODE_IDENTIFIER".to_string()),
+            node_uuid: node_item.uuid,
+            name: VendorProfile::find_name(&node_item.name, &profile.names.node),
+            topology_uuid,
             owned_node_edge_points: owned_node_edge_point_vector,
         });
     }
     node_vector
 }
+
+/// Converts spectrum bands into frequency pairs.
+fn frequency_pairs(bands: &[tapi_model::photonic_media::SpectrumBand]) -> Vec<FrecuencyPair> {
+    bands
+        .iter()
+        .map(|band| FrecuencyPair {
+            upper_frequency: band.upper_frequency,
+            lower_frequency: band.lower_frequency,
+        })
+        .collect()
+}
@@ -0,0 +1,220 @@
+use serde_json::Value;
+use std::collections::HashSet;
+use tapi_model::Uuid;
+
+use super::schema_builder::schema_from_endpoints;
+use crate::{
+    models::{
+        endpoint::{Endpoint, HopDirection},
+        links::Link,
+        path_computation::{ComputedPath, LinkRef},
+        provisioning::AvailableSip,
+        schema::{TraceWarning, TraceWarningKind},
+        topology_graph::TopologyGraph,
+        vendor_profile::UNKNOWN_NAME,
+    },
+    AppError,
+};
+
+/// Parses a UUID read from a device response.
+fn parse_uuid(uuid: &str) -> Result<Uuid, AppError> {
+    uuid.parse()
+        .map_err(|_| AppError::validation_error(format!("Invalid UUID {}", uuid)))
+}
+
+/// Lists the nodes a path crosses, in the order of its links and without duplicates.
+///
+/// # Returns
+/// The UUIDs of the nodes, or an `Error` if a link of the path is not in the topology.
+pub fn path_nodes(path: &ComputedPath, graph: &TopologyGraph) -> Result<Vec<String>, AppError> {
+    let mut seen_node_uuids: HashSet<Uuid> = HashSet::new();
+    let mut node_uuids: Vec<String> = vec![];
+    for link_ref in &path.links {
+        let link = path_link(link_ref, graph)?;
+        for node_edge_point in &link.node_edge_points {
+            if seen_node_uuids.insert(node_edge_point.node_uuid) {
+                node_uuids.push(node_edge_point.node_uuid.to_string());
+            }
+        }
+    }
+    Ok(node_uuids)
+}
+
+/// Finds a link crossed by a path in the topology.
+///
+/// # Returns
+/// The link, or an `Error` if its UUID is not valid or it is not in the topology.
+fn path_link<'a>(link_ref: &LinkRef, graph: &'a TopologyGraph) -> Result<&'a Link, AppError> {
+    graph
+        .links_by_uuid(parse_uuid(&link_ref.link_uuid)?)
+        .next()
+        .ok_or_else(|| {
+            AppError::validation_error(format!(
+                "Link {} is not in the topology",
+                link_ref.link_uuid
+            ))
+        })
+}
+
+/// Builds the schema of a computed path, with the nodes, inventories and endpoints of the schema
+/// of a connectivity service, so it can be drawn by the same node visualisation.
+///
+/// The endpoints are numbered from the source service interface point, through both edge points of
+/// each link in the order given by the device, to the destination service interface point. The path
+/// leaves each node through the first edge point of a link and enters the next through the second.
+/// Edge points missing from the topology, or without inventory name, are reported as warnings.
+///
+/// # Arguments
+/// - `path`: The path, as the ordered list of the links it crosses.
+/// - `source`: The service interface point the path starts from.
+/// - `destination`: The service interface point the path ends at.
+/// - `graph`: The `TopologyGraph` of the topology, to find the edge points of each link and the
+///   inventory of each edge point.
+///
+/// # Returns
+/// A serialized `ServiceResponse` named after the path, or an `Error` if a link of the path is not in
+/// the topology.
+pub fn build_path_schema(
+    path: &ComputedPath,
+    source: &AvailableSip,
+    destination: &AvailableSip,
+    graph: &TopologyGraph,
+) -> Result<Value, AppError> {
+    let mut endpoint_vector: Vec<Endpoint> = Vec::new();
+    let mut warnings: Vec<TraceWarning> = Vec::new();
+
+    let mut push_endpoint = |node_edge_point_uuid: Uuid,
+                             node_uuid: Uuid,
+                             service_interface_point_uuid: Option<Uuid>,
+                             link_uuid: Option<Uuid>,
+                             direction: HopDirection| {
+        // An edge point reached twice, e.g. the edge point of a service interface point that is
+        // also the end of a link, keeps its first place in the path.
+        if let Some(endpoint) = endpoint_vector
+            .iter_mut()
+            .find(|endpoint| endpoint.node_edge_point_uuid == node_edge_point_uuid)
+        {
+            endpoint.service_interface_point_uuid = endpoint
+                .service_interface_point_uuid
+                .or(service_interface_point_uuid);
+            endpoint.link_uuid = endpoint.link_uuid.or(link_uuid);
+            return;
+        }
+
+        let owned_node_edge_point = graph.owned_node_edge_point(node_uuid, node_edge_point_uuid);
+        match owned_node_edge_point {
+            None => {
+                let mut warning = TraceWarning::new(
+                    if link_uuid.is_some() {
+                        TraceWarningKind::LinkEndMissing
+                    } else {
+                        TraceWarningKind::DanglingNodeEdgePoint
+                    },
+                    node_uuid,
+                    node_edge_point_uuid,
+                    format!(
+                        "Node edge point {} of node {} is not in the topology",
+                        node_edge_point_uuid, node_uuid
+                    ),
+                );
+                warning.link_uuid = link_uuid;
+                warnings.push(warning);
+            }
+            Some(owned_node_edge_point)
+                if owned_node_edge_point.inventory_id.is_empty()
+                    || owned_node_edge_point.inventory_id == UNKNOWN_NAME =>
+            {
+                warnings.push(TraceWarning::new(
+                    TraceWarningKind::MissingInventory,
+                    node_uuid,
+                    node_edge_point_uuid,
+                    format!(
+                        "Node edge point {} has no inventory name",
+                        node_edge_point_uuid
+                    ),
+                ));
+            }
+            Some(_) => {}
+        }
+        let connection_end_point = owned_node_edge_point
+            .and_then(|owned_node_edge_point| owned_node_edge_point.connection_end_points.first());
+        let id = endpoint_vector.len() as i32 + 1;
+        endpoint_vector.push(Endpoint {
+            node_edge_point_uuid,
+            layer_protocol_name: connection_end_point
+                .and_then(|connection_end_point| connection_end_point.layer_protocol_name),
+            layer_protocol_qualifier: connection_end_point
+                .map(|connection_end_point| connection_end_point.layer_protocol_qualifier.clone())
+                .unwrap_or_default(),
+            node_uuid,
+            inventory_id: owned_node_edge_point
+                .map(|owned_node_edge_point| owned_node_edge_point.inventory_id.clone())
+                .unwrap_or_default(),
+            connection_end_point_uuid: None,
+            service_interface_point_uuid,
+            connection_uuid: None,
+            client_node_edge_point_uuid: None,
+            lower_connection: None,
+            link_uuid,
+            mc_pool: owned_node_edge_point
+                .and_then(|owned_node_edge_point| owned_node_edge_point.mc_pool.clone()),
+            extensions: owned_node_edge_point
+                .map(|owned_node_edge_point| owned_node_edge_point.extensions.clone())
+                .unwrap_or_default(),
+            id,
+            direction,
+        });
+    };
+
+    let source_node_uuid = source.node_uuid;
+    push_endpoint(
+        source.node_edge_point_uuid,
+        source_node_uuid,
+        Some(source.uuid),
+        None,
+        HopDirection::Ingress,
+    );
+
+    // Each link is crossed from the edge point on the node reached so far
+    let mut current_node_uuid = source_node_uuid;
+    for link_ref in &path.links {
+        let link = path_link(link_ref, graph)?;
+        let link_uuid = link.link_uuid;
+        let mut node_edge_points: Vec<_> = link.node_edge_points.iter().collect();
+        node_edge_points
+            .sort_by_key(|node_edge_point| node_edge_point.node_uuid != current_node_uuid);
+        for node_edge_point in node_edge_points {
+            // The link leaves the node reached so far and enters the next one
+            let direction = if node_edge_point.node_uuid == current_node_uuid {
+                HopDirection::Egress
+            } else {
+                HopDirection::Ingress
+            };
+            push_endpoint(
+                node_edge_point.node_edge_point_uuid,
+                node_edge_point.node_uuid,
+                None,
+                Some(link_uuid),
+                direction,
+            );
+            current_node_uuid = node_edge_point.node_uuid;
+        }
+    }
+
+    push_endpoint(
+        destination.node_edge_point_uuid,
+        destination.node_uuid,
+        Some(destination.uuid),
+        None,
+        HopDirection::Egress,
+    );
+
+    Ok(schema_from_endpoints(
+        parse_uuid(&path.uuid)?,
+        path.name.clone().unwrap_or_else(|| path.uuid.clone()),
+        endpoint_vector,
+        graph,
+        Vec::new(),
+        warnings,
+    ))
+}
@@ -1,185 +1,135 @@
 use serde_json::{json, Value};
+use std::collections::HashMap;
+use tapi_model::Uuid;
 
-use super::endpoint_builder::build_endpoint_vector;
+use super::{endpoint_builder::build_endpoint_vector, layer_builder::build_layers};
 use crate::{
     models::{
-        connections::Connection,
         connectivity_services::Service,
         endpoint::Endpoint,
-        links::Link,
-        nodes::Node,
-        schema::{Inventory, NodeResponse, Schema, ServiceResponse},
+        schema::{
+            Hop, Inventory, LayerResponse, NodeResponse, Schema, ServiceResponse, TraceWarning,
+        },
+        topology_graph::TopologyGraph,
     },
     AppError,
 };
 
-/// Builds the schema for a connectivity service, including its nodes, inventories, and endpoints.
+/// Builds the schema for a connectivity service, including its nodes, inventories, and endpoints,
+/// the layers of its trace and the anomalies of the topology found while tracing it.
 ///
 /// # Arguments
 /// - `service`: A reference to the `Service` object representing the connectivity service.
-/// - `link_vector`: A reference to a vector of `Link` objects representing links in the topology.
-/// - `node_vector`: A reference to a vector of `Node` objects representing nodes in the topology.
-/// - `connection_vector`: A reference to a vector of `Connection` objects representing connections.
+/// - `graph`: A reference to the `TopologyGraph` with the links, nodes and connections of the topology.
 ///
 /// # Returns
 /// A `Result` containing a serialized JSON value representing the schema or an `aPPError`.
-pub fn build_schema(
-    service: &Service,
-    link_vector: &Vec<Link>,
-    node_vector: &Vec<Node>,
-    connection_vector: &Vec<Connection>,
-) -> Result<Value, AppError> {
+pub fn build_schema(service: &Service, graph: &TopologyGraph) -> Result<Value, AppError> {
+    let (endpoint_vector, warnings) = build_endpoint_vector(service, graph);
+    let layers = build_layers(&endpoint_vector, graph);
+
+    Ok(schema_from_endpoints(
+        service.service_uuid,
+        service.name.clone(),
+        endpoint_vector,
+        graph,
+        layers,
+        warnings,
+    ))
+}
+
+/// Groups endpoints by inventory and node, in the order the node visualisation draws them: nodes,
+/// inventories and endpoints follow the route from its A-end. The route is listed as well, one hop
+/// per endpoint.
+///
+/// # Arguments
+/// - `uuid`: UUID of the service or path the endpoints belong to.
+/// - `value_name`: Name shown for the service or path.
+/// - `endpoint_vector`: The endpoints, numbered along the route from its A-end with their `id`.
+/// - `graph`: The `TopologyGraph` of the topology, to name the nodes of the endpoints.
+/// - `layers`: The layers of the trace, if any.
+/// - `warnings`: The anomalies found while tracing the endpoints, if any.
+///
+/// # Returns
+/// The serialized `ServiceResponse`.
+pub fn schema_from_endpoints(
+    uuid: Uuid,
+    value_name: String,
+    mut endpoint_vector: Vec<Endpoint>,
+    graph: &TopologyGraph,
+    layers: Vec<LayerResponse>,
+    warnings: Vec<TraceWarning>,
+) -> Value {
     let mut node_response_vector: Vec<NodeResponse> = Vec::new();
     let mut inventories_response_vector: Vec<Inventory> = Vec::new();
 
-    let endpoint_vector =
-        build_endpoint_vector(service, link_vector, node_vector, connection_vector);
+    endpoint_vector.sort_by_key(|endpoint| endpoint.id);
+    let route: Vec<Hop> = endpoint_vector
+        .iter()
+        .map(|endpoint| Hop {
+            hop: endpoint.id,
+            node_uuid: endpoint.node_uuid,
+            node_edge_point_uuid: endpoint.node_edge_point_uuid,
+            inventory_id: endpoint.inventory_id.clone(),
+            direction: endpoint.direction,
+            layer_protocol_name: endpoint.layer_protocol_name,
+            layer_protocol_qualifier: endpoint.layer_protocol_qualifier.clone(),
+        })
+        .collect();
 
+    // Grouped in route order, inventories and nodes come in the order of their first endpoint
+    let mut inventory_positions: HashMap<String, usize> = HashMap::new();
     for endpoint in endpoint_vector {
         // Create inventories
-        if !inventories_response_vector
-            .iter()
-            .any(|inventory| inventory.inventory_id == endpoint.inventory_id)
-        {
-            let inventory_endpoint_vector: Vec<Endpoint> = vec![endpoint.clone()];
-            inventories_response_vector.push(Inventory {
-                inventory_id: endpoint.inventory_id.clone(),
-                endpoints: inventory_endpoint_vector,
-            });
-        } else {
-            inventories_response_vector
-                .iter_mut()
-                .filter(|inventory| inventory.inventory_id == endpoint.inventory_id)
-                .for_each(|inventory| {
-                    inventory.endpoints.push(endpoint.clone());
+        match inventory_positions.get(&endpoint.inventory_id) {
+            Some(&position) => inventories_response_vector[position]
+                .endpoints
+                .push(endpoint),
+            None => {
+                inventory_positions.insert(
+                    endpoint.inventory_id.clone(),
+                    inventories_response_vector.len(),
+                );
+                inventories_response_vector.push(Inventory {
+                    inventory_id: endpoint.inventory_id.clone(),
+                    endpoints: vec![endpoint],
                 });
+            }
         }
     }
 
-    for inventory in &inventories_response_vector {
+    let mut node_positions: HashMap<Uuid, usize> = HashMap::new();
+    for inventory in inventories_response_vector {
         // Create nodes
-        if !node_response_vector
-            .iter()
-            .any(|node| node.node_uuid == inventory.endpoints[0].node_uuid)
-        {
-            node_response_vector.push(NodeResponse {
-                node_uuid: inventory.endpoints[0].node_uuid.clone(),
-                value_name: node_vector
-                    .iter()
-                    .find(|node| node.node_uuid == inventory.endpoints[0].node_uuid)
-                    .map_or("Unknown".to_string(), |node| node.name.clone()),
-                inventories: vec![inventory.clone()],
-            });
-        } else {
-            node_response_vector
-                .iter_mut()
-                .filter(|node| node.node_uuid == inventory.endpoints[0].node_uuid)
-                .for_each(|node| {
-                    node.inventories.push(inventory.clone());
+        let node_uuid = inventory.endpoints[0].node_uuid;
+        match node_positions.get(&node_uuid) {
+            Some(&position) => node_response_vector[position].inventories.push(inventory),
+            None => {
+                node_positions.insert(node_uuid, node_response_vector.len());
+                let node = graph.nodes_by_uuid(node_uuid).next();
+                node_response_vector.push(NodeResponse {
+                    node_uuid,
+                    value_name: node.map_or("Unknown".to_string(), |node| node.name.clone()),
+                    topology_uuid: node.map(|node| node.topology_uuid),
+                    inventories: vec![inventory],
                 });
+            }
         }
     }
 
-    // Alternated obtain sorted nodes
-    let mut node_with_min_ids: Vec<(NodeResponse, i32)> = node_response_vector
-        .iter()
-        .filter_map(|node| {
-            let min_id = node
-                .inventories
-                .iter()
-                .flat_map(|inv| inv.endpoints.iter().map(|e| e.id))
-                .min();
-            min_id.map(|id| (node.clone(), id))
-        })
-        .collect();
-
-    node_with_min_ids.sort_by(|a, b| a.1.cmp(&b.1));
-
-    let mut reordered_nodes: Vec<NodeResponse> = Vec::new();
-    let mut left = Vec::new();
-    let mut right = Vec::new();
-
-    for (i, (node, _)) in node_with_min_ids.iter().enumerate() {
-        if i % 2 == 0 {
-            left.push(node.clone());
-        } else {
-            right.push(node.clone());
-        }
-    }
-
-    right.reverse(); // The right side have to keep it reverse.
-
-    let total_nodes = left.len() + right.len();
-    let middle_index = total_nodes / 2;
-
-    // Nodes processing
-    for (i, node) in left.iter_mut().enumerate() {
-        if i == middle_index && total_nodes % 2 != 0 {
-            // Middle Node
-            node.inventories = sort_middle_inventories(node.inventories.clone());
-            reordered_nodes.push(node.clone());
-        } else {
-            node.inventories
-                .sort_by_key(|inv| inv.endpoints.iter().map(|e| e.id).min().unwrap_or(i32::MAX));
-            reordered_nodes.push(node.clone());
-        }
-    }
-
-    for node in right.iter_mut() {
-        node.inventories.sort_by_key(|inv| {
-            std::cmp::Reverse(inv.endpoints.iter().map(|e| e.id).min().unwrap_or(i32::MAX))
-        });
-        reordered_nodes.push(node.clone());
-    }
-
     // Build final response
     let service_response = ServiceResponse {
-        uuid: service.service_uuid.clone(),
-        value_name: service.name.clone(),
-        nodes: reordered_nodes,
+        uuid,
+        value_name,
+        nodes: node_response_vector,
+        route,
+        layers,
+        warnings,
     };
 
     let schema = Schema {
         connectivity_service: service_response,
     };
 
-    Ok(json!(schema.connectivity_service))
-}
-
-/// Sorts inventories in a zig-zag pattern for the middle node.
-///
-/// # Arguments
-/// - `inventories`: A vector of `Inventory` objects to be sorted.
-///
-/// # Returns
-/// A vector of `Inventory` objects sorted alternately (zig-zag pattern).
-///
-/// # Process
-/// - Sorts inventories by the smallest endpoint ID in ascending order.
-/// - Alternates inventories between the left and right sides.
-/// - Returns the reordered inventories as a single vector.
-///
-/// # Notes
-/// - This function is used specifically for the middle node in the schema to achieve
-///   an alternate ordering effect.
-fn sort_middle_inventories(mut inventories: Vec<Inventory>) -> Vec<Inventory> {
-    inventories.sort_by_key(|inv| inv.endpoints.iter().map(|e| e.id).min().unwrap_or(i32::MAX));
-
-    let mut result = vec![None; inventories.len()];
-    let mut left = 0;
-    let mut right = inventories.len() - 1;
-    let mut toggle = true;
-
-    for inventory in inventories {
-        if toggle {
-            result[left] = Some(inventory);
-            left += 1;
-        } else {
-            result[right] = Some(inventory);
-            right -= 1;
-        }
-        toggle = !toggle;
-    }
-
-    result.into_iter().flatten().collect()
+    json!(schema.connectivity_service)
 }
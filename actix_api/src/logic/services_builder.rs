@@ -1,23 +1,25 @@
-use serde_json::Value;
+use tapi_model::connectivity::ConnectivityService;
 
-use crate::{models::connectivity_services::SimpleService, utils::find_name};
+use crate::models::{connectivity_services::SimpleService, vendor_profile::VendorProfile};
 
-/// Builds a vector of `SimpleService` objects from a JSON array of connectivity services.
+/// Builds a vector of `SimpleService` objects from the connectivity services of the TAPI model.
 ///
 /// # Arguments
-/// - `connectivity_service_json`: A reference to a vector of JSON values representing connectivity services.
+/// - `connectivity_services`: The connectivity services parsed with the TAPI model.
+/// - `profile`: The `VendorProfile` with the name keys of the vendor.
 ///
 /// # Returns
 /// A vector of `SimpleService` objects containing the UUID and name of each service.
 pub fn connectivity_services_vector_build(
-    connectivity_service_json: &Vec<Value>,
+    connectivity_services: &[ConnectivityService],
+    profile: &VendorProfile,
 ) -> Vec<SimpleService> {
     let mut connectivity_services_vector: Vec<SimpleService> = Vec::new();
 
-    for service in connectivity_service_json {
+    for service in connectivity_services {
         connectivity_services_vector.push(SimpleService {
-            uuid: service.get("uuid").unwrap_or(&Value::default()).to_string(),
-            name: find_name(service, "SERVICE_NAME".to_string()),
+            uuid: service.uuid,
+            name: VendorProfile::find_name(&service.name, &profile.names.service),
         });
     }
 
@@ -0,0 +1,84 @@
+use std::collections::HashMap;
+
+use crate::models::{
+    schema::{
+        TopologyInventory, TopologyLink, TopologyLinkEnd, TopologyNode, TopologyNodeEdgePoint,
+        TopologyResponse,
+    },
+    topology_graph::TopologyGraph,
+};
+
+/// Builds the whole network of a snapshot, to draw it as a map instead of one service at a time.
+///
+/// # Arguments
+/// - `graph`: A reference to the `TopologyGraph` with the nodes and links of the topology.
+///
+/// # Returns
+/// Every node with its edge points grouped by inventory, in the order of the topology, and every
+/// link with the node, edge point and inventory of its ends.
+pub fn build_topology(graph: &TopologyGraph) -> TopologyResponse {
+    let nodes = graph
+        .nodes()
+        .iter()
+        .map(|node| {
+            let mut inventories: Vec<TopologyInventory> = Vec::new();
+            // Position of each inventory in `inventories`, which keeps the order of the topology
+            let mut inventory_positions: HashMap<&str, usize> = HashMap::new();
+            for owned_node_edge_point in &node.owned_node_edge_points {
+                let connection_end_point = owned_node_edge_point.connection_end_points.first();
+                let node_edge_point = TopologyNodeEdgePoint {
+                    node_edge_point_uuid: owned_node_edge_point.node_edge_point_uuid,
+                    layer_protocol_name: connection_end_point
+                        .and_then(|connection_end_point| connection_end_point.layer_protocol_name),
+                    layer_protocol_qualifier: connection_end_point
+                        .map(|connection_end_point| {
+                            connection_end_point.layer_protocol_qualifier.clone()
+                        })
+                        .unwrap_or_default(),
+                };
+                match inventory_positions.get(owned_node_edge_point.inventory_id.as_str()) {
+                    Some(&position) => inventories[position].node_edge_points.push(node_edge_point),
+                    None => {
+                        inventory_positions
+                            .insert(&owned_node_edge_point.inventory_id, inventories.len());
+                        inventories.push(TopologyInventory {
+                            inventory_id: owned_node_edge_point.inventory_id.clone(),
+                            node_edge_points: vec![node_edge_point],
+                        });
+                    }
+                }
+            }
+            TopologyNode {
+                node_uuid: node.node_uuid,
+                value_name: node.name.clone(),
+                topology_uuid: node.topology_uuid,
+                inventories,
+            }
+        })
+        .collect();
+
+    let links = graph
+        .links()
+        .iter()
+        .map(|link| TopologyLink {
+            link_uuid: link.link_uuid,
+            topology_uuid: link.topology_uuid,
+            node_edge_points: link
+                .node_edge_points
+                .iter()
+                .map(|node_edge_point| TopologyLinkEnd {
+                    node_uuid: node_edge_point.node_uuid,
+                    node_edge_point_uuid: node_edge_point.node_edge_point_uuid,
+                    inventory_id: graph
+                        .owned_node_edge_point(
+                            node_edge_point.node_uuid,
+                            node_edge_point.node_edge_point_uuid,
+                        )
+                        .map(|owned_node_edge_point| owned_node_edge_point.inventory_id.clone()),
+                })
+                .collect(),
+        })
+        .collect();
+
+    TopologyResponse { nodes, links }
+}
@@ -0,0 +1,260 @@
+use crate::handlers::requester::FullContext;
+use crate::handlers::vendor::VendorProfiles;
+use crate::logic::context_index::{read_file, read_span, ContextIndex, IndexKind, Span};
+use crate::models::files_model::{DanglingReference, FilesEnum, UploadCounts, UploadReport};
+use crate::utils::{find_topologies, parse_tapi, parse_topologies};
+
+use serde_json::Value;
+use std::collections::HashSet;
+use tapi_model::connectivity::{Connection, ConnectivityService};
+use tapi_model::topology::Topology;
+use tapi_model::Uuid;
+
+const SERVICES_POINTER: &str =
+    "/tapi-common:context/tapi-connectivity:connectivity-context/connectivity-service";
+const CONNECTIONS_POINTER: &str =
+    "/tapi-common:context/tapi-connectivity:connectivity-context/connection";
+const TOPOLOGY_POINTER: &str = "/tapi-common:context/tapi-topology:topology-context/topology";
+
+/// Checks an uploaded file set against the TAPI structure.
+///
+/// # Arguments
+/// - `files_enum`: The stored files of the upload, either complete or by parts.
+///
+/// # Returns
+/// An `UploadReport` with the missing sections, the entity counts, the dangling references and the
+/// detected vendor profile. The upload is valid when no section is missing and every entity
+/// matches the TAPI model; dangling references alone do not reject it.
+pub fn validate_upload(files_enum: &FilesEnum) -> UploadReport {
+    let mut report = UploadReport {
+        id: files_enum.id().to_string(),
+        ..Default::default()
+    };
+    if let Some(context) = read_sections(files_enum, &mut report) {
+        check_context(&context, &mut report);
+    }
+    report.valid = report.missing_sections.is_empty() && report.errors.is_empty();
+    report
+}
+
+/// Reads the services, connections and topologies of the upload, recording the missing ones.
+fn read_sections(files_enum: &FilesEnum, report: &mut UploadReport) -> Option<FullContext> {
+    match files_enum {
+        FilesEnum::Complete(complete) => {
+            let path = complete.complete_context_path.as_str();
+            let index = match ContextIndex::load(path, IndexKind::Complete) {
+                Ok(index) => index,
+                Err(err) => {
+                    report
+                        .errors
+                        .push(format!("complete_context_file: {}", err));
+                    return None;
+                }
+            };
+            let connectivity_services = match index.services() {
+                Ok(services) => services
+                    .iter()
+                    .map(|service| read_span::<Value>(path, service.span))
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(|err| report.errors.push(format!("{}: {}", SERVICES_POINTER, err)))
+                    .ok(),
+                Err(_) => {
+                    report.missing_sections.push(SERVICES_POINTER.to_string());
+                    None
+                }
+            };
+            let mut read = |span: Option<Span>, pointer: &str| match span {
+                Some(span) => read_span::<Value>(path, span)
+                    .map_err(|err| report.errors.push(format!("{}: {}", pointer, err)))
+                    .ok(),
+                None => {
+                    report.missing_sections.push(pointer.to_string());
+                    None
+                }
+            };
+            let connections = read(index.connections, CONNECTIONS_POINTER);
+            let topology = read(index.topology, TOPOLOGY_POINTER);
+
+            Some(FullContext {
+                connectivity_services: connectivity_services?,
+                connections: list(connections?, CONNECTIONS_POINTER, report)?,
+                topology: topology?,
+                yang_modules: Vec::new(),
+                strategy: None,
+            })
+        }
+        FilesEnum::ByPart(by_part) => {
+            let mut read = |path: &str, name: &str| {
+                read_file::<Value>(path)
+                    .map_err(|err| report.errors.push(format!("{}: {}", name, err)))
+                    .ok()
+            };
+            let topology = read(&by_part.topology_path, "topology_file");
+            let connections = read(&by_part.connections_path, "connections_file");
+            let connectivity_services = read(
+                &by_part.connectivity_services_path,
+                "connectivity_services_file",
+            );
+
+            Some(FullContext {
+                connectivity_services: list(
+                    connectivity_services?,
+                    "connectivity_services_file",
+                    report,
+                )?,
+                connections: list(connections?, "connections_file", report)?,
+                topology: topology?,
+                yang_modules: Vec::new(),
+                strategy: None,
+            })
+        }
+    }
+}
+
+/// Items of a section that must hold a list.
+fn list(value: Value, section: &str, report: &mut UploadReport) -> Option<Vec<Value>> {
+    match value {
+        Value::Array(items) => Some(items),
+        _ => {
+            report.errors.push(format!("{}: Expected a list", section));
+            None
+        }
+    }
+}
+
+/// Parses the sections with the TAPI model and fills the counts, references and vendor profile.
+fn check_context(context: &FullContext, report: &mut UploadReport) {
+    if find_topologies(&context.topology).is_empty() {
+        report.errors.push("No topology found".to_string());
+    }
+    let topologies = parse_topologies(&context.topology)
+        .map_err(|err| report.errors.push(err.to_string()))
+        .unwrap_or_default();
+    let connections = parse_tapi::<Connection>(&context.connections, "connection")
+        .map_err(|err| report.errors.push(err.to_string()))
+        .unwrap_or_default();
+    let connectivity_services =
+        parse_tapi::<ConnectivityService>(&context.connectivity_services, "connectivity service")
+            .map_err(|err| report.errors.push(err.to_string()))
+            .unwrap_or_default();
+
+    report.counts = UploadCounts {
+        nodes: topologies
+            .iter()
+            .flat_map(|topology| &topology.node)
+            .map(|node| node.uuid)
+            .collect::<HashSet<_>>()
+            .len(),
+        links: topologies.iter().map(|topology| topology.link.len()).sum(),
+        connections: connections.len(),
+        connectivity_services: connectivity_services.len(),
+    };
+    report.dangling_references =
+        dangling_references(&topologies, &connections, &connectivity_services);
+    report.vendor_profile = Some(
+        VendorProfiles::global()
+            .select(None, &context.modules())
+            .name,
+    );
+}
+
+/// Lists the references of links, connections and services to entities missing from the upload.
+fn dangling_references(
+    topologies: &[Topology],
+    connections: &[Connection],
+    connectivity_services: &[ConnectivityService],
+) -> Vec<DanglingReference> {
+    let nodes = topologies.iter().flat_map(|topology| &topology.node);
+    let node_edge_points: HashSet<(Uuid, Uuid)> = nodes
+        .clone()
+        .flat_map(|node| {
+            node.owned_node_edge_point
+                .iter()
+                .map(move |owned_node_edge_point| (node.uuid, owned_node_edge_point.uuid))
+        })
+        .collect();
+    let connection_end_points: HashSet<Uuid> = nodes
+        .flat_map(|node| &node.owned_node_edge_point)
+        .filter_map(|owned_node_edge_point| owned_node_edge_point.cep_list.as_ref())
+        .flat_map(|cep_list| &cep_list.connection_end_point)
+        .map(|connection_end_point| connection_end_point.uuid)
+        .collect();
+    let links: HashSet<Uuid> = topologies
+        .iter()
+        .flat_map(|topology| &topology.link)
+        .map(|link| link.uuid)
+        .collect();
+    let connection_uuids: HashSet<Uuid> = connections
+        .iter()
+        .map(|connection| connection.uuid)
+        .collect();
+
+    let mut dangling = Vec::new();
+    let mut check = |found: bool, source: &str, source_uuid: Uuid, target: &str, target_uuid| {
+        if !found {
+            dangling.push(DanglingReference {
+                source: source.to_string(),
+                source_uuid,
+                target: target.to_string(),
+                target_uuid,
+            });
+        }
+    };
+
+    for link in topologies.iter().flat_map(|topology| &topology.link) {
+        for node_edge_point in &link.node_edge_point {
+            check(
+                node_edge_points.contains(&(
+                    node_edge_point.node_uuid,
+                    node_edge_point.node_edge_point_uuid,
+                )),
+                "link",
+                link.uuid,
+                "node-edge-point",
+                node_edge_point.node_edge_point_uuid,
+            );
+        }
+    }
+    for connection in connections {
+        for connection_end_point in &connection.connection_end_point {
+            check(
+                connection_end_points.contains(&connection_end_point.connection_end_point_uuid),
+                "connection",
+                connection.uuid,
+                "connection-end-point",
+                connection_end_point.connection_end_point_uuid,
+            );
+        }
+        for lower_connection in &connection.lower_connection {
+            check(
+                connection_uuids.contains(&lower_connection.connection_uuid),
+                "connection",
+                connection.uuid,
+                "connection",
+                lower_connection.connection_uuid,
+            );
+        }
+        for supported_client_link in &connection.supported_client_link {
+            check(
+                links.contains(&supported_client_link.link_uuid),
+                "connection",
+                connection.uuid,
+                "link",
+                supported_client_link.link_uuid,
+            );
+        }
+    }
+    for service in connectivity_services {
+        for connection in &service.connection {
+            check(
+                connection_uuids.contains(&connection.connection_uuid),
+                "connectivity-service",
+                service.uuid,
+                "connection",
+                connection.connection_uuid,
+            );
+        }
+    }
+
+    dangling
+}
@@ -0,0 +1,835 @@
+//! Offline RESTCONF/TAPI device used to test the device path end to end without network access.
+//!
+//! The mock serves a TAPI context built from fixture files (`devices_files`, `sienna_files`) over HTTPS
+//! with a certificate issued by its own CA, and over plain HTTP on the same port, for devices
+//! registered with the `http` scheme.
+//!
+//! Tests can change the datastore while the mock runs and publish TAPI notifications on the stream
+//! announced in `tapi-streaming:stream-context`, or given to the `tapi-notification` subscriptions
+//! created on devices that announce none. Data resources accept RESTCONF POST and DELETE, and
+//! created connectivity services go from `PLANNED` to `INSTALLED` as a device provisioning them would.
+//! The `compute-p-2-p-path` operation routes over the links of the topology.
+pub mod oauth2;
+pub mod path_computation;
+pub mod pki;
+pub mod restconf;
+
+use crate::mock::oauth2::MockTokens;
+use crate::mock::pki::MockPki;
+use crate::mock::restconf::EditError;
+use crate::models::devices::{Auth, Device, TlsConfig};
+use crate::AppError;
+
+use actix_web::dev::ServerHandle;
+use actix_web::http::{header, Method, StatusCode};
+use actix_web::{web, App, HttpRequest, HttpResponse, HttpServer};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use chrono::Utc;
+use futures_util::stream;
+use serde_json::{json, Map, Value};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::BufReader;
+use std::net::SocketAddr;
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+
+/// First byte of every TLS handshake, used to tell HTTPS connections apart from plain HTTP ones.
+const TLS_HANDSHAKE: u8 = 0x16;
+
+/// Token returned by the mock token endpoint.
+const MOCK_TOKEN: &str = "mock-tapi-token-0123456789";
+
+/// Name of the notification stream, served on `/{root}/streams/notifications`.
+const MOCK_STREAM: &str = "notifications";
+
+/// Format of the RESTCONF data responses.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MockFormat {
+    /// `application/yang-data+json`
+    Json,
+    /// `application/yang-data+xml`
+    Xml,
+}
+
+/// Behaviour of a `MockDevice`.
+#[derive(Clone, Debug)]
+pub struct MockDeviceConfig {
+    /// Datastore served under `/{root}/data`, e.g. `{"tapi-common:context": {...}}`.
+    pub datastore: Value,
+    /// Credentials the device accepts. Token auth is served on `auth_uri` for POST and PUT.
+    pub auth: Auth,
+    /// Format used when the request does not ask for one in the `Accept` header.
+    pub format: MockFormat,
+    /// When `false` the `fields` and `depth` query parameters are rejected with `400`, as some devices do.
+    pub supports_query_parameters: bool,
+    /// Number of data requests answered with `503` before answering normally, to exercise retries.
+    pub transient_failures: usize,
+    /// Size above which data responses are rejected with `413`, as devices that cannot serve their
+    /// complete context do.
+    pub max_response_bytes: Option<usize>,
+    /// Lifetime of the access tokens issued to `Auth::OAuth2` clients, announced in `expires_in`.
+    pub token_lifetime: Duration,
+    /// When `true` HTTPS clients must present the certificate of `MockDevice::client_identity`.
+    pub require_client_certificate: bool,
+    /// Time a created connectivity service stays `PLANNED` before it is `INSTALLED`.
+    pub provisioning_delay: Duration,
+    /// When `false` the `compute-p-2-p-path` operation is answered with `404`, as devices without a
+    /// path computation service do.
+    pub supports_path_computation: bool,
+    /// When `false` the notification stream is not announced, and is only given to the
+    /// `tapi-notification:notif-subscription` created on the device, as some devices do.
+    pub announces_stream: bool,
+}
+
+impl MockDeviceConfig {
+    /// Builds the datastore from a directory of fixture files split by part.
+    ///
+    /// # Arguments
+    /// - `dir`: Directory with any of `topology.json`, `connections.json`, `connectivity_services.json`
+    ///   and `services_interface_point.json`/`service_interface_points.json`.
+    /// - `auth`: Credentials the device accepts.
+    ///
+    /// # Returns
+    /// A `MockDeviceConfig` answering JSON and supporting `fields` and `depth`, or an `Error` if a file is invalid.
+    pub fn from_fixtures(dir: impl AsRef<Path>, auth: Auth) -> Result<Self, AppError> {
+        let dir = dir.as_ref();
+        let mut context = Map::new();
+
+        let service_interface_points = match read_list(&dir.join("services_interface_point.json"))?
+        {
+            Some(list) => Some(list),
+            None => read_list(&dir.join("service_interface_points.json"))?,
+        };
+        if let Some(service_interface_points) = service_interface_points {
+            context.insert(
+                "service-interface-point".to_string(),
+                Value::Array(service_interface_points),
+            );
+        }
+
+        if let Some(topologies) = read_list(&dir.join("topology.json"))? {
+            context.insert(
+                "tapi-topology:topology-context".to_string(),
+                json!({ "topology": topologies }),
+            );
+        }
+
+        let connectivity_services = read_list(&dir.join("connectivity_services.json"))?;
+        let connections = read_list(&dir.join("connections.json"))?;
+        if connectivity_services.is_some() || connections.is_some() {
+            context.insert(
+                "tapi-connectivity:connectivity-context".to_string(),
+                json!({
+                    "connectivity-service": connectivity_services.unwrap_or_default(),
+                    "connection": connections.unwrap_or_default(),
+                }),
+            );
+        }
+
+        if context.is_empty() {
+            return Err(AppError::validation_error(format!(
+                "There are no fixtures in {}",
+                dir.display()
+            )));
+        }
+
+        Ok(Self {
+            datastore: json!({ "tapi-common:context": context }),
+            auth,
+            format: MockFormat::Json,
+            supports_query_parameters: true,
+            transient_failures: 0,
+            max_response_bytes: None,
+            token_lifetime: Duration::from_secs(3600),
+            require_client_certificate: false,
+            provisioning_delay: Duration::from_millis(200),
+            supports_path_computation: true,
+            announces_stream: true,
+        })
+    }
+}
+
+/// Reads a fixture holding a list, either as a JSON array or as an object with a single array member.
+fn read_list(path: &Path) -> Result<Option<Vec<Value>>, AppError> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let file = File::open(path).map_err(|err| AppError::validation_error(err.to_string()))?;
+    let value: Value = serde_json::from_reader(BufReader::new(file))
+        .map_err(|err| AppError::validation_error(err.to_string()))?;
+
+    match value {
+        Value::Array(list) => Ok(Some(list)),
+        Value::Object(object) if object.len() == 1 => match object.into_iter().next() {
+            Some((_, Value::Array(list))) => Ok(Some(list)),
+            _ => Err(AppError::validation_error(format!(
+                "{} does not contain a list",
+                path.display()
+            ))),
+        },
+        Value::Object(object) => Ok(Some(vec![Value::Object(object)])),
+        _ => Err(AppError::validation_error(format!(
+            "{} does not contain a list",
+            path.display()
+        ))),
+    }
+}
+
+/// Running mock device. The server stops when `stop` is called or the value is dropped.
+pub struct MockDevice {
+    address: SocketAddr,
+    auth: Auth,
+    datastore: Arc<RwLock<Value>>,
+    notifications: broadcast::Sender<Value>,
+    tokens: Arc<MockTokens>,
+    pki: MockPki,
+    server_handle: ServerHandle,
+    accept_task: JoinHandle<()>,
+}
+
+impl MockDevice {
+    /// Starts a mock device listening on the given address, e.g. `127.0.0.1:0` for a random port.
+    ///
+    /// # Returns
+    /// The running `MockDevice`, or an `Error` if the address cannot be bound or the certificates cannot be created.
+    pub async fn start(config: MockDeviceConfig, address: &str) -> Result<Self, AppError> {
+        let listener = TcpListener::bind(address)
+            .await
+            .map_err(|err| AppError::server_error(err.to_string()))?;
+        let address = listener
+            .local_addr()
+            .map_err(|err| AppError::server_error(err.to_string()))?;
+
+        // Both servers only listen on loopback, connections reach them through the accept loop
+        let https_listener = std::net::TcpListener::bind("127.0.0.1:0")
+            .map_err(|err| AppError::server_error(err.to_string()))?;
+        let http_listener = std::net::TcpListener::bind("127.0.0.1:0")
+            .map_err(|err| AppError::server_error(err.to_string()))?;
+        let https_address = https_listener
+            .local_addr()
+            .map_err(|err| AppError::server_error(err.to_string()))?;
+        let http_address = http_listener
+            .local_addr()
+            .map_err(|err| AppError::server_error(err.to_string()))?;
+
+        let auth = config.auth.clone();
+        let mut datastore = config.datastore.clone();
+        if let Some(context) = datastore
+            .get_mut("tapi-common:context")
+            .and_then(Value::as_object_mut)
+        {
+            if config.announces_stream {
+                context.insert(
+                    "tapi-streaming:stream-context".to_string(),
+                    json!({
+                        "available-stream": [{
+                            "uuid": "00000000-0000-0000-0000-000000000001",
+                            "stream-id": MOCK_STREAM,
+                            "stream-state": "ACTIVE",
+                            "connection-address": format!("/restconf/streams/{}", MOCK_STREAM),
+                        }]
+                    }),
+                );
+            } else {
+                context.insert(
+                    "tapi-notification:notification-context".to_string(),
+                    json!({ "notif-subscription": [] }),
+                );
+            }
+        }
+        let datastore = Arc::new(RwLock::new(datastore));
+        let datastore_data = web::Data::from(datastore.clone());
+        let (notifications, _) = broadcast::channel(64);
+        let notifications_data = web::Data::new(notifications.clone());
+        let pki = MockPki::generate()?;
+        let acceptor = pki.acceptor(config.require_client_certificate)?;
+        let tokens = Arc::new(MockTokens::default());
+        let tokens_data = web::Data::from(tokens.clone());
+        let failures_left = web::Data::new(AtomicUsize::new(config.transient_failures));
+        let config = web::Data::new(config);
+        let server = HttpServer::new(move || {
+            App::new()
+                .app_data(config.clone())
+                .app_data(datastore_data.clone())
+                .app_data(notifications_data.clone())
+                .app_data(failures_left.clone())
+                .app_data(tokens_data.clone())
+                .route("/.well-known/host-meta", web::get().to(host_meta))
+                .route("/.well-known/host-meta/", web::get().to(host_meta))
+                .route("/{root}/data", web::get().to(data))
+                .route("/{root}/data/{path:.*}", web::get().to(data))
+                .route("/{root}/data/{path:.*}", web::post().to(create_data))
+                .route("/{root}/data/{path:.*}", web::delete().to(delete_data))
+                .route("/{root}/operations/{operation}", web::post().to(operation))
+                .route("/{root}/streams/{name}", web::get().to(notification_stream))
+                .default_service(web::route().to(token))
+        })
+        .workers(1)
+        .disable_signals()
+        .listen_openssl(https_listener, acceptor)
+        .map_err(|err| AppError::server_error(err.to_string()))?
+        .listen(http_listener)
+        .map_err(|err| AppError::server_error(err.to_string()))?
+        .run();
+
+        let server_handle = server.handle();
+        tokio::spawn(server);
+
+        let accept_task = tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                tokio::spawn(forward(stream, https_address, http_address));
+            }
+        });
+
+        Ok(Self {
+            address,
+            auth,
+            datastore,
+            notifications,
+            tokens,
+            pki,
+            server_handle,
+            accept_task,
+        })
+    }
+
+    /// Port the mock device is listening on.
+    pub fn port(&self) -> u16 {
+        self.address.port()
+    }
+
+    /// `Device` pointing to this mock with the credentials it accepts, trusting the CA of the mock.
+    pub fn device(&self) -> Device {
+        let ip = if self.address.ip().is_unspecified() {
+            "127.0.0.1".to_string()
+        } else {
+            self.address.ip().to_string()
+        };
+
+        Device {
+            ip,
+            port: Some(self.address.port() as i64),
+            auth: self.auth.clone(),
+            vendor: None,
+            retrieval_strategy: None,
+            tls: TlsConfig {
+                ca_certificate: Some(self.pki.ca_certificate()),
+                ..TlsConfig::default()
+            },
+        }
+    }
+
+    /// PEM certificate of the CA that issued the certificate of the mock.
+    pub fn ca_certificate(&self) -> String {
+        self.pki.ca_certificate()
+    }
+
+    /// PEM certificate and PKCS#8 PEM key accepted when `require_client_certificate` is set.
+    pub fn client_identity(&self) -> (String, String) {
+        self.pki.client_identity()
+    }
+
+    /// `grant_type` of every token accepted by the OAuth2 token endpoint, in order.
+    pub fn token_grants(&self) -> Vec<String> {
+        self.tokens.grants()
+    }
+
+    /// Revokes the OAuth2 access tokens, so the next data requests are answered with `401`.
+    pub fn revoke_tokens(&self) {
+        self.tokens.revoke_access_tokens();
+    }
+
+    /// Changes the datastore served from now on, e.g. to add the object of an `OBJECT_CREATION`.
+    pub fn update_datastore(&self, update: impl FnOnce(&mut Value)) {
+        let mut datastore = self
+            .datastore
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        update(&mut datastore);
+    }
+
+    /// Sends a `tapi-notification:notification` to the clients of the notification stream, wrapped
+    /// in an `ietf-restconf:notification` (RFC 8040, section 6.4).
+    ///
+    /// # Returns
+    /// The number of clients the notification was sent to.
+    pub fn publish(&self, notification: Value) -> usize {
+        let event = json!({
+            "ietf-restconf:notification": {
+                "eventTime": Utc::now().to_rfc3339(),
+                "tapi-notification:notification": notification,
+            }
+        });
+        self.notifications.send(event).unwrap_or_default()
+    }
+
+    /// Number of clients reading the notification stream.
+    pub fn subscribers(&self) -> usize {
+        self.notifications.receiver_count()
+    }
+
+    /// Stops accepting connections and shuts the server down.
+    pub async fn stop(self) {
+        self.accept_task.abort();
+        self.server_handle.stop(false).await;
+    }
+}
+
+impl Drop for MockDevice {
+    fn drop(&mut self) {
+        self.accept_task.abort();
+    }
+}
+
+/// Forwards a connection to the HTTPS or the plain HTTP server depending on its first byte.
+async fn forward(mut stream: TcpStream, https_address: SocketAddr, http_address: SocketAddr) {
+    let mut first_byte = [0u8; 1];
+    let target = match stream.peek(&mut first_byte).await {
+        Ok(1) if first_byte[0] == TLS_HANDSHAKE => https_address,
+        Ok(1) => http_address,
+        _ => return,
+    };
+
+    if let Ok(mut upstream) = TcpStream::connect(target).await {
+        let _ = tokio::io::copy_bidirectional(&mut stream, &mut upstream).await;
+    }
+}
+
+/// Builds a RESTCONF error response.
+fn restconf_error(status: actix_web::http::StatusCode, tag: &str, message: &str) -> HttpResponse {
+    HttpResponse::build(status)
+        .content_type("application/yang-data+json")
+        .json(json!({
+            "ietf-restconf:errors": {
+                "error": [{
+                    "error-type": "protocol",
+                    "error-tag": tag,
+                    "error-message": message,
+                }]
+            }
+        }))
+}
+
+/// Returns `true` if the request carries the credentials configured for the device.
+fn is_authorized(req: &HttpRequest, auth: &Auth, tokens: &MockTokens) -> bool {
+    let Some(authorization) = req
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+    else {
+        return false;
+    };
+
+    match auth {
+        Auth::Basic(basic_auth) => {
+            let expected =
+                STANDARD.encode(format!("{}:{}", basic_auth.username, basic_auth.password));
+            authorization == format!("Basic {}", expected)
+        }
+        Auth::Token(_) => authorization == format!("Bearer {}", MOCK_TOKEN),
+        Auth::OAuth2(_) => authorization
+            .strip_prefix("Bearer ")
+            .is_some_and(|access_token| tokens.is_valid(access_token)),
+    }
+}
+
+/// `/.well-known/host-meta`, pointing to the RESTCONF root as RFC 8040 describes.
+async fn host_meta() -> HttpResponse {
+    HttpResponse::Ok()
+        .content_type("application/xrd+xml")
+        .body(
+            r#"<XRD xmlns="http://docs.oasis-open.org/ns/xri/xrd-1.0"><Link rel="restconf" href="/restconf"/></XRD>"#,
+        )
+}
+
+/// Token endpoint. Answers POST and PUT on the configured `auth_uri` when the body matches `auth_body`,
+/// or the OAuth2 grants POSTed on `token_uri`.
+async fn token(
+    req: HttpRequest,
+    body: web::Bytes,
+    config: web::Data<MockDeviceConfig>,
+    tokens: web::Data<MockTokens>,
+) -> HttpResponse {
+    let not_found = || restconf_error(StatusCode::NOT_FOUND, "invalid-value", "Resource Not Found");
+
+    let token_auth = match &config.auth {
+        Auth::Token(token_auth) => token_auth,
+        Auth::OAuth2(oauth2_auth) => {
+            let is_token_endpoint = req.method() == Method::POST
+                && req.path().trim_start_matches('/')
+                    == oauth2_auth.token_uri.trim_start_matches('/');
+            if !is_token_endpoint {
+                return not_found();
+            }
+            return oauth2::grant(&req, &body, oauth2_auth, &tokens, config.token_lifetime);
+        }
+        Auth::Basic(_) => return not_found(),
+    };
+    let is_token_request = req.method() == Method::POST || req.method() == Method::PUT;
+    if !is_token_request
+        || req.path().trim_start_matches('/') != token_auth.auth_uri.trim_start_matches('/')
+    {
+        return not_found();
+    }
+
+    match serde_json::from_slice::<Value>(&body) {
+        Ok(auth_body) if auth_body == token_auth.auth_body => {
+            HttpResponse::Ok().json(json!({ "token": MOCK_TOKEN }))
+        }
+        _ => restconf_error(
+            StatusCode::UNAUTHORIZED,
+            "access-denied",
+            "Invalid credentials",
+        ),
+    }
+}
+
+/// RESTCONF data resource, with support for list keys and the `fields` and `depth` query parameters.
+async fn data(
+    req: HttpRequest,
+    query: web::Query<HashMap<String, String>>,
+    config: web::Data<MockDeviceConfig>,
+    datastore: web::Data<RwLock<Value>>,
+    failures_left: web::Data<AtomicUsize>,
+    tokens: web::Data<MockTokens>,
+) -> HttpResponse {
+    let is_failure = failures_left
+        .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |left| {
+            left.checked_sub(1)
+        })
+        .is_ok();
+    if is_failure {
+        return restconf_error(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "resource-denied",
+            "Device is busy",
+        );
+    }
+
+    if !is_authorized(&req, &config.auth, &tokens) {
+        return restconf_error(
+            StatusCode::UNAUTHORIZED,
+            "access-denied",
+            "Invalid credentials",
+        );
+    }
+
+    let has_query_parameters = query.contains_key("fields") || query.contains_key("depth");
+    if has_query_parameters && !config.supports_query_parameters {
+        return restconf_error(
+            StatusCode::BAD_REQUEST,
+            "invalid-value",
+            "Query parameters are not supported",
+        );
+    }
+
+    let datastore = datastore
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    let path = req.match_info().get("path").unwrap_or_default();
+    let target = match restconf::resolve(&datastore, path) {
+        Ok(target) => target,
+        Err(err) => {
+            return restconf_error(StatusCode::NOT_FOUND, "invalid-value", &err.to_string())
+        }
+    };
+
+    let mut value = target.value;
+    if let Some(fields) = query.get("fields") {
+        match restconf::parse_fields(fields) {
+            Ok(selectors) => value = Cow::Owned(restconf::select_fields(&value, &selectors)),
+            Err(err) => {
+                return restconf_error(StatusCode::BAD_REQUEST, "invalid-value", &err.to_string())
+            }
+        }
+    }
+    match query.get("depth").map(String::as_str) {
+        None | Some("unbounded") => {}
+        Some(depth) => match depth.parse::<usize>() {
+            Ok(depth) if depth > 0 => value = Cow::Owned(restconf::limit_depth(&value, depth)),
+            _ => {
+                return restconf_error(
+                    StatusCode::BAD_REQUEST,
+                    "invalid-value",
+                    "depth must be a positive integer or unbounded",
+                )
+            }
+        },
+    }
+
+    if let Some(max_response_bytes) = config.max_response_bytes {
+        let size = serde_json::to_vec(&*value)
+            .map(|body| body.len())
+            .unwrap_or_default();
+        if size > max_response_bytes {
+            return restconf_error(
+                StatusCode::PAYLOAD_TOO_LARGE,
+                "too-big",
+                "Response is too big",
+            );
+        }
+    }
+
+    let accept = req
+        .headers()
+        .get(header::ACCEPT)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default();
+    let format = if accept.contains("yang-data+xml") {
+        MockFormat::Xml
+    } else if accept.contains("yang-data+json") {
+        MockFormat::Json
+    } else {
+        config.format
+    };
+
+    match format {
+        MockFormat::Json => {
+            let mut body = Map::new();
+            body.insert(target.name, value.into_owned());
+            HttpResponse::Ok()
+                .content_type("application/yang-data+json")
+                .json(Value::Object(body))
+        }
+        MockFormat::Xml => HttpResponse::Ok()
+            .content_type("application/yang-data+xml")
+            .body(restconf::to_xml(&target.name, &value)),
+    }
+}
+
+/// Builds the RESTCONF error response of a failed edit.
+fn edit_error(err: EditError) -> HttpResponse {
+    match err {
+        EditError::NotFound(message) => {
+            restconf_error(StatusCode::NOT_FOUND, "invalid-value", &message)
+        }
+        EditError::Exists(message) => restconf_error(StatusCode::CONFLICT, "data-exists", &message),
+        EditError::Invalid(message) => {
+            restconf_error(StatusCode::BAD_REQUEST, "invalid-value", &message)
+        }
+    }
+}
+
+/// Marks the connectivity services of a create body as `PLANNED`, returning their UUIDs.
+fn plan_services(body: &mut Value) -> Vec<String> {
+    let Some(body) = body.as_object_mut() else {
+        return vec![];
+    };
+
+    body.iter_mut()
+        .filter(|(name, _)| name.rsplit(':').next() == Some("connectivity-service"))
+        .filter_map(|(_, services)| services.as_array_mut())
+        .flatten()
+        .filter_map(|service| {
+            service["lifecycle-state"] = json!("PLANNED");
+            service["operational-state"] = json!("DISABLED");
+            service
+                .get("uuid")
+                .and_then(Value::as_str)
+                .map(str::to_string)
+        })
+        .collect()
+}
+
+/// Gives the notification stream to the `notif-subscription` entries of a create body.
+fn open_subscriptions(body: &mut Value) {
+    let Some(body) = body.as_object_mut() else {
+        return;
+    };
+
+    body.iter_mut()
+        .filter(|(name, _)| name.rsplit(':').next() == Some("notif-subscription"))
+        .filter_map(|(_, subscriptions)| subscriptions.as_array_mut())
+        .flatten()
+        .for_each(|subscription| {
+            subscription["notification-channel"] = json!({
+                "stream-address": format!("/restconf/streams/{}", MOCK_STREAM),
+            });
+        });
+}
+
+/// RESTCONF create of the data in the body under the data resource, answered with `201`.
+///
+/// Created connectivity services are `PLANNED` and become `INSTALLED` after `provisioning_delay`,
+/// created notification subscriptions are given the notification stream.
+async fn create_data(
+    req: HttpRequest,
+    body: web::Bytes,
+    config: web::Data<MockDeviceConfig>,
+    datastore: web::Data<RwLock<Value>>,
+    tokens: web::Data<MockTokens>,
+) -> HttpResponse {
+    if !is_authorized(&req, &config.auth, &tokens) {
+        return restconf_error(
+            StatusCode::UNAUTHORIZED,
+            "access-denied",
+            "Invalid credentials",
+        );
+    }
+    let mut body: Value = match serde_json::from_slice(&body) {
+        Ok(body) => body,
+        Err(err) => {
+            return restconf_error(
+                StatusCode::BAD_REQUEST,
+                "malformed-message",
+                &err.to_string(),
+            )
+        }
+    };
+
+    let path = req.match_info().get("path").unwrap_or_default().to_string();
+    let services = plan_services(&mut body);
+    open_subscriptions(&mut body);
+    let created = restconf::create(
+        &mut datastore
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()),
+        &path,
+        &body,
+    );
+    if let Err(err) = created {
+        return edit_error(err);
+    }
+
+    if !services.is_empty() {
+        let datastore = datastore.into_inner();
+        let provisioning_delay = config.provisioning_delay;
+        tokio::spawn(async move {
+            tokio::time::sleep(provisioning_delay).await;
+            let mut datastore = datastore
+                .write()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            for uuid in services {
+                let service_path = format!("{}/connectivity-service={}", path, uuid);
+                // The service may have been deleted meanwhile
+                if let Ok(service) = restconf::resolve_mut(&mut datastore, &service_path) {
+                    service["lifecycle-state"] = json!("INSTALLED");
+                    service["operational-state"] = json!("ENABLED");
+                }
+            }
+        });
+    }
+
+    HttpResponse::Created().finish()
+}
+
+/// RESTCONF delete of the data resource, answered with `204`.
+async fn delete_data(
+    req: HttpRequest,
+    config: web::Data<MockDeviceConfig>,
+    datastore: web::Data<RwLock<Value>>,
+    tokens: web::Data<MockTokens>,
+) -> HttpResponse {
+    if !is_authorized(&req, &config.auth, &tokens) {
+        return restconf_error(
+            StatusCode::UNAUTHORIZED,
+            "access-denied",
+            "Invalid credentials",
+        );
+    }
+
+    let path = req.match_info().get("path").unwrap_or_default();
+    let mut datastore = datastore
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    match restconf::delete(&mut datastore, path) {
+        Ok(()) => HttpResponse::NoContent().finish(),
+        Err(err) => edit_error(err),
+    }
+}
+
+/// RESTCONF operation (RFC 8040, section 3.6), answering `compute-p-2-p-path` with its output.
+async fn operation(
+    req: HttpRequest,
+    body: web::Bytes,
+    config: web::Data<MockDeviceConfig>,
+    datastore: web::Data<RwLock<Value>>,
+    tokens: web::Data<MockTokens>,
+) -> HttpResponse {
+    if !is_authorized(&req, &config.auth, &tokens) {
+        return restconf_error(
+            StatusCode::UNAUTHORIZED,
+            "access-denied",
+            "Invalid credentials",
+        );
+    }
+    let name = req.match_info().get("operation").unwrap_or_default();
+    if !config.supports_path_computation || name.rsplit(':').next() != Some("compute-p-2-p-path") {
+        return restconf_error(
+            StatusCode::NOT_FOUND,
+            "operation-not-supported",
+            &format!("Operation {} is not supported", name),
+        );
+    }
+    let input: Value = match serde_json::from_slice(&body) {
+        Ok(input) => input,
+        Err(err) => {
+            return restconf_error(
+                StatusCode::BAD_REQUEST,
+                "malformed-message",
+                &err.to_string(),
+            )
+        }
+    };
+
+    let mut datastore = datastore
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    match path_computation::compute(&mut datastore, &input) {
+        Ok(output) => HttpResponse::Ok()
+            .content_type("application/yang-data+json")
+            .json(output),
+        Err(err) => edit_error(err),
+    }
+}
+
+/// RESTCONF notification stream (RFC 8040, section 6.3), sending the notifications given to
+/// `MockDevice::publish` as server-sent events.
+async fn notification_stream(
+    req: HttpRequest,
+    config: web::Data<MockDeviceConfig>,
+    notifications: web::Data<broadcast::Sender<Value>>,
+    tokens: web::Data<MockTokens>,
+) -> HttpResponse {
+    if !is_authorized(&req, &config.auth, &tokens) {
+        return restconf_error(
+            StatusCode::UNAUTHORIZED,
+            "access-denied",
+            "Invalid credentials",
+        );
+    }
+    if req.match_info().get("name") != Some(MOCK_STREAM) {
+        return restconf_error(StatusCode::NOT_FOUND, "invalid-value", "Unknown stream");
+    }
+
+    // The first comment sends the headers before any notification is published
+    let receiver = notifications.subscribe();
+    let events = stream::unfold((receiver, true), |(mut receiver, is_first)| async move {
+        if is_first {
+            let comment = web::Bytes::from_static(b": connected\n\n");
+            return Some((Ok::<_, actix_web::Error>(comment), (receiver, false)));
+        }
+        loop {
+            match receiver.recv().await {
+                Ok(event) => {
+                    let chunk = format!("data: {}\n\n", event);
+                    return Some((Ok(web::Bytes::from(chunk)), (receiver, false)));
+                }
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => return None,
+            }
+        }
+    });
+
+    HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .insert_header((header::CACHE_CONTROL, "no-cache"))
+        .streaming(events)
+}
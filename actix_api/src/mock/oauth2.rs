@@ -0,0 +1,148 @@
+//! OAuth2 token endpoint of the mock device (RFC 6749), issuing expiring access tokens and refresh tokens.
+use crate::models::devices::{GrantType, OAuth2Auth};
+
+use actix_web::http::{header, StatusCode};
+use actix_web::{web, HttpRequest, HttpResponse};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde_json::json;
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Tokens issued by the OAuth2 token endpoint of a `MockDevice`.
+#[derive(Debug, Default)]
+pub struct MockTokens {
+    /// Valid access tokens with the moment they expire.
+    access_tokens: Mutex<HashMap<String, Instant>>,
+    /// Refresh tokens not used yet. Each one is replaced by a new one when it is used.
+    refresh_tokens: Mutex<HashSet<String>>,
+    /// `grant_type` of every accepted token request, in order.
+    grants: Mutex<Vec<String>>,
+    issued: AtomicUsize,
+}
+
+impl MockTokens {
+    /// Returns `true` if the access token was issued, has not expired and was not revoked.
+    pub fn is_valid(&self, access_token: &str) -> bool {
+        self.access_tokens
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .get(access_token)
+            .is_some_and(|expires_at| Instant::now() < *expires_at)
+    }
+
+    /// Revokes every access token, keeping the refresh tokens valid.
+    pub fn revoke_access_tokens(&self) {
+        self.access_tokens
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .clear();
+    }
+
+    /// `grant_type` of every accepted token request, in order.
+    pub fn grants(&self) -> Vec<String> {
+        self.grants
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .clone()
+    }
+
+    /// Issues an access token and a refresh token for an accepted grant.
+    fn issue(&self, grant_type: &str, lifetime: Duration) -> HttpResponse {
+        let number = self.issued.fetch_add(1, Ordering::SeqCst);
+        let access_token = format!("mock-access-token-{:04}", number);
+        let refresh_token = format!("mock-refresh-token-{:04}", number);
+
+        self.access_tokens
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .insert(access_token.clone(), Instant::now() + lifetime);
+        self.refresh_tokens
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .insert(refresh_token.clone());
+        self.grants
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .push(grant_type.to_string());
+
+        HttpResponse::Ok().json(json!({
+            "access_token": access_token,
+            "token_type": "Bearer",
+            "expires_in": lifetime.as_secs(),
+            "refresh_token": refresh_token,
+        }))
+    }
+}
+
+/// Builds an OAuth2 error response (RFC 6749, section 5.2).
+fn oauth2_error(status: StatusCode, error: &str) -> HttpResponse {
+    HttpResponse::build(status).json(json!({ "error": error }))
+}
+
+/// Answers a form-encoded token request.
+///
+/// The client is authenticated with HTTP basic authentication or with `client_id` and
+/// `client_secret` in the form. The `refresh_token` grant is accepted whatever the grant type of
+/// the device.
+///
+/// # Arguments
+/// - `body`: The form-encoded body of the request.
+/// - `oauth2_auth`: The grant and credentials the device accepts.
+/// - `lifetime`: Lifetime of the issued access tokens.
+pub fn grant(
+    req: &HttpRequest,
+    body: &[u8],
+    oauth2_auth: &OAuth2Auth,
+    tokens: &MockTokens,
+    lifetime: Duration,
+) -> HttpResponse {
+    let Ok(form) = web::Query::<HashMap<String, String>>::from_query(
+        std::str::from_utf8(body).unwrap_or_default(),
+    ) else {
+        return oauth2_error(StatusCode::BAD_REQUEST, "invalid_request");
+    };
+    let parameter = |name: &str| form.get(name).map(String::as_str);
+
+    if let Some(client_id) = &oauth2_auth.client_id {
+        let client_secret = oauth2_auth.client_secret.as_deref().unwrap_or_default();
+        let expected = format!(
+            "Basic {}",
+            STANDARD.encode(format!("{}:{}", client_id, client_secret))
+        );
+        let is_basic_client = req
+            .headers()
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .is_some_and(|authorization| authorization == expected);
+        let is_form_client = parameter("client_id") == Some(client_id)
+            && parameter("client_secret") == Some(client_secret);
+        if !is_basic_client && !is_form_client {
+            return oauth2_error(StatusCode::UNAUTHORIZED, "invalid_client");
+        }
+    }
+
+    let is_granted = match parameter("grant_type") {
+        Some("client_credentials") => oauth2_auth.grant_type == GrantType::ClientCredentials,
+        Some("password") => {
+            oauth2_auth.grant_type == GrantType::Password
+                && parameter("username") == oauth2_auth.username.as_deref()
+                && parameter("password") == oauth2_auth.password.as_deref()
+        }
+        Some("refresh_token") => parameter("refresh_token").is_some_and(|refresh_token| {
+            tokens
+                .refresh_tokens
+                .lock()
+                .unwrap_or_else(|err| err.into_inner())
+                .remove(refresh_token)
+        }),
+        _ => return oauth2_error(StatusCode::BAD_REQUEST, "unsupported_grant_type"),
+    };
+
+    if is_granted {
+        tokens.issue(parameter("grant_type").unwrap_or_default(), lifetime)
+    } else {
+        oauth2_error(StatusCode::BAD_REQUEST, "invalid_grant")
+    }
+}
@@ -0,0 +1,204 @@
+//! `compute-p-2-p-path` RPC of the mock device, routing over the links of its topology.
+
+use crate::mock::restconf::EditError;
+
+use serde_json::{json, Map, Value};
+use std::collections::{BTreeMap, HashSet, VecDeque};
+use tapi_model::Uuid;
+
+/// Link leaving a node: the node it reaches, and the topology and UUID of the link.
+type Hop = (String, String, String);
+
+/// Finds the member of an object by its name, with or without module prefix.
+fn member<'a>(value: &'a Value, name: &str) -> Option<&'a Value> {
+    value
+        .as_object()?
+        .iter()
+        .find(|(key, _)| key.rsplit(':').next() == Some(name))
+        .map(|(_, value)| value)
+}
+
+/// Reads a list member, an absent member being an empty list.
+fn list<'a>(value: &'a Value, name: &str) -> &'a [Value] {
+    member(value, name)
+        .and_then(Value::as_array)
+        .map(Vec::as_slice)
+        .unwrap_or_default()
+}
+
+/// Reads a string member.
+fn string(value: &Value, name: &str) -> Option<String> {
+    member(value, name)
+        .and_then(Value::as_str)
+        .map(str::to_string)
+}
+
+/// UUIDs of the nodes referenced by a `topology-constraint`, e.g. by its `include-node` list.
+fn constrained_nodes(input: &Value, name: &str) -> Vec<String> {
+    let constraints = match member(input, "topology-constraint") {
+        Some(Value::Array(constraints)) => constraints.iter().collect(),
+        Some(constraint) => vec![constraint],
+        None => vec![],
+    };
+    constraints
+        .into_iter()
+        .flat_map(|constraint| list(constraint, name))
+        .filter_map(|node| string(node, "node-uuid"))
+        .collect()
+}
+
+/// Shortest sequence of links from `from` to `to` avoiding the `excluded` nodes.
+fn route(
+    adjacency: &BTreeMap<String, Vec<Hop>>,
+    from: &str,
+    to: &str,
+    excluded: &HashSet<String>,
+) -> Option<Vec<Hop>> {
+    let mut previous: BTreeMap<&str, (&str, &Hop)> = BTreeMap::new();
+    let mut visited = HashSet::from([from]);
+    let mut queue = VecDeque::from([from]);
+    while let Some(node) = queue.pop_front() {
+        if node == to {
+            let mut hops = vec![];
+            let mut current = to;
+            while let Some((before, hop)) = previous.get(current) {
+                hops.push((*hop).clone());
+                current = before;
+            }
+            hops.reverse();
+            return Some(hops);
+        }
+        for hop in adjacency.get(node).into_iter().flatten() {
+            let next = hop.0.as_str();
+            if excluded.contains(next) || !visited.insert(next) {
+                continue;
+            }
+            previous.insert(next, (node, hop));
+            queue.push_back(next);
+        }
+    }
+    None
+}
+
+/// Computes a path between the service interface points of a `compute-p-2-p-path` input.
+///
+/// Links are followed in both directions, through the `include-node` of the topology constraint
+/// in their order and around its `exclude-node`. The path and a `path-comp-service` referencing it
+/// are stored in the path computation context of the datastore.
+///
+/// # Returns
+/// The RPC output, with the `path-comp-service` referencing the path by UUID, or an `Error` if the
+/// input is invalid or no path joins the service interface points.
+pub fn compute(datastore: &mut Value, input: &Value) -> Result<Value, EditError> {
+    let input = member(input, "input").unwrap_or(input);
+    let sips: Vec<String> = list(input, "sep")
+        .iter()
+        .filter_map(|end_point| {
+            member(end_point, "service-interface-point")
+                .and_then(|sip| string(sip, "service-interface-point-uuid"))
+        })
+        .collect();
+    let [source, destination] = sips.as_slice() else {
+        return Err(EditError::Invalid(
+            "sep must hold two service interface points".to_string(),
+        ));
+    };
+
+    let context = &datastore["tapi-common:context"];
+    let topologies = member(context, "topology-context")
+        .map(|topology_context| list(topology_context, "topology"))
+        .unwrap_or_default();
+    let mut sip_nodes: BTreeMap<String, String> = BTreeMap::new();
+    let mut adjacency: BTreeMap<String, Vec<Hop>> = BTreeMap::new();
+    for topology in topologies {
+        let topology_uuid = string(topology, "uuid").unwrap_or_default();
+        for node in list(topology, "node") {
+            let node_uuid = string(node, "uuid").unwrap_or_default();
+            for edge_point in list(node, "owned-node-edge-point") {
+                for sip in list(edge_point, "mapped-service-interface-point") {
+                    if let Some(sip_uuid) = string(sip, "service-interface-point-uuid") {
+                        sip_nodes.insert(sip_uuid, node_uuid.clone());
+                    }
+                }
+            }
+        }
+        for link in list(topology, "link") {
+            let link_uuid = string(link, "uuid").unwrap_or_default();
+            let nodes: Vec<String> = list(link, "node-edge-point")
+                .iter()
+                .filter_map(|edge_point| string(edge_point, "node-uuid"))
+                .collect();
+            if let [a, b] = nodes.as_slice() {
+                for (from, to) in [(a, b), (b, a)] {
+                    adjacency.entry(from.clone()).or_default().push((
+                        to.clone(),
+                        topology_uuid.clone(),
+                        link_uuid.clone(),
+                    ));
+                }
+            }
+        }
+    }
+
+    let node_of = |sip: &String| {
+        sip_nodes
+            .get(sip)
+            .cloned()
+            .ok_or_else(|| EditError::Invalid(format!("Unknown service interface point {}", sip)))
+    };
+    let mut waypoints = vec![node_of(source)?];
+    waypoints.extend(constrained_nodes(input, "include-node"));
+    waypoints.push(node_of(destination)?);
+    let excluded: HashSet<String> = constrained_nodes(input, "exclude-node")
+        .into_iter()
+        .collect();
+    if waypoints.iter().any(|node| excluded.contains(node)) {
+        return Err(EditError::NotFound(format!(
+            "No path between {} and {}",
+            source, destination
+        )));
+    }
+
+    let mut hops = vec![];
+    for segment in waypoints.windows(2) {
+        let segment_hops =
+            route(&adjacency, &segment[0], &segment[1], &excluded).ok_or_else(|| {
+                EditError::NotFound(format!("No path between {} and {}", source, destination))
+            })?;
+        hops.extend(segment_hops);
+    }
+
+    let service_uuid = Uuid::new_v4().to_string();
+    let path_uuid = Uuid::new_v4().to_string();
+    let path = json!({
+        "uuid": path_uuid,
+        "name": [{ "value-name": "PATH_NAME", "value": format!("{} - {}", source, destination) }],
+        "link": hops
+            .iter()
+            .map(|(_, topology_uuid, link_uuid)| json!({
+                "topology-uuid": topology_uuid,
+                "link-uuid": link_uuid,
+            }))
+            .collect::<Vec<_>>(),
+    });
+    let service = json!({
+        "uuid": service_uuid,
+        "end-point": list(input, "sep"),
+        "path": [{ "path-uuid": path_uuid }],
+    });
+
+    let context = datastore["tapi-common:context"]
+        .as_object_mut()
+        .ok_or_else(|| EditError::NotFound("tapi-common:context".to_string()))?;
+    let path_computation_context = context
+        .entry("tapi-path-computation:path-computation-context")
+        .or_insert_with(|| Value::Object(Map::new()));
+    for (name, entry) in [("path-comp-service", service.clone()), ("path", path)] {
+        match path_computation_context[name].as_array_mut() {
+            Some(entries) => entries.push(entry),
+            None => path_computation_context[name] = json!([entry]),
+        }
+    }
+
+    Ok(json!({ "tapi-path-computation:output": { "service": service } }))
+}
@@ -0,0 +1,141 @@
+//! Certificates of the mock device: a CA issuing the server certificate and a client certificate for
+//! mutual TLS, so clients can verify the mock instead of accepting any certificate.
+use crate::AppError;
+
+use openssl::asn1::Asn1Time;
+use openssl::bn::BigNum;
+use openssl::ec::{EcGroup, EcKey};
+use openssl::error::ErrorStack;
+use openssl::hash::MessageDigest;
+use openssl::nid::Nid;
+use openssl::pkey::{PKey, Private};
+use openssl::ssl::{SslAcceptor, SslAcceptorBuilder, SslMethod, SslVerifyMode};
+use openssl::x509::extension::{
+    BasicConstraints, ExtendedKeyUsage, KeyUsage, SubjectAlternativeName,
+};
+use openssl::x509::{X509Builder, X509NameBuilder, X509};
+
+/// Certificate with its private key.
+struct Issued {
+    certificate: X509,
+    key: PKey<Private>,
+}
+
+/// CA, server and client certificates generated for a `MockDevice`.
+pub struct MockPki {
+    ca: Issued,
+    server: Issued,
+    client: Issued,
+}
+
+impl MockPki {
+    /// Generates a CA, a server certificate for `localhost` and `127.0.0.1`, and a client
+    /// certificate, all valid for a year.
+    pub fn generate() -> Result<Self, AppError> {
+        let generate = || -> Result<Self, ErrorStack> {
+            let ca = issue("Mock TAPI CA", 1, None, |builder, _| {
+                builder.append_extension(BasicConstraints::new().critical().ca().build()?)?;
+                builder.append_extension(KeyUsage::new().key_cert_sign().crl_sign().build()?)
+            })?;
+            let server = issue("localhost", 2, Some(&ca), |builder, ca| {
+                let subject_alternative_name = SubjectAlternativeName::new()
+                    .dns("localhost")
+                    .ip("127.0.0.1")
+                    .build(&builder.x509v3_context(ca.map(AsRef::as_ref), None))?;
+                builder.append_extension(subject_alternative_name)?;
+                builder.append_extension(ExtendedKeyUsage::new().server_auth().build()?)
+            })?;
+            let client = issue("Mock TAPI client", 3, Some(&ca), |builder, _| {
+                builder.append_extension(ExtendedKeyUsage::new().client_auth().build()?)
+            })?;
+
+            Ok(Self { ca, server, client })
+        };
+
+        generate().map_err(|err| AppError::server_error(err.to_string()))
+    }
+
+    /// PEM certificate of the CA, to be trusted by the clients of the mock.
+    pub fn ca_certificate(&self) -> String {
+        pem(self.ca.certificate.to_pem())
+    }
+
+    /// PEM certificate and PKCS#8 PEM key accepted from clients when mutual TLS is required.
+    pub fn client_identity(&self) -> (String, String) {
+        (
+            pem(self.client.certificate.to_pem()),
+            pem(self.client.key.private_key_to_pem_pkcs8()),
+        )
+    }
+
+    /// Creates the TLS acceptor of the mock, requiring a certificate issued by the CA from clients
+    /// when `require_client_certificate` is set.
+    pub fn acceptor(
+        &self,
+        require_client_certificate: bool,
+    ) -> Result<SslAcceptorBuilder, AppError> {
+        let build = || -> Result<SslAcceptorBuilder, ErrorStack> {
+            let mut acceptor = SslAcceptor::mozilla_intermediate(SslMethod::tls())?;
+            acceptor.set_private_key(&self.server.key)?;
+            acceptor.set_certificate(&self.server.certificate)?;
+            acceptor.add_extra_chain_cert(self.ca.certificate.clone())?;
+            if require_client_certificate {
+                acceptor
+                    .cert_store_mut()
+                    .add_cert(self.ca.certificate.clone())?;
+                acceptor.add_client_ca(&self.ca.certificate)?;
+                acceptor.set_verify(SslVerifyMode::PEER | SslVerifyMode::FAIL_IF_NO_PEER_CERT);
+            }
+            Ok(acceptor)
+        };
+
+        build().map_err(|err| AppError::server_error(err.to_string()))
+    }
+}
+
+/// Issues a certificate with a new key, signed by `issuer` or self-signed when there is none.
+fn issue(
+    common_name: &str,
+    serial_number: u32,
+    issuer: Option<&Issued>,
+    extensions: impl FnOnce(&mut X509Builder, Option<&X509>) -> Result<(), ErrorStack>,
+) -> Result<Issued, ErrorStack> {
+    let group = EcGroup::from_curve_name(Nid::X9_62_PRIME256V1)?;
+    let key = PKey::from_ec_key(EcKey::generate(&group)?)?;
+
+    let mut name = X509NameBuilder::new()?;
+    name.append_entry_by_nid(Nid::COMMONNAME, common_name)?;
+    let name = name.build();
+
+    let mut builder = X509::builder()?;
+    builder.set_version(2)?;
+    let serial_number = BigNum::from_u32(serial_number)?.to_asn1_integer()?;
+    let not_before = Asn1Time::days_from_now(0)?;
+    let not_after = Asn1Time::days_from_now(365)?;
+    builder.set_serial_number(&serial_number)?;
+    builder.set_subject_name(&name)?;
+    builder.set_issuer_name(
+        issuer
+            .map(|issuer| issuer.certificate.subject_name())
+            .unwrap_or(&name),
+    )?;
+    builder.set_pubkey(&key)?;
+    builder.set_not_before(&not_before)?;
+    builder.set_not_after(&not_after)?;
+    extensions(&mut builder, issuer.map(|issuer| &issuer.certificate))?;
+    builder.sign(
+        issuer.map(|issuer| &issuer.key).unwrap_or(&key),
+        MessageDigest::sha256(),
+    )?;
+
+    Ok(Issued {
+        certificate: builder.build(),
+        key,
+    })
+}
+
+/// Reads a PEM, which is always ASCII.
+fn pem(pem: Result<Vec<u8>, ErrorStack>) -> String {
+    pem.map(|pem| String::from_utf8_lossy(&pem).into_owned())
+        .unwrap_or_default()
+}
@@ -0,0 +1,609 @@
+use crate::AppError;
+
+use serde_json::{Map, Value};
+use std::borrow::Cow;
+
+/// Namespace used for the elements of every YANG module in XML responses.
+const NAMESPACE_PREFIX: &str = "urn:onf:otcc:yang:";
+
+/// Namespace of the wrapper element used when an XML response holds more than one list entry.
+const RESTCONF_NAMESPACE: &str = "urn:ietf:params:xml:ns:yang:ietf-restconf";
+
+/// Data node addressed by a RESTCONF path.
+#[derive(Debug)]
+pub struct Target<'a> {
+    /// Module-qualified name of the data node, e.g. `tapi-topology:node`.
+    pub name: String,
+    /// Content of the data node. List entries are returned as a one element array, as RESTCONF does.
+    pub value: Cow<'a, Value>,
+}
+
+/// Selector parsed from the RESTCONF `fields` query parameter.
+///
+/// `connectivity-service(uuid;name)` is parsed as the path `["connectivity-service"]` with the
+/// children `uuid` and `name`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FieldSelector {
+    pub path: Vec<String>,
+    pub children: Vec<FieldSelector>,
+}
+
+/// Returns the name without the module prefix, e.g. `topology` for `tapi-topology:topology`.
+fn local_name(name: &str) -> &str {
+    name.split_once(':').map(|(_, local)| local).unwrap_or(name)
+}
+
+/// Finds the member of an object matching a node identifier, with or without module prefix.
+fn find_member<'a>(object: &'a Map<String, Value>, name: &str) -> Option<(&'a String, &'a Value)> {
+    object
+        .iter()
+        .find(|(key, _)| key.as_str() == name || local_name(key) == local_name(name))
+}
+
+/// Returns `true` if the list entry is identified by the given key.
+fn entry_matches(entry: &Value, key: &str) -> bool {
+    ["uuid", "local-id", "value-name"].iter().any(|key_leaf| {
+        entry
+            .get(key_leaf)
+            .and_then(Value::as_str)
+            .map(|value| value == key)
+            .unwrap_or(false)
+    })
+}
+
+/// Resolves a RESTCONF data resource path such as
+/// `tapi-common:context/tapi-topology:topology-context/topology=<uuid>/node=<uuid>`.
+///
+/// # Arguments
+/// - `datastore`: Root of the data, e.g. `{"tapi-common:context": {...}}`.
+/// - `path`: The path after `/data/`, already percent-decoded.
+///
+/// # Returns
+/// The addressed `Target`, or an `Error` if any segment does not exist.
+pub fn resolve<'a>(datastore: &'a Value, path: &str) -> Result<Target<'a>, AppError> {
+    let segments: Vec<&str> = path
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .collect();
+
+    if segments.is_empty() {
+        return Ok(Target {
+            name: "ietf-restconf:data".to_string(),
+            value: Cow::Borrowed(datastore),
+        });
+    }
+
+    let mut current = datastore;
+    let mut module = String::new();
+    let mut is_list_entry = false;
+    let mut name = "";
+
+    for segment in segments {
+        let (node, key) = match segment.split_once('=') {
+            Some((node, key)) => (node, Some(key)),
+            None => (segment, None),
+        };
+        if let Some((prefix, _)) = node.split_once(':') {
+            module = prefix.to_string();
+        }
+        name = local_name(node);
+
+        let object = current
+            .as_object()
+            .ok_or_else(|| AppError::validation_error(format!("{} is not a container", node)))?;
+        let (_, child) = find_member(object, node)
+            .ok_or_else(|| AppError::validation_error(format!("{} Not Found", node)))?;
+
+        match key {
+            Some(key) => {
+                current = child
+                    .as_array()
+                    .ok_or_else(|| AppError::validation_error(format!("{} is not a list", node)))?
+                    .iter()
+                    .find(|entry| entry_matches(entry, key))
+                    .ok_or_else(|| {
+                        AppError::validation_error(format!("{}={} Not Found", node, key))
+                    })?;
+                is_list_entry = true;
+            }
+            None => {
+                current = child;
+                is_list_entry = false;
+            }
+        }
+    }
+
+    let name = if module.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}:{}", module, name)
+    };
+
+    let value = if is_list_entry {
+        Cow::Owned(Value::Array(vec![current.clone()]))
+    } else {
+        Cow::Borrowed(current)
+    };
+
+    Ok(Target { name, value })
+}
+
+/// Failure of a RESTCONF edit, answered with the status of each variant.
+#[derive(Debug, PartialEq)]
+pub enum EditError {
+    /// The target resource does not exist, `404`.
+    NotFound(String),
+    /// The created data already exists, `409`.
+    Exists(String),
+    /// The body does not fit the target resource, `400`.
+    Invalid(String),
+}
+
+/// Splits a path segment into its node identifier and list key, e.g. `node` and `<uuid>` for `node=<uuid>`.
+fn split_segment(segment: &str) -> (&str, Option<&str>) {
+    match segment.split_once('=') {
+        Some((node, key)) => (node, Some(key)),
+        None => (segment, None),
+    }
+}
+
+/// Resolves a RESTCONF data resource path for editing, as `resolve` does.
+///
+/// # Returns
+/// The addressed data node, a single list entry for paths ending with a key, or an `Error` if any
+/// segment does not exist.
+pub fn resolve_mut<'a>(datastore: &'a mut Value, path: &str) -> Result<&'a mut Value, EditError> {
+    let mut current = datastore;
+
+    for segment in path.split('/').filter(|segment| !segment.is_empty()) {
+        let (node, key) = split_segment(segment);
+        let object = current
+            .as_object_mut()
+            .ok_or_else(|| EditError::NotFound(format!("{} is not a container", node)))?;
+        let member = find_member(object, node)
+            .map(|(member, _)| member.clone())
+            .ok_or_else(|| EditError::NotFound(format!("{} Not Found", node)))?;
+        let child = object
+            .get_mut(&member)
+            .ok_or_else(|| EditError::NotFound(format!("{} Not Found", node)))?;
+
+        current = match key {
+            Some(key) => child
+                .as_array_mut()
+                .ok_or_else(|| EditError::NotFound(format!("{} is not a list", node)))?
+                .iter_mut()
+                .find(|entry| entry_matches(entry, key))
+                .ok_or_else(|| EditError::NotFound(format!("{}={} Not Found", node, key)))?,
+            None => child,
+        };
+    }
+
+    Ok(current)
+}
+
+/// Creates the data nodes of `body` as children of the addressed container (RFC 8040, section 4.4.1).
+///
+/// List entries are appended to their list, e.g. a `{"tapi-connectivity:connectivity-service": [...]}`
+/// body POSTed to the connectivity context.
+///
+/// # Returns
+/// `Ok` once every node is created, or an `Error` without changing the datastore if the target does
+/// not exist, the body is not an object or any node or list entry already exists.
+pub fn create(datastore: &mut Value, path: &str, body: &Value) -> Result<(), EditError> {
+    let children = body
+        .as_object()
+        .ok_or_else(|| EditError::Invalid("The body must be an object".to_string()))?;
+    let target = resolve_mut(datastore, path)?
+        .as_object_mut()
+        .ok_or_else(|| EditError::Invalid(format!("{} is not a container", path)))?;
+
+    for (name, value) in children {
+        let Some((_, existing)) = find_member(target, name) else {
+            continue;
+        };
+        let (Value::Array(entries), Value::Array(existing)) = (value, existing) else {
+            return Err(EditError::Exists(format!("{} already exists", name)));
+        };
+        let duplicate = entries.iter().find(|entry| {
+            ["uuid", "local-id"].iter().any(|key_leaf| {
+                entry
+                    .get(key_leaf)
+                    .and_then(Value::as_str)
+                    .is_some_and(|key| existing.iter().any(|other| entry_matches(other, key)))
+            })
+        });
+        if let Some(duplicate) = duplicate {
+            return Err(EditError::Exists(format!(
+                "{} {} already exists",
+                name,
+                duplicate.get("uuid").unwrap_or(&Value::Null)
+            )));
+        }
+    }
+
+    for (name, value) in children {
+        let member = find_member(target, name)
+            .map(|(member, _)| member.clone())
+            .unwrap_or_else(|| name.clone());
+        match (target.get_mut(&member), value) {
+            (Some(Value::Array(existing)), Value::Array(entries)) => {
+                existing.extend(entries.iter().cloned())
+            }
+            _ => {
+                target.insert(member, value.clone());
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Deletes the addressed data node or list entry (RFC 8040, section 4.7).
+///
+/// # Returns
+/// `Ok` once the node is removed, or an `Error` if it does not exist.
+pub fn delete(datastore: &mut Value, path: &str) -> Result<(), EditError> {
+    let path = path.trim_end_matches('/');
+    let (parent, segment) = path.rsplit_once('/').unwrap_or(("", path));
+    if segment.is_empty() {
+        return Err(EditError::Invalid(
+            "The datastore cannot be deleted".to_string(),
+        ));
+    }
+    let (node, key) = split_segment(segment);
+
+    let parent = resolve_mut(datastore, parent)?
+        .as_object_mut()
+        .ok_or_else(|| EditError::NotFound(format!("{} is not a container", parent)))?;
+    let member = find_member(parent, node)
+        .map(|(member, _)| member.clone())
+        .ok_or_else(|| EditError::NotFound(format!("{} Not Found", node)))?;
+
+    match key {
+        Some(key) => {
+            let entries = parent
+                .get_mut(&member)
+                .and_then(Value::as_array_mut)
+                .ok_or_else(|| EditError::NotFound(format!("{} is not a list", node)))?;
+            let position = entries
+                .iter()
+                .position(|entry| entry_matches(entry, key))
+                .ok_or_else(|| EditError::NotFound(format!("{}={} Not Found", node, key)))?;
+            entries.remove(position);
+        }
+        None => {
+            parent.remove(&member);
+        }
+    }
+
+    Ok(())
+}
+
+/// Parses the value of the RESTCONF `fields` query parameter.
+///
+/// Supports `;` separated selectors, `/` separated paths and nested selectors in parentheses,
+/// e.g. `uuid;name;node(uuid;owned-node-edge-point(uuid))`.
+pub fn parse_fields(expression: &str) -> Result<Vec<FieldSelector>, AppError> {
+    let (selectors, rest) = parse_selectors(expression)?;
+    if !rest.is_empty() {
+        return Err(AppError::validation_error(format!(
+            "Invalid fields expression: {}",
+            expression
+        )));
+    }
+    Ok(selectors)
+}
+
+/// Parses selectors until the end of the input or an unbalanced `)`, returning the remaining input.
+fn parse_selectors(mut input: &str) -> Result<(Vec<FieldSelector>, &str), AppError> {
+    let mut selectors = vec![];
+
+    loop {
+        let end = input.find([';', '(', ')']).unwrap_or(input.len());
+        let path: Vec<String> = input[..end]
+            .split('/')
+            .map(|node| node.trim().to_string())
+            .collect();
+        if path.iter().any(|node| node.is_empty()) {
+            return Err(AppError::validation_error(
+                "Empty node in fields expression",
+            ));
+        }
+        input = &input[end..];
+
+        let mut children = vec![];
+        if let Some(nested) = input.strip_prefix('(') {
+            let (nested_selectors, rest) = parse_selectors(nested)?;
+            input = rest
+                .strip_prefix(')')
+                .ok_or_else(|| AppError::validation_error("Unbalanced parentheses in fields"))?;
+            children = nested_selectors;
+        }
+        selectors.push(FieldSelector { path, children });
+
+        match input.strip_prefix(';') {
+            Some(rest) => input = rest,
+            None => return Ok((selectors, input)),
+        }
+    }
+}
+
+/// Keeps only the data nodes selected by the `fields` selectors.
+pub fn select_fields(value: &Value, selectors: &[FieldSelector]) -> Value {
+    match value {
+        Value::Array(entries) => Value::Array(
+            entries
+                .iter()
+                .map(|entry| select_fields(entry, selectors))
+                .collect(),
+        ),
+        Value::Object(object) => {
+            let mut selected = Value::Object(Map::new());
+            for selector in selectors {
+                if let Some(value) = select_path(object, &selector.path, &selector.children) {
+                    merge(&mut selected, value);
+                }
+            }
+            selected
+        }
+        scalar => scalar.clone(),
+    }
+}
+
+/// Selects a single path inside an object, returning the partial object it produces.
+fn select_path(
+    object: &Map<String, Value>,
+    path: &[String],
+    children: &[FieldSelector],
+) -> Option<Value> {
+    let (key, child) = find_member(object, &path[0])?;
+
+    let selected = if path.len() > 1 {
+        let nested = FieldSelector {
+            path: path[1..].to_vec(),
+            children: children.to_vec(),
+        };
+        select_fields(child, &[nested])
+    } else if children.is_empty() {
+        child.clone()
+    } else {
+        select_fields(child, children)
+    };
+
+    let mut partial = Map::new();
+    partial.insert(key.clone(), selected);
+    Some(Value::Object(partial))
+}
+
+/// Merges two partial selections of the same data node.
+fn merge(target: &mut Value, value: Value) {
+    match (target, value) {
+        (Value::Object(target), Value::Object(value)) => {
+            for (key, value) in value {
+                match target.get_mut(&key) {
+                    Some(existing) => merge(existing, value),
+                    None => {
+                        target.insert(key, value);
+                    }
+                }
+            }
+        }
+        (Value::Array(target), Value::Array(value)) if target.len() == value.len() => {
+            for (existing, value) in target.iter_mut().zip(value) {
+                merge(existing, value);
+            }
+        }
+        (target, value) => *target = value,
+    }
+}
+
+/// Drops the data nodes deeper than `depth`, as the RESTCONF `depth` query parameter does.
+///
+/// The requested data node has depth 1. List entries are on the same level as their list.
+pub fn limit_depth(value: &Value, depth: usize) -> Value {
+    match value {
+        Value::Array(entries) => Value::Array(
+            entries
+                .iter()
+                .map(|entry| limit_depth(entry, depth))
+                .collect(),
+        ),
+        Value::Object(_) if depth <= 1 => Value::Object(Map::new()),
+        Value::Object(object) => Value::Object(
+            object
+                .iter()
+                .map(|(key, value)| (key.clone(), limit_depth(value, depth - 1)))
+                .collect(),
+        ),
+        scalar => scalar.clone(),
+    }
+}
+
+/// Escapes the characters that cannot appear in XML text or attributes.
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Writes a data node as an XML element, declaring the namespace when the module changes.
+fn write_element(xml: &mut String, name: &str, value: &Value, parent_module: Option<&str>) {
+    let (module, local) = match name.split_once(':') {
+        Some((module, local)) => (Some(module), local),
+        None => (parent_module, name),
+    };
+
+    xml.push('<');
+    xml.push_str(local);
+    if let Some(module) = module.filter(|module| Some(*module) != parent_module) {
+        xml.push_str(&format!(" xmlns=\"{}{}\"", NAMESPACE_PREFIX, module));
+    }
+
+    match value {
+        Value::Null => xml.push_str("/>"),
+        Value::Object(object) => {
+            xml.push('>');
+            for (key, child) in object {
+                match child {
+                    Value::Array(entries) => {
+                        for entry in entries {
+                            write_element(xml, key, entry, module);
+                        }
+                    }
+                    child => write_element(xml, key, child, module),
+                }
+            }
+            xml.push_str(&format!("</{}>", local));
+        }
+        Value::String(text) => xml.push_str(&format!(">{}</{}>", escape_xml(text), local)),
+        Value::Array(_) => {
+            // Nested arrays have no XML representation, the entries are written in place
+            xml.push_str("/>");
+        }
+        scalar => xml.push_str(&format!(">{}</{}>", scalar, local)),
+    }
+}
+
+/// Serializes a data node as a RESTCONF XML document.
+///
+/// A single list entry becomes the root element. Several entries are wrapped in a `<data>` element.
+pub fn to_xml(name: &str, value: &Value) -> String {
+    let mut xml = String::from(r#"<?xml version="1.0" encoding="UTF-8"?>"#);
+
+    match value {
+        Value::Array(entries) if entries.len() == 1 => {
+            write_element(&mut xml, name, &entries[0], None)
+        }
+        Value::Array(entries) => {
+            xml.push_str(&format!("<data xmlns=\"{}\">", RESTCONF_NAMESPACE));
+            for entry in entries {
+                write_element(&mut xml, name, entry, None);
+            }
+            xml.push_str("</data>");
+        }
+        value => write_element(&mut xml, name, value, None),
+    }
+
+    xml
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        create, delete, limit_depth, parse_fields, resolve, select_fields, to_xml, EditError,
+        FieldSelector,
+    };
+    use crate::utils::xml_to_json;
+    use serde_json::json;
+
+    fn datastore() -> serde_json::Value {
+        json!({
+            "tapi-common:context": {
+                "tapi-topology:topology-context": {
+                    "topology": [{
+                        "uuid": "topology-1",
+                        "name": [{"value-name": "TOPOLOGY_NAME", "value": "mock"}],
+                        "node": [
+                            {"uuid": "node-1", "owned-node-edge-point": [{"uuid": "nep-1"}]},
+                            {"uuid": "node-2", "owned-node-edge-point": []}
+                        ]
+                    }]
+                }
+            }
+        })
+    }
+
+    #[test]
+    fn test_parse_fields() {
+        let selectors = parse_fields("uuid;node(uuid;owned-node-edge-point/uuid)").unwrap();
+
+        assert_eq!(selectors.len(), 2);
+        assert_eq!(selectors[0].path, vec!["uuid"]);
+        assert_eq!(
+            selectors[1].children[1],
+            FieldSelector {
+                path: vec!["owned-node-edge-point".to_string(), "uuid".to_string()],
+                children: vec![],
+            }
+        );
+        assert!(parse_fields("node(uuid").is_err());
+        assert!(parse_fields("node;;uuid").is_err());
+    }
+
+    #[test]
+    fn test_resolve_list_entry_with_fields_and_depth() {
+        let datastore = datastore();
+        let target = resolve(
+            &datastore,
+            "tapi-common:context/tapi-topology:topology-context/topology=topology-1",
+        )
+        .unwrap();
+        assert_eq!(target.name, "tapi-topology:topology");
+
+        let selected = select_fields(&target.value, &parse_fields("node(uuid)").unwrap());
+        assert_eq!(
+            selected,
+            json!([{"node": [{"uuid": "node-1"}, {"uuid": "node-2"}]}])
+        );
+        assert_eq!(limit_depth(&target.value, 2)[0]["node"], json!([{}, {}]));
+
+        assert!(resolve(
+            &datastore,
+            "tapi-common:context/tapi-topology:topology-context/topology=missing"
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_to_xml_roundtrip() {
+        let datastore = datastore();
+        let target = resolve(
+            &datastore,
+            "tapi-common:context/tapi-topology:topology-context/topology=topology-1/node=node-1",
+        )
+        .unwrap();
+        let xml = to_xml(&target.name, &target.value);
+
+        assert!(xml.contains(r#"<node xmlns="urn:onf:otcc:yang:tapi-topology">"#));
+        let json = xml_to_json(&xml).unwrap();
+        assert_eq!(json["node"]["uuid"]["text"], "node-1");
+    }
+
+    #[test]
+    fn test_create_and_delete_list_entries() {
+        let mut datastore = datastore();
+        let topology = "tapi-common:context/tapi-topology:topology-context/topology=topology-1";
+
+        create(
+            &mut datastore,
+            topology,
+            &json!({"tapi-topology:node": [{"uuid": "node-3"}]}),
+        )
+        .unwrap();
+        assert!(resolve(&datastore, &format!("{}/node=node-3", topology)).is_ok());
+        assert!(matches!(
+            create(
+                &mut datastore,
+                topology,
+                &json!({"node": [{"uuid": "node-1"}]})
+            ),
+            Err(EditError::Exists(_))
+        ));
+        assert!(matches!(
+            create(
+                &mut datastore,
+                &format!("{}/node=missing", topology),
+                &json!({})
+            ),
+            Err(EditError::NotFound(_))
+        ));
+
+        delete(&mut datastore, &format!("{}/node=node-1", topology)).unwrap();
+        assert!(resolve(&datastore, &format!("{}/node=node-1", topology)).is_err());
+        assert_eq!(
+            delete(&mut datastore, &format!("{}/node=node-1", topology)),
+            Err(EditError::NotFound("node=node-1 Not Found".to_string()))
+        );
+    }
+}
@@ -1,27 +1,33 @@
-use super::endpoint::BaseEndpoint;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use tapi_model::Uuid;
+
+use super::{endpoint::BaseEndpoint, topology_graph::TopologyGraph};
 
 /// Represents a connection in the network.
 #[derive(Debug, Clone)]
 pub struct Connection {
-    pub connection_uuid: String,
+    pub connection_uuid: Uuid,
     #[allow(dead_code)]
     pub name: String,
     pub lower_connections: Vec<LowerConnection>,
     pub connection_end_points: Vec<CConnectionEndPoint>,
+    /// Vendor augmentations selected by the `VendorProfile`.
+    pub extensions: BTreeMap<String, Value>,
 }
 
 /// Represents a lower connection that is part of a higher-level connection.
 #[derive(Debug, Clone)]
 pub struct LowerConnection {
-    pub connection_uuid: String,
+    pub connection_uuid: Uuid,
 }
 
 /// Represents a connection endpoint.
 #[derive(Debug, Clone)]
 pub struct CConnectionEndPoint {
-    pub node_edge_point_uuid: String,
-    pub connection_end_point_uuid: String,
-    pub node_uuid: String,
+    pub node_edge_point_uuid: Uuid,
+    pub connection_end_point_uuid: Uuid,
+    pub node_uuid: Uuid,
 }
 
 /// Enum representing the role of a connection relative to others.
@@ -37,21 +43,15 @@ impl Connection {
     ///
     /// # Arguments
     /// - `base_endpoint`: A mutable reference to the `BaseEndpoint` being processed.
-    /// - `connection_vector`: A vector of all available connections.
+    /// - `graph`: The `TopologyGraph` resolving the other connections.
     ///
     /// # Returns
     /// A vector of generated `BaseEndpoint` objects based on the connection's role and associated endpoints.
     pub fn provide_connection(
         &self,
         base_endpoint: &mut BaseEndpoint,
-        connection_vector: &[Self],
+        graph: &TopologyGraph,
     ) -> Vec<BaseEndpoint> {
-        let connection_vector: Vec<Connection> = connection_vector
-            .to_owned()
-            .clone()
-            .into_iter()
-            .filter(|connection| connection.connection_uuid != self.connection_uuid)
-            .collect();
         let mut base_endpoint_vector: Vec<BaseEndpoint> = Vec::new();
 
         if self
@@ -65,51 +65,45 @@ impl Connection {
                 .iter()
                 .find(|cep| cep.node_edge_point_uuid == base_endpoint.node_edge_point_uuid)
             {
-                base_endpoint.connection_end_point_uuid =
-                    Some(cep.connection_end_point_uuid.clone());
+                base_endpoint.connection_end_point_uuid = Some(cep.connection_end_point_uuid);
             }
 
             // Determine the connection's role and process accordingly.
-            match self.determine_connection_role(&connection_vector, base_endpoint) {
+            match self.determine_connection_role(graph, base_endpoint) {
                 ConnectionRole::UpperWithLowers => {
-                    base_endpoint.connection_uuid = Some(self.connection_uuid.clone());
+                    base_endpoint.connection_uuid = Some(self.connection_uuid);
 
                     // Generate new base endpoints for the connection's endpoints.
-                    base_endpoint_vector.extend(self.generate_base_endpoints(
-                        base_endpoint,
-                        &base_endpoint.node_edge_point_uuid,
-                        1,
-                    ));
+                    base_endpoint_vector
+                        .extend(self.generate_base_endpoints(base_endpoint.node_edge_point_uuid));
 
                     // Process lower connections recursively.
                     for lower in &self.lower_connections {
-                        if let Some(conn) = connection_vector.iter().find(|c| {
-                            c.is_right_lower_connection(&lower.connection_uuid, base_endpoint)
-                        }) {
-                            base_endpoint.lower_connection = Some(lower.connection_uuid.clone());
-                            base_endpoint_vector.extend(conn.generate_base_endpoints(
-                                base_endpoint,
-                                &base_endpoint.node_edge_point_uuid,
-                                1,
-                            ));
+                        if let Some(conn) =
+                            graph.connections_by_uuid(lower.connection_uuid).find(|c| {
+                                c.connection_uuid != self.connection_uuid
+                                    && c.is_right_lower_connection(
+                                        lower.connection_uuid,
+                                        base_endpoint,
+                                    )
+                            })
+                        {
+                            base_endpoint.lower_connection = Some(lower.connection_uuid);
+                            base_endpoint_vector.extend(
+                                conn.generate_base_endpoints(base_endpoint.node_edge_point_uuid),
+                            );
                         }
                     }
                 }
                 ConnectionRole::UpperWithoutLowers => {
-                    base_endpoint.connection_uuid = Some(self.connection_uuid.clone());
-                    base_endpoint_vector.extend(self.generate_base_endpoints(
-                        base_endpoint,
-                        &base_endpoint.node_edge_point_uuid,
-                        1,
-                    ));
+                    base_endpoint.connection_uuid = Some(self.connection_uuid);
+                    base_endpoint_vector
+                        .extend(self.generate_base_endpoints(base_endpoint.node_edge_point_uuid));
                 }
                 ConnectionRole::Lower => {
-                    base_endpoint.connection_uuid = Some(self.connection_uuid.clone());
-                    base_endpoint_vector.extend(self.generate_base_endpoints(
-                        base_endpoint,
-                        &base_endpoint.node_edge_point_uuid,
-                        1,
-                    ));
+                    base_endpoint.connection_uuid = Some(self.connection_uuid);
+                    base_endpoint_vector
+                        .extend(self.generate_base_endpoints(base_endpoint.node_edge_point_uuid));
                 }
                 ConnectionRole::UnknownUpper => {}
             }
@@ -121,7 +115,7 @@ impl Connection {
     /// Determines the role of the connection relative to other connections in the network.
     ///
     /// # Arguments
-    /// - `connection_vector`: A reference to a vector of all available connections.
+    /// - `graph`: The `TopologyGraph` resolving the connections above this one.
     /// - `base_endpoint`: A reference to the `BaseEndpoint` being evaluated.
     ///
     /// # Returns
@@ -132,26 +126,23 @@ impl Connection {
     /// - `UnknownUpper`: The connection's role could not be determined.
     fn determine_connection_role(
         &self,
-        connection_vector: &[Connection],
+        graph: &TopologyGraph,
         base_endpoint: &BaseEndpoint,
     ) -> ConnectionRole {
+        let upper_connections: Vec<&Connection> = graph
+            .upper_connections(self.connection_uuid)
+            .filter(|conn| conn.connection_uuid != self.connection_uuid)
+            .collect();
+
         if !self.lower_connections.is_empty() {
             ConnectionRole::UpperWithLowers
-        } else if connection_vector.iter().any(|conn| {
-            conn.lower_connections
+        } else if upper_connections.iter().any(|conn| {
+            conn.connection_end_points
                 .iter()
-                .any(|lc| lc.connection_uuid == self.connection_uuid)
-                && conn
-                    .connection_end_points
-                    .iter()
-                    .all(|cep| cep.node_edge_point_uuid != base_endpoint.node_edge_point_uuid)
+                .all(|cep| cep.node_edge_point_uuid != base_endpoint.node_edge_point_uuid)
         }) {
             ConnectionRole::Lower
-        } else if !connection_vector.iter().any(|conn| {
-            conn.lower_connections
-                .iter()
-                .any(|lc| lc.connection_uuid == self.connection_uuid)
-        }) {
+        } else if upper_connections.is_empty() {
             ConnectionRole::UpperWithoutLowers
         } else {
             ConnectionRole::UnknownUpper
@@ -162,21 +153,14 @@ impl Connection {
     ///
     /// # Arguments
     /// - `excluded_uuid`: Node edge point UUID to exclude from the results.
-    /// - `id_offset`: Offset to apply to the IDs of the generated endpoints.
-    fn generate_base_endpoints(
-        &self,
-        base_endpoint: &BaseEndpoint,
-        excluded_uuid: &str,
-        id_offset: i32,
-    ) -> Vec<BaseEndpoint> {
+    fn generate_base_endpoints(&self, excluded_uuid: Uuid) -> Vec<BaseEndpoint> {
         self.connection_end_points
             .iter()
             .filter(|cep| cep.node_edge_point_uuid != excluded_uuid)
             .map(|cep| BaseEndpoint {
-                node_edge_point_uuid: cep.node_edge_point_uuid.clone(),
-                node_uuid: cep.node_uuid.clone(),
-                connection_end_point_uuid: Some(cep.connection_end_point_uuid.clone()),
-                id: base_endpoint.id.map(|id| id + id_offset),
+                node_edge_point_uuid: cep.node_edge_point_uuid,
+                node_uuid: cep.node_uuid,
+                connection_end_point_uuid: Some(cep.connection_end_point_uuid),
                 ..Default::default()
             })
             .collect()
@@ -192,7 +176,7 @@ impl Connection {
     /// `true` if the current connection matches the lower connection and the base endpoint; otherwise, `false`.
     pub fn is_right_lower_connection(
         &self,
-        lower_connection_uuid: &str,
+        lower_connection_uuid: Uuid,
         base_endpoint: &BaseEndpoint,
     ) -> bool {
         self.connection_uuid == lower_connection_uuid
@@ -1,16 +1,17 @@
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
+use tapi_model::connectivity::ConnectivityService;
+use tapi_model::Uuid;
 
+use super::vendor_profile::VendorProfile;
 use super::{
     connections::{Connection, LowerConnection},
     endpoint::BaseEndpoint,
 };
-use crate::utils::find_name;
 
 /// Represents a connectivity service, including its endpoints and associated connections.
 #[derive(Debug, Clone)]
 pub struct Service {
-    pub service_uuid: String,
+    pub service_uuid: Uuid,
     pub name: String,
     pub end_points: Vec<EndPoint>,
     pub connections: Vec<ServiceConnection>,
@@ -25,27 +26,27 @@ pub struct EndPoint {
     #[allow(dead_code)]
     pub location: String,
     pub connection_end_points: Vec<ServiceConnectionEndPoint>,
-    pub service_interface_point_uuid: String,
+    pub service_interface_point_uuid: Option<Uuid>,
 }
 
 /// Represents a direct connection in a service.
 #[derive(Debug, Clone)]
 pub struct ServiceConnection {
-    pub connection_uuid: String,
+    pub connection_uuid: Uuid,
 }
 
 /// Represents a connection endpoint associated with a node.
 #[derive(Debug, Clone)]
 pub struct ServiceConnectionEndPoint {
-    pub node_edge_point_uuid: String,
-    pub connection_end_point_uuid: String,
-    pub node_uuid: String,
+    pub node_edge_point_uuid: Uuid,
+    pub connection_end_point_uuid: Uuid,
+    pub node_uuid: Uuid,
 }
 
 /// Represents a simplified version of a service with minimal data.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SimpleService {
-    pub uuid: String,
+    pub uuid: Uuid,
     pub name: String,
 }
 
@@ -56,130 +57,96 @@ impl Service {
     /// A vector of `BaseEndpoint` objects with basic initialization.
     pub fn first_base_endpoint_vector(&self) -> Vec<BaseEndpoint> {
         let mut base_endpoint_vector = Vec::new();
-        let id: i32 = 1;
-
         self.end_points.iter().for_each(|end_point| {
             end_point
                 .connection_end_points
                 .iter()
                 .for_each(|connection_end_point| {
                     base_endpoint_vector.push(BaseEndpoint {
-                        node_edge_point_uuid: connection_end_point.node_edge_point_uuid.clone(),
-                        node_uuid: connection_end_point.node_uuid.clone(),
+                        node_edge_point_uuid: connection_end_point.node_edge_point_uuid,
+                        node_uuid: connection_end_point.node_uuid,
                         connection_end_point_uuid: Some(
-                            connection_end_point.connection_end_point_uuid.clone(),
-                        ),
-                        service_interface_point_uuid: Some(
-                            end_point.service_interface_point_uuid.clone(),
+                            connection_end_point.connection_end_point_uuid,
                         ),
+                        service_interface_point_uuid: end_point.service_interface_point_uuid,
                         mc_pool: None,
                         connection_uuid: None,
                         client_node_edge_point_uuid: None,
                         lower_connection: None,
                         link_uuid: None,
+                        layer_protocol_name: None,
                         layer_protocol_qualifier: None,
                         inventory_id: None,
-                        id: Some(id),
                     });
-                    //id += 1;
                 });
         });
         base_endpoint_vector
     }
 
-    /// Constructs a `Service` object from JSON and a list of available connections.
+    /// Constructs a `Service` object from a connectivity service and a list of available connections.
     ///
     /// # Arguments
-    /// - `connectivity_service_json`: The JSON object representing the service.
+    /// - `connectivity_service`: The connectivity service parsed with the TAPI model.
     /// - `connection_vector`: A vector of available `Connection` objects.
+    /// - `profile`: The `VendorProfile` with the name keys of the vendor.
     ///
     /// # Returns
     /// A `Service` object with initialized properties.
     pub fn connectivity_service_build(
-        connectivity_service_json: &Value,
+        connectivity_service: &ConnectivityService,
         connection_vector: &Vec<Connection>,
+        profile: &VendorProfile,
     ) -> Self {
         let mut end_point_vector: Vec<EndPoint> = Vec::new();
 
-        if let Some(end_point_section) = connectivity_service_json
-            .get("end-point")
-            .and_then(Value::as_array)
-        {
-            for end_point_item in end_point_section {
-                let mut service_connection_end_point_vector: Vec<ServiceConnectionEndPoint> =
-                    Vec::new();
-
-                // Parse "connection-end-point" for each endpoint.
-                if let Some(connection_end_point_section) = end_point_item
-                    .get("connection-end-point")
-                    .and_then(Value::as_array)
-                {
-                    for connection_end_point_item in connection_end_point_section {
-                        service_connection_end_point_vector.push(ServiceConnectionEndPoint {
-                            node_edge_point_uuid: connection_end_point_item
-                                .get("node-edge-point-uuid")
-                                .unwrap_or(&Value::default())
-                                .to_string(),
-                            connection_end_point_uuid: connection_end_point_item
-                                .get("connection-end-point-uuid")
-                                .unwrap_or(&Value::default())
-                                .to_string(),
-                            node_uuid: connection_end_point_item
-                                .get("node-uuid")
-                                .unwrap_or(&Value::default())
-                                .to_string(),
-                        });
-                    }
-                }
-
-                end_point_vector.push(EndPoint {
-                    name: find_name(end_point_item, "CSEP_NAME".to_string()),
-                    location: find_name(end_point_item, "location".to_string()),
-                    connection_end_points: service_connection_end_point_vector,
-                    service_interface_point_uuid: end_point_item
-                        .get("service-interface-point")
-                        .unwrap_or(&Value::default())
-                        .get("service-interface-point-uuid")
-                        .unwrap_or(&Value::default())
-                        .to_string(),
-                });
-            }
+        for end_point_item in &connectivity_service.end_point {
+            // Parse "connection-end-point" for each endpoint.
+            let service_connection_end_point_vector: Vec<ServiceConnectionEndPoint> =
+                end_point_item
+                    .connection_end_point
+                    .iter()
+                    .map(|connection_end_point_item| ServiceConnectionEndPoint {
+                        node_edge_point_uuid: connection_end_point_item.node_edge_point_uuid,
+                        connection_end_point_uuid: connection_end_point_item
+                            .connection_end_point_uuid,
+                        node_uuid: connection_end_point_item.node_uuid,
+                    })
+                    .collect();
+
+            end_point_vector.push(EndPoint {
+                name: VendorProfile::find_name(
+                    &end_point_item.name,
+                    &profile.names.service_end_point,
+                ),
+                location: VendorProfile::find_name(&end_point_item.name, &profile.names.location),
+                connection_end_points: service_connection_end_point_vector,
+                service_interface_point_uuid: end_point_item.service_interface_point.as_ref().map(
+                    |service_interface_point| service_interface_point.service_interface_point_uuid,
+                ),
+            });
         }
 
         let mut service_connection_vector: Vec<ServiceConnection> = Vec::new();
         let mut service_lower_connection_vector: Vec<LowerConnection> = Vec::new();
 
         // Parse the "connection" section to build service connections and lower connections.
-        if let Some(connection_section) = connectivity_service_json
-            .get("connection")
-            .and_then(Value::as_array)
-        {
-            for connection in connection_section {
-                let connection_uuid = connection
-                    .get("connection-uuid")
-                    .unwrap_or(&Value::default())
-                    .to_string();
-                service_connection_vector.push(ServiceConnection {
-                    connection_uuid: connection_uuid.clone(),
-                });
-
-                // Find lower connections for the current connection.
-                'lower_loop: for connection_struct in connection_vector {
-                    if connection_struct.connection_uuid == connection_uuid {
-                        service_lower_connection_vector
-                            .extend(connection_struct.lower_connections.clone());
-                        break 'lower_loop; // Exit loop early as match is found.
-                    }
+        for connection in &connectivity_service.connection {
+            let connection_uuid = connection.connection_uuid;
+            service_connection_vector.push(ServiceConnection { connection_uuid });
+
+            // Find lower connections for the current connection.
+            'lower_loop: for connection_struct in connection_vector {
+                if connection_struct.connection_uuid == connection_uuid {
+                    service_lower_connection_vector
+                        .extend(connection_struct.lower_connections.clone());
+                    break 'lower_loop; // Exit loop early as match is found.
                 }
             }
         }
 
         Self {
-            service_uuid: connectivity_service_json
-                .get("uuid")
-                .unwrap_or(&Value::default())
-                .to_string(),
-            name: find_name(connectivity_service_json, "SERVICE_NAME".to_string()),
+            service_uuid: connectivity_service.uuid,
+            name: VendorProfile::find_name(&connectivity_service.name, &profile.names.service),
             end_points: end_point_vector,
             connections: service_connection_vector,
             lower_connections: service_lower_connection_vector,
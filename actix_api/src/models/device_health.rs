@@ -0,0 +1,64 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+/// Overall result of a health check.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum HealthStatus {
+    /// Every step succeeded.
+    Healthy,
+    /// The device is reachable but rejects the credentials or fails the RESTCONF request.
+    Degraded,
+    /// The TCP connection or the TLS handshake failed.
+    Unreachable,
+}
+
+impl HealthStatus {
+    /// Name of the status, as stored in the `status` column.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Healthy => "healthy",
+            Self::Degraded => "degraded",
+            Self::Unreachable => "unreachable",
+        }
+    }
+}
+
+impl std::str::FromStr for HealthStatus {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "healthy" => Ok(Self::Healthy),
+            "degraded" => Ok(Self::Degraded),
+            "unreachable" => Ok(Self::Unreachable),
+            other => Err(format!("Unknown health status: {}", other)),
+        }
+    }
+}
+
+impl std::fmt::Display for HealthStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Result of checking a device step by step: TCP connection to its RESTCONF port, TLS handshake,
+/// authentication and a `context?fields=name;uuid` request.
+///
+/// Steps after the first failure are not attempted and left as `None`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DeviceHealth {
+    pub status: HealthStatus,
+    pub tcp_connect: bool,
+    /// `None` for devices reached over plain HTTP.
+    pub tls_handshake: Option<bool>,
+    pub authenticated: Option<bool>,
+    /// Whether the device answered the context request with a 2xx status and a parsable body.
+    pub restconf: Option<bool>,
+    /// Time the device took to answer the context request, in milliseconds.
+    pub latency_ms: Option<i32>,
+    /// Failure of the first step that did not succeed.
+    pub error: Option<String>,
+    pub checked_at: NaiveDateTime,
+}
@@ -1,3 +1,5 @@
+use crate::models::restconf_validations::RestconfValidation;
+
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
@@ -11,56 +13,258 @@ pub struct Device {
     pub port: Option<i64>,
     #[serde(skip_serializing)]
     pub auth: Auth, // Authentication method (enum)
+    /// Name of the `VendorProfile` of the device, detected from its YANG modules when missing.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vendor: Option<String>,
+    /// Strategy the context of the device is read with, chosen from its probe results or from the
+    /// first retrieval that succeeds when missing.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retrieval_strategy: Option<RetrievalStrategy>,
+    /// Scheme and certificates used for every request sent to the device.
+    #[serde(default)]
+    pub tls: TlsConfig,
+}
+
+/// Scheme the device is reached with.
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq, Eq, Hash, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum Scheme {
+    #[default]
+    Https,
+    Http,
+}
+
+/// TLS settings of a device, applied alike to token, basic and data requests.
+///
+/// Certificates are verified unless `insecure` is set, trusting `ca_certificate` besides the
+/// system roots.
+#[derive(Serialize, Deserialize, Default, PartialEq, Eq, Hash, Clone)]
+pub struct TlsConfig {
+    #[serde(default)]
+    pub scheme: Scheme,
+    /// PEM bundle of the CA certificates the device certificate is issued by.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ca_certificate: Option<String>,
+    /// PEM certificate presented to devices requiring mutual TLS.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_certificate: Option<String>,
+    /// PKCS#8 PEM private key of `client_certificate`. Accepted but never serialized back.
+    #[serde(default, skip_serializing)]
+    pub client_key: Option<String>,
+    /// Accepts invalid and self-signed certificates. An explicit opt-in, flagged in the UI.
+    #[serde(default)]
+    pub insecure: bool,
+}
+
+/// How the TAPI context of a device is requested.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum RetrievalStrategy {
+    /// A single request for the complete `tapi-common:context`.
+    FullContext,
+    /// The topologies are listed with `depth` and requested one by one, and the connectivity
+    /// context is requested at once. For devices without `fields` support.
+    DepthLimited,
+    /// One request per context, with `fields` selecting only the data nodes that are used.
+    FieldsFiltered,
+    /// Every topology, node, link, connection and service is listed with `fields` and requested by
+    /// UUID concurrently. The slowest, but no response holds more than one entry.
+    PerUuid,
 }
 
 /// Enum representing the different authentication methods
+///
+/// The variants are told apart by their fields, so `OAuth2` goes first: its credentials would also
+/// match `Basic`.
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
 #[serde(untagged)]
 pub enum Auth {
+    OAuth2(OAuth2Auth),
     Basic(BasicAuth),
     Token(TokenAuth),
 }
 
 /// Represents Basic Authentication with username and password
-#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[derive(Serialize, Deserialize, PartialEq, Clone)]
 pub struct BasicAuth {
     pub username: String,
     pub password: String,
 }
 
 /// Represents Token Authentication with an arbitrary body and authentication URI
-#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[derive(Serialize, Deserialize, PartialEq, Clone)]
 pub struct TokenAuth {
     pub auth_body: Value, // A JSON object containing Token authentication data
+    #[serde(alias = "auth_sufix")]
     pub auth_uri: String, // URI for Token authentication
 }
 
+/// OAuth2 grant used to obtain the access tokens of a device (RFC 6749).
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum GrantType {
+    /// The client authenticates with its `client_id` and `client_secret` only.
+    ClientCredentials,
+    /// The resource owner `username` and `password` are sent along with the client credentials.
+    Password,
+}
+
+/// Represents OAuth2 Authentication, whose access tokens are requested on `token_uri` and renewed
+/// with the refresh token when they expire
+#[derive(Serialize, Deserialize, PartialEq, Clone)]
+pub struct OAuth2Auth {
+    pub grant_type: GrantType,
+    #[serde(alias = "auth_sufix")]
+    pub token_uri: String, // URI of the token endpoint
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_secret: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>, // Resource owner, only for the password grant
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
+}
+
+/// Placeholder printed instead of any secret.
+pub const REDACTED: &str = "<redacted>";
+
 // ==== Implementation ====
 
 impl Device {
+    /// Root URL of the device with the scheme of its `TlsConfig`, e.g. `https://10.95.87.21:18010`.
     pub fn get_device_base_url(&self) -> String {
         format!(
-            "http://{}{}",
+            "{}://{}{}",
+            self.tls.scheme,
             &self.ip,
             &self.port.map(|p| format!(":{}", p)).unwrap_or_default()
         )
     }
 
     pub fn get_full_auth_url(&self) -> String {
-        match &self.auth {
-            Auth::Basic(_) => self.get_device_base_url(),
-            Auth::Token(token_auth) => {
-                format!(
-                    "{}/{}",
-                    self.get_device_base_url(),
-                    &token_auth
-                        .auth_uri
-                        .strip_prefix("/")
-                        .unwrap_or(&token_auth.auth_uri)
-                )
-            }
+        let auth_uri = match &self.auth {
+            Auth::Basic(_) => return self.get_device_base_url(),
+            Auth::Token(token_auth) => &token_auth.auth_uri,
+            Auth::OAuth2(oauth2_auth) => &oauth2_auth.token_uri,
+        };
+        format!(
+            "{}/{}",
+            self.get_device_base_url(),
+            auth_uri.strip_prefix("/").unwrap_or(auth_uri)
+        )
+    }
+}
+
+impl RetrievalStrategy {
+    /// Every strategy, from the fewest to the smallest requests. Strategies are preferred and
+    /// tried in this order.
+    pub const ALL: [Self; 4] = [
+        Self::FullContext,
+        Self::FieldsFiltered,
+        Self::DepthLimited,
+        Self::PerUuid,
+    ];
+
+    /// Name of the strategy, as stored in the `retrieval_strategy` column.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::FullContext => "full_context",
+            Self::DepthLimited => "depth_limited",
+            Self::FieldsFiltered => "fields_filtered",
+            Self::PerUuid => "per_uuid",
+        }
+    }
+
+    /// `tapi_uris` topics a device must answer to be read with the strategy.
+    pub fn required_topics(&self) -> &'static [&'static str] {
+        match self {
+            Self::FullContext => &["context"],
+            Self::DepthLimited => &["context.depth=3", "connectivity-context"],
+            Self::FieldsFiltered => &[
+                "topology.uuid-list",
+                "connectivity-service.uuid-list",
+                "connection.uuid-list",
+            ],
+            Self::PerUuid => &[
+                "topology.uuid;name;layer-protocol-name",
+                "node.by-parts",
+                "link.by-parts",
+                "connection.by-parts",
+                "connectivity-service.by-parts",
+            ],
         }
     }
+
+    /// Strategy a device should be read with according to its probe results: the first one whose
+    /// required topics all succeeded.
+    ///
+    /// # Returns
+    /// The strategy, or `None` if the device supports none of them or was never probed.
+    pub fn from_validations(validations: &[RestconfValidation]) -> Option<Self> {
+        Self::ALL.into_iter().find(|strategy| {
+            strategy.required_topics().iter().all(|topic| {
+                validations
+                    .iter()
+                    .any(|validation| validation.topic == *topic && validation.success)
+            })
+        })
+    }
+}
+
+impl std::str::FromStr for RetrievalStrategy {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|strategy| strategy.as_str() == name)
+            .ok_or_else(|| format!("Unknown retrieval strategy: {}", name))
+    }
+}
+
+impl std::fmt::Display for RetrievalStrategy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Scheme {
+    /// Name of the scheme, as used in URLs and stored in the `tls_scheme` column.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Https => "https",
+            Self::Http => "http",
+        }
+    }
+
+    /// Port the device is reached on when it has none, `443` for HTTPS and `80` for HTTP.
+    pub fn default_port(&self) -> u16 {
+        match self {
+            Self::Https => 443,
+            Self::Http => 80,
+        }
+    }
+}
+
+impl std::str::FromStr for Scheme {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "https" => Ok(Self::Https),
+            "http" => Ok(Self::Http),
+            other => Err(format!("Unknown scheme: {}", other)),
+        }
+    }
+}
+
+impl std::fmt::Display for Scheme {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
 }
 
 impl std::fmt::Display for Device {
@@ -76,7 +280,7 @@ impl std::fmt::Display for Device {
                     username: {},
                     password: {}, 
                 "#,
-                    self.ip, self.port, basic_auth.username, basic_auth.password
+                    self.ip, self.port, basic_auth.username, REDACTED
                 )
             }
             Auth::Token(token_auth) => {
@@ -86,12 +290,77 @@ impl std::fmt::Display for Device {
                     ip: {},
                     port: {:?},
                     auth_type: token,
-                    auth_body: {:?}, 
+                    auth_body: {}, 
                     auth_uri: {:?},
                 "#,
-                    self.ip, self.port, token_auth.auth_body, token_auth.auth_uri
+                    self.ip, self.port, REDACTED, token_auth.auth_uri
+                )
+            }
+            Auth::OAuth2(oauth2_auth) => {
+                write!(
+                    f,
+                    r#"
+                    ip: {},
+                    port: {:?},
+                    auth_type: oauth2,
+                    grant_type: {:?},
+                    token_uri: {:?},
+                    client_id: {:?},
+                    username: {:?},
+                "#,
+                    self.ip,
+                    self.port,
+                    oauth2_auth.grant_type,
+                    oauth2_auth.token_uri,
+                    oauth2_auth.client_id,
+                    oauth2_auth.username
                 )
             }
         }
     }
 }
+
+impl std::fmt::Debug for BasicAuth {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BasicAuth")
+            .field("username", &self.username)
+            .field("password", &REDACTED)
+            .finish()
+    }
+}
+
+impl std::fmt::Debug for TokenAuth {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TokenAuth")
+            .field("auth_body", &REDACTED)
+            .field("auth_uri", &self.auth_uri)
+            .finish()
+    }
+}
+
+impl std::fmt::Debug for OAuth2Auth {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let redacted = |secret: &Option<String>| secret.as_ref().map(|_| REDACTED);
+        f.debug_struct("OAuth2Auth")
+            .field("grant_type", &self.grant_type)
+            .field("token_uri", &self.token_uri)
+            .field("client_id", &self.client_id)
+            .field("client_secret", &redacted(&self.client_secret))
+            .field("username", &self.username)
+            .field("password", &redacted(&self.password))
+            .field("scope", &self.scope)
+            .finish()
+    }
+}
+
+impl std::fmt::Debug for TlsConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TlsConfig")
+            .field("scheme", &self.scheme)
+            .field("ca_certificate", &self.ca_certificate.is_some())
+            .field("client_certificate", &self.client_certificate.is_some())
+            .field("client_key", &self.client_key.as_ref().map(|_| REDACTED))
+            .field("insecure", &self.insecure)
+            .finish()
+    }
+}
@@ -1,59 +1,100 @@
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashSet};
+use tapi_model::common::LayerProtocolName;
+use tapi_model::Uuid;
 
-use crate::models::nodes::{FrecuencyPair, McPool};
+use crate::models::nodes::McPool;
 
-use super::{connections::Connection, links::Link, nodes::Node};
+use super::{
+    schema::{TraceWarning, TraceWarningKind},
+    topology_graph::TopologyGraph,
+    vendor_profile::UNKNOWN_NAME,
+};
 
 /// Represents a detailed endpoint with various optional fields and associated metadata.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Endpoint {
-    pub node_edge_point_uuid: String,
+    pub node_edge_point_uuid: Uuid,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub layer_protocol_name: Option<LayerProtocolName>,
+
     pub layer_protocol_qualifier: String,
-    pub node_uuid: String,
+    pub node_uuid: Uuid,
     pub inventory_id: String,
-    pub connection_end_point_uuid: String,
 
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub service_interface_point_uuid: Option<String>,
+    pub connection_end_point_uuid: Option<Uuid>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub connection_uuid: Option<String>,
+    pub service_interface_point_uuid: Option<Uuid>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub client_node_edge_point_uuid: Option<String>,
+    pub connection_uuid: Option<Uuid>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub lower_connection: Option<String>,
+    pub client_node_edge_point_uuid: Option<Uuid>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub link_uuid: Option<String>,
+    pub lower_connection: Option<Uuid>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub link_uuid: Option<Uuid>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub mc_pool: Option<McPool>,
 
-    /// Unique identifier for the endpoint.
+    /// Vendor augmentations of the connection and the node edge point.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub extensions: BTreeMap<String, Value>,
+
+    /// Position of the endpoint along the route of the service, from 1 at its A-end.
     pub id: i32,
+
+    /// Whether the route enters or leaves the node through the endpoint.
+    #[serde(default)]
+    pub direction: HopDirection,
+}
+
+/// Direction of the route through an endpoint, going from the A-end to the Z-end of a service.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum HopDirection {
+    /// The route enters the node through the endpoint.
+    #[default]
+    Ingress,
+    /// The route leaves the node through the endpoint.
+    Egress,
+}
+
+impl HopDirection {
+    /// The direction on the other side of a connection or a link.
+    pub fn reverse(self) -> Self {
+        match self {
+            Self::Ingress => Self::Egress,
+            Self::Egress => Self::Ingress,
+        }
+    }
 }
 
 /// Represents a basic endpoint with optional fields, often used as a starting point.
 #[derive(Debug, Clone, Eq, Serialize, Deserialize, Default)]
 pub struct BaseEndpoint {
-    pub node_edge_point_uuid: String,
-    pub node_uuid: String,
-    pub connection_end_point_uuid: Option<String>,
-    pub service_interface_point_uuid: Option<String>,
-    pub connection_uuid: Option<String>,
-    pub client_node_edge_point_uuid: Option<String>,
-    pub lower_connection: Option<String>,
-    pub link_uuid: Option<String>,
+    pub node_edge_point_uuid: Uuid,
+    pub node_uuid: Uuid,
+    pub connection_end_point_uuid: Option<Uuid>,
+    pub service_interface_point_uuid: Option<Uuid>,
+    pub connection_uuid: Option<Uuid>,
+    pub client_node_edge_point_uuid: Option<Uuid>,
+    pub lower_connection: Option<Uuid>,
+    pub link_uuid: Option<Uuid>,
+    pub layer_protocol_name: Option<LayerProtocolName>,
     pub layer_protocol_qualifier: Option<String>,
     pub mc_pool: Option<McPool>,
 
     /// INVENTORY ID (card id)
     pub inventory_id: Option<String>,
-
-    /// Unique identifier for the endpoint.
-    pub id: Option<i32>,
 }
 
 impl PartialEq for BaseEndpoint {
@@ -66,25 +107,23 @@ impl BaseEndpoint {
     /// Builds an `Endpoint` and associated `BaseEndpoint` objects by resolving connections, links, and inventories.
     ///
     /// # Arguments
-    /// - `link_vector`: A vector of links to check for associations.
-    /// - `node_vector`: A vector of nodes to resolve inventories.
-    /// - `connection_vector`: A vector of connections to determine relationships.
+    /// - `graph`: The `TopologyGraph` with the connections, links and nodes of the edge point.
+    /// - `warnings`: The warnings of the trace, extended with the anomalies of the edge point.
     ///
     /// # Returns
     /// A tuple containing the constructed `Endpoint` and a deduplicated vector of `BaseEndpoint` objects.
     pub fn build(
         mut self,
-        link_vector: &Vec<Link>,
-        node_vector: &Vec<Node>,
-        connection_vector: &Vec<Connection>,
+        graph: &TopologyGraph,
+        warnings: &mut Vec<TraceWarning>,
     ) -> (Endpoint, Vec<Self>) {
         let mut base_endpoint_vector: Vec<Self> = Vec::new();
 
         if self.connection_uuid.is_none() {
             // Loop through connections to find matching ones and extend the base endpoint vector.
-            'connection_loop: for connection in connection_vector {
+            'connection_loop: for connection in graph.connections_of(self.node_edge_point_uuid) {
                 let connection_base_endpoint_vector =
-                    connection.provide_connection(&mut self, connection_vector);
+                    connection.provide_connection(&mut self, graph);
                 if !connection_base_endpoint_vector.is_empty() {
                     base_endpoint_vector.extend(connection_base_endpoint_vector);
                     if self.connection_uuid.is_some() {
@@ -96,7 +135,7 @@ impl BaseEndpoint {
 
         if self.link_uuid.is_none() {
             // Loop through links to find matches and extend the base endpoint vector.
-            'link_loop: for link in link_vector {
+            'link_loop: for link in graph.links_of(self.node_edge_point_uuid) {
                 let link_base_endpoint_vector = link.provide_link(&mut self);
                 if !link_base_endpoint_vector.is_empty() {
                     base_endpoint_vector.extend(link_base_endpoint_vector);
@@ -109,37 +148,110 @@ impl BaseEndpoint {
 
         if self.inventory_id.is_none() {
             // Check nodes to resolve inventory IDs.
-            for node in node_vector {
-                let node_base_endpoint_vector = node.provide_inventory(&mut self);
+            for node in graph.inventory_nodes(self.node_uuid, self.node_edge_point_uuid) {
+                let node_base_endpoint_vector = node.provide_inventory(&mut self, warnings);
                 if !node_base_endpoint_vector.is_empty() {
                     base_endpoint_vector.extend(node_base_endpoint_vector);
                 }
             }
         }
 
+        // Report the edge points and links the topology cannot resolve.
+        let owned_node_edge_point =
+            graph.owned_node_edge_point(self.node_uuid, self.node_edge_point_uuid);
+        let link = self.link_uuid.and_then(|link_uuid| {
+            graph
+                .links_of(self.node_edge_point_uuid)
+                .find(|link| link.link_uuid == link_uuid)
+        });
+        match (owned_node_edge_point, self.link_uuid) {
+            (None, Some(link_uuid)) => {
+                let mut warning = TraceWarning::new(
+                    TraceWarningKind::LinkEndMissing,
+                    self.node_uuid,
+                    self.node_edge_point_uuid,
+                    format!(
+                        "Link {} ends on node edge point {} of node {}, which is not in the topology",
+                        link_uuid, self.node_edge_point_uuid, self.node_uuid
+                    ),
+                );
+                warning.link_uuid = Some(link_uuid);
+                warnings.push(warning);
+            }
+            (None, None) => warnings.push(TraceWarning::new(
+                TraceWarningKind::DanglingNodeEdgePoint,
+                self.node_uuid,
+                self.node_edge_point_uuid,
+                format!(
+                    "Node edge point {} of node {} is not in the topology",
+                    self.node_edge_point_uuid, self.node_uuid
+                ),
+            )),
+            (Some(owned_node_edge_point), _) => {
+                if owned_node_edge_point.inventory_id.is_empty()
+                    || owned_node_edge_point.inventory_id == UNKNOWN_NAME
+                {
+                    warnings.push(TraceWarning::new(
+                        TraceWarningKind::MissingInventory,
+                        self.node_uuid,
+                        self.node_edge_point_uuid,
+                        format!(
+                            "Node edge point {} has no inventory name",
+                            self.node_edge_point_uuid
+                        ),
+                    ));
+                }
+            }
+        }
+        if let Some(link) = link.filter(|link| link.node_edge_points.len() < 2) {
+            let mut warning = TraceWarning::new(
+                TraceWarningKind::LinkEndMissing,
+                self.node_uuid,
+                self.node_edge_point_uuid,
+                format!("Link {} has no other end", link.link_uuid),
+            );
+            warning.link_uuid = Some(link.link_uuid);
+            warnings.push(warning);
+        }
+
+        // Collect the vendor extensions of the connection and the node edge point.
+        let mut extensions = BTreeMap::new();
+        if let Some(connection) = self
+            .connection_uuid
+            .and_then(|connection_uuid| graph.connections_by_uuid(connection_uuid).next())
+        {
+            extensions.extend(connection.extensions.clone());
+        }
+        if let Some(owned_node_edge_point) = owned_node_edge_point {
+            extensions.extend(owned_node_edge_point.extensions.clone());
+        }
+
         // Construct the final `Endpoint` object.
         let endpoint = Endpoint {
             node_edge_point_uuid: self.node_edge_point_uuid,
+            layer_protocol_name: self.layer_protocol_name,
             layer_protocol_qualifier: self.layer_protocol_qualifier.unwrap_or_default(),
             node_uuid: self.node_uuid,
             inventory_id: self.inventory_id.unwrap_or_default(),
-            connection_end_point_uuid: self.connection_end_point_uuid.unwrap_or_default(),
+            connection_end_point_uuid: self.connection_end_point_uuid,
             service_interface_point_uuid: self.service_interface_point_uuid,
             connection_uuid: self.connection_uuid,
             client_node_edge_point_uuid: self.client_node_edge_point_uuid,
             lower_connection: self.lower_connection,
             link_uuid: self.link_uuid,
             mc_pool: self.mc_pool,
-            id: self.id.unwrap_or_default(),
+            extensions,
+            // Numbered once the route of the service is ordered
+            id: 0,
+            direction: HopDirection::default(),
         };
 
         // Deduplicate the resulting `BaseEndpoint` vector.
-        let mut seen_node_edge_point_uuids = Vec::new();
+        let mut seen_node_edge_point_uuids = HashSet::new();
         let mut unique_base_endpoints = Vec::new();
 
         for base_endpoint in base_endpoint_vector {
-            if !seen_node_edge_point_uuids.contains(&base_endpoint.node_edge_point_uuid) {
-                seen_node_edge_point_uuids.push(base_endpoint.node_edge_point_uuid.clone());
+            if seen_node_edge_point_uuids.insert(base_endpoint.node_edge_point_uuid) {
                 unique_base_endpoints.push(base_endpoint);
             }
         }
@@ -1,5 +1,6 @@
 use actix_multipart::form::{json::Json, tempfile::TempFile, MultipartForm};
 use serde::{Deserialize, Serialize};
+use tapi_model::Uuid;
 
 /// Metadata associated with the uploaded files, including an identifier.
 #[derive(Debug, Deserialize)]
@@ -44,3 +45,40 @@ pub enum FilesEnum {
     Complete(Complete),
     ByPart(ByPart),
 }
+
+/// Number of entities found in an uploaded file set.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UploadCounts {
+    pub nodes: usize,
+    pub links: usize,
+    pub connections: usize,
+    pub connectivity_services: usize,
+}
+
+/// Reference from an uploaded entity to another one that is not part of the upload.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DanglingReference {
+    /// Kind of the entity holding the reference, e.g. `link`.
+    pub source: String,
+    pub source_uuid: Uuid,
+    /// Kind of the referenced entity, e.g. `node-edge-point`.
+    pub target: String,
+    pub target_uuid: Uuid,
+}
+
+/// Result of checking an uploaded file set against the TAPI structure.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UploadReport {
+    pub id: String,
+    /// Whether the upload is accepted: no section is missing and every entity matches the model.
+    pub valid: bool,
+    /// Form files or JSON pointers that were expected and not found.
+    pub missing_sections: Vec<String>,
+    /// Sections that are present but cannot be read with the TAPI model.
+    pub errors: Vec<String>,
+    pub counts: UploadCounts,
+    /// References to entities outside the upload, reported without rejecting it.
+    pub dangling_references: Vec<DanglingReference>,
+    /// Name of the `VendorProfile` detected from the YANG modules of the upload.
+    pub vendor_profile: Option<String>,
+}
@@ -1,17 +1,21 @@
+use tapi_model::Uuid;
+
 use super::endpoint::BaseEndpoint;
 
 /// Represents a link in the network, connecting multiple node edge points.
 #[derive(Debug, Clone)]
 pub struct Link {
-    pub link_uuid: String,
+    pub link_uuid: Uuid,
     pub node_edge_points: Vec<NodeEdgePoint>,
+    /// UUID of the topology the link was read from.
+    pub topology_uuid: Uuid,
 }
 
 /// Represents a node edge point, which belongs to a specific node.
 #[derive(Debug, Clone)]
 pub struct NodeEdgePoint {
-    pub node_edge_point_uuid: String,
-    pub node_uuid: String,
+    pub node_edge_point_uuid: Uuid,
+    pub node_uuid: Uuid,
 }
 
 impl Link {
@@ -29,9 +33,7 @@ impl Link {
         if self.node_edge_points.iter().any(|node_edge_point| {
             node_edge_point.node_edge_point_uuid == base_endpoint.node_edge_point_uuid
         }) {
-            base_endpoint.link_uuid = Some(self.link_uuid.clone());
-
-            let possible_id = base_endpoint.id.map(|id| id + 1);
+            base_endpoint.link_uuid = Some(self.link_uuid);
 
             // Create new `BaseEndpoint` objects for node edge points not matching the base endpoint.
             base_endpoint_vector = self
@@ -41,18 +43,18 @@ impl Link {
                     node_edge_point.node_edge_point_uuid != base_endpoint.node_edge_point_uuid
                 })
                 .map(|node_edge_point| BaseEndpoint {
-                    node_edge_point_uuid: node_edge_point.node_edge_point_uuid.clone(),
-                    node_uuid: node_edge_point.node_uuid.clone(),
+                    node_edge_point_uuid: node_edge_point.node_edge_point_uuid,
+                    node_uuid: node_edge_point.node_uuid,
                     mc_pool: None,
                     connection_end_point_uuid: None,
                     service_interface_point_uuid: None,
                     connection_uuid: None,
                     client_node_edge_point_uuid: None,
                     lower_connection: None,
-                    link_uuid: Some(self.link_uuid.clone()),
+                    link_uuid: Some(self.link_uuid),
                     inventory_id: None,
+                    layer_protocol_name: None,
                     layer_protocol_qualifier: None,
-                    id: possible_id,
                 })
                 .collect();
         }
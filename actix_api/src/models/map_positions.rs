@@ -0,0 +1,11 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Positions of the nodes moved on the network map of a host, by node UUID, in the SVG units of
+/// the map.
+///
+/// Serialized as an object whose values are `[x, y]` pairs. Nodes without a position are laid out
+/// by the map itself.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(transparent)]
+pub struct MapPositions(pub HashMap<String, (f64, f64)>);
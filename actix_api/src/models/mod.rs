@@ -1,10 +1,18 @@
 pub mod connections;
 pub mod connectivity_services;
+pub mod device_health;
 pub mod devices;
 pub mod endpoint;
 pub mod files_model;
 pub mod links;
+pub mod map_positions;
 pub mod nodes;
+pub mod notification;
+pub mod path_computation;
+pub mod provisioning;
+pub mod restconf_validations;
 pub mod schema;
 pub mod tapi_uris;
+pub mod topology_graph;
 pub mod user;
+pub mod vendor_profile;
@@ -1,22 +1,31 @@
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use tapi_model::common::LayerProtocolName;
+use tapi_model::Uuid;
 
 use super::endpoint::BaseEndpoint;
+use super::schema::{TraceWarning, TraceWarningKind};
 
 /// Represents a node in the network with associated edge points and a unique identifier.
 #[derive(Debug, Clone)]
 pub struct Node {
-    pub node_uuid: String,
+    pub node_uuid: Uuid,
     pub name: String,
     pub owned_node_edge_points: Vec<OwnedNodeEdgePoint>,
+    /// UUID of the topology the node was read from.
+    pub topology_uuid: Uuid,
 }
 
 /// Represents an edge point owned by a node.
 #[derive(Debug, Clone)]
 pub struct OwnedNodeEdgePoint {
-    pub node_edge_point_uuid: String,
+    pub node_edge_point_uuid: Uuid,
     pub inventory_id: String,
     pub connection_end_points: Vec<NodeConnectionEndPoint>,
     pub mc_pool: Option<McPool>,
+    /// Vendor augmentations selected by the `VendorProfile`.
+    pub extensions: BTreeMap<String, Value>,
 }
 
 /// Represents an mc pool of an owned-node-edge-point.
@@ -36,7 +45,9 @@ pub struct FrecuencyPair {
 /// Represents a connection endpoint within a node.
 #[derive(Debug, Clone)]
 pub struct NodeConnectionEndPoint {
-    pub connection_end_point_uuid: String,
+    pub connection_end_point_uuid: Uuid,
+    /// Layer of the connection end point, or of its node edge point if it has none.
+    pub layer_protocol_name: Option<LayerProtocolName>,
     pub layer_protocol_qualifier: String,
     pub client_node_edge_points: Vec<ClientNodeEdgePoint>,
 }
@@ -44,8 +55,8 @@ pub struct NodeConnectionEndPoint {
 /// Represents a client node edge point, which references another node.
 #[derive(Debug, Clone)]
 pub struct ClientNodeEdgePoint {
-    pub node_edge_point_uuid: String,
-    pub node_uuid: String,
+    pub node_edge_point_uuid: Uuid,
+    pub node_uuid: Uuid,
 }
 
 impl Node {
@@ -53,10 +64,15 @@ impl Node {
     ///
     /// # Arguments
     /// - `base_endpoint`: A mutable reference to the `BaseEndpoint` being processed.
+    /// - `warnings`: The warnings of the trace, extended with the anomalies of the edge point.
     ///
     /// # Returns
     /// A vector of new `BaseEndpoint` objects based on the node's relationships and inventory.
-    pub fn provide_inventory(&self, base_endpoint: &mut BaseEndpoint) -> Vec<BaseEndpoint> {
+    pub fn provide_inventory(
+        &self,
+        base_endpoint: &mut BaseEndpoint,
+        warnings: &mut Vec<TraceWarning>,
+    ) -> Vec<BaseEndpoint> {
         let mut base_endpoint_vector = Vec::new();
 
         if self.node_uuid == base_endpoint.node_uuid {
@@ -68,35 +84,41 @@ impl Node {
                         
                     for connection_end_point in owned_node_edge_point.connection_end_points.iter() {
                         if base_endpoint.connection_end_point_uuid.is_none()
-                            || base_endpoint.connection_end_point_uuid.clone().unwrap()
-                                == connection_end_point.connection_end_point_uuid
+                            || base_endpoint.connection_end_point_uuid
+                                == Some(connection_end_point.connection_end_point_uuid)
                         {
+                            base_endpoint.layer_protocol_name =
+                                connection_end_point.layer_protocol_name;
                             base_endpoint.layer_protocol_qualifier =
                                 Some(connection_end_point.layer_protocol_qualifier.clone());
                             base_endpoint.connection_end_point_uuid =
-                                Some(connection_end_point.connection_end_point_uuid.clone());
+                                Some(connection_end_point.connection_end_point_uuid);
 
                             if connection_end_point.client_node_edge_points.len() > 1 {
-                                println!("There is more than 2 Clients for the same Endpoint for node-edge-point-uuid: {}", base_endpoint.node_edge_point_uuid);
+                                warnings.push(TraceWarning::new(
+                                    TraceWarningKind::MultipleClientNodeEdgePoints,
+                                    base_endpoint.node_uuid,
+                                    base_endpoint.node_edge_point_uuid,
+                                    format!(
+                                        "Connection end point {} has {} client node edge points, only the first is traced",
+                                        connection_end_point.connection_end_point_uuid,
+                                        connection_end_point.client_node_edge_points.len()
+                                    ),
+                                ));
                             }
 
                             if !connection_end_point.client_node_edge_points.is_empty() {
                                 base_endpoint.client_node_edge_point_uuid = Some(
                                     connection_end_point.client_node_edge_points[0]
-                                        .node_edge_point_uuid
-                                        .clone(),
+                                        .node_edge_point_uuid,
                                 );
 
-                                let possible_id = base_endpoint.id.map(|id| id + 1);
-
                                 base_endpoint_vector.push(BaseEndpoint {
                                     node_edge_point_uuid: connection_end_point
                                         .client_node_edge_points[0]
-                                        .node_edge_point_uuid
-                                        .clone(),
+                                        .node_edge_point_uuid,
                                     node_uuid: connection_end_point.client_node_edge_points[0]
-                                        .node_uuid
-                                        .clone(),
+                                        .node_uuid,
                                     mc_pool: None,
                                     connection_end_point_uuid: None,
                                     service_interface_point_uuid: None,
@@ -104,9 +126,9 @@ impl Node {
                                     client_node_edge_point_uuid: None,
                                     lower_connection: None,
                                     link_uuid: None,
+                                    layer_protocol_name: None,
                                     layer_protocol_qualifier: None,
                                     inventory_id: None,
-                                    id: possible_id,
                                 });
                             }
                         }
@@ -124,20 +146,19 @@ impl Node {
                     .node_edge_point_uuid
                     == base_endpoint.node_edge_point_uuid
             {
-                let possible_id = base_endpoint.id.map(|id| id + 1);
                 base_endpoint_vector.push(BaseEndpoint {
-                    node_edge_point_uuid: owned_node_edge_point.node_edge_point_uuid.clone(),
+                    node_edge_point_uuid: owned_node_edge_point.node_edge_point_uuid,
                     mc_pool: None,
-                    node_uuid: self.node_uuid.clone(),
+                    node_uuid: self.node_uuid,
                     connection_end_point_uuid: None,
                     service_interface_point_uuid: None,
                     connection_uuid: None,
                     client_node_edge_point_uuid: None,
                     lower_connection: None,
                     link_uuid: None,
+                    layer_protocol_name: None,
                     layer_protocol_qualifier: None,
                     inventory_id: None,
-                    id: possible_id,
                 });
             }
         }
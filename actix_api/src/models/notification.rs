@@ -0,0 +1,169 @@
+use crate::AppError;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// RESTCONF path of the notification context, relative to the RESTCONF root.
+pub const NOTIFICATION_CONTEXT_PATH: &str =
+    "/data/tapi-common:context/tapi-notification:notification-context";
+
+/// Kind of change reported by a TAPI notification.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationType {
+    ObjectCreation,
+    ObjectDeletion,
+    AttributeValueChange,
+}
+
+/// Kind of object a TAPI notification is about.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ObjectType {
+    Topology,
+    Node,
+    NodeEdgePoint,
+    Link,
+    ConnectivityService,
+    Connection,
+    ServiceInterfacePoint,
+    /// Any object the topology and connection model do not hold, e.g. an alarm.
+    Other,
+}
+
+impl ObjectType {
+    /// Name of the lists holding the objects of this type in the context, e.g. `owned-node-edge-point`.
+    pub fn list_name(&self) -> Option<&'static str> {
+        match self {
+            Self::Topology => Some("topology"),
+            Self::Node => Some("node"),
+            Self::NodeEdgePoint => Some("owned-node-edge-point"),
+            Self::Link => Some("link"),
+            Self::ConnectivityService => Some("connectivity-service"),
+            Self::Connection => Some("connection"),
+            Self::ServiceInterfacePoint => Some("service-interface-point"),
+            Self::Other => None,
+        }
+    }
+}
+
+/// Attribute changed on the target object of a notification.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ChangedAttribute {
+    /// Name of the attribute, e.g. `operational-state`.
+    pub value_name: String,
+    pub old_value: Option<Value>,
+    /// Value after the change, `None` if the attribute was removed.
+    pub new_value: Option<Value>,
+}
+
+/// `tapi-notification:notification` sent by a device when an object of its context changes.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TapiNotification {
+    pub uuid: Option<String>,
+    pub notification_type: NotificationType,
+    pub target_object_type: ObjectType,
+    /// UUID of the created, deleted or changed object.
+    pub target_object_identifier: String,
+    pub event_time_stamp: Option<String>,
+    pub changed_attributes: Vec<ChangedAttribute>,
+}
+
+impl TapiNotification {
+    /// Reads the notification carried by an event, either a bare `tapi-notification:notification` or
+    /// one wrapped in an `ietf-restconf:notification` (RFC 8040, section 6.4).
+    ///
+    /// # Returns
+    /// The `TapiNotification`, or an `Error` if the event holds no notification or its type is unknown.
+    pub fn from_event(event: &Value) -> Result<Self, AppError> {
+        let notification = find_notification(event).ok_or(AppError::validation_error(
+            "The event holds no TAPI notification",
+        ))?;
+        let string = |name: &str| {
+            notification
+                .get(name)
+                .and_then(Value::as_str)
+                .map(str::to_string)
+        };
+
+        let notification_type = match enum_name(&string("notification-type").unwrap_or_default()) {
+            "OBJECT_CREATION" => NotificationType::ObjectCreation,
+            "OBJECT_DELETION" => NotificationType::ObjectDeletion,
+            "ATTRIBUTE_VALUE_CHANGE" => NotificationType::AttributeValueChange,
+            other => {
+                return Err(AppError::validation_error(format!(
+                    "Unknown notification type: {}",
+                    other
+                )))
+            }
+        };
+        let target_object_type = match enum_name(&string("target-object-type").unwrap_or_default())
+        {
+            "TOPOLOGY" => ObjectType::Topology,
+            "NODE" => ObjectType::Node,
+            "NODE_EDGE_POINT" => ObjectType::NodeEdgePoint,
+            "LINK" => ObjectType::Link,
+            "CONNECTIVITY_SERVICE" => ObjectType::ConnectivityService,
+            "CONNECTION" => ObjectType::Connection,
+            "SERVICE_INTERFACE_POINT" => ObjectType::ServiceInterfacePoint,
+            _ => ObjectType::Other,
+        };
+        let target_object_identifier = string("target-object-identifier").ok_or(
+            AppError::validation_error("The notification has no target-object-identifier"),
+        )?;
+        let changed_attributes = notification
+            .get("changed-attributes")
+            .and_then(Value::as_array)
+            .map(|attributes| {
+                attributes
+                    .iter()
+                    .filter_map(|attribute| {
+                        Some(ChangedAttribute {
+                            value_name: attribute.get("value-name")?.as_str()?.to_string(),
+                            old_value: attribute.get("old-value").cloned(),
+                            new_value: attribute.get("new-value").cloned(),
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Ok(Self {
+            uuid: string("uuid"),
+            notification_type,
+            target_object_type,
+            target_object_identifier,
+            event_time_stamp: string("event-time-stamp"),
+            changed_attributes,
+        })
+    }
+}
+
+/// Change pushed to the browsers following a device.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TopologyEvent {
+    pub notification: TapiNotification,
+    /// Moment the updated snapshot was taken, `None` if no snapshot of the device was cached.
+    pub snapshot_timestamp: Option<String>,
+}
+
+/// Finds the object holding `notification-type`, looking into the `notification` members of the wrappers.
+fn find_notification(value: &Value) -> Option<&Map<String, Value>> {
+    let object = value.as_object()?;
+    if object.contains_key("notification-type") {
+        return Some(object);
+    }
+    object
+        .iter()
+        .filter(|(key, _)| key.rsplit(':').next() == Some("notification"))
+        .find_map(|(_, child)| find_notification(child))
+}
+
+/// Name of an identity without its module and `*_TYPE_` prefix, e.g. `NODE` for
+/// `tapi-topology:TOPOLOGY_OBJECT_TYPE_NODE`.
+fn enum_name(identity: &str) -> &str {
+    let name = identity.rsplit(':').next().unwrap_or(identity);
+    name.split_once("_TYPE_")
+        .map(|(_, name)| name)
+        .unwrap_or(name)
+}
@@ -0,0 +1,262 @@
+use crate::models::provisioning::node_refs;
+use crate::AppError;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::collections::{HashMap, HashSet};
+
+/// RESTCONF path of the path computation context, relative to the RESTCONF root.
+pub const PATH_COMPUTATION_CONTEXT_PATH: &str =
+    "/data/tapi-common:context/tapi-path-computation:path-computation-context";
+
+/// RESTCONF path of the `compute-p-2-p-path` RPC, relative to the RESTCONF root.
+pub const COMPUTE_P2P_PATH_OPERATION: &str = "/operations/tapi-path-computation:compute-p-2-p-path";
+
+/// Nodes a computed path must go through or avoid.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct PathConstraints {
+    /// UUIDs of the nodes the path must go through.
+    #[serde(default)]
+    pub include_nodes: Vec<String>,
+    /// UUIDs of the nodes the path must avoid.
+    #[serde(default)]
+    pub exclude_nodes: Vec<String>,
+}
+
+impl PathConstraints {
+    /// `true` if a path crossing the given nodes goes through every included node and around every
+    /// excluded one.
+    pub fn allow(&self, node_uuids: &[String]) -> bool {
+        self.include_nodes
+            .iter()
+            .all(|node_uuid| node_uuids.contains(node_uuid))
+            && !self
+                .exclude_nodes
+                .iter()
+                .any(|node_uuid| node_uuids.contains(node_uuid))
+    }
+}
+
+/// Path requested between two service interface points.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PathRequest {
+    /// UUIDs of the source and destination service interface points.
+    pub service_interface_points: Vec<String>,
+    /// Layer of the path, e.g. `PHOTONIC_MEDIA`.
+    pub layer_protocol_name: Option<String>,
+    #[serde(default)]
+    pub constraints: PathConstraints,
+}
+
+impl PathRequest {
+    /// Checks the request joins exactly two distinct service interface points.
+    pub fn validate(&self) -> Result<(), AppError> {
+        let distinct: HashSet<&String> = self.service_interface_points.iter().collect();
+        if distinct.len() != 2 || self.service_interface_points.len() != 2 {
+            return Err(AppError::validation_error(
+                "A path needs two distinct service interface points",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Builds the input of the `compute-p-2-p-path` RPC.
+    ///
+    /// # Arguments
+    /// - `node_topologies`: Topology of each node of the device, to reference the constrained nodes.
+    ///
+    /// # Returns
+    /// The `tapi-path-computation:input` body, or an `Error` if the request is not valid or
+    /// references unknown nodes.
+    pub fn input(&self, node_topologies: &HashMap<String, String>) -> Result<Value, AppError> {
+        self.validate()?;
+
+        let end_points: Vec<Value> = self
+            .service_interface_points
+            .iter()
+            .enumerate()
+            .map(|(index, sip_uuid)| {
+                let mut end_point = json!({
+                    "local-id": (index + 1).to_string(),
+                    "service-interface-point": { "service-interface-point-uuid": sip_uuid },
+                    "direction": "BIDIRECTIONAL",
+                    "role": "SYMMETRIC",
+                });
+                if let Some(layer_protocol_name) = &self.layer_protocol_name {
+                    end_point["layer-protocol-name"] = json!(layer_protocol_name);
+                }
+                end_point
+            })
+            .collect();
+
+        let mut input = Map::new();
+        input.insert("sep".to_string(), Value::Array(end_points));
+        let constraints = &self.constraints;
+        if !constraints.include_nodes.is_empty() || !constraints.exclude_nodes.is_empty() {
+            input.insert(
+                "topology-constraint".to_string(),
+                json!({
+                    "local-id": "1",
+                    "include-node": node_refs(&constraints.include_nodes, node_topologies)?,
+                    "exclude-node": node_refs(&constraints.exclude_nodes, node_topologies)?,
+                }),
+            );
+        }
+
+        Ok(json!({ "tapi-path-computation:input": Value::Object(input) }))
+    }
+
+    /// `true` if a `path-comp-service` joins the two service interface points of the request.
+    pub fn matches(&self, service: &Value) -> bool {
+        let sips: HashSet<&str> = member(service, "end-point")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(|end_point| {
+                member(end_point, "service-interface-point")
+                    .and_then(|sip| member(sip, "service-interface-point-uuid"))
+                    .and_then(Value::as_str)
+            })
+            .collect();
+        self.service_interface_points
+            .iter()
+            .all(|sip| sips.contains(sip.as_str()))
+    }
+}
+
+/// Finds the member of an object by its name, with or without module prefix.
+fn member<'a>(value: &'a Value, name: &str) -> Option<&'a Value> {
+    value
+        .as_object()?
+        .iter()
+        .find(|(key, _)| key.rsplit(':').next() == Some(name))
+        .map(|(_, value)| value)
+}
+
+/// Link crossed by a path.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct LinkRef {
+    pub topology_uuid: Option<String>,
+    pub link_uuid: String,
+}
+
+/// Path returned by the device, as the ordered list of the links it crosses.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ComputedPath {
+    pub uuid: String,
+    pub name: Option<String>,
+    pub links: Vec<LinkRef>,
+}
+
+impl ComputedPath {
+    /// Reads a TAPI `path`, e.g. `{"uuid": ..., "link": [{"topology-uuid": ..., "link-uuid": ...}]}`.
+    ///
+    /// # Returns
+    /// The path, or `None` if it has no UUID or no links, as the references to a path do.
+    pub fn from_value(path: &Value) -> Option<Self> {
+        let uuid = member(path, "uuid")?.as_str()?.to_string();
+        let links: Vec<LinkRef> = member(path, "link")?
+            .as_array()?
+            .iter()
+            .filter_map(|link| {
+                Some(LinkRef {
+                    topology_uuid: member(link, "topology-uuid")
+                        .and_then(Value::as_str)
+                        .map(str::to_string),
+                    link_uuid: member(link, "link-uuid")?.as_str()?.to_string(),
+                })
+            })
+            .collect();
+        let name = member(path, "name")
+            .and_then(Value::as_array)
+            .and_then(|names| names.first())
+            .and_then(|name| member(name, "value"))
+            .and_then(Value::as_str)
+            .map(str::to_string);
+
+        Some(Self { uuid, name, links })
+    }
+}
+
+/// `path-comp-service` found in the output of `compute-p-2-p-path` or in the path computation
+/// context, with the paths it refers to.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PathCompService {
+    pub uuid: Option<String>,
+    /// UUIDs of the paths, in the order given by the device.
+    pub path_uuids: Vec<String>,
+    /// Paths given in full in the service, as some devices answer the RPC.
+    pub paths: Vec<ComputedPath>,
+}
+
+impl PathCompService {
+    /// Reads the `path-comp-service` of a `compute-p-2-p-path` output, wrapped or not in
+    /// `tapi-path-computation:output` and `service`.
+    pub fn from_output(output: &Value) -> Option<Self> {
+        let output = member(output, "output").unwrap_or(output);
+        let service = member(output, "service").unwrap_or(output);
+        Self::from_value(service)
+    }
+
+    /// Reads a `path-comp-service`, whose `path` list holds `{"path-uuid": ...}` references or full
+    /// paths.
+    pub fn from_value(service: &Value) -> Option<Self> {
+        let entries = member(service, "path")?.as_array()?;
+        let mut path_uuids = vec![];
+        let mut paths = vec![];
+        for entry in entries {
+            if let Some(path) = ComputedPath::from_value(entry) {
+                path_uuids.push(path.uuid.clone());
+                paths.push(path);
+            } else if let Some(path_uuid) = member(entry, "path-uuid").and_then(Value::as_str) {
+                path_uuids.push(path_uuid.to_string());
+            }
+        }
+
+        Some(Self {
+            uuid: member(service, "uuid")
+                .and_then(Value::as_str)
+                .map(str::to_string),
+            path_uuids,
+            paths,
+        })
+    }
+}
+
+/// Reads the `path-comp-service` list of a path computation context.
+pub fn path_comp_services(context: &Value) -> Vec<&Value> {
+    let context = member(context, "path-computation-context").unwrap_or(context);
+    member(context, "path-comp-service")
+        .and_then(Value::as_array)
+        .map(|services| services.iter().collect())
+        .unwrap_or_default()
+}
+
+/// Reads the `path` list of a path computation context.
+pub fn context_paths(context: &Value) -> Vec<ComputedPath> {
+    let context = member(context, "path-computation-context").unwrap_or(context);
+    member(context, "path")
+        .and_then(Value::as_array)
+        .map(|paths| paths.iter().filter_map(ComputedPath::from_value).collect())
+        .unwrap_or_default()
+}
+
+/// Where the returned paths come from.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum PathSource {
+    /// Computed by the device with `compute-p-2-p-path`.
+    Computed,
+    /// Read from a `path-comp-service` already held by the device.
+    Existing,
+}
+
+/// Paths found between two service interface points.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PathComputationResult {
+    pub source: PathSource,
+    /// UUID of the `path-comp-service` holding the paths.
+    pub service_uuid: Option<String>,
+    /// Each path rendered as the schema of a service, with `ServiceResponse` nodes.
+    pub paths: Vec<Value>,
+}
@@ -0,0 +1,257 @@
+use crate::AppError;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::collections::{HashMap, HashSet};
+use tapi_model::Uuid;
+
+/// RESTCONF path of the list holding the connectivity services, relative to the RESTCONF root.
+pub const CONNECTIVITY_CONTEXT_PATH: &str =
+    "/data/tapi-common:context/tapi-connectivity:connectivity-context";
+
+/// Capacity requested for a service, e.g. `100 GBPS`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RequestedCapacity {
+    pub value: u64,
+    pub unit: String,
+}
+
+/// Routing constraints of a requested service.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct ServiceConstraints {
+    /// Service level agreed with the device, e.g. `GOLD`.
+    pub service_level: Option<String>,
+    /// UUIDs of the nodes the service must go through.
+    #[serde(default)]
+    pub include_nodes: Vec<String>,
+    /// UUIDs of the nodes the service must avoid.
+    #[serde(default)]
+    pub exclude_nodes: Vec<String>,
+    /// UUIDs of the services the new one must not share resources with.
+    #[serde(default)]
+    pub diverse_from: Vec<String>,
+}
+
+/// Connectivity service to be created between service interface points.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ServiceRequest {
+    /// UUID of the new service, a random one if missing.
+    pub uuid: Option<Uuid>,
+    pub name: Option<String>,
+    /// UUIDs of the service interface points joined by the service, at least two.
+    pub service_interface_points: Vec<Uuid>,
+    /// Layer of the service, e.g. `PHOTONIC_MEDIA` or `DSR`.
+    pub layer_protocol_name: String,
+    /// Qualifier of the end points, e.g. `tapi-photonic-media:PHOTONIC_LAYER_QUALIFIER_MC`.
+    pub layer_protocol_qualifier: Option<String>,
+    pub capacity: Option<RequestedCapacity>,
+    #[serde(default)]
+    pub constraints: ServiceConstraints,
+}
+
+impl ServiceRequest {
+    /// Builds the body POSTed to `CONNECTIVITY_CONTEXT_PATH` to create the service.
+    ///
+    /// # Arguments
+    /// - `uuid`: UUID of the new service.
+    /// - `node_topologies`: Topology of each node of the device, to reference the constrained nodes.
+    ///
+    /// # Returns
+    /// The `tapi-connectivity:connectivity-service` payload, or an `Error` if the request has fewer
+    /// than two distinct service interface points, no layer, an empty capacity or unknown nodes.
+    pub fn payload(
+        &self,
+        uuid: Uuid,
+        node_topologies: &HashMap<String, String>,
+    ) -> Result<Value, AppError> {
+        let distinct: HashSet<&Uuid> = self.service_interface_points.iter().collect();
+        if distinct.len() < 2 || distinct.len() != self.service_interface_points.len() {
+            return Err(AppError::validation_error(
+                "A service needs at least two distinct service interface points",
+            ));
+        }
+        if self.layer_protocol_name.trim().is_empty() {
+            return Err(AppError::validation_error(
+                "The layer protocol name is required",
+            ));
+        }
+
+        let end_points: Vec<Value> = self
+            .service_interface_points
+            .iter()
+            .enumerate()
+            .map(|(index, sip_uuid)| {
+                let mut end_point = json!({
+                    "local-id": (index + 1).to_string(),
+                    "layer-protocol-name": self.layer_protocol_name,
+                    "service-interface-point": { "service-interface-point-uuid": sip_uuid },
+                    "direction": "BIDIRECTIONAL",
+                    "role": "SYMMETRIC",
+                    "administrative-state": "UNLOCKED",
+                });
+                if let Some(qualifier) = &self.layer_protocol_qualifier {
+                    end_point["layer-protocol-qualifier"] = json!(qualifier);
+                }
+                end_point
+            })
+            .collect();
+
+        let mut service = Map::new();
+        service.insert("uuid".to_string(), json!(uuid));
+        if let Some(name) = &self.name {
+            service.insert(
+                "name".to_string(),
+                json!([{ "value-name": "SERVICE_NAME", "value": name }]),
+            );
+        }
+        service.insert(
+            "layer-protocol-name".to_string(),
+            json!(self.layer_protocol_name),
+        );
+        service.insert("connectivity-direction".to_string(), json!("BIDIRECTIONAL"));
+        service.insert("administrative-state".to_string(), json!("UNLOCKED"));
+        service.insert("end-point".to_string(), Value::Array(end_points));
+
+        if let Some(capacity) = &self.capacity {
+            if capacity.value == 0 || capacity.unit.trim().is_empty() {
+                return Err(AppError::validation_error(
+                    "The capacity needs a positive value and a unit",
+                ));
+            }
+            service.insert(
+                "requested-capacity".to_string(),
+                json!({ "total-size": { "value": capacity.value, "unit": capacity.unit } }),
+            );
+        }
+
+        let constraints = &self.constraints;
+        if let Some(service_level) = &constraints.service_level {
+            service.insert("service-level".to_string(), json!(service_level));
+        }
+        if !constraints.include_nodes.is_empty() || !constraints.exclude_nodes.is_empty() {
+            service.insert(
+                "topology-constraint".to_string(),
+                json!([{
+                    "local-id": "1",
+                    "include-node": node_refs(&constraints.include_nodes, node_topologies)?,
+                    "exclude-node": node_refs(&constraints.exclude_nodes, node_topologies)?,
+                }]),
+            );
+        }
+        if !constraints.diverse_from.is_empty() {
+            let exclusions: Vec<Value> = constraints
+                .diverse_from
+                .iter()
+                .map(|service_uuid| json!({ "connectivity-service-uuid": service_uuid }))
+                .collect();
+            service.insert("diversity-exclusion".to_string(), Value::Array(exclusions));
+        }
+
+        Ok(json!({ "tapi-connectivity:connectivity-service": [Value::Object(service)] }))
+    }
+}
+
+/// References the nodes of a topology constraint, e.g. `{"topology-uuid": ..., "node-uuid": ...}`.
+pub(crate) fn node_refs(
+    nodes: &[String],
+    node_topologies: &HashMap<String, String>,
+) -> Result<Vec<Value>, AppError> {
+    nodes
+        .iter()
+        .map(|node_uuid| {
+            let topology_uuid =
+                node_topologies
+                    .get(node_uuid)
+                    .ok_or(AppError::validation_error(format!(
+                        "Unknown node {}",
+                        node_uuid
+                    )))?;
+            Ok(json!({ "topology-uuid": topology_uuid, "node-uuid": node_uuid }))
+        })
+        .collect()
+}
+
+/// Service interface point offered to new services, found on the edge points of the topology.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct AvailableSip {
+    pub uuid: Uuid,
+    pub topology_uuid: Uuid,
+    pub node_uuid: Uuid,
+    pub node_name: String,
+    pub node_edge_point_uuid: Uuid,
+    /// Layer of the edge point, e.g. `PHOTONIC_MEDIA`.
+    pub layer_protocol_name: Option<String>,
+}
+
+/// `lifecycle-state` and `operational-state` of a service read from the device.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct ServiceState {
+    pub lifecycle_state: Option<String>,
+    pub operational_state: Option<String>,
+}
+
+impl ServiceState {
+    /// Reads the states of the first `connectivity-service` found in a response.
+    pub fn from_response(response: &Value) -> Self {
+        let service = response
+            .as_object()
+            .and_then(|object| {
+                object
+                    .iter()
+                    .find(|(key, _)| key.rsplit(':').next() == Some("connectivity-service"))
+            })
+            .map(|(_, service)| service)
+            .map(|service| match service {
+                Value::Array(services) => services.first().unwrap_or(&Value::Null),
+                service => service,
+            })
+            .unwrap_or(response);
+        let state = |name: &str| {
+            service
+                .get(name)
+                .and_then(Value::as_str)
+                .map(str::to_string)
+        };
+
+        Self {
+            lifecycle_state: state("lifecycle-state"),
+            operational_state: state("operational-state"),
+        }
+    }
+
+    /// `true` once the device reports the service as installed, or as enabled if it has no
+    /// lifecycle.
+    pub fn is_installed(&self) -> bool {
+        match self.lifecycle_state.as_deref() {
+            Some(lifecycle_state) => lifecycle_state == "INSTALLED",
+            None => self.operational_state.as_deref() == Some("ENABLED"),
+        }
+    }
+}
+
+/// Outcome of a create or delete request.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ProvisioningResult {
+    /// UUID of the created or deleted service.
+    pub uuid: Uuid,
+    /// `true` if the request was only rendered, without sending it to the device.
+    pub dry_run: bool,
+    /// Method of the request, `POST` or `DELETE`.
+    pub method: String,
+    /// Data resource of the request, relative to the RESTCONF root.
+    pub path: String,
+    /// Body of the request, `None` for deletions.
+    pub payload: Option<Value>,
+    /// Last state read while polling the device, `None` if the service is gone or nothing was sent.
+    pub state: Option<ServiceState>,
+    /// `true` once the service is installed or, for deletions, no longer found.
+    pub completed: bool,
+}
+
+/// RESTCONF path of a connectivity service, relative to the RESTCONF root.
+pub fn service_path(uuid: Uuid) -> String {
+    format!(
+        "{}/connectivity-service={}",
+        CONNECTIVITY_CONTEXT_PATH, uuid
+    )
+}
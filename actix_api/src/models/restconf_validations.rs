@@ -0,0 +1,20 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+/// Result of requesting one of the `tapi_uris` to a device.
+#[derive(sqlx::FromRow, Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RestconfValidation {
+    /// Id of the probed URI in `tapi_uris`.
+    pub uri_id: i32,
+    pub topic: String,
+    /// URI as stored in `tapi_uris`, with its `{uuid}` placeholders.
+    pub uri: String,
+    /// Status code of the response, `None` if the request could not be sent, e.g. because no
+    /// UUID was found for its placeholders.
+    pub status_code: Option<i32>,
+    /// Content type of the response, e.g. `application/yang-data+json`.
+    pub response_type: Option<String>,
+    /// Whether the device answered with a 2xx status and a body that could be parsed.
+    pub success: bool,
+    pub validation_datetime: NaiveDateTime,
+}
@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
+use tapi_model::common::LayerProtocolName;
+use tapi_model::Uuid;
 
-use super::endpoint::Endpoint;
+use super::endpoint::{Endpoint, HopDirection};
 
 /// Represents the overall schema, including a connectivity service.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -11,16 +13,26 @@ pub struct Schema {
 /// Represents a service and its associated nodes.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ServiceResponse {
-    pub uuid: String,
+    pub uuid: Uuid,
     pub value_name: String,
     pub nodes: Vec<NodeResponse>,
+    /// Every endpoint of the service, in order from its A-end to its Z-end.
+    pub route: Vec<Hop>,
+    /// The connections of the service and those carrying them, layer by layer from the client layer.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub layers: Vec<LayerResponse>,
+    /// Anomalies of the topology found while tracing the service.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<TraceWarning>,
 }
 
 /// Represents a node and its associated inventories.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NodeResponse {
-    pub node_uuid: String,
+    pub node_uuid: Uuid,
     pub value_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub topology_uuid: Option<Uuid>,
     pub inventories: Vec<Inventory>,
 }
 
@@ -30,3 +42,206 @@ pub struct Inventory {
     pub inventory_id: String,
     pub endpoints: Vec<Endpoint>,
 }
+
+/// Represents an endpoint of the route of a service.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Hop {
+    /// Position along the route, the `id` of the endpoint.
+    pub hop: i32,
+    pub node_uuid: Uuid,
+    pub node_edge_point_uuid: Uuid,
+    pub inventory_id: String,
+    pub direction: HopDirection,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub layer_protocol_name: Option<LayerProtocolName>,
+    pub layer_protocol_qualifier: String,
+}
+
+/// Layers of a multi-layer trace, from the client signal down to the media of the fibre.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum TraceLayer {
+    #[serde(rename = "DSR")]
+    Dsr,
+    #[serde(rename = "ODU")]
+    Odu,
+    #[serde(rename = "OTSi")]
+    Otsi,
+    #[serde(rename = "MC")]
+    Mc,
+    #[serde(rename = "OMS/OTS")]
+    OmsOts,
+    #[serde(rename = "UNKNOWN")]
+    Unknown,
+}
+
+impl TraceLayer {
+    /// Places a connection end point in a layer, by its qualifier and otherwise by its layer protocol.
+    ///
+    /// Photonic media end points with an unspecified qualifier are the line ports of the fibre, in
+    /// the OMS/OTS layer.
+    pub fn of(
+        layer_protocol_name: Option<LayerProtocolName>,
+        layer_protocol_qualifier: &str,
+    ) -> Self {
+        let qualifier = layer_protocol_qualifier
+            .rsplit(':')
+            .next()
+            .unwrap_or_default()
+            .to_uppercase();
+        if qualifier.ends_with("_MC")
+            || qualifier.ends_with("_NMC")
+            || qualifier.ends_with("OTSIMC")
+        {
+            Self::Mc
+        } else if qualifier.contains("OTSI") {
+            Self::Otsi
+        } else if qualifier.ends_with("_OMS") || qualifier.ends_with("_OTS") {
+            Self::OmsOts
+        } else if qualifier.starts_with("ODU_TYPE") {
+            Self::Odu
+        } else if qualifier.starts_with("DIGITAL_SIGNAL_TYPE") {
+            Self::Dsr
+        } else {
+            match layer_protocol_name {
+                Some(LayerProtocolName::Dsr | LayerProtocolName::Eth) => Self::Dsr,
+                Some(LayerProtocolName::Odu) => Self::Odu,
+                Some(LayerProtocolName::PhotonicMedia) => Self::OmsOts,
+                Some(LayerProtocolName::Unknown) | None => Self::Unknown,
+            }
+        }
+    }
+}
+
+/// Represents a layer of the trace of a service.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LayerResponse {
+    pub layer: TraceLayer,
+    /// Connections of the layer, in the order of their first endpoint along the route.
+    pub connections: Vec<LayerConnection>,
+    /// Links between traced edge points of the layer.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub links: Vec<Uuid>,
+}
+
+/// Represents a connection of a layer and the lower connections carrying it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LayerConnection {
+    pub connection_uuid: Uuid,
+    pub connection_end_points: Vec<LayerConnectionEndPoint>,
+    /// Connections of a lower layer carrying this connection.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub lower_connections: Vec<Uuid>,
+}
+
+/// Represents a connection end point of a layer and the edge points serving it from the layer below.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LayerConnectionEndPoint {
+    pub connection_end_point_uuid: Uuid,
+    pub node_uuid: Uuid,
+    pub node_edge_point_uuid: Uuid,
+    /// Position of the edge point in the route, if the trace reached it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hop: Option<i32>,
+    /// Edge points of the layer below whose connection end points serve this edge point.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub server_node_edge_points: Vec<Uuid>,
+}
+
+/// Kind of anomaly found in the topology while tracing a service.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TraceWarningKind {
+    /// A connection, link or client reference names a node edge point that no node owns.
+    DanglingNodeEdgePoint,
+    /// A node edge point without the name of its inventory.
+    MissingInventory,
+    /// A connection end point with several client node edge points. The trace follows the first.
+    MultipleClientNodeEdgePoints,
+    /// The connection of an edge point belongs to another service. The edge point is left out.
+    ForeignConnection,
+    /// A link with an end missing from the topology.
+    LinkEndMissing,
+}
+
+/// Represents an anomaly found while tracing a service, on the edge point where it was found.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct TraceWarning {
+    pub kind: TraceWarningKind,
+    pub node_uuid: Uuid,
+    pub node_edge_point_uuid: Uuid,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub connection_uuid: Option<Uuid>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub link_uuid: Option<Uuid>,
+    pub message: String,
+}
+
+impl TraceWarning {
+    /// Creates a warning on a node edge point, without connection or link.
+    pub fn new(
+        kind: TraceWarningKind,
+        node_uuid: Uuid,
+        node_edge_point_uuid: Uuid,
+        message: impl Into<String>,
+    ) -> Self {
+        Self {
+            kind,
+            node_uuid,
+            node_edge_point_uuid,
+            connection_uuid: None,
+            link_uuid: None,
+            message: message.into(),
+        }
+    }
+}
+
+/// Represents the whole network of a data source: every node and every link between them.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TopologyResponse {
+    pub nodes: Vec<TopologyNode>,
+    pub links: Vec<TopologyLink>,
+}
+
+/// Represents a node of the network and its edge points, grouped by inventory.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TopologyNode {
+    pub node_uuid: Uuid,
+    pub value_name: String,
+    pub topology_uuid: Uuid,
+    pub inventories: Vec<TopologyInventory>,
+}
+
+/// Represents an inventory of a node with the edge points it holds.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TopologyInventory {
+    pub inventory_id: String,
+    pub node_edge_points: Vec<TopologyNodeEdgePoint>,
+}
+
+/// Represents an edge point owned by a node.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TopologyNodeEdgePoint {
+    pub node_edge_point_uuid: Uuid,
+    /// Layer of the first connection end point of the edge point, if it has any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub layer_protocol_name: Option<LayerProtocolName>,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub layer_protocol_qualifier: String,
+}
+
+/// Represents a link between edge points of the network.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TopologyLink {
+    pub link_uuid: Uuid,
+    pub topology_uuid: Uuid,
+    pub node_edge_points: Vec<TopologyLinkEnd>,
+}
+
+/// Represents an end of a link, with the inventory of its edge point if a node owns it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TopologyLinkEnd {
+    pub node_uuid: Uuid,
+    pub node_edge_point_uuid: Uuid,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub inventory_id: Option<String>,
+}
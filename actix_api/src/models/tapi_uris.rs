@@ -1,4 +1,4 @@
-#[derive(sqlx::FromRow, Debug)]
+#[derive(sqlx::FromRow, Debug, Clone, PartialEq)]
 pub struct TapiUri {
     pub id: i32,
     pub uri: String,
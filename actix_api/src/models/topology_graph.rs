@@ -0,0 +1,350 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use tapi_model::Uuid;
+
+use super::{
+    connections::Connection,
+    links::Link,
+    nodes::{ClientNodeEdgePoint, Node, NodeConnectionEndPoint, OwnedNodeEdgePoint},
+};
+
+/// Nodes, links and connections of a snapshot, indexed by the UUIDs the endpoint trace follows.
+///
+/// Every index holds positions in the vectors, in the order of the vectors, so lookups return the
+/// same objects in the same order as scanning the vectors did.
+#[derive(Debug, Clone, Default)]
+pub struct TopologyGraph {
+    nodes: Arc<Vec<Node>>,
+    links: Arc<Vec<Link>>,
+    connections: Arc<Vec<Connection>>,
+    /// Nodes by node UUID. A node merged from several topologies appears once per topology.
+    nodes_by_uuid: HashMap<Uuid, Vec<usize>>,
+    /// Owner node and position of each owned node edge point, by node edge point UUID.
+    node_edge_points: HashMap<Uuid, Vec<(usize, usize)>>,
+    /// Links by link UUID. A link merged from several topologies appears once per topology.
+    links_by_uuid: HashMap<Uuid, Vec<usize>>,
+    /// Links ending on each node edge point.
+    links_by_node_edge_point: HashMap<Uuid, Vec<usize>>,
+    /// Connections with a connection end point on each node edge point.
+    connections_by_node_edge_point: HashMap<Uuid, Vec<usize>>,
+    /// Connections by connection UUID, to resolve the lower connections of a connection.
+    connections_by_uuid: HashMap<Uuid, Vec<usize>>,
+    /// Connections listing each connection as a lower connection.
+    upper_connections: HashMap<Uuid, Vec<usize>>,
+    /// Node, node edge point and position of each connection end point owned by a node.
+    connection_end_points: HashMap<Uuid, (usize, usize, usize)>,
+    /// Nodes with an edge point whose first connection end point serves a node edge point as its
+    /// first client.
+    parent_nodes: HashMap<Uuid, Vec<usize>>,
+    /// Owner node and position of the edge points with a connection end point serving each client
+    /// node edge point.
+    server_node_edge_points: HashMap<Uuid, Vec<(usize, usize)>>,
+}
+
+/// Appends a position to an index entry, once.
+fn push_position(index: &mut HashMap<Uuid, Vec<usize>>, uuid: Uuid, position: usize) {
+    let positions = index.entry(uuid).or_default();
+    if positions.last() != Some(&position) {
+        positions.push(position);
+    }
+}
+
+impl TopologyGraph {
+    /// Indexes the nodes, links and connections of a snapshot.
+    pub fn new(
+        nodes: Arc<Vec<Node>>,
+        links: Arc<Vec<Link>>,
+        connections: Arc<Vec<Connection>>,
+    ) -> Self {
+        let mut graph = Self {
+            nodes,
+            links,
+            connections,
+            ..Default::default()
+        };
+        graph.index_nodes();
+        graph.index_links();
+        graph.index_connections();
+        graph
+    }
+
+    /// The same graph with other nodes, indexing only the nodes again.
+    pub fn with_nodes(&self, nodes: Arc<Vec<Node>>) -> Self {
+        let mut graph = Self {
+            nodes,
+            links: self.links.clone(),
+            connections: self.connections.clone(),
+            links_by_node_edge_point: self.links_by_node_edge_point.clone(),
+            connections_by_node_edge_point: self.connections_by_node_edge_point.clone(),
+            connections_by_uuid: self.connections_by_uuid.clone(),
+            upper_connections: self.upper_connections.clone(),
+            ..Default::default()
+        };
+        graph.index_nodes();
+        graph
+    }
+
+    /// The same graph with other links, indexing only the links again.
+    pub fn with_links(&self, links: Arc<Vec<Link>>) -> Self {
+        let mut graph = Self {
+            nodes: self.nodes.clone(),
+            links,
+            connections: self.connections.clone(),
+            nodes_by_uuid: self.nodes_by_uuid.clone(),
+            node_edge_points: self.node_edge_points.clone(),
+            connections_by_node_edge_point: self.connections_by_node_edge_point.clone(),
+            connections_by_uuid: self.connections_by_uuid.clone(),
+            upper_connections: self.upper_connections.clone(),
+            connection_end_points: self.connection_end_points.clone(),
+            parent_nodes: self.parent_nodes.clone(),
+            server_node_edge_points: self.server_node_edge_points.clone(),
+            ..Default::default()
+        };
+        graph.index_links();
+        graph
+    }
+
+    /// The same graph with other connections, indexing only the connections again.
+    pub fn with_connections(&self, connections: Arc<Vec<Connection>>) -> Self {
+        let mut graph = Self {
+            nodes: self.nodes.clone(),
+            links: self.links.clone(),
+            connections,
+            nodes_by_uuid: self.nodes_by_uuid.clone(),
+            node_edge_points: self.node_edge_points.clone(),
+            links_by_node_edge_point: self.links_by_node_edge_point.clone(),
+            connection_end_points: self.connection_end_points.clone(),
+            parent_nodes: self.parent_nodes.clone(),
+            server_node_edge_points: self.server_node_edge_points.clone(),
+            ..Default::default()
+        };
+        graph.index_connections();
+        graph
+    }
+
+    /// Fills the indexes over the nodes, their edge points and their connection end points.
+    fn index_nodes(&mut self) {
+        for (node_position, node) in self.nodes.iter().enumerate() {
+            push_position(&mut self.nodes_by_uuid, node.node_uuid, node_position);
+            for (edge_point_position, owned_node_edge_point) in
+                node.owned_node_edge_points.iter().enumerate()
+            {
+                self.node_edge_points
+                    .entry(owned_node_edge_point.node_edge_point_uuid)
+                    .or_default()
+                    .push((node_position, edge_point_position));
+                for (cep_position, connection_end_point) in owned_node_edge_point
+                    .connection_end_points
+                    .iter()
+                    .enumerate()
+                {
+                    self.connection_end_points
+                        .entry(connection_end_point.connection_end_point_uuid)
+                        .or_insert((node_position, edge_point_position, cep_position));
+                    for client in &connection_end_point.client_node_edge_points {
+                        let servers = self
+                            .server_node_edge_points
+                            .entry(client.node_edge_point_uuid)
+                            .or_default();
+                        if !servers.contains(&(node_position, edge_point_position)) {
+                            servers.push((node_position, edge_point_position));
+                        }
+                    }
+                }
+                if let Some(client) = owned_node_edge_point
+                    .connection_end_points
+                    .first()
+                    .and_then(|connection_end_point| {
+                        connection_end_point.client_node_edge_points.first()
+                    })
+                {
+                    push_position(
+                        &mut self.parent_nodes,
+                        client.node_edge_point_uuid,
+                        node_position,
+                    );
+                }
+            }
+        }
+    }
+
+    /// Fills the index of the links ending on each node edge point.
+    fn index_links(&mut self) {
+        for (link_position, link) in self.links.iter().enumerate() {
+            push_position(&mut self.links_by_uuid, link.link_uuid, link_position);
+            for node_edge_point in &link.node_edge_points {
+                push_position(
+                    &mut self.links_by_node_edge_point,
+                    node_edge_point.node_edge_point_uuid,
+                    link_position,
+                );
+            }
+        }
+    }
+
+    /// Fills the indexes over the connections, their end points and their lower connections.
+    fn index_connections(&mut self) {
+        for (connection_position, connection) in self.connections.iter().enumerate() {
+            push_position(
+                &mut self.connections_by_uuid,
+                connection.connection_uuid,
+                connection_position,
+            );
+            for connection_end_point in &connection.connection_end_points {
+                push_position(
+                    &mut self.connections_by_node_edge_point,
+                    connection_end_point.node_edge_point_uuid,
+                    connection_position,
+                );
+            }
+            for lower_connection in &connection.lower_connections {
+                push_position(
+                    &mut self.upper_connections,
+                    lower_connection.connection_uuid,
+                    connection_position,
+                );
+            }
+        }
+    }
+
+    /// Every node of the snapshot, in topology order.
+    pub fn nodes(&self) -> &[Node] {
+        &self.nodes
+    }
+
+    /// Every link of the snapshot, in topology order.
+    pub fn links(&self) -> &[Link] {
+        &self.links
+    }
+
+    /// Every connection of the snapshot, in context order.
+    pub fn connections(&self) -> &[Connection] {
+        &self.connections
+    }
+
+    /// Nodes with the given UUID, once per topology holding it.
+    pub fn nodes_by_uuid(&self, node_uuid: Uuid) -> impl Iterator<Item = &Node> {
+        self.positions(&self.nodes_by_uuid, node_uuid)
+            .map(|position| &self.nodes[position])
+    }
+
+    /// The nodes owning a node edge point, with the edge point.
+    pub fn node_edge_point_owners(
+        &self,
+        node_edge_point_uuid: Uuid,
+    ) -> impl Iterator<Item = (&Node, &OwnedNodeEdgePoint)> {
+        self.node_edge_points
+            .get(&node_edge_point_uuid)
+            .into_iter()
+            .flatten()
+            .map(|&(node_position, edge_point_position)| {
+                let node = &self.nodes[node_position];
+                (node, &node.owned_node_edge_points[edge_point_position])
+            })
+    }
+
+    /// The edge point of a node, as the first node with that UUID owning it.
+    pub fn owned_node_edge_point(
+        &self,
+        node_uuid: Uuid,
+        node_edge_point_uuid: Uuid,
+    ) -> Option<&OwnedNodeEdgePoint> {
+        self.node_edge_point_owners(node_edge_point_uuid)
+            .find(|(node, _)| node.node_uuid == node_uuid)
+            .map(|(_, owned_node_edge_point)| owned_node_edge_point)
+    }
+
+    /// Links with the given UUID, once per topology holding it.
+    pub fn links_by_uuid(&self, link_uuid: Uuid) -> impl Iterator<Item = &Link> {
+        self.positions(&self.links_by_uuid, link_uuid)
+            .map(|position| &self.links[position])
+    }
+
+    /// Links ending on a node edge point.
+    pub fn links_of(&self, node_edge_point_uuid: Uuid) -> impl Iterator<Item = &Link> {
+        self.positions(&self.links_by_node_edge_point, node_edge_point_uuid)
+            .map(|position| &self.links[position])
+    }
+
+    /// Connections with a connection end point on a node edge point.
+    pub fn connections_of(&self, node_edge_point_uuid: Uuid) -> impl Iterator<Item = &Connection> {
+        self.positions(&self.connections_by_node_edge_point, node_edge_point_uuid)
+            .map(|position| &self.connections[position])
+    }
+
+    /// Connections with the given UUID.
+    pub fn connections_by_uuid(&self, connection_uuid: Uuid) -> impl Iterator<Item = &Connection> {
+        self.positions(&self.connections_by_uuid, connection_uuid)
+            .map(|position| &self.connections[position])
+    }
+
+    /// Connections listing a connection as one of their lower connections.
+    pub fn upper_connections(&self, connection_uuid: Uuid) -> impl Iterator<Item = &Connection> {
+        self.positions(&self.upper_connections, connection_uuid)
+            .map(|position| &self.connections[position])
+    }
+
+    /// A connection end point owned by a node.
+    pub fn node_connection_end_point(
+        &self,
+        connection_end_point_uuid: Uuid,
+    ) -> Option<&NodeConnectionEndPoint> {
+        self.connection_end_points
+            .get(&connection_end_point_uuid)
+            .map(|&(node_position, edge_point_position, cep_position)| {
+                &self.nodes[node_position].owned_node_edge_points[edge_point_position]
+                    .connection_end_points[cep_position]
+            })
+    }
+
+    /// Client node edge points of a connection end point owned by a node.
+    pub fn client_node_edge_points(
+        &self,
+        connection_end_point_uuid: Uuid,
+    ) -> &[ClientNodeEdgePoint] {
+        self.node_connection_end_point(connection_end_point_uuid)
+            .map(|connection_end_point| connection_end_point.client_node_edge_points.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// Edge points with a connection end point serving a node edge point, with the nodes owning
+    /// them. They belong to the layer below the node edge point.
+    pub fn server_node_edge_points(
+        &self,
+        node_edge_point_uuid: Uuid,
+    ) -> impl Iterator<Item = (&Node, &OwnedNodeEdgePoint)> {
+        self.server_node_edge_points
+            .get(&node_edge_point_uuid)
+            .into_iter()
+            .flatten()
+            .map(|&(node_position, edge_point_position)| {
+                let node = &self.nodes[node_position];
+                (node, &node.owned_node_edge_points[edge_point_position])
+            })
+    }
+
+    /// Nodes that can resolve the inventory of a node edge point or name it as a client: the nodes
+    /// with the UUID of its node, and those with an edge point whose first connection end point
+    /// serves it first. They are returned once each, in topology order.
+    pub fn inventory_nodes(&self, node_uuid: Uuid, node_edge_point_uuid: Uuid) -> Vec<&Node> {
+        let mut positions: Vec<usize> = self
+            .positions(&self.nodes_by_uuid, node_uuid)
+            .chain(self.positions(&self.parent_nodes, node_edge_point_uuid))
+            .collect();
+        positions.sort_unstable();
+        positions.dedup();
+        positions
+            .into_iter()
+            .map(|position| &self.nodes[position])
+            .collect()
+    }
+
+    /// Positions held by an index for a UUID.
+    fn positions<'a>(
+        &self,
+        index: &'a HashMap<Uuid, Vec<usize>>,
+        uuid: Uuid,
+    ) -> impl Iterator<Item = usize> + 'a {
+        index.get(&uuid).into_iter().flatten().copied()
+    }
+}
@@ -0,0 +1,114 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use tapi_model::common::{find_name, NameAndValue};
+
+/// Name given to objects without any of the `value-name` keys of the profile.
+pub const UNKNOWN_NAME: &str = "UNKNOWN";
+
+/// Describes how a vendor names TAPI objects and which of its augmentations are extracted.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VendorProfile {
+    /// Name used to select the profile, e.g. `ciena`.
+    pub name: String,
+    /// Prefixes of the YANG modules that identify the vendor, e.g. `tapi-ciena-`.
+    #[serde(default)]
+    pub modules: Vec<String>,
+    #[serde(default)]
+    pub names: NameKeys,
+    #[serde(default)]
+    pub extensions: Vec<ExtensionField>,
+}
+
+/// `value-name` keys of each object, tried in order until one is found.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NameKeys {
+    pub node: Vec<String>,
+    pub inventory: Vec<String>,
+    pub service: Vec<String>,
+    pub connection: Vec<String>,
+    pub service_end_point: Vec<String>,
+    pub location: Vec<String>,
+}
+
+/// Object an extension field is read from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExtensionEntity {
+    Connection,
+    NodeEdgePoint,
+}
+
+/// Vendor augmentation copied into the `extensions` of each `Endpoint`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExtensionField {
+    pub entity: ExtensionEntity,
+    /// Module-qualified key of the augmentation, e.g. `tapi-ciena-connection-extensions:service-class`.
+    pub key: String,
+    /// Name of the field in the `Endpoint`, e.g. `service_class`.
+    pub name: String,
+}
+
+impl Default for NameKeys {
+    fn default() -> Self {
+        Self {
+            node: vec!["NODE_IDENTIFIER".to_string()],
+            inventory: vec!["INVENTORY_ID".to_string()],
+            service: vec!["SERVICE_NAME".to_string()],
+            connection: vec!["CONNECTION_NAME".to_string()],
+            service_end_point: vec!["CSEP_NAME".to_string()],
+            location: vec!["location".to_string()],
+        }
+    }
+}
+
+impl Default for VendorProfile {
+    fn default() -> Self {
+        Self {
+            name: "generic".to_string(),
+            modules: Vec::new(),
+            names: NameKeys::default(),
+            extensions: Vec::new(),
+        }
+    }
+}
+
+impl VendorProfile {
+    /// Finds the first name whose `value-name` is one of the given keys.
+    ///
+    /// # Returns
+    /// The name, or `UNKNOWN_NAME` if none of the keys is found.
+    pub fn find_name(names: &[NameAndValue], keys: &[String]) -> String {
+        keys.iter()
+            .find_map(|key| find_name(names, key))
+            .unwrap_or(UNKNOWN_NAME)
+            .to_string()
+    }
+
+    /// Returns `true` if any of the YANG modules belongs to the vendor.
+    pub fn matches(&self, modules: &[String]) -> bool {
+        self.modules.iter().any(|prefix| {
+            modules
+                .iter()
+                .any(|module| module.starts_with(prefix.as_str()))
+        })
+    }
+
+    /// Extracts the extension fields of an entity present in the fields outside the TAPI model.
+    pub fn extract_extensions(
+        &self,
+        entity: ExtensionEntity,
+        extensions: &Map<String, Value>,
+    ) -> BTreeMap<String, Value> {
+        self.extensions
+            .iter()
+            .filter(|extension| extension.entity == entity)
+            .filter_map(|extension| {
+                extensions
+                    .get(&extension.key)
+                    .map(|value| (extension.name.clone(), value.clone()))
+            })
+            .collect()
+    }
+}
@@ -1,18 +1,21 @@
 use actix_web::{error, post, web, Error, HttpResponse};
 use ipaddress::IPAddress;
 use serde_json::json;
-use std::collections::HashMap;
-use std::sync::Arc;
-use tokio::sync::Mutex;
 
+use crate::handlers::http::HttpHandler;
+use crate::handlers::repository::DataSourceRepository;
 use crate::handlers::requester::DataSource;
+use crate::handlers::snapshot::SnapshotCache;
+use crate::handlers::token::TokenCache;
 use crate::models::devices::Device;
+use crate::AppError;
 
 /// HTTP POST endpoint to add a new host to the host dictionary.
 ///
 /// # Arguments
 ///
-/// * `data_source_dictionary` - A `web::Data<Arc<Mutex<HashMap<String, DataSource>>>>` representing the host dictionary.
+/// * `repository` - A `web::Data<DataSourceRepository>` where the registered hosts are persisted.
+/// * `cache` - A `web::Data<SnapshotCache>` whose snapshot of a replaced host is dropped.
 /// * `request` - A `web::Json<AddHostRequest>` representing the request body containing host details.
 ///
 /// # Returns
@@ -20,7 +23,8 @@ use crate::models::devices::Device;
 /// An `impl Responder` containing an `HttpResponse` indicating the result of the operation.
 #[post("/add_host")]
 pub async fn add_host(
-    data_source_dictionary: web::Data<Arc<Mutex<HashMap<String, DataSource>>>>,
+    repository: web::Data<DataSourceRepository>,
+    cache: web::Data<SnapshotCache>,
     request_device: web::Json<Device>,
 ) -> Result<HttpResponse, Error> {
     // Validate the IP address or hostname
@@ -28,15 +32,28 @@ pub async fn add_host(
         log::error!("Host cannot be added: {}", &request_device.ip);
         return Err(error::ErrorBadRequest("Host cannot be added"));
     }
+    // Reject certificates and keys that cannot be read before storing them
+    if let Err(err) = HttpHandler::check_tls(&request_device.tls) {
+        log::error!("Host cannot be added: {}", err);
+        return Err(error::ErrorBadRequest(err.to_string()));
+    }
 
-    // Lock the host dictionary for writing.
-    let mut host_dictionary = data_source_dictionary.lock().await;
-
-    // Insert the new host into the dictionary.
-    host_dictionary.insert(
-        request_device.ip.clone(),
-        DataSource::Device(request_device.clone()),
-    );
+    // Persist the new host.
+    let id = repository
+        .insert(DataSource::Device(request_device.clone()))
+        .await
+        .map_err(|err| {
+            log::error!("{}", err);
+            match err {
+                AppError::IoError(_) => {
+                    error::ErrorInternalServerError("Cannot remove the replaced files")
+                }
+                _ => error::ErrorServiceUnavailable("Database disconnected"),
+            }
+        })?;
+    cache.invalidate(&id).await;
+    // The cached token may belong to the replaced credentials.
+    TokenCache::global().invalidate(&request_device).await;
     // Return an HTTP response indicating successful addition.
     Ok(HttpResponse::Ok()
         .json(json!({"message": &format!("{} added successfully", request_device.ip)})))
@@ -1,38 +1,90 @@
 use actix_multipart::form::MultipartForm;
 use actix_web::{error, post, web, Error, HttpResponse};
 use serde_json::json;
-use std::collections::HashMap;
-use std::sync::Arc;
-use tokio::sync::Mutex;
 
+use crate::handlers::repository::DataSourceRepository;
 use crate::handlers::requester::DataSource;
-use crate::models::files_model::UploadForm;
+use crate::handlers::snapshot::SnapshotCache;
+use crate::logic::upload_validator::validate_upload;
+use crate::models::files_model::{UploadForm, UploadReport};
+use crate::AppError;
 
 /// Handles the `/upload_services` endpoint for uploading service data.
 ///
+/// The files are staged and checked against the TAPI structure, and only moved over the files
+/// registered with the same id once they are valid.
+///
 /// # Arguments
-/// - `data_source_dictionary`: A thread-safe shared dictionary to store the data source.
+/// - `repository`: The `DataSourceRepository` where the uploaded file set is persisted.
+/// - `cache`: The `SnapshotCache` whose snapshot of a replaced file set is dropped.
 /// - `MultipartForm(form)`: The multipart form containing the uploaded files and metadata.
 ///
 /// # Returns
-/// - `HttpResponse`: A success message if the files are processed and added to the data source.
+/// - `HttpResponse`: A success message and the `UploadReport` if the files are added to the data source.
+/// - `422 Unprocessable Entity` with the `UploadReport` if files or sections are missing or invalid.
 #[post("/upload_services")]
 pub async fn upload_services(
-    data_source_dictionary: web::Data<Arc<Mutex<HashMap<String, DataSource>>>>,
+    repository: web::Data<DataSourceRepository>,
+    cache: web::Data<SnapshotCache>,
     MultipartForm(form): MultipartForm<UploadForm>,
 ) -> Result<HttpResponse, Error> {
-    let mut data_source_dictionary = data_source_dictionary.lock().await;
+    let missing_files = form.missing_files();
+    if !missing_files.is_empty() {
+        log::error!("Upload {} is missing {:?}", form.json.id, missing_files);
+        return Ok(HttpResponse::UnprocessableEntity().json(UploadReport {
+            id: form.json.id.clone(),
+            missing_sections: missing_files,
+            ..Default::default()
+        }));
+    }
+
+    let files_enum = match form.to_filesenum() {
+        Ok(files_enum) => files_enum,
+        Err(AppError::IoError(err)) => {
+            log::error!("Cannot store the files of {}: {}", form.json.id, err);
+            return Err(error::ErrorInternalServerError(
+                "Cannot store the uploaded files",
+            ));
+        }
+        Err(err) => {
+            log::error!("Cannot parse FilesEnum {:?}: {}", &form, err);
+            return Ok(HttpResponse::UnprocessableEntity().json(UploadReport {
+                id: form.json.id.clone(),
+                errors: vec![err.to_string()],
+                ..Default::default()
+            }));
+        }
+    };
+
+    let report = validate_upload(&files_enum);
+    if !report.valid {
+        log::error!("Upload {} rejected: {:?}", form.json.id, report);
+        if let Err(err) = files_enum.remove_files() {
+            log::error!("{}", err);
+        }
+        return Ok(HttpResponse::UnprocessableEntity().json(report));
+    }
 
-    data_source_dictionary.insert(
-        form.json.id.to_string(),
-        DataSource::FilesEnum(form.to_filesenum().map_err(|_| {
-            log::error!("Cannot parse FilesEnum {:?}", &form);
-            error::ErrorNotAcceptable("Cannot parse FilesEnum")
-        })?),
-    );
+    let files_enum = files_enum.promote_staged().map_err(|err| {
+        log::error!("{}", err);
+        error::ErrorInternalServerError("Cannot store the uploaded files")
+    })?;
+    let id = repository
+        .insert(DataSource::FilesEnum(files_enum))
+        .await
+        .map_err(|err| {
+            log::error!("{}", err);
+            match err {
+                AppError::IoError(_) => {
+                    error::ErrorInternalServerError("Cannot remove the replaced files")
+                }
+                _ => error::ErrorServiceUnavailable("Database disconnected"),
+            }
+        })?;
+    cache.invalidate(&id).await;
 
-    Ok(
-        HttpResponse::Ok()
-            .json(json!({"message": &format!("{} added successfully", form.json.id)})),
-    )
+    Ok(HttpResponse::Ok().json(json!({
+        "message": &format!("{} added successfully", form.json.id),
+        "report": report
+    })))
 }
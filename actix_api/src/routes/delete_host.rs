@@ -1,17 +1,21 @@
-use actix_web::{delete, web, Error, HttpResponse};
-use std::collections::HashMap;
-use std::fs;
-use std::sync::Arc;
-use tokio::sync::Mutex;
+use actix_web::{delete, error, web, Error, HttpResponse};
 
+use crate::handlers::notifications::NotificationHub;
+use crate::handlers::repository::DataSourceRepository;
 use crate::handlers::requester::DataSource;
-use crate::models::files_model::FilesEnum;
+use crate::handlers::snapshot::SnapshotCache;
+use crate::handlers::token::TokenCache;
+use crate::AppError;
 
-/// HTTP DELETE endpoint to remove a host from the host dictionary.
+/// HTTP DELETE endpoint to remove a host from the repository.
+///
+/// Uploaded file sets also get their files removed from disk.
 ///
 /// # Arguments
 ///
-/// * `data_source_dictionary` - A `web::Data<Arc<Mutex<HashMap<String, DataSource>>>>` representing the host dictionary.
+/// * `repository` - A `web::Data<DataSourceRepository>` where the registered hosts are persisted.
+/// * `cache` - A `web::Data<SnapshotCache>` whose snapshot of the host is dropped.
+/// * `hub` - A `web::Data<NotificationHub>` closing the notification stream of the host.
 /// * `hostname` - A `web::Path<String>` representing the hostname to be deleted.
 ///
 /// # Returns
@@ -19,29 +23,28 @@ use crate::models::files_model::FilesEnum;
 /// An `impl Responder` containing an `HttpResponse` indicating the result of the operation.
 #[delete("/delete_host/{id}")]
 pub async fn delete_host(
-    data_source_dictionary: web::Data<Arc<Mutex<HashMap<String, DataSource>>>>,
+    repository: web::Data<DataSourceRepository>,
+    cache: web::Data<SnapshotCache>,
+    hub: web::Data<NotificationHub>,
     id: web::Path<String>,
 ) -> Result<HttpResponse, Error> {
-    let id = id.clone();
-    // Lock the host dictionary for writing.
-    let mut data_source_dictionary = data_source_dictionary.lock().await;
+    let id = id.into_inner();
 
-    // Attempt to remove the host from the dictionary.
-    if let Some(data_source) = data_source_dictionary.get(&id).cloned() {
-        match data_source {
-            DataSource::Device(_) => {}
-            DataSource::FilesEnum(files_enum) => match files_enum {
-                FilesEnum::ByPart(by_part) => {
-                    fs::remove_file(&by_part.connections_path)?;
-                    fs::remove_file(&by_part.connectivity_services_path)?;
-                    fs::remove_file(&by_part.topology_path)?;
-                }
-                FilesEnum::Complete(complete) => {
-                    fs::remove_file(&complete.complete_context_path)?;
-                }
-            },
+    // Attempt to remove the host from the repository.
+    let removed = repository.remove(&id).await.map_err(|err| {
+        log::error!("{}", err);
+        match err {
+            AppError::IoError(_) => error::ErrorInternalServerError("Cannot remove the host files"),
+            _ => error::ErrorServiceUnavailable("Database disconnected"),
         }
-        data_source_dictionary.remove(&id);
+    })?;
+    cache.invalidate(&id).await;
+    hub.unsubscribe(&id).await;
+    if let Some(DataSource::Device(device)) = &removed {
+        TokenCache::global().invalidate(device).await;
+    }
+
+    if removed.is_some() {
         // Host was successfully removed.
         Ok(HttpResponse::Ok().body(format!("{} removed successfully", id)))
     } else {
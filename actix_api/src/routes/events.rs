@@ -0,0 +1,78 @@
+use actix_web::{error, get, web, Error, HttpResponse};
+use futures_util::stream;
+use std::time::Duration;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::time;
+
+use crate::handlers::notifications::NotificationHub;
+use crate::handlers::repository::DataSourceRepository;
+use crate::handlers::requester::DataSource;
+
+/// Time between two comments sent while the device is silent, so closed connections are noticed.
+const KEEP_ALIVE: Duration = Duration::from_secs(15);
+
+/// HTTP GET endpoint streaming the changes of a device to the browser as server-sent events.
+///
+/// Every notification of the device is applied to its cached snapshot and sent as a
+/// `TopologyEvent` in the `data` of an event, so the schemas can be fetched again.
+///
+/// # Arguments
+///
+/// * `id` - A `web::Path<String>` representing the ip of the device.
+/// * `repository` - A `web::Data<DataSourceRepository>` where the registered devices are persisted.
+/// * `hub` - A `web::Data<NotificationHub>` following the notification streams of the devices.
+///
+/// # Returns
+///
+/// An `Result<HttpResponse, Error>` with the `text/event-stream`, or an error if the id is not a registered device.
+#[get("/events/{id}")]
+pub async fn events(
+    id: web::Path<String>,
+    repository: web::Data<DataSourceRepository>,
+    hub: web::Data<NotificationHub>,
+) -> Result<HttpResponse, Error> {
+    let id = id.into_inner();
+    let device = match repository.get(&id).await.map_err(|err| {
+        log::error!("{}", err);
+        error::ErrorServiceUnavailable("Database disconnected")
+    })? {
+        Some(DataSource::Device(device)) => device,
+        Some(DataSource::FilesEnum(_)) => {
+            return Err(error::ErrorBadRequest(
+                "Uploaded files send no notifications",
+            ))
+        }
+        None => return Err(error::ErrorNotFound("Id not on database")),
+    };
+
+    let receiver = hub.subscribe(&device).await;
+    let events = stream::unfold((receiver, true), |(mut receiver, is_first)| async move {
+        if is_first {
+            let comment = web::Bytes::from_static(b": subscribed\n\n");
+            return Some((Ok::<_, Error>(comment), (receiver, false)));
+        }
+        loop {
+            let chunk = match time::timeout(KEEP_ALIVE, receiver.recv()).await {
+                Ok(Ok(event)) => match serde_json::to_string(&event) {
+                    Ok(event) => format!("data: {}\n\n", event),
+                    Err(err) => {
+                        log::error!("{}", err);
+                        continue;
+                    }
+                },
+                Ok(Err(RecvError::Lagged(skipped))) => {
+                    log::warn!("A follower skipped {} events", skipped);
+                    continue;
+                }
+                Ok(Err(RecvError::Closed)) => return None,
+                Err(_) => ": keep-alive\n\n".to_string(),
+            };
+            return Some((Ok(web::Bytes::from(chunk)), (receiver, false)));
+        }
+    });
+
+    Ok(HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .insert_header(("Cache-Control", "no-cache"))
+        .streaming(events))
+}
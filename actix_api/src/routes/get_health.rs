@@ -0,0 +1,51 @@
+use actix_web::{error, get, web, Error, HttpResponse};
+use serde::Deserialize;
+
+use crate::handlers::repository::DataSourceRepository;
+
+/// Health checks returned when the request does not set `limit`.
+const DEFAULT_LIMIT: usize = 100;
+
+/// Query parameters of `get_health`.
+#[derive(Deserialize)]
+pub struct HealthQuery {
+    /// Maximum number of checks returned, 100 by default.
+    pub limit: Option<usize>,
+}
+
+/// HTTP GET endpoint to retrieve the health checks of a device, stored by the `HealthMonitor`.
+///
+/// # Arguments
+///
+/// * `id` - A `web::Path<String>` representing the ip of the device.
+/// * `query` - A `web::Query<HealthQuery>` with the maximum number of checks returned.
+/// * `repository` - A `web::Data<DataSourceRepository>` where the registered devices and the checks are persisted.
+///
+/// # Returns
+///
+/// An `Result<HttpResponse, Error>` with the `DeviceHealth` list, the most recent first, empty if the device was never checked.
+#[get("/hosts/{id}/health")]
+pub async fn get_health(
+    id: web::Path<String>,
+    query: web::Query<HealthQuery>,
+    repository: web::Data<DataSourceRepository>,
+) -> Result<HttpResponse, Error> {
+    let id = id.into_inner();
+    let data_source = repository.get(&id).await.map_err(|err| {
+        log::error!("{}", err);
+        error::ErrorServiceUnavailable("Database disconnected")
+    })?;
+    if data_source.is_none() {
+        return Err(error::ErrorNotFound("Id not on database"));
+    }
+
+    let health = repository
+        .health(&id, query.limit.unwrap_or(DEFAULT_LIMIT))
+        .await
+        .map_err(|err| {
+            log::error!("{}", err);
+            error::ErrorServiceUnavailable("Database disconnected")
+        })?;
+
+    Ok(HttpResponse::Ok().json(health))
+}
@@ -1,31 +1,23 @@
-use actix_web::{get, web, Responder};
-use std::collections::HashMap;
-use std::sync::Arc;
-use tokio::sync::Mutex;
+use actix_web::{error, get, web, Error, HttpResponse};
 
-use crate::handlers::requester::DataSource;
+use crate::handlers::repository::DataSourceRepository;
 
 /// HTTP GET endpoint to retrieve a list of all hosts with their details.
 ///
 /// # Arguments
 ///
-/// * `data_source_dictionary` - A `web::Data<Arc<Mutex<HashMap<String, DataSource>>>>` representing the host dictionary.
+/// * `repository` - A `web::Data<DataSourceRepository>` where the registered hosts are persisted.
 ///
 /// # Returns
 ///
-/// An `impl Responder` containing the JSON response with a list of hosts and their details.
+/// An `Result<HttpResponse, Error>` containing the JSON response with a list of hosts and their details.
 #[get("/get_hosts")]
-pub async fn get_hosts(
-    data_source_dictionary: web::Data<Arc<Mutex<HashMap<String, DataSource>>>>,
-) -> impl Responder {
-    // Lock the host dictionary for reading.
-    let data_source_dictionary = data_source_dictionary.lock().await;
-    // Initialize a vector to store host information.
-    let mut data_source_vector: Vec<DataSource> = vec![];
-    // Collect all host information from the dictionary.
-    for (_, data_source) in data_source_dictionary.iter() {
-        data_source_vector.push(data_source.clone());
-    }
+pub async fn get_hosts(repository: web::Data<DataSourceRepository>) -> Result<HttpResponse, Error> {
+    // Collect all host information from the repository.
+    let data_source_vector = repository.list().await.map_err(|err| {
+        log::error!("{}", err);
+        error::ErrorServiceUnavailable("Database disconnected")
+    })?;
     // Return the JSON response with the list of hosts and their details.
-    web::Json(data_source_vector)
+    Ok(HttpResponse::Ok().json(data_source_vector))
 }
@@ -1,59 +1,73 @@
 use actix_web::{error, get, web, Error, HttpResponse};
-use std::collections::HashMap;
-use std::sync::Arc;
-use tokio::sync::Mutex;
+use serde_json::Value;
 
-use crate::handlers::requester::{DataSource, Requester};
-use crate::{
-    logic::connection_builder::connection_vector_build, logic::link_builder::link_vector_build,
-    logic::node_builder::node_vector_building, logic::schema_builder::build_schema,
-    models::connectivity_services::Service,
-};
+use crate::handlers::repository::DataSourceRepository;
+use crate::handlers::snapshot::SnapshotCache;
+use crate::{logic::schema_builder::build_schema, models::connectivity_services::Service};
 
 /// HTTP GET endpoint to retrieve JSON data for a specified id.
 ///
+/// The topology and connections are read from the cached snapshot of the data source, which is
+/// taken again once it expires or through `/refresh/{id}`.
+///
 /// # Arguments
 ///
 /// * `id` - A `web::Path<String>` representing the id or ip.
 /// * `service_uuid` - A `web::Path<String>` representing the service_uuid.
-/// * `data_source_dictionary` - A `web::Data<Arc<Mutex<HashMap<String, DataSource>>>>` representing the data_source dictionary.
+/// * `repository` - A `web::Data<DataSourceRepository>` where the registered data sources are persisted.
+/// * `cache` - A `web::Data<SnapshotCache>` with the last snapshot of each data source.
 ///
 /// # Returns
 ///
-/// An `Result<HttpResponse, Error>` containing the JSON response.
+/// An `Result<HttpResponse, Error>` containing the JSON response, with the moment the snapshot was
+/// taken in `snapshot_timestamp` and in the `X-Snapshot-Timestamp` header.
 #[get("/get_schema/{id}/{service_uuid}")]
 async fn schema_by_service(
     path: web::Path<(String, String)>,
-    data_source_dictionary: web::Data<Arc<Mutex<HashMap<String, DataSource>>>>,
+    repository: web::Data<DataSourceRepository>,
+    cache: web::Data<SnapshotCache>,
 ) -> Result<HttpResponse, Error> {
     let (id, service_uuid) = path.into_inner();
-    let id = id.clone();
-    let cloned_data_source_dictionary = data_source_dictionary.clone();
-    let cloned_data_source_dictionary = cloned_data_source_dictionary.lock().await;
-    // Lock the host dictionary for reading.
-    if let Some(data_source) = cloned_data_source_dictionary.get(&id) {
-        let context = Requester::get_service_context(data_source, &service_uuid)
+    let data_source = repository.get(&id).await.map_err(|err| {
+        log::error!("{}", err);
+        error::ErrorServiceUnavailable("Database disconnected")
+    })?;
+    if let Some(data_source) = &data_source {
+        let snapshot = cache.get(data_source).await.map_err(|err| {
+            log::error!("{}", err);
+            error::ErrorNotAcceptable("Cannot extract Services from data_sources")
+        })?;
+        if let Err(err) = repository
+            .remember_strategy(data_source, snapshot.strategy)
             .await
-            .map_err(|err| {
-                log::error!("{}", err);
-                error::ErrorNotAcceptable("Cannot extract Services from data_sources")
-            })?;
-        let topology = context.topology.clone();
-        let connections = context.connections.clone();
-        let service_value = context.connectivity_service.clone();
+        {
+            log::warn!("{}", err);
+        }
+        let connectivity_service = snapshot.service(&service_uuid).ok_or_else(|| {
+            log::error!("Service {} not found in {}", service_uuid, id);
+            error::ErrorNotFound("Service not found")
+        })?;
 
-        let link_vector = link_vector_build(&topology);
-        let node_vector = node_vector_building(&topology);
-        let connection_vector = connection_vector_build(&connections);
-        let service = Service::connectivity_service_build(&service_value, &connection_vector);
+        let service = Service::connectivity_service_build(
+            connectivity_service,
+            &snapshot.connections,
+            &snapshot.profile,
+        );
 
-        let schema = build_schema(&service, &link_vector, &node_vector, &connection_vector)
-            .map_err(|err| {
-                log::error!("{}", err);
-                error::ErrorNotAcceptable("Cannot Build Services from data_sources")
-            })?;
+        let mut schema = build_schema(&service, &snapshot.graph).map_err(|err| {
+            log::error!("{}", err);
+            error::ErrorNotAcceptable("Cannot Build Services from data_sources")
+        })?;
+        if let Some(schema) = schema.as_object_mut() {
+            schema.insert(
+                "snapshot_timestamp".to_string(),
+                Value::String(snapshot.timestamp()),
+            );
+        }
 
-        Ok(HttpResponse::Ok().json(schema))
+        Ok(HttpResponse::Ok()
+            .insert_header(("X-Snapshot-Timestamp", snapshot.timestamp()))
+            .json(schema))
     } else {
         Err(error::ErrorNotFound("Id not on database"))
     }
@@ -1,18 +1,18 @@
 use actix_web::{error, get, web, Error, HttpResponse};
 use serde_json::json;
-use std::collections::HashMap;
-use std::sync::Arc;
-use tokio::sync::Mutex;
 
-use crate::handlers::requester::{DataSource, Requester};
+use crate::handlers::repository::DataSourceRepository;
+use crate::handlers::requester::Requester;
+use crate::handlers::vendor::VendorProfiles;
 use crate::logic::services_builder::connectivity_services_vector_build;
+use crate::utils::{collect_modules, parse_tapi};
 
 /// HTTP GET endpoint to retrieve JSON data for a specified id.
 ///
 /// # Arguments
 ///
 /// * `id` - A `web::Path<String>` representing the id or ip.
-/// * `data_source_dictionary` - A `web::Data<Arc<Mutex<HashMap<String, DataSource>>>>` representing the data_source dictionary.
+/// * `repository` - A `web::Data<DataSourceRepository>` where the registered data sources are persisted.
 ///
 /// # Returns
 ///
@@ -20,18 +20,33 @@ use crate::logic::services_builder::connectivity_services_vector_build;
 #[get("/get_services/{id}")]
 async fn connectivity_services(
     id: web::Path<String>,
-    data_source_dictionary: web::Data<Arc<Mutex<HashMap<String, DataSource>>>>,
+    repository: web::Data<DataSourceRepository>,
 ) -> Result<HttpResponse, Error> {
     let id = id.clone();
-    let cloned_data_source_dictionary = data_source_dictionary.clone();
-    let cloned_data_source_dictionary = cloned_data_source_dictionary.lock().await;
-    // Lock the host dictionary for reading.
-    if let Some(data_source) = cloned_data_source_dictionary.get(&id) {
-        let services_value = Requester::get_services(data_source).await.map_err(|err| {
-            log::error!("{}", err);
-            error::ErrorNotAcceptable("Cannot extract Services from data_sources")
-        })?;
-        let services = connectivity_services_vector_build(&services_value);
+    let data_source = repository.get(&id).await.map_err(|err| {
+        log::error!("{}", err);
+        error::ErrorServiceUnavailable("Database disconnected")
+    })?;
+    if let Some(data_source) = &data_source {
+        let (services_value, strategy) =
+            Requester::get_services(data_source).await.map_err(|err| {
+                log::error!("{}", err);
+                error::ErrorNotAcceptable("Cannot extract Services from data_sources")
+            })?;
+        if let Err(err) = repository.remember_strategy(data_source, strategy).await {
+            log::warn!("{}", err);
+        }
+        let mut modules: Vec<String> = Vec::new();
+        for service in &services_value {
+            collect_modules(service, &mut modules);
+        }
+        let profile = VendorProfiles::global().select(data_source.vendor(), &modules);
+        let connectivity_services =
+            parse_tapi(&services_value, "connectivity service").map_err(|err| {
+                log::error!("{}", err);
+                error::ErrorNotAcceptable("Cannot extract Services from data_sources")
+            })?;
+        let services = connectivity_services_vector_build(&connectivity_services, &profile);
 
         Ok(HttpResponse::Ok().json(json!(services)))
     } else {
@@ -0,0 +1,60 @@
+use actix_web::{error, get, web, Error, HttpResponse};
+use serde_json::{json, Value};
+
+use crate::handlers::repository::DataSourceRepository;
+use crate::handlers::snapshot::SnapshotCache;
+use crate::logic::topology_builder::build_topology;
+
+/// HTTP GET endpoint to retrieve the whole network of a specified id as a graph.
+///
+/// The nodes and links are read from the cached snapshot of the data source, which is taken again
+/// once it expires or through `/refresh/{id}`.
+///
+/// # Arguments
+///
+/// * `id` - A `web::Path<String>` representing the id or ip.
+/// * `repository` - A `web::Data<DataSourceRepository>` where the registered data sources are persisted.
+/// * `cache` - A `web::Data<SnapshotCache>` with the last snapshot of each data source.
+///
+/// # Returns
+///
+/// An `Result<HttpResponse, Error>` containing every node with its edge points grouped by inventory
+/// and every link, with the moment the snapshot was taken in `snapshot_timestamp` and in the
+/// `X-Snapshot-Timestamp` header.
+#[get("/get_topology/{id}")]
+async fn topology_by_id(
+    id: web::Path<String>,
+    repository: web::Data<DataSourceRepository>,
+    cache: web::Data<SnapshotCache>,
+) -> Result<HttpResponse, Error> {
+    let data_source = repository.get(&id).await.map_err(|err| {
+        log::error!("{}", err);
+        error::ErrorServiceUnavailable("Database disconnected")
+    })?;
+    if let Some(data_source) = &data_source {
+        let snapshot = cache.get(data_source).await.map_err(|err| {
+            log::error!("{}", err);
+            error::ErrorNotAcceptable("Cannot extract Topology from data_sources")
+        })?;
+        if let Err(err) = repository
+            .remember_strategy(data_source, snapshot.strategy)
+            .await
+        {
+            log::warn!("{}", err);
+        }
+
+        let mut topology = json!(build_topology(&snapshot.graph));
+        if let Some(topology) = topology.as_object_mut() {
+            topology.insert(
+                "snapshot_timestamp".to_string(),
+                Value::String(snapshot.timestamp()),
+            );
+        }
+
+        Ok(HttpResponse::Ok()
+            .insert_header(("X-Snapshot-Timestamp", snapshot.timestamp()))
+            .json(topology))
+    } else {
+        Err(error::ErrorNotFound("Id not on database"))
+    }
+}
@@ -0,0 +1,35 @@
+use actix_web::{error, get, web, Error, HttpResponse};
+
+use crate::handlers::repository::DataSourceRepository;
+
+/// HTTP GET endpoint to retrieve the last probe result of each TAPI RESTCONF URI for a device.
+///
+/// # Arguments
+///
+/// * `ip` - A `web::Path<String>` representing the ip of the device.
+/// * `repository` - A `web::Data<DataSourceRepository>` where the registered devices and the results are persisted.
+///
+/// # Returns
+///
+/// An `Result<HttpResponse, Error>` with the `RestconfValidation` list, empty if the device was never probed.
+#[get("/get_validations/{ip}")]
+pub async fn get_validations(
+    ip: web::Path<String>,
+    repository: web::Data<DataSourceRepository>,
+) -> Result<HttpResponse, Error> {
+    let ip = ip.into_inner();
+    let data_source = repository.get(&ip).await.map_err(|err| {
+        log::error!("{}", err);
+        error::ErrorServiceUnavailable("Database disconnected")
+    })?;
+    if data_source.is_none() {
+        return Err(error::ErrorNotFound("Id not on database"));
+    }
+
+    let validations = repository.validations(&ip).await.map_err(|err| {
+        log::error!("{}", err);
+        error::ErrorServiceUnavailable("Database disconnected")
+    })?;
+
+    Ok(HttpResponse::Ok().json(validations))
+}
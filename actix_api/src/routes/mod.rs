@@ -1,7 +1,16 @@
 pub mod add_host;
 pub mod by_files;
 pub mod delete_host;
+pub mod events;
+pub mod get_health;
 pub mod get_hosts;
 pub mod get_schema;
 pub mod get_services;
+pub mod get_topology;
+pub mod get_validations;
 pub mod home;
+pub mod path_computation;
+pub mod positions;
+pub mod probe;
+pub mod provisioning;
+pub mod refresh;
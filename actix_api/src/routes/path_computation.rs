@@ -0,0 +1,117 @@
+use actix_web::{error, post, web, Error, HttpResponse};
+use tapi_model::Uuid;
+
+use crate::handlers::path_computation::PathComputer;
+use crate::handlers::repository::DataSourceRepository;
+use crate::handlers::requester::DataSource;
+use crate::handlers::snapshot::SnapshotCache;
+use crate::logic::path_builder::{build_path_schema, path_nodes};
+use crate::models::path_computation::{PathComputationResult, PathRequest, PathSource};
+use crate::AppError;
+
+/// HTTP POST endpoint finding the paths between two service interface points of a device.
+///
+/// The device computes the paths with the TAPI `compute-p-2-p-path` RPC, honouring the nodes to
+/// include and exclude, or answers with a `path-comp-service` it already holds, whose paths are kept
+/// if they meet the constraints. Each path is rendered with the nodes, inventories and endpoints of
+/// `/get_schema`, so it is drawn like a service.
+///
+/// # Arguments
+///
+/// * `id` - A `web::Path<String>` representing the ip of the device.
+/// * `request` - A `web::Json<PathRequest>` with the source and destination service interface points and the constraints.
+/// * `repository` - A `web::Data<DataSourceRepository>` where the registered data sources are persisted.
+/// * `cache` - A `web::Data<SnapshotCache>` with the last snapshot of each data source.
+///
+/// # Returns
+///
+/// An `Result<HttpResponse, Error>` with the `PathComputationResult`, and the moment the snapshot was
+/// taken in the `X-Snapshot-Timestamp` header. `404 Not Found` if the device has no path.
+#[post("/paths/{id}")]
+pub async fn compute_paths(
+    id: web::Path<String>,
+    request: web::Json<PathRequest>,
+    repository: web::Data<DataSourceRepository>,
+    cache: web::Data<SnapshotCache>,
+) -> Result<HttpResponse, Error> {
+    let data_source = repository
+        .get(&id)
+        .await
+        .map_err(|err| {
+            log::error!("{}", err);
+            error::ErrorServiceUnavailable("Database disconnected")
+        })?
+        .ok_or(error::ErrorNotFound("Id not on database"))?;
+    let snapshot = cache.get(&data_source).await.map_err(|err| {
+        log::error!("{}", err);
+        error::ErrorNotAcceptable("Cannot extract Topology from data_sources")
+    })?;
+
+    let input = request
+        .input(&snapshot.node_topologies())
+        .map_err(|err| error::ErrorBadRequest(err.to_string()))?;
+    let known_sips = snapshot.service_interface_points();
+    let mut sips = vec![];
+    for sip_uuid in &request.service_interface_points {
+        let sip = Uuid::parse_str(sip_uuid)
+            .ok()
+            .and_then(|uuid| known_sips.iter().find(|known| known.uuid == uuid))
+            .ok_or_else(|| {
+                error::ErrorBadRequest(format!("Unknown service interface point {}", sip_uuid))
+            })?;
+        sips.push(sip);
+    }
+    let DataSource::Device(device) = &data_source else {
+        return Err(error::ErrorBadRequest(
+            "Paths cannot be computed on uploaded files",
+        ));
+    };
+
+    let (source, service, paths) = PathComputer::paths(device, &request, &input)
+        .await
+        .map_err(|err| {
+            log::error!("{}", err);
+            match err {
+                AppError::ValidationError(message) => error::ErrorNotFound(message),
+                err => error::ErrorBadGateway(err.to_string()),
+            }
+        })?;
+    // Paths held by the device were not computed for this request, so they may not meet it
+    let paths = match source {
+        PathSource::Computed => paths,
+        PathSource::Existing => {
+            let mut allowed = vec![];
+            for path in paths {
+                let node_uuids = path_nodes(&path, &snapshot.graph).map_err(|err| {
+                    log::error!("{}", err);
+                    error::ErrorNotAcceptable("Cannot extract Paths from data_sources")
+                })?;
+                if request.constraints.allow(&node_uuids) {
+                    allowed.push(path);
+                }
+            }
+            if allowed.is_empty() {
+                return Err(error::ErrorNotFound(
+                    "No path of the device meets the constraints",
+                ));
+            }
+            allowed
+        }
+    };
+    let paths = paths
+        .iter()
+        .map(|path| build_path_schema(path, sips[0], sips[1], &snapshot.graph))
+        .collect::<Result<Vec<_>, AppError>>()
+        .map_err(|err| {
+            log::error!("{}", err);
+            error::ErrorNotAcceptable("Cannot extract Paths from data_sources")
+        })?;
+
+    Ok(HttpResponse::Ok()
+        .insert_header(("X-Snapshot-Timestamp", snapshot.timestamp()))
+        .json(PathComputationResult {
+            source,
+            service_uuid: service.uuid,
+            paths,
+        }))
+}
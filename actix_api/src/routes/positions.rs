@@ -0,0 +1,75 @@
+use actix_web::{error, get, put, web, Error, HttpResponse};
+
+use crate::handlers::repository::DataSourceRepository;
+use crate::models::map_positions::MapPositions;
+
+/// HTTP GET endpoint to retrieve the positions of the nodes moved on the network map of a host.
+///
+/// # Arguments
+///
+/// * `id` - A `web::Path<String>` representing the ip of the device or the id of the file set.
+/// * `repository` - A `web::Data<DataSourceRepository>` where the registered hosts and their positions are persisted.
+///
+/// # Returns
+///
+/// An `Result<HttpResponse, Error>` with the `MapPositions` by node UUID, empty if no node was moved.
+#[get("/hosts/{id}/positions")]
+pub async fn get_positions(
+    id: web::Path<String>,
+    repository: web::Data<DataSourceRepository>,
+) -> Result<HttpResponse, Error> {
+    let id = id.into_inner();
+    let data_source = repository.get(&id).await.map_err(|err| {
+        log::error!("{}", err);
+        error::ErrorServiceUnavailable("Database disconnected")
+    })?;
+    if data_source.is_none() {
+        return Err(error::ErrorNotFound("Id not on database"));
+    }
+
+    let positions = repository.positions(&id).await.map_err(|err| {
+        log::error!("{}", err);
+        error::ErrorServiceUnavailable("Database disconnected")
+    })?;
+
+    Ok(HttpResponse::Ok().json(positions))
+}
+
+/// HTTP PUT endpoint to store the positions of the nodes moved on the network map of a host,
+/// replacing the previous ones. An empty object resets the layout of the map.
+///
+/// # Arguments
+///
+/// * `id` - A `web::Path<String>` representing the ip of the device or the id of the file set.
+/// * `positions` - A `web::Json<MapPositions>` with the `[x, y]` position of each moved node, by node UUID.
+/// * `repository` - A `web::Data<DataSourceRepository>` where the registered hosts and their positions are persisted.
+///
+/// # Returns
+///
+/// An `Result<HttpResponse, Error>` with the stored `MapPositions`.
+#[put("/hosts/{id}/positions")]
+pub async fn set_positions(
+    id: web::Path<String>,
+    positions: web::Json<MapPositions>,
+    repository: web::Data<DataSourceRepository>,
+) -> Result<HttpResponse, Error> {
+    let id = id.into_inner();
+    let data_source = repository.get(&id).await.map_err(|err| {
+        log::error!("{}", err);
+        error::ErrorServiceUnavailable("Database disconnected")
+    })?;
+    if data_source.is_none() {
+        return Err(error::ErrorNotFound("Id not on database"));
+    }
+
+    let positions = positions.into_inner();
+    repository
+        .set_positions(&id, &positions)
+        .await
+        .map_err(|err| {
+            log::error!("{}", err);
+            error::ErrorServiceUnavailable("Database disconnected")
+        })?;
+
+    Ok(HttpResponse::Ok().json(positions))
+}
@@ -0,0 +1,67 @@
+use actix_web::{error, post, web, Error, HttpResponse};
+
+use crate::handlers::prober::RestconfProber;
+use crate::handlers::repository::DataSourceRepository;
+use crate::handlers::requester::DataSource;
+use crate::models::devices::RetrievalStrategy;
+
+/// HTTP POST endpoint to request every TAPI RESTCONF URI to a device and store the results.
+///
+/// The `RetrievalStrategy` supported according to the results is remembered for the device.
+///
+/// # Arguments
+///
+/// * `ip` - A `web::Path<String>` representing the ip of the device.
+/// * `repository` - A `web::Data<DataSourceRepository>` where the registered devices and the results are persisted.
+///
+/// # Returns
+///
+/// An `Result<HttpResponse, Error>` with the `RestconfValidation` of each URI.
+#[post("/probe/{ip}")]
+pub async fn probe(
+    ip: web::Path<String>,
+    repository: web::Data<DataSourceRepository>,
+) -> Result<HttpResponse, Error> {
+    let ip = ip.into_inner();
+    let data_source = repository.get(&ip).await.map_err(|err| {
+        log::error!("{}", err);
+        error::ErrorServiceUnavailable("Database disconnected")
+    })?;
+    let device = match data_source {
+        Some(DataSource::Device(device)) => device,
+        Some(DataSource::FilesEnum(_)) => {
+            return Err(error::ErrorBadRequest("Only devices can be probed"))
+        }
+        None => return Err(error::ErrorNotFound("Id not on database")),
+    };
+
+    let tapi_uris = repository.tapi_uris().await.map_err(|err| {
+        log::error!("{}", err);
+        error::ErrorServiceUnavailable("Database disconnected")
+    })?;
+    let validations = RestconfProber::probe(&device, &tapi_uris)
+        .await
+        .map_err(|err| {
+            log::error!("{}", err);
+            error::ErrorBadGateway("Device cannot be reached")
+        })?;
+    repository
+        .insert_validations(&ip, &validations)
+        .await
+        .map_err(|err| {
+            log::error!("{}", err);
+            error::ErrorServiceUnavailable("Database disconnected")
+        })?;
+    repository
+        .remember_strategy(
+            &DataSource::Device(device),
+            RetrievalStrategy::from_validations(&validations),
+        )
+        .await
+        .map_err(|err| {
+            log::error!("{}", err);
+            error::ErrorServiceUnavailable("Database disconnected")
+        })?;
+
+    Ok(HttpResponse::Ok().json(validations))
+}
@@ -0,0 +1,219 @@
+use actix_web::{delete, error, get, post, web, Error, HttpResponse};
+use serde::Deserialize;
+use tapi_model::Uuid;
+
+use crate::handlers::provisioning::Provisioner;
+use crate::handlers::repository::DataSourceRepository;
+use crate::handlers::requester::DataSource;
+use crate::handlers::snapshot::SnapshotCache;
+use crate::models::provisioning::{
+    service_path, ProvisioningResult, ServiceRequest, CONNECTIVITY_CONTEXT_PATH,
+};
+use crate::AppError;
+
+/// Query parameters of `create_service` and `delete_service`.
+#[derive(Deserialize)]
+pub struct ProvisioningQuery {
+    /// When `true` the request is rendered without sending it to the device.
+    pub dry_run: Option<bool>,
+}
+
+/// Finds a registered data source, answering `404` if there is none.
+async fn data_source(repository: &DataSourceRepository, id: &str) -> Result<DataSource, Error> {
+    repository
+        .get(id)
+        .await
+        .map_err(|err| {
+            log::error!("{}", err);
+            error::ErrorServiceUnavailable("Database disconnected")
+        })?
+        .ok_or(error::ErrorNotFound("Id not on database"))
+}
+
+/// Maps the failure of a request sent to the device.
+fn device_error(err: AppError) -> Error {
+    log::error!("{}", err);
+    match err {
+        AppError::ValidationError(message) => error::ErrorBadRequest(message),
+        err => error::ErrorBadGateway(err.to_string()),
+    }
+}
+
+/// HTTP GET endpoint listing the service interface points new services can join.
+///
+/// # Arguments
+///
+/// * `id` - A `web::Path<String>` representing the id or ip.
+/// * `repository` - A `web::Data<DataSourceRepository>` where the registered data sources are persisted.
+/// * `cache` - A `web::Data<SnapshotCache>` with the last snapshot of each data source.
+///
+/// # Returns
+///
+/// An `Result<HttpResponse, Error>` with the `AvailableSip` list mapped to the edge points of the topology.
+#[get("/sips/{id}")]
+pub async fn get_sips(
+    id: web::Path<String>,
+    repository: web::Data<DataSourceRepository>,
+    cache: web::Data<SnapshotCache>,
+) -> Result<HttpResponse, Error> {
+    let data_source = data_source(&repository, &id).await?;
+    let sips = cache
+        .get(&data_source)
+        .await
+        .map(|snapshot| snapshot.service_interface_points().to_vec())
+        .map_err(|err| {
+            log::error!("{}", err);
+            error::ErrorNotAcceptable("Cannot extract Topology from data_sources")
+        })?;
+
+    Ok(HttpResponse::Ok().json(sips))
+}
+
+/// HTTP POST endpoint creating a connectivity service on a device.
+///
+/// The payload is built from the request and the topology snapshot, which resolves the topology
+/// of the constrained nodes. With `dry_run` the payload is returned without sending it, which also
+/// works for uploaded files. Otherwise it is POSTed to the device, whose snapshot is then dropped,
+/// and the service is polled until it is installed or `PROVISIONING_TIMEOUT_SECS` elapses.
+///
+/// # Arguments
+///
+/// * `id` - A `web::Path<String>` representing the ip of the device.
+/// * `query` - A `web::Query<ProvisioningQuery>` telling whether the request is only rendered.
+/// * `request` - A `web::Json<ServiceRequest>` with the service interface points, layer, capacity and constraints.
+/// * `repository` - A `web::Data<DataSourceRepository>` where the registered data sources are persisted.
+/// * `cache` - A `web::Data<SnapshotCache>` with the last snapshot of each data source.
+/// * `provisioner` - A `web::Data<Provisioner>` sending the request and polling the device.
+///
+/// # Returns
+///
+/// An `Result<HttpResponse, Error>` with the `ProvisioningResult`: `201 Created` once the service is
+/// installed, `202 Accepted` while it is not, and `200 OK` for a dry run.
+#[post("/services/{id}")]
+pub async fn create_service(
+    id: web::Path<String>,
+    query: web::Query<ProvisioningQuery>,
+    request: web::Json<ServiceRequest>,
+    repository: web::Data<DataSourceRepository>,
+    cache: web::Data<SnapshotCache>,
+    provisioner: web::Data<Provisioner>,
+) -> Result<HttpResponse, Error> {
+    let id = id.into_inner();
+    let data_source = data_source(&repository, &id).await?;
+    let snapshot = cache.get(&data_source).await.map_err(|err| {
+        log::error!("{}", err);
+        error::ErrorNotAcceptable("Cannot extract Topology from data_sources")
+    })?;
+
+    let uuid = request.uuid.unwrap_or_else(Uuid::new_v4);
+    if snapshot.service(&uuid.to_string()).is_some() {
+        return Err(error::ErrorConflict(format!(
+            "Service {} already exists",
+            uuid
+        )));
+    }
+    let known_sips = snapshot.service_interface_points();
+    if let Some(unknown) = request
+        .service_interface_points
+        .iter()
+        .find(|sip| !known_sips.iter().any(|known| &known.uuid == *sip))
+    {
+        return Err(error::ErrorBadRequest(format!(
+            "Unknown service interface point {}",
+            unknown
+        )));
+    }
+    let payload = request
+        .payload(uuid, &snapshot.node_topologies())
+        .map_err(|err| error::ErrorBadRequest(err.to_string()))?;
+
+    if query.dry_run.unwrap_or(false) {
+        return Ok(HttpResponse::Ok().json(ProvisioningResult {
+            uuid,
+            dry_run: true,
+            method: "POST".to_string(),
+            path: CONNECTIVITY_CONTEXT_PATH.to_string(),
+            payload: Some(payload),
+            state: None,
+            completed: false,
+        }));
+    }
+    let DataSource::Device(device) = &data_source else {
+        return Err(error::ErrorBadRequest(
+            "Services cannot be created on uploaded files",
+        ));
+    };
+
+    let result = provisioner
+        .create(device, uuid, payload)
+        .await
+        .map_err(device_error)?;
+    cache.invalidate(&id).await;
+
+    if result.completed {
+        Ok(HttpResponse::Created().json(result))
+    } else {
+        Ok(HttpResponse::Accepted().json(result))
+    }
+}
+
+/// HTTP DELETE endpoint removing a connectivity service from a device.
+///
+/// With `dry_run` the request is returned without sending it. Otherwise the service is deleted,
+/// the snapshot of the device dropped, and the service polled until the device no longer finds it.
+///
+/// # Arguments
+///
+/// * `path` - A `web::Path<(String, String)>` with the ip of the device and the UUID of the service.
+/// * `query` - A `web::Query<ProvisioningQuery>` telling whether the request is only rendered.
+/// * `repository` - A `web::Data<DataSourceRepository>` where the registered data sources are persisted.
+/// * `cache` - A `web::Data<SnapshotCache>` with the last snapshot of each data source.
+/// * `provisioner` - A `web::Data<Provisioner>` sending the request and polling the device.
+///
+/// # Returns
+///
+/// An `Result<HttpResponse, Error>` with the `ProvisioningResult`: `200 OK` once the service is gone
+/// or for a dry run, `202 Accepted` while the device still holds it, `400 Bad Request` if the UUID
+/// is not valid.
+#[delete("/services/{id}/{uuid}")]
+pub async fn delete_service(
+    path: web::Path<(String, String)>,
+    query: web::Query<ProvisioningQuery>,
+    repository: web::Data<DataSourceRepository>,
+    cache: web::Data<SnapshotCache>,
+    provisioner: web::Data<Provisioner>,
+) -> Result<HttpResponse, Error> {
+    let (id, uuid) = path.into_inner();
+    let uuid = Uuid::parse_str(&uuid)
+        .map_err(|_| error::ErrorBadRequest(format!("Invalid service UUID {}", uuid)))?;
+    let data_source = data_source(&repository, &id).await?;
+
+    if query.dry_run.unwrap_or(false) {
+        return Ok(HttpResponse::Ok().json(ProvisioningResult {
+            path: service_path(uuid),
+            uuid,
+            dry_run: true,
+            method: "DELETE".to_string(),
+            payload: None,
+            state: None,
+            completed: false,
+        }));
+    }
+    let DataSource::Device(device) = &data_source else {
+        return Err(error::ErrorBadRequest(
+            "Services cannot be deleted from uploaded files",
+        ));
+    };
+
+    let result = provisioner
+        .delete(device, uuid)
+        .await
+        .map_err(device_error)?;
+    cache.invalidate(&id).await;
+
+    if result.completed {
+        Ok(HttpResponse::Ok().json(result))
+    } else {
+        Ok(HttpResponse::Accepted().json(result))
+    }
+}
@@ -0,0 +1,55 @@
+use actix_web::{error, post, web, Error, HttpResponse};
+use serde_json::json;
+
+use crate::handlers::repository::DataSourceRepository;
+use crate::handlers::snapshot::SnapshotCache;
+
+/// HTTP POST endpoint to take a new topology snapshot of a data source, ignoring the TTL.
+///
+/// # Arguments
+///
+/// * `id` - A `web::Path<String>` representing the id or ip.
+/// * `repository` - A `web::Data<DataSourceRepository>` where the registered data sources are persisted.
+/// * `cache` - A `web::Data<SnapshotCache>` with the last snapshot of each data source.
+///
+/// # Returns
+///
+/// An `Result<HttpResponse, Error>` with the timestamp of the new snapshot and the number of
+/// elements it contains.
+#[post("/refresh/{id}")]
+pub async fn refresh(
+    id: web::Path<String>,
+    repository: web::Data<DataSourceRepository>,
+    cache: web::Data<SnapshotCache>,
+) -> Result<HttpResponse, Error> {
+    let id = id.into_inner();
+    let data_source = repository.get(&id).await.map_err(|err| {
+        log::error!("{}", err);
+        error::ErrorServiceUnavailable("Database disconnected")
+    })?;
+    if let Some(data_source) = &data_source {
+        let snapshot = cache.refresh(data_source).await.map_err(|err| {
+            log::error!("{}", err);
+            error::ErrorNotAcceptable("Cannot extract Topology from data_sources")
+        })?;
+        if let Err(err) = repository
+            .remember_strategy(data_source, snapshot.strategy)
+            .await
+        {
+            log::warn!("{}", err);
+        }
+
+        Ok(HttpResponse::Ok()
+            .insert_header(("X-Snapshot-Timestamp", snapshot.timestamp()))
+            .json(json!({
+                "id": id,
+                "snapshot_timestamp": snapshot.timestamp(),
+                "nodes": snapshot.nodes.len(),
+                "links": snapshot.links.len(),
+                "connections": snapshot.connections.len(),
+                "connectivity_services": snapshot.connectivity_services.len(),
+            })))
+    } else {
+        Err(error::ErrorNotFound("Id not on database"))
+    }
+}
@@ -5,7 +5,9 @@ use std::str::FromStr;
 
 use minidom::Element;
 use regex::Regex;
+use serde::de::DeserializeOwned;
 use serde_json::{Map, Value};
+use tapi_model::topology::Topology;
 
 /// This function uses the system's `ping` command.
 /// - On Windows it uses `-n 1` to send 1 echo request.
@@ -63,6 +65,80 @@ pub fn find_name(item: &Value, value: String) -> String {
     name
 }
 
+/// Lists the topologies of a topology JSON, which may be a single topology or an array of them.
+///
+/// # Arguments
+/// - `topology_json`: A reference to a `serde_json::Value` containing one or more topologies.
+///
+/// # Returns
+/// - A vector with the object of each topology, in order. Entries that are not objects are skipped.
+pub fn find_topologies(topology_json: &Value) -> Vec<&Map<String, Value>> {
+    match topology_json {
+        Value::Array(topology_vec) => topology_vec.iter().filter_map(Value::as_object).collect(),
+        Value::Object(topology_object) => vec![topology_object],
+        _ => Vec::new(),
+    }
+}
+
+/// Deserializes TAPI objects into the typed model.
+///
+/// # Arguments
+/// - `items`: The JSON objects, e.g. the connections of a connectivity context.
+/// - `kind`: Name of the objects, used in the error message.
+///
+/// # Returns
+/// - The parsed objects, or an `AppError::ModelError` with the UUID of the first object that does not
+///   match the model.
+pub fn parse_tapi<T: DeserializeOwned>(items: &[Value], kind: &str) -> Result<Vec<T>, AppError> {
+    items
+        .iter()
+        .map(|item| {
+            T::deserialize(item).map_err(|err| {
+                let uuid = item
+                    .get("uuid")
+                    .and_then(Value::as_str)
+                    .unwrap_or("unknown");
+                AppError::model_error(format!("Invalid {} {}: {}", kind, uuid, err))
+            })
+        })
+        .collect()
+}
+
+/// Deserializes every topology of a topology JSON, see `find_topologies`.
+pub fn parse_topologies(topology_json: &Value) -> Result<Vec<Topology>, AppError> {
+    let topologies = find_topologies(topology_json)
+        .into_iter()
+        .map(|topology| Value::Object(topology.clone()))
+        .collect::<Vec<Value>>();
+    parse_tapi(&topologies, "topology")
+}
+
+/// Collects the YANG modules used as key prefixes anywhere in a JSON document, e.g. `tapi-topology`.
+///
+/// # Arguments
+/// - `json`: A reference to a `serde_json::Value` to search.
+/// - `modules`: The modules found so far, extended in order of appearance without duplicates.
+pub fn collect_modules(json: &Value, modules: &mut Vec<String>) {
+    match json {
+        Value::Object(object) => {
+            for (key, value) in object {
+                if let Some((module, _)) = key.split_once(':') {
+                    if !modules.iter().any(|known| known == module) {
+                        modules.push(module.to_string());
+                    }
+                }
+                collect_modules(value, modules);
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                collect_modules(item, modules);
+            }
+        }
+        _ => {}
+    }
+}
+
 /// Ensures that the XML contains a namespace declaration. If none exists, a default namespace is added.
 ///
 /// # Arguments
@@ -162,6 +238,123 @@ pub fn xml_to_json(xml: &str) -> Result<Value, AppError> {
     Ok(xml_to_map(&root))
 }
 
+/// TAPI lists whose entries are repeated elements in XML, so that a single entry is still
+/// converted into a JSON array.
+const TAPI_LISTS: &[&str] = &[
+    "available-spectrum",
+    "client-node-edge-point",
+    "connection",
+    "connection-end-point",
+    "connectivity-service",
+    "device",
+    "end-point",
+    "equipment",
+    "link",
+    "lower-connection",
+    "mapped-service-interface-point",
+    "name",
+    "node",
+    "node-edge-point",
+    "occupied-spectrum",
+    "owned-node-edge-point",
+    "route",
+    "supportable-spectrum",
+    "supported-cep-layer-protocol-qualifier",
+    "supported-client-link",
+    "supported-layer-protocol-qualifier",
+    "topology",
+];
+
+/// Whether a child element is an entry of a TAPI list. Some names are lists in one parent and
+/// single leaves or references in another: `layer-protocol-name` is a leaf-list only in
+/// topologies, nodes and links, and the service interface points of service end points, the
+/// access port of `supporting-access-port` and the occupied spectrum of a media channel are
+/// single containers.
+fn is_tapi_list(parent: &str, child: &str) -> bool {
+    match child {
+        "layer-protocol-name" => matches!(parent, "topology" | "node" | "link"),
+        "access-port" => parent == "device",
+        "service-interface-point" => parent == "context",
+        "occupied-spectrum" => parent != "media-channel",
+        child => TAPI_LISTS.contains(&child),
+    }
+}
+
+/// YANG module of an XML namespace, e.g. `tapi-topology` for `urn:onf:otcc:yang:tapi-topology`.
+fn namespace_module(namespace: &str) -> Option<&str> {
+    let module = namespace.rsplit([':', '/']).next()?;
+    (!module.is_empty() && module != "default").then_some(module)
+}
+
+/// Recursively converts a TAPI XML element into its RESTCONF JSON value: leaves become strings
+/// and the children of another module are prefixed with its name.
+fn convert_tapi_node(el: &Element) -> Value {
+    if el.children().next().is_none() {
+        let text = el.text();
+        return match text.trim() {
+            "" => Value::Object(Map::new()),
+            text => Value::String(text.to_owned()),
+        };
+    }
+
+    let module = namespace_module(&el.ns()).map(str::to_owned);
+    let mut obj = Map::new();
+    for child in el.children() {
+        let child_module = namespace_module(&child.ns()).map(str::to_owned);
+        let key = match child_module {
+            Some(child_module) if Some(&child_module) != module.as_ref() => {
+                format!("{}:{}", child_module, child.name())
+            }
+            _ => child.name().to_owned(),
+        };
+        let child_value = convert_tapi_node(child);
+        match obj.get_mut(&key) {
+            Some(Value::Array(arr)) => arr.push(child_value),
+            Some(existing) => {
+                let old_value = existing.take();
+                *existing = Value::Array(vec![old_value, child_value]);
+            }
+            None if is_tapi_list(el.name(), child.name()) => {
+                obj.insert(key, Value::Array(vec![child_value]));
+            }
+            None => {
+                obj.insert(key, child_value);
+            }
+        }
+    }
+    Value::Object(obj)
+}
+
+/// Converts a TAPI XML document, e.g. a NETCONF export, into the JSON encoding of RESTCONF, so it
+/// can be read like a JSON upload.
+///
+/// # Arguments
+/// - `xml`: A string slice containing the XML data.
+///
+/// # Returns
+/// - A `tapi-common:context` object if the root is the context.
+/// - The entries of a `<data>` root, or a single root entry, as a list otherwise.
+/// - A `ValidationError` if the XML cannot be parsed.
+pub fn xml_to_tapi_json(xml: &str) -> Result<Value, AppError> {
+    let xml = ensure_namespace(xml);
+    let root =
+        Element::from_str(&xml).map_err(|err| AppError::validation_error(err.to_string()))?;
+
+    Ok(match root.name() {
+        "context" => {
+            let mut map = Map::new();
+            let key = match namespace_module(&root.ns()) {
+                Some(module) => format!("{}:context", module),
+                None => "tapi-common:context".to_string(),
+            };
+            map.insert(key, convert_tapi_node(&root));
+            Value::Object(map)
+        }
+        "data" => Value::Array(root.children().map(convert_tapi_node).collect()),
+        _ => Value::Array(vec![convert_tapi_node(&root)]),
+    })
+}
+
 /// Attempts to find the most likely key containing a token in a JSON object.
 ///
 /// Priority is given to any key that contains the word "token" (case-insensitive),
@@ -233,7 +426,10 @@ pub fn find_key_recursively(key: &str, value: &Value) -> Option<Value> {
 /// Module containing unit tests for the `find_name` function.
 #[cfg(test)]
 mod tests {
-    use super::{find_key_recursively, find_name, find_token_key, is_reachable, xml_to_json};
+    use super::{
+        find_key_recursively, find_name, find_token_key, find_topologies, xml_to_json,
+        xml_to_tapi_json,
+    };
     use serde_json::json;
 
     /// Tests the `find_name` function with valid and invalid inputs.
@@ -258,6 +454,22 @@ mod tests {
         assert_eq!(unknown_name, "UNKNOWN");
     }
 
+    #[test]
+    fn test_find_topologies() {
+        let topologies = json!([
+            { "uuid": "topology-1", "node": [] },
+            "not a topology",
+            { "uuid": "topology-2", "link": [] }
+        ]);
+        let found = find_topologies(&topologies);
+        assert_eq!(found.len(), 2);
+        assert_eq!(found[1]["uuid"], "topology-2");
+
+        let single = json!({ "uuid": "topology-1" });
+        assert_eq!(find_topologies(&single).len(), 1);
+        assert!(find_topologies(&json!(null)).is_empty());
+    }
+
     //#[test]
     //fn test_google_ping() {
     //    assert!(is_reachable("google.com"))
@@ -388,4 +600,58 @@ mod tests {
         let result = find_key_recursively("rel", &data);
         assert_eq!(result, None);
     }
+
+    #[test]
+    fn test_xml_to_tapi_json() {
+        let xml = r#"<?xml version="1.0"?>
+        <context xmlns="urn:onf:otcc:yang:tapi-common">
+            <uuid>ea7b6fbd-6a2d-4d53-90d2-2bcd5c4e5ad1</uuid>
+            <topology-context xmlns="urn:onf:otcc:yang:tapi-topology">
+                <topology>
+                    <uuid>topology-1</uuid>
+                    <layer-protocol-name>PHOTONIC_MEDIA</layer-protocol-name>
+                    <node>
+                        <uuid>node-1</uuid>
+                        <name><value-name>NODE_IDENTIFIER</value-name><value>7</value></name>
+                        <owned-node-edge-point>
+                            <uuid>nep-1</uuid>
+                            <layer-protocol-name>PHOTONIC_MEDIA</layer-protocol-name>
+                            <cep-list xmlns="urn:onf:otcc:yang:tapi-connectivity"/>
+                        </owned-node-edge-point>
+                    </node>
+                </topology>
+            </topology-context>
+        </context>"#;
+        let expected_json = json!({
+            "tapi-common:context": {
+                "uuid": "ea7b6fbd-6a2d-4d53-90d2-2bcd5c4e5ad1",
+                "tapi-topology:topology-context": {
+                    "topology": [{
+                        "uuid": "topology-1",
+                        "layer-protocol-name": ["PHOTONIC_MEDIA"],
+                        "node": [{
+                            "uuid": "node-1",
+                            "name": [{ "value-name": "NODE_IDENTIFIER", "value": "7" }],
+                            "owned-node-edge-point": [{
+                                "uuid": "nep-1",
+                                "layer-protocol-name": "PHOTONIC_MEDIA",
+                                "tapi-connectivity:cep-list": {}
+                            }]
+                        }]
+                    }]
+                }
+            }
+        });
+        assert_eq!(xml_to_tapi_json(xml).unwrap(), expected_json);
+
+        let xml = r#"<data xmlns="urn:ietf:params:xml:ns:yang:ietf-restconf">
+            <connection xmlns="urn:onf:otcc:yang:tapi-connectivity"><uuid>c-1</uuid></connection>
+            <connection xmlns="urn:onf:otcc:yang:tapi-connectivity"><uuid>c-2</uuid></connection>
+        </data>"#;
+        assert_eq!(
+            xml_to_tapi_json(xml).unwrap(),
+            json!([{ "uuid": "c-1" }, { "uuid": "c-2" }])
+        );
+        assert!(xml_to_tapi_json("<context>").is_err());
+    }
 }
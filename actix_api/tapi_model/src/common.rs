@@ -0,0 +1,264 @@
+use serde::{de, Deserialize, Deserializer, Serialize};
+use serde_json::{Map, Value};
+use std::fmt::Display;
+use std::str::FromStr;
+use uuid::Uuid;
+
+use crate::connectivity::ConnectivityContext;
+use crate::equipment::PhysicalContext;
+use crate::photonic_media::MediaChannelServiceInterfacePointSpec;
+use crate::topology::TopologyContext;
+
+/// Root of the TAPI datastore, i.e. the content of `tapi-common:context`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Context {
+    pub uuid: Option<Uuid>,
+    #[serde(default)]
+    pub name: Vec<NameAndValue>,
+    #[serde(default, alias = "tapi-common:service-interface-point")]
+    pub service_interface_point: Vec<ServiceInterfacePoint>,
+    #[serde(
+        rename = "tapi-topology:topology-context",
+        alias = "topology-context",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub topology_context: Option<TopologyContext>,
+    #[serde(
+        rename = "tapi-connectivity:connectivity-context",
+        alias = "connectivity-context",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub connectivity_context: Option<ConnectivityContext>,
+    #[serde(
+        rename = "tapi-equipment:physical-context",
+        alias = "physical-context",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub physical_context: Option<PhysicalContext>,
+    #[serde(flatten)]
+    pub extensions: Map<String, Value>,
+}
+
+/// Entry of the `name` list of an object.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct NameAndValue {
+    pub value_name: String,
+    /// Some controllers send numbers, which are kept as text.
+    #[serde(default, deserialize_with = "scalar_as_string")]
+    pub value: String,
+}
+
+/// Finds the value of a `name` entry by its `value-name`.
+pub fn find_name<'a>(names: &'a [NameAndValue], value_name: &str) -> Option<&'a str> {
+    names
+        .iter()
+        .find(|name| name.value_name == value_name)
+        .map(|name| name.value.as_str())
+}
+
+/// Administrative state of a managed object.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum AdministrativeState {
+    Locked,
+    Unlocked,
+    #[serde(other)]
+    Unknown,
+}
+
+/// Operational state of a managed object.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum OperationalState {
+    Enabled,
+    Disabled,
+    #[serde(other)]
+    Unknown,
+}
+
+/// Lifecycle state of a managed object.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum LifecycleState {
+    Planned,
+    PotentialAvailable,
+    PotentialBusy,
+    Installed,
+    PendingRemoval,
+    #[serde(other)]
+    Unknown,
+}
+
+/// Layer protocols of TAPI 2.x.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum LayerProtocolName {
+    Odu,
+    Eth,
+    Dsr,
+    PhotonicMedia,
+    #[serde(other)]
+    Unknown,
+}
+
+/// Direction of a connection or link.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ForwardingDirection {
+    Bidirectional,
+    Unidirectional,
+    UndefinedOrUnknown,
+    #[serde(other)]
+    Unknown,
+}
+
+/// Direction of a port, end point or service interface point.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum PortDirection {
+    Bidirectional,
+    Input,
+    Output,
+    UnidentifiedOrUnknown,
+    #[serde(other)]
+    Unknown,
+}
+
+/// Role of a port in a connection or link.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum PortRole {
+    Symmetric,
+    Root,
+    Leaf,
+    Trunk,
+    #[serde(other)]
+    Unknown,
+}
+
+/// Direction of a termination point.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum TerminationDirection {
+    Bidirectional,
+    Sink,
+    Source,
+    UndefinedOrUnknown,
+    #[serde(other)]
+    Unknown,
+}
+
+/// Identity reference, e.g. `tapi-photonic-media:PHOTONIC_LAYER_QUALIFIER_OMS`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct IdentityRef(pub String);
+
+impl IdentityRef {
+    /// Module of the identity, if it is qualified.
+    pub fn module(&self) -> Option<&str> {
+        self.0.split_once(':').map(|(module, _)| module)
+    }
+
+    /// Identity without its module, e.g. `PHOTONIC_LAYER_QUALIFIER_OMS`.
+    pub fn name(&self) -> &str {
+        self.0
+            .split_once(':')
+            .map(|(_, name)| name)
+            .unwrap_or(&self.0)
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+/// Capacity of a port, link or service.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Capacity {
+    pub total_size: Option<CapacityValue>,
+    #[serde(flatten)]
+    pub extensions: Map<String, Value>,
+}
+
+/// Amount of capacity with its unit, e.g. `100 GBPS`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CapacityValue {
+    #[serde(default, deserialize_with = "optional_integer")]
+    pub value: Option<u64>,
+    pub unit: Option<String>,
+}
+
+/// Access point of a service to the network.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct ServiceInterfacePoint {
+    pub uuid: Uuid,
+    #[serde(default)]
+    pub name: Vec<NameAndValue>,
+    pub layer_protocol_name: Option<LayerProtocolName>,
+    #[serde(default)]
+    pub supported_layer_protocol_qualifier: Vec<IdentityRef>,
+    pub direction: Option<PortDirection>,
+    pub administrative_state: Option<AdministrativeState>,
+    pub operational_state: Option<OperationalState>,
+    pub lifecycle_state: Option<LifecycleState>,
+    pub available_capacity: Option<Capacity>,
+    pub total_potential_capacity: Option<Capacity>,
+    #[serde(
+        rename = "tapi-photonic-media:media-channel-service-interface-point-spec",
+        alias = "media-channel-service-interface-point-spec",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub media_channel_service_interface_point_spec: Option<MediaChannelServiceInterfacePointSpec>,
+    #[serde(flatten)]
+    pub extensions: Map<String, Value>,
+}
+
+/// Reference to a service interface point.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct ServiceInterfacePointRef {
+    pub service_interface_point_uuid: Uuid,
+}
+
+/// Reads a scalar as text, keeping strings unquoted.
+fn scalar_as_string<'de, D: Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    Ok(match Value::deserialize(deserializer)? {
+        Value::String(value) => value,
+        Value::Null => String::new(),
+        value => value.to_string(),
+    })
+}
+
+/// Reads an integer sent as a number or, as RFC 7951 does for 64-bit types, as a string.
+pub(crate) fn integer<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr,
+    T::Err: Display,
+{
+    match Value::deserialize(deserializer)? {
+        Value::Number(number) => number.to_string().parse().map_err(de::Error::custom),
+        Value::String(text) => text.parse().map_err(de::Error::custom),
+        value => Err(de::Error::custom(format!(
+            "expected an integer, found {}",
+            value
+        ))),
+    }
+}
+
+/// Reads an optional integer, see `integer`.
+pub(crate) fn optional_integer<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr,
+    T::Err: Display,
+{
+    match Option::<Value>::deserialize(deserializer)? {
+        None | Some(Value::Null) => Ok(None),
+        Some(value) => integer(value).map(Some).map_err(de::Error::custom),
+    }
+}
@@ -0,0 +1,171 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use uuid::Uuid;
+
+use crate::common::{
+    AdministrativeState, ForwardingDirection, IdentityRef, LayerProtocolName, LifecycleState,
+    NameAndValue, OperationalState, PortDirection, PortRole, ServiceInterfacePointRef,
+    TerminationDirection,
+};
+use crate::odu::OduConnectionEndPointSpec;
+use crate::photonic_media::{MediaChannelConnectionEndPointSpec, OtsiConnectionEndPointSpec};
+use crate::topology::NodeEdgePointRef;
+
+/// Services and connections of the controller, `tapi-connectivity:connectivity-context`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct ConnectivityContext {
+    #[serde(default, alias = "tapi-connectivity:connectivity-service")]
+    pub connectivity_service: Vec<ConnectivityService>,
+    #[serde(default, alias = "tapi-connectivity:connection")]
+    pub connection: Vec<Connection>,
+    #[serde(flatten)]
+    pub extensions: Map<String, Value>,
+}
+
+/// Connection end points of a node edge point, `tapi-connectivity:cep-list`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct CepList {
+    #[serde(default)]
+    pub connection_end_point: Vec<ConnectionEndPoint>,
+}
+
+/// Termination of a connection on a node edge point.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct ConnectionEndPoint {
+    pub uuid: Uuid,
+    #[serde(default)]
+    pub name: Vec<NameAndValue>,
+    pub layer_protocol_name: Option<LayerProtocolName>,
+    pub layer_protocol_qualifier: Option<IdentityRef>,
+    #[serde(default)]
+    pub client_node_edge_point: Vec<NodeEdgePointRef>,
+    pub parent_node_edge_point: Option<NodeEdgePointRef>,
+    pub connection_port_direction: Option<PortDirection>,
+    pub connection_port_role: Option<PortRole>,
+    pub termination_direction: Option<TerminationDirection>,
+    pub operational_state: Option<OperationalState>,
+    pub lifecycle_state: Option<LifecycleState>,
+    #[serde(
+        rename = "tapi-odu:odu-connection-end-point-spec",
+        alias = "odu-connection-end-point-spec",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub odu_connection_end_point_spec: Option<OduConnectionEndPointSpec>,
+    #[serde(
+        rename = "tapi-photonic-media:media-channel-connection-end-point-spec",
+        alias = "media-channel-connection-end-point-spec",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub media_channel_connection_end_point_spec: Option<MediaChannelConnectionEndPointSpec>,
+    #[serde(
+        rename = "tapi-photonic-media:otsi-connection-end-point-spec",
+        alias = "otsi-connection-end-point-spec",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub otsi_connection_end_point_spec: Option<OtsiConnectionEndPointSpec>,
+    #[serde(flatten)]
+    pub extensions: Map<String, Value>,
+}
+
+/// Connection between connection end points, made of lower connections.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Connection {
+    pub uuid: Uuid,
+    #[serde(default)]
+    pub name: Vec<NameAndValue>,
+    pub layer_protocol_name: Option<LayerProtocolName>,
+    #[serde(default)]
+    pub connection_end_point: Vec<ConnectionEndPointRef>,
+    #[serde(default)]
+    pub lower_connection: Vec<ConnectionRef>,
+    #[serde(default)]
+    pub supported_client_link: Vec<LinkRef>,
+    #[serde(default)]
+    pub route: Vec<Route>,
+    pub direction: Option<ForwardingDirection>,
+    pub operational_state: Option<OperationalState>,
+    pub lifecycle_state: Option<LifecycleState>,
+    #[serde(flatten)]
+    pub extensions: Map<String, Value>,
+}
+
+/// Route of a connection through its connection end points.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Route {
+    pub local_id: Option<String>,
+    #[serde(default)]
+    pub name: Vec<NameAndValue>,
+    #[serde(default)]
+    pub connection_end_point: Vec<ConnectionEndPointRef>,
+}
+
+/// Service requested between service interface points.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct ConnectivityService {
+    pub uuid: Uuid,
+    #[serde(default)]
+    pub name: Vec<NameAndValue>,
+    #[serde(default)]
+    pub end_point: Vec<ConnectivityServiceEndPoint>,
+    #[serde(default)]
+    pub connection: Vec<ConnectionRef>,
+    pub service_layer: Option<LayerProtocolName>,
+    pub connectivity_direction: Option<ForwardingDirection>,
+    pub administrative_state: Option<AdministrativeState>,
+    pub operational_state: Option<OperationalState>,
+    pub lifecycle_state: Option<LifecycleState>,
+    #[serde(flatten)]
+    pub extensions: Map<String, Value>,
+}
+
+/// End point of a connectivity service.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct ConnectivityServiceEndPoint {
+    pub local_id: Option<String>,
+    #[serde(default)]
+    pub name: Vec<NameAndValue>,
+    pub layer_protocol_name: Option<LayerProtocolName>,
+    pub layer_protocol_qualifier: Option<IdentityRef>,
+    pub service_interface_point: Option<ServiceInterfacePointRef>,
+    #[serde(default)]
+    pub connection_end_point: Vec<ConnectionEndPointRef>,
+    pub direction: Option<PortDirection>,
+    pub role: Option<PortRole>,
+    pub administrative_state: Option<AdministrativeState>,
+    pub operational_state: Option<OperationalState>,
+    pub lifecycle_state: Option<LifecycleState>,
+    #[serde(flatten)]
+    pub extensions: Map<String, Value>,
+}
+
+/// Reference to a connection end point.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct ConnectionEndPointRef {
+    pub topology_uuid: Option<Uuid>,
+    pub node_uuid: Uuid,
+    pub node_edge_point_uuid: Uuid,
+    pub connection_end_point_uuid: Uuid,
+}
+
+/// Reference to a connection.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct ConnectionRef {
+    pub connection_uuid: Uuid,
+}
+
+/// Reference to a link.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct LinkRef {
+    pub topology_uuid: Option<Uuid>,
+    pub link_uuid: Uuid,
+}
@@ -0,0 +1,69 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use uuid::Uuid;
+
+use crate::common::NameAndValue;
+
+/// Equipment of the controller, `tapi-equipment:physical-context`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct PhysicalContext {
+    #[serde(default)]
+    pub device: Vec<Device>,
+    #[serde(flatten)]
+    pub extensions: Map<String, Value>,
+}
+
+/// Network element with its equipment and access ports.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Device {
+    pub uuid: Uuid,
+    #[serde(default)]
+    pub name: Vec<NameAndValue>,
+    #[serde(default)]
+    pub equipment: Vec<Equipment>,
+    #[serde(default)]
+    pub access_port: Vec<AccessPort>,
+    #[serde(flatten)]
+    pub extensions: Map<String, Value>,
+}
+
+/// Card, shelf or pluggable of a device.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Equipment {
+    pub uuid: Uuid,
+    #[serde(default)]
+    pub name: Vec<NameAndValue>,
+    pub category: Option<String>,
+    pub equipment_location: Option<String>,
+    #[serde(flatten)]
+    pub extensions: Map<String, Value>,
+}
+
+/// Port of a device where node edge points are supported.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct AccessPort {
+    pub uuid: Uuid,
+    #[serde(default)]
+    pub name: Vec<NameAndValue>,
+    #[serde(flatten)]
+    pub extensions: Map<String, Value>,
+}
+
+/// Equipment augmentation of a node edge point, `tapi-equipment:supporting-access-port`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct SupportingAccessPort {
+    pub access_port: AccessPortRef,
+}
+
+/// Reference to an access port of a device.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct AccessPortRef {
+    pub device_uuid: Uuid,
+    pub access_port_uuid: Uuid,
+}
@@ -0,0 +1,17 @@
+//! Serde model of the TAPI 2.x context.
+//!
+//! Covers the objects read from TAPI controllers: `tapi-common`, `tapi-topology`, `tapi-connectivity`,
+//! `tapi-photonic-media`, `tapi-odu` and `tapi-equipment`.
+//!
+//! Augmentations are read with their module-qualified key (e.g. `tapi-connectivity:cep-list`) or the
+//! bare one, since controllers use both. Fields outside the model, such as vendor augmentations, are
+//! kept in the `extensions` of each object.
+
+pub mod common;
+pub mod connectivity;
+pub mod equipment;
+pub mod odu;
+pub mod photonic_media;
+pub mod topology;
+
+pub use uuid::Uuid;
@@ -0,0 +1,45 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::common::{optional_integer, IdentityRef};
+
+/// ODU augmentation of a node edge point, `tapi-odu:odu-node-edge-point-spec`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct OduNodeEdgePointSpec {
+    pub odu_pool: Option<OduPool>,
+    #[serde(flatten)]
+    pub extensions: Map<String, Value>,
+}
+
+/// ODU clients a node edge point can hold.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct OduPool {
+    #[serde(default, deserialize_with = "optional_integer")]
+    pub client_capacity: Option<u64>,
+    #[serde(default, deserialize_with = "optional_integer")]
+    pub max_client_instances: Option<u64>,
+    #[serde(default, deserialize_with = "optional_integer")]
+    pub max_client_size: Option<u64>,
+}
+
+/// ODU augmentation of a connection end point, `tapi-odu:odu-connection-end-point-spec`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct OduConnectionEndPointSpec {
+    pub odu_common: Option<OduCommon>,
+    #[serde(flatten)]
+    pub extensions: Map<String, Value>,
+}
+
+/// Type and rate of an ODU.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct OduCommon {
+    pub odu_type: Option<IdentityRef>,
+    #[serde(default, deserialize_with = "optional_integer")]
+    pub odu_rate: Option<u64>,
+    #[serde(flatten)]
+    pub extensions: Map<String, Value>,
+}
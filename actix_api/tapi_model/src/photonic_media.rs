@@ -0,0 +1,62 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::common::integer;
+
+/// Media channel augmentation of a node edge point, `tapi-photonic-media:media-channel-node-edge-point-spec`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct MediaChannelNodeEdgePointSpec {
+    pub mc_pool: Option<McPool>,
+    #[serde(flatten)]
+    pub extensions: Map<String, Value>,
+}
+
+/// Media channel augmentation of a service interface point, with the same pool as a node edge point.
+pub type MediaChannelServiceInterfacePointSpec = MediaChannelNodeEdgePointSpec;
+
+/// Spectrum of a media channel pool.
+///
+/// A missing list is kept as `None`, since it is not the same as a list without bands.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct McPool {
+    pub supportable_spectrum: Option<Vec<SpectrumBand>>,
+    pub available_spectrum: Option<Vec<SpectrumBand>>,
+    pub occupied_spectrum: Option<Vec<SpectrumBand>>,
+}
+
+/// Band of spectrum, with its frequencies in MHz.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct SpectrumBand {
+    #[serde(deserialize_with = "integer")]
+    pub upper_frequency: i64,
+    #[serde(deserialize_with = "integer")]
+    pub lower_frequency: i64,
+    pub frequency_constraint: Option<FrequencyConstraint>,
+}
+
+/// Grid of a band of spectrum.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct FrequencyConstraint {
+    pub adjustment_granularity: Option<String>,
+    pub grid_type: Option<String>,
+}
+
+/// Media channel augmentation of a connection end point, `tapi-photonic-media:media-channel-connection-end-point-spec`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct MediaChannelConnectionEndPointSpec {
+    #[serde(flatten)]
+    pub extensions: Map<String, Value>,
+}
+
+/// OTSi augmentation of a connection end point, `tapi-photonic-media:otsi-connection-end-point-spec`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct OtsiConnectionEndPointSpec {
+    #[serde(flatten)]
+    pub extensions: Map<String, Value>,
+}
@@ -0,0 +1,144 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use uuid::Uuid;
+
+use crate::common::{
+    AdministrativeState, Capacity, ForwardingDirection, IdentityRef, LayerProtocolName,
+    LifecycleState, NameAndValue, OperationalState, PortDirection, PortRole,
+    ServiceInterfacePointRef, TerminationDirection,
+};
+use crate::connectivity::CepList;
+use crate::equipment::SupportingAccessPort;
+use crate::odu::OduNodeEdgePointSpec;
+use crate::photonic_media::MediaChannelNodeEdgePointSpec;
+
+/// Topologies of the controller, `tapi-topology:topology-context`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct TopologyContext {
+    #[serde(default, alias = "tapi-topology:topology")]
+    pub topology: Vec<Topology>,
+    #[serde(flatten)]
+    pub extensions: Map<String, Value>,
+}
+
+/// Topology with its nodes and links.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Topology {
+    pub uuid: Uuid,
+    #[serde(default)]
+    pub name: Vec<NameAndValue>,
+    #[serde(default)]
+    pub layer_protocol_name: Vec<LayerProtocolName>,
+    #[serde(default, alias = "tapi-topology:node")]
+    pub node: Vec<Node>,
+    #[serde(default, alias = "tapi-topology:link")]
+    pub link: Vec<Link>,
+    #[serde(flatten)]
+    pub extensions: Map<String, Value>,
+}
+
+/// Node of a topology.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Node {
+    pub uuid: Uuid,
+    #[serde(default)]
+    pub name: Vec<NameAndValue>,
+    #[serde(default)]
+    pub layer_protocol_name: Vec<LayerProtocolName>,
+    #[serde(default)]
+    pub owned_node_edge_point: Vec<OwnedNodeEdgePoint>,
+    pub encap_topology: Option<TopologyRef>,
+    pub administrative_state: Option<AdministrativeState>,
+    pub operational_state: Option<OperationalState>,
+    pub lifecycle_state: Option<LifecycleState>,
+    #[serde(flatten)]
+    pub extensions: Map<String, Value>,
+}
+
+/// Edge point of a node, where links and connections end.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct OwnedNodeEdgePoint {
+    pub uuid: Uuid,
+    #[serde(default)]
+    pub name: Vec<NameAndValue>,
+    pub layer_protocol_name: Option<LayerProtocolName>,
+    #[serde(default)]
+    pub supported_cep_layer_protocol_qualifier: Vec<IdentityRef>,
+    #[serde(default)]
+    pub mapped_service_interface_point: Vec<ServiceInterfacePointRef>,
+    pub link_port_direction: Option<PortDirection>,
+    pub link_port_role: Option<PortRole>,
+    pub termination_direction: Option<TerminationDirection>,
+    pub administrative_state: Option<AdministrativeState>,
+    pub operational_state: Option<OperationalState>,
+    pub lifecycle_state: Option<LifecycleState>,
+    pub available_capacity: Option<Capacity>,
+    pub total_potential_capacity: Option<Capacity>,
+    #[serde(
+        rename = "tapi-connectivity:cep-list",
+        alias = "cep-list",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub cep_list: Option<CepList>,
+    #[serde(
+        rename = "tapi-photonic-media:media-channel-node-edge-point-spec",
+        alias = "media-channel-node-edge-point-spec",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub media_channel_node_edge_point_spec: Option<MediaChannelNodeEdgePointSpec>,
+    #[serde(
+        rename = "tapi-odu:odu-node-edge-point-spec",
+        alias = "odu-node-edge-point-spec",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub odu_node_edge_point_spec: Option<OduNodeEdgePointSpec>,
+    #[serde(
+        rename = "tapi-equipment:supporting-access-port",
+        alias = "supporting-access-port",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub supporting_access_port: Option<SupportingAccessPort>,
+    #[serde(flatten)]
+    pub extensions: Map<String, Value>,
+}
+
+/// Link between node edge points.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Link {
+    pub uuid: Uuid,
+    #[serde(default)]
+    pub name: Vec<NameAndValue>,
+    #[serde(default)]
+    pub layer_protocol_name: Vec<LayerProtocolName>,
+    #[serde(default)]
+    pub node_edge_point: Vec<NodeEdgePointRef>,
+    pub direction: Option<ForwardingDirection>,
+    pub administrative_state: Option<AdministrativeState>,
+    pub operational_state: Option<OperationalState>,
+    pub lifecycle_state: Option<LifecycleState>,
+    pub available_capacity: Option<Capacity>,
+    pub total_potential_capacity: Option<Capacity>,
+    #[serde(flatten)]
+    pub extensions: Map<String, Value>,
+}
+
+/// Reference to a topology.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct TopologyRef {
+    pub topology_uuid: Uuid,
+}
+
+/// Reference to a node edge point.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct NodeEdgePointRef {
+    pub topology_uuid: Option<Uuid>,
+    pub node_uuid: Uuid,
+    pub node_edge_point_uuid: Uuid,
+}
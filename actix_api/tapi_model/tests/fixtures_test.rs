@@ -0,0 +1,125 @@
+#[cfg(test)]
+mod tests {
+    // Parse the fixtures of the API with the TAPI model
+    use tapi_model::common::{find_name, Context, LayerProtocolName, OperationalState};
+    use tapi_model::connectivity::{Connection, ConnectivityService};
+    use tapi_model::topology::Topology;
+    use tapi_model::Uuid;
+
+    use serde::de::DeserializeOwned;
+    use serde_json::{json, Value};
+    use std::fs;
+
+    fn read_list<T: DeserializeOwned>(path: &str) -> Vec<T> {
+        let contents = fs::read_to_string(path).unwrap();
+        let value: Value = serde_json::from_str(contents.trim_start_matches('\u{feff}')).unwrap();
+        let list = match value {
+            Value::Object(object) if object.len() == 1 => object.into_iter().next().unwrap().1,
+            value => value,
+        };
+        serde_json::from_value(list).unwrap()
+    }
+
+    #[test]
+    fn test_parse_topology() {
+        let topologies: Vec<Topology> = read_list("../devices_files/topology.json");
+        let topology = &topologies[0];
+
+        assert_eq!(topology.node.len(), 38);
+        assert_eq!(topology.link.len(), 79);
+        assert!(topology
+            .layer_protocol_name
+            .contains(&LayerProtocolName::PhotonicMedia));
+
+        let owned_node_edge_points = topology
+            .node
+            .iter()
+            .flat_map(|node| node.owned_node_edge_point.iter());
+        let mut cep_count = 0;
+        let mut mc_pool_count = 0;
+        for owned_node_edge_point in owned_node_edge_points {
+            if let Some(cep_list) = &owned_node_edge_point.cep_list {
+                cep_count += cep_list.connection_end_point.len();
+            }
+            if let Some(spec) = &owned_node_edge_point.media_channel_node_edge_point_spec {
+                mc_pool_count += spec.mc_pool.is_some() as usize;
+            }
+        }
+        assert!(cep_count > 0);
+        assert!(mc_pool_count > 0);
+    }
+
+    #[test]
+    fn test_parse_connectivity() {
+        let services: Vec<ConnectivityService> =
+            read_list("../devices_files/connectivity_services.json");
+        assert_eq!(services.len(), 7);
+        let service = services
+            .iter()
+            .find(|service| {
+                service.uuid == Uuid::parse_str("00000000-0000-0000-0000-000000000009").unwrap()
+            })
+            .unwrap();
+        assert!(!service.end_point.is_empty());
+
+        let connections: Vec<Connection> = read_list("../sienna_files/connections.json");
+        assert!(connections.iter().any(|connection| connection
+            .extensions
+            .contains_key("tapi-ciena-connection-extensions:service-class")));
+        assert!(connections
+            .iter()
+            .all(|connection| connection.operational_state.is_some()));
+    }
+
+    #[test]
+    fn test_namespaces_and_errors() {
+        let context: Context = serde_json::from_value(json!({
+            "tapi-topology:topology-context": {
+                "topology": [{
+                    "uuid": "0b57c5fc-4fff-3e28-9f94-b764c5da38a3",
+                    "node": [{
+                        "uuid": "00000000-0000-0000-0000-000000000001",
+                        "name": [{"value-name": "NODE_IDENTIFIER", "value": 7}],
+                        "operational-state": "ENABLED",
+                        "owned-node-edge-point": [{
+                            "uuid": "00000000-0000-0000-0000-000000000002",
+                            "cep-list": {"connection-end-point": []},
+                            "tapi-photonic-media:media-channel-node-edge-point-spec": {
+                                "mc-pool": {"occupied-spectrum": [
+                                    {"upper-frequency": "191350000", "lower-frequency": 191300000}
+                                ]}
+                            },
+                            "acme:port-speed": "100G"
+                        }]
+                    }]
+                }]
+            }
+        }))
+        .unwrap();
+
+        let node = &context.topology_context.unwrap().topology[0].node[0];
+        assert_eq!(find_name(&node.name, "NODE_IDENTIFIER"), Some("7"));
+        assert_eq!(node.operational_state, Some(OperationalState::Enabled));
+        let owned_node_edge_point = &node.owned_node_edge_point[0];
+        assert!(owned_node_edge_point.cep_list.is_some());
+        let mc_pool = owned_node_edge_point
+            .media_channel_node_edge_point_spec
+            .as_ref()
+            .and_then(|spec| spec.mc_pool.as_ref())
+            .unwrap();
+        assert_eq!(
+            mc_pool.occupied_spectrum.as_ref().unwrap()[0].upper_frequency,
+            191350000
+        );
+        assert!(mc_pool.available_spectrum.is_none());
+        assert_eq!(
+            owned_node_edge_point.extensions["acme:port-speed"],
+            json!("100G")
+        );
+
+        let wrong_uuid = serde_json::from_value::<Topology>(json!({"uuid": "node-1"}));
+        assert!(wrong_uuid.is_err());
+        let missing_uuid = serde_json::from_value::<Topology>(json!({"node": []}));
+        assert!(missing_uuid.is_err());
+    }
+}
@@ -0,0 +1,33 @@
+// Mock devices shared by the integration tests, each test binary uses only some of them
+#![allow(dead_code)]
+
+use actix_api::mock::{MockDevice, MockDeviceConfig};
+use actix_api::models::devices::{Auth, BasicAuth};
+
+/// Credentials accepted by the mock devices serving the fixtures.
+pub fn basic_auth() -> Auth {
+    Auth::Basic(BasicAuth {
+        username: "tapi".to_string(),
+        password: "2025_T3st".to_string(),
+    })
+}
+
+/// Configuration of a mock device serving the `devices_files` fixtures with basic auth.
+pub fn mock_config() -> MockDeviceConfig {
+    MockDeviceConfig::from_fixtures("devices_files", basic_auth()).unwrap()
+}
+
+/// Starts a mock device serving the `devices_files` fixtures with basic auth.
+pub async fn start_mock_device() -> MockDevice {
+    start_mock_device_with(|_| ()).await
+}
+
+/// Starts a mock device serving the `devices_files` fixtures, with the settings changed by
+/// `configure`.
+pub async fn start_mock_device_with(configure: impl FnOnce(&mut MockDeviceConfig)) -> MockDevice {
+    let mut config = mock_config();
+    configure(&mut config);
+    MockDevice::start(config, "127.0.0.1:0")
+        .await
+        .expect("Mock device cannot be started")
+}
@@ -0,0 +1,161 @@
+#[cfg(test)]
+mod tests {
+    // Index uploaded files and read single services from them
+    use actix_api::handlers::requester::{DataSource, Requester};
+    use actix_api::logic::context_index::{read_span, ContextIndex, IndexKind};
+    use actix_api::models::files_model::{ByPart, Complete, FilesEnum};
+
+    use serde_json::{json, Value};
+    use std::fs;
+    use std::path::{Path, PathBuf};
+
+    const SERVICE_UUID: &str = "00000000-0000-0000-0000-000000000009";
+
+    /// Creates an empty directory under the system temp dir for a single test.
+    fn test_data_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("tapi_index_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).expect("Test data dir cannot be created");
+        dir
+    }
+
+    fn fixture(name: &str) -> Value {
+        serde_json::from_str(&fs::read_to_string(format!("devices_files/{}", name)).unwrap())
+            .unwrap()
+    }
+
+    /// Writes the `devices_files` fixtures as a single complete context file.
+    fn complete_context(dir: &Path) -> String {
+        let context = json!({
+            "tapi-common:context": {
+                "uuid": "ea7b6fbd-6a2d-4d53-90d2-2bcd5c4e5ad1",
+                "tapi-topology:topology-context": {"topology": fixture("topology.json")},
+                "tapi-connectivity:connectivity-context": {
+                    "connectivity-service": fixture("connectivity_services.json"),
+                    "connection": fixture("connections.json")
+                }
+            }
+        });
+        let path = dir.join("context.json");
+        fs::write(&path, serde_json::to_string_pretty(&context).unwrap()).unwrap();
+        path.to_string_lossy().to_string()
+    }
+
+    #[test]
+    fn test_index_complete_context() {
+        let dir = test_data_dir("complete");
+        let path = complete_context(&dir);
+
+        let index = ContextIndex::load(&path, IndexKind::Complete).unwrap();
+        assert!(PathBuf::from(ContextIndex::index_path(&path)).exists());
+        assert_eq!(index.services().unwrap().len(), 7);
+        assert!(index.connections.is_some());
+        assert!(index.topology.is_some());
+
+        let service: Value = read_span(&path, index.service(SERVICE_UUID).unwrap()).unwrap();
+        let expected = fixture("connectivity_services.json")
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|service| service["uuid"] == SERVICE_UUID)
+            .cloned()
+            .unwrap();
+        assert_eq!(service, expected);
+        let topology: Value = read_span(&path, index.topology.unwrap()).unwrap();
+        assert_eq!(topology, fixture("topology.json"));
+
+        // The stored index is reused until the file changes
+        assert_eq!(
+            ContextIndex::load(&path, IndexKind::Complete).unwrap(),
+            index
+        );
+        fs::write(&path, r#"{"tapi-common:context": {"uuid": "x"}}"#).unwrap();
+        let index = ContextIndex::load(&path, IndexKind::Complete).unwrap();
+        assert!(index.services().is_err());
+        assert!(index.topology.is_none());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_index_rejects_invalid_json() {
+        let dir = test_data_dir("invalid");
+        let path = dir.join("services.json").to_string_lossy().to_string();
+
+        for invalid in [
+            r#"[{"uuid": "a"}"#,
+            r#"[{"uuid": "a"}] ]"#,
+            r#"[{"uuid" "a"}]"#,
+        ] {
+            fs::write(&path, invalid).unwrap();
+            assert!(ContextIndex::build(&path, IndexKind::Services).is_err());
+        }
+        fs::write(
+            &path,
+            r#" [ {"name": [{"value": "a\"}]"}], "uuid": "b"}, {} ] "#,
+        )
+        .unwrap();
+        let index = ContextIndex::build(&path, IndexKind::Services).unwrap();
+        let services = index.services().unwrap();
+        assert_eq!(services[0].uuid.as_deref(), Some("b"));
+        assert_eq!(services[1].uuid, None);
+        let empty: Value = read_span(&path, services[1].span).unwrap();
+        assert_eq!(empty, json!({}));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_complete_and_by_part_contexts_match() {
+        let dir = test_data_dir("requester");
+        let complete = DataSource::FilesEnum(FilesEnum::Complete(Complete {
+            id: "complete".to_string(),
+            complete_context_path: complete_context(&dir),
+        }));
+        let by_part_services = dir.join("connectivity_services.json");
+        fs::copy(
+            "devices_files/connectivity_services.json",
+            &by_part_services,
+        )
+        .unwrap();
+        let by_part = DataSource::FilesEnum(FilesEnum::ByPart(ByPart {
+            id: "by_part".to_string(),
+            topology_path: "devices_files/topology.json".to_string(),
+            connections_path: "devices_files/connections.json".to_string(),
+            connectivity_services_path: by_part_services.to_string_lossy().to_string(),
+        }));
+
+        assert_eq!(
+            Requester::get_services(&complete).await.unwrap().0,
+            Requester::get_services(&by_part).await.unwrap().0
+        );
+        let complete_context = Requester::get_service_context(&complete, SERVICE_UUID)
+            .await
+            .unwrap();
+        let by_part_context = Requester::get_service_context(&by_part, SERVICE_UUID)
+            .await
+            .unwrap();
+        assert_eq!(
+            complete_context.connectivity_service,
+            by_part_context.connectivity_service
+        );
+        assert_eq!(complete_context.connections, by_part_context.connections);
+        assert_eq!(complete_context.topology, by_part_context.topology);
+        assert!(Requester::get_service_context(&complete, "missing")
+            .await
+            .is_err());
+
+        let full_context = Requester::get_full_context(&complete).await.unwrap();
+        assert_eq!(full_context.connectivity_services.len(), 7);
+
+        FilesEnum::Complete(Complete {
+            id: "complete".to_string(),
+            complete_context_path: dir.join("context.json").to_string_lossy().to_string(),
+        })
+        .remove_files()
+        .unwrap();
+        assert!(!dir.join("context.json.index").exists());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -1,7 +1,7 @@
 #[cfg(test)]
 mod tests {
     // Import the necessary structs and enums from your backend models
-    use actix_api::models::devices::{Auth, Device};
+    use actix_api::models::devices::{Auth, Device, GrantType, REDACTED};
 
     // Import necessary modules from serde_json for JSON handling
     use serde_json::{from_str, Value};
@@ -31,11 +31,10 @@ mod tests {
         let device: Device = serde_json::from_value(json_value).expect("Device cannot be created");
 
         // Match the type of authentication used in the device
-        match device.auth {
-            // Check if it uses Basic Authentication
-            Auth::Basic(_) => assert!(true), // Pass if it's BasicAuth
-            _ => panic!("There isn't Basic Authentication here"), // Fail if it's not BasicAuth
-        }
+        assert!(
+            matches!(device.auth, Auth::Basic(_)),
+            "There isn't Basic Authentication here"
+        );
     }
 
     /// Test case for creating a `Device` with Token Authentication
@@ -65,10 +64,82 @@ mod tests {
         let device: Device = serde_json::from_value(json_value).expect("Device cannot be created");
 
         // Match the type of authentication used in the device
-        match device.auth {
-            // Check if it uses Token Authentication
-            Auth::Token(_) => assert!(true), // Pass if it's TokenAuth
-            _ => panic!("There isn't Token Authentication here"), // Fail if it's not TokenAuth
+        assert!(
+            matches!(device.auth, Auth::Token(_)),
+            "There isn't Token Authentication here"
+        );
+    }
+
+    /// Test case for creating a `Device` with OAuth2 Authentication, also from the legacy
+    /// `auth_sufix` field the frontend used to send
+    #[test]
+    fn test_oauth2_device() {
+        let json_data = r#"
+        {
+            "ip": "10.95.86.185",
+            "auth": {
+                "grant_type": "password",
+                "auth_sufix": "/oauth2/token",
+                "username": "admin",
+                "password": "Telef@12!"
+            }
+        }
+        "#;
+        let device: Device = from_str(json_data).expect("Device cannot be created");
+
+        let Auth::OAuth2(oauth2_auth) = &device.auth else {
+            panic!("There isn't OAuth2 Authentication here");
+        };
+        assert_eq!(oauth2_auth.grant_type, GrantType::Password);
+        assert_eq!(oauth2_auth.token_uri, "/oauth2/token");
+        assert_eq!(oauth2_auth.client_id, None);
+        assert_eq!(
+            device.get_full_auth_url(),
+            "https://10.95.86.185/oauth2/token"
+        );
+
+        // Serialized with the current field names only
+        let serialized = serde_json::to_value(&device.auth).unwrap();
+        assert_eq!(serialized["token_uri"], "/oauth2/token");
+        assert!(serialized.get("auth_sufix").is_none());
+        assert!(serialized.get("client_id").is_none());
+    }
+
+    /// Credentials must never show up when a `Device` is printed or logged
+    #[test]
+    fn test_credentials_are_redacted() {
+        let json_data = r#"
+        {
+            "ip": "10.95.86.185",
+            "auth": {
+                "auth_body": {
+                    "username": "admin",
+                    "password": "Telef@12!"
+                },
+                "auth_uri": "/tron/api/v1/tokens"
+            }
+        }
+        "#;
+        let token_device: Device = from_str(json_data).expect("Device cannot be created");
+        let basic_device: Device = from_str(
+            r#"{"ip": "10.95.87.21", "auth": {"username": "tapi", "password": "2025_T3st"}}"#,
+        )
+        .expect("Device cannot be created");
+
+        let oauth2_device: Device = from_str(
+            r#"{"ip": "10.95.87.21", "auth": {"grant_type": "client_credentials",
+                "token_uri": "/oauth2/token", "client_id": "tapi", "client_secret": "s3cr3t"}}"#,
+        )
+        .expect("Device cannot be created");
+
+        for (device, password) in [
+            (token_device, "Telef@12!"),
+            (basic_device, "2025_T3st"),
+            (oauth2_device, "s3cr3t"),
+        ] {
+            assert!(!format!("{}", device).contains(password));
+            assert!(!format!("{:?}", device).contains(password));
+            assert!(format!("{:?}", device).contains(REDACTED));
         }
     }
 }
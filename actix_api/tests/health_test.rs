@@ -0,0 +1,127 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    // Check the health of mock devices step by step and expose the stored checks
+    use actix_api::handlers::health::HealthMonitor;
+    use actix_api::handlers::repository::DataSourceRepository;
+    use actix_api::handlers::requester::DataSource;
+    use actix_api::models::device_health::{DeviceHealth, HealthStatus};
+    use actix_api::models::devices::{Auth, BasicAuth, Scheme, TlsConfig};
+    use actix_api::routes::get_health::get_health;
+
+    use crate::common::start_mock_device;
+
+    use actix_web::{test, web, App};
+    use std::time::Duration;
+
+    /// A reachable device answering the context request is healthy, with or without TLS
+    #[tokio::test]
+    async fn test_healthy_device() {
+        let mock_device = start_mock_device().await;
+
+        let health = HealthMonitor::check(&mock_device.device()).await;
+        assert_eq!(health.status, HealthStatus::Healthy, "{:?}", health.error);
+        assert!(health.tcp_connect);
+        assert_eq!(health.tls_handshake, Some(true));
+        assert_eq!(health.authenticated, Some(true));
+        assert_eq!(health.restconf, Some(true));
+        assert!(health.latency_ms.is_some());
+        assert_eq!(health.error, None);
+
+        let mut device = mock_device.device();
+        device.tls = TlsConfig {
+            scheme: Scheme::Http,
+            ..TlsConfig::default()
+        };
+        let health = HealthMonitor::check(&device).await;
+        assert_eq!(health.status, HealthStatus::Healthy, "{:?}", health.error);
+        assert_eq!(health.tls_handshake, None);
+
+        mock_device.stop().await;
+    }
+
+    /// The check stops at the first failing step: connection, handshake or credentials
+    #[tokio::test]
+    async fn test_failing_steps() {
+        let mock_device = start_mock_device().await;
+
+        let mut device = mock_device.device();
+        device.auth = Auth::Basic(BasicAuth {
+            username: "tapi".to_string(),
+            password: "wrong".to_string(),
+        });
+        let health = HealthMonitor::check(&device).await;
+        assert_eq!(health.status, HealthStatus::Degraded);
+        assert_eq!(health.tls_handshake, Some(true));
+        assert_eq!(health.authenticated, Some(false));
+        assert_eq!(health.restconf, None);
+
+        let mut device = mock_device.device();
+        device.tls = TlsConfig::default();
+        let health = HealthMonitor::check(&device).await;
+        assert_eq!(health.status, HealthStatus::Unreachable);
+        assert!(health.tcp_connect);
+        assert_eq!(health.tls_handshake, Some(false));
+        assert!(health.error.unwrap().starts_with("TLS handshake failed"));
+
+        let device = mock_device.device();
+        mock_device.stop().await;
+        let health = HealthMonitor::check(&device).await;
+        assert_eq!(health.status, HealthStatus::Unreachable);
+        assert!(!health.tcp_connect);
+        assert_eq!(health.tls_handshake, None);
+    }
+
+    /// Every check of the monitor is stored and listed by `/hosts/{id}/health`, the most recent first
+    #[actix_web::test]
+    async fn test_health_route() {
+        let mock_device = start_mock_device().await;
+        let device = mock_device.device();
+
+        let repository = DataSourceRepository::in_memory();
+        repository
+            .insert(DataSource::Device(device.clone()))
+            .await
+            .unwrap();
+        let monitor = HealthMonitor::new(repository.clone(), Duration::from_secs(60));
+        let results = monitor.check_all().await.unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].0, device.ip);
+
+        mock_device.stop().await;
+        monitor.check_all().await.unwrap();
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(repository.clone()))
+                .service(get_health),
+        )
+        .await;
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/hosts/{}/health", device.ip))
+            .to_request();
+        let health: Vec<DeviceHealth> = test::call_and_read_body_json(&app, req).await;
+        let statuses: Vec<HealthStatus> = health.iter().map(|health| health.status).collect();
+        assert_eq!(
+            statuses,
+            vec![HealthStatus::Unreachable, HealthStatus::Healthy]
+        );
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/hosts/{}/health?limit=1", device.ip))
+            .to_request();
+        let health: Vec<DeviceHealth> = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(health.len(), 1);
+
+        let req = test::TestRequest::get()
+            .uri("/hosts/10.0.0.1/health")
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 404);
+
+        // Deleting the device drops its checks
+        repository.remove(&device.ip).await.unwrap();
+        assert!(repository.health(&device.ip, 100).await.unwrap().is_empty());
+    }
+}
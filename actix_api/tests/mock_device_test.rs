@@ -0,0 +1,350 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    // Import the mock device and the handlers that talk to devices
+    use actix_api::handlers::http::HttpHandler;
+    use actix_api::handlers::requester::{DataSource, DeviceHandler, Requester};
+    use actix_api::handlers::snapshot::TopologySnapshot;
+    use actix_api::handlers::vendor::VendorProfiles;
+    use actix_api::logic::connection_builder::connection_vector_build;
+    use actix_api::mock::{MockDevice, MockDeviceConfig, MockFormat};
+    use actix_api::models::devices::{Auth, BasicAuth, TokenAuth};
+    use actix_api::models::vendor_profile::VendorProfile;
+    use actix_api::utils::parse_tapi;
+    use tapi_model::connectivity::Connection as TapiConnection;
+
+    use crate::common::mock_config;
+
+    use serde_json::{json, Value};
+    use std::collections::HashSet;
+
+    const DEVICES_SERVICE_UUID: &str = "00000000-0000-0000-0000-000000000009";
+    const DEVICES_TOPOLOGY_UUID: &str = "0b57c5fc-4fff-3e28-9f94-b764c5da38a3";
+    const SECOND_TOPOLOGY_UUID: &str = "00000000-0000-0000-0000-00000000000a";
+
+    fn token_auth() -> Auth {
+        Auth::Token(TokenAuth {
+            auth_body: json!({"username": "admin", "password": "Telef@12!"}),
+            auth_uri: "/tron/api/v1/tokens".to_string(),
+        })
+    }
+
+    /// Moves half of the nodes and links to a second topology, keeping the first node in both, and
+    /// lists both topologies in the `nw-topology-service`
+    fn split_topology(config: &mut MockDeviceConfig) {
+        let topology_context = config
+            .datastore
+            .pointer_mut("/tapi-common:context/tapi-topology:topology-context")
+            .unwrap();
+        let topology = &mut topology_context["topology"][0];
+        let nodes = topology["node"].as_array_mut().unwrap();
+        let mut second_nodes = nodes.split_off(nodes.len() / 2);
+        second_nodes.push(nodes[0].clone());
+        let links = topology["link"].as_array_mut().unwrap();
+        let second_links = links.split_off(links.len() / 2);
+
+        topology_context["topology"]
+            .as_array_mut()
+            .unwrap()
+            .push(json!({
+                "uuid": SECOND_TOPOLOGY_UUID,
+                "layer-protocol-name": ["PHOTONIC_MEDIA"],
+                "node": second_nodes,
+                "link": second_links
+            }));
+        topology_context["nw-topology-service"] = json!({
+            "uuid": "nw-topology-service",
+            "topology": [
+                {"topology-uuid": DEVICES_TOPOLOGY_UUID},
+                {"topology-uuid": SECOND_TOPOLOGY_UUID}
+            ]
+        });
+    }
+
+    async fn start(config: MockDeviceConfig) -> MockDevice {
+        MockDevice::start(config, "127.0.0.1:0")
+            .await
+            .expect("Mock device cannot be started")
+    }
+
+    /// Services are listed through `?fields=connectivity-service(uuid)` and per-UUID requests
+    #[tokio::test]
+    async fn test_get_services_with_basic_auth() {
+        let config = mock_config();
+        let mock_device = start(config).await;
+
+        let (services, _) = DeviceHandler::get_services(&mock_device.device())
+            .await
+            .expect("Services cannot be retrieved");
+
+        assert_eq!(services.len(), 7);
+        assert!(services
+            .iter()
+            .any(|service| service["uuid"] == DEVICES_SERVICE_UUID));
+        assert!(services[0].get("end-point").is_some());
+
+        mock_device.stop().await;
+    }
+
+    /// The token is requested and then used as bearer over HTTPS
+    #[tokio::test]
+    async fn test_get_services_with_token_auth() {
+        let config = MockDeviceConfig::from_fixtures("sienna_files", token_auth()).unwrap();
+        let mock_device = start(config).await;
+
+        let (services, _) = Requester::get_services(&DataSource::Device(mock_device.device()))
+            .await
+            .expect("Services cannot be retrieved");
+
+        assert_eq!(services.len(), 2);
+
+        mock_device.stop().await;
+    }
+
+    #[tokio::test]
+    async fn test_wrong_credentials_are_rejected() {
+        let config = mock_config();
+        let mock_device = start(config).await;
+        let mut device = mock_device.device();
+        device.auth = Auth::Basic(BasicAuth {
+            username: "tapi".to_string(),
+            password: "wrong".to_string(),
+        });
+
+        assert!(DeviceHandler::get_services(&device).await.is_err());
+
+        let config = MockDeviceConfig::from_fixtures("sienna_files", token_auth()).unwrap();
+        let token_device = start(config).await;
+        let mut device = token_device.device();
+        device.auth = Auth::Token(TokenAuth {
+            auth_body: json!({"username": "admin", "password": "wrong"}),
+            auth_uri: "/tron/api/v1/tokens".to_string(),
+        });
+
+        assert!(DeviceHandler::get_services(&device).await.is_err());
+
+        mock_device.stop().await;
+        token_device.stop().await;
+    }
+
+    /// Devices without `fields` support are read through the complete context
+    #[tokio::test]
+    async fn test_get_services_without_query_parameters() {
+        let mut config = mock_config();
+        config.supports_query_parameters = false;
+        let mock_device = start(config).await;
+
+        let (services, _) = DeviceHandler::get_services(&mock_device.device())
+            .await
+            .expect("Services cannot be retrieved");
+
+        assert_eq!(services.len(), 7);
+
+        mock_device.stop().await;
+    }
+
+    #[tokio::test]
+    async fn test_get_service_context() {
+        let config = mock_config();
+        let mock_device = start(config).await;
+
+        let context =
+            DeviceHandler::get_service_context(&mock_device.device(), DEVICES_SERVICE_UUID)
+                .await
+                .expect("Service context cannot be retrieved");
+
+        assert_eq!(context.connectivity_service["uuid"], DEVICES_SERVICE_UUID);
+        assert_eq!(context.connections.len(), 67);
+        assert_eq!(context.topology[0]["node"].as_array().unwrap().len(), 38);
+        assert_eq!(context.topology[0]["link"].as_array().unwrap().len(), 79);
+
+        mock_device.stop().await;
+    }
+
+    #[tokio::test]
+    async fn test_fields_and_depth() {
+        let config = mock_config();
+        let mock_device = start(config).await;
+        let topology_url = format!(
+            "https://127.0.0.1:{}/restconf/data/tapi-common:context/tapi-topology:topology-context/topology={}",
+            mock_device.port(),
+            DEVICES_TOPOLOGY_UUID
+        );
+
+        let fields = HttpHandler::basic_request(
+            &format!("{}?fields=uuid;node(uuid)", topology_url),
+            "tapi".to_string(),
+            Some("2025_T3st".to_string()),
+            &mock_device.device().tls,
+        )
+        .await
+        .unwrap();
+        let topology = &fields["tapi-topology:topology"][0];
+        assert_eq!(topology["uuid"], DEVICES_TOPOLOGY_UUID);
+        assert!(topology.get("link").is_none());
+        assert_eq!(topology["node"][0].as_object().unwrap().len(), 1);
+
+        let depth = HttpHandler::basic_request(
+            &format!("{}?depth=2", topology_url),
+            "tapi".to_string(),
+            Some("2025_T3st".to_string()),
+            &mock_device.device().tls,
+        )
+        .await
+        .unwrap();
+        let topology = &depth["tapi-topology:topology"][0];
+        assert_eq!(topology["uuid"], DEVICES_TOPOLOGY_UUID);
+        assert_eq!(topology["node"][0], json!({}));
+
+        let missing = HttpHandler::basic_request(
+            &format!("{}/node=missing", topology_url),
+            "tapi".to_string(),
+            Some("2025_T3st".to_string()),
+            &mock_device.device().tls,
+        )
+        .await;
+        assert!(missing.is_err());
+
+        mock_device.stop().await;
+    }
+
+    #[tokio::test]
+    async fn test_xml_responses() {
+        let mut config = mock_config();
+        config.format = MockFormat::Xml;
+        let mock_device = start(config).await;
+
+        let service = HttpHandler::basic_request(
+            &format!(
+                "https://127.0.0.1:{}/restconf/data/tapi-common:context/tapi-connectivity:connectivity-context/connectivity-service={}",
+                mock_device.port(),
+                DEVICES_SERVICE_UUID
+            ),
+            "tapi".to_string(),
+            Some("2025_T3st".to_string()),
+            &mock_device.device().tls,
+        )
+        .await
+        .expect("XML response cannot be parsed");
+
+        assert_eq!(
+            service["connectivity-service"]["uuid"]["text"],
+            DEVICES_SERVICE_UUID
+        );
+        assert_eq!(
+            service["connectivity-service"]["end-point"]
+                .as_array()
+                .unwrap()
+                .len(),
+            8
+        );
+
+        mock_device.stop().await;
+    }
+
+    /// Transient 5xx answers are retried with backoff
+    #[tokio::test]
+    async fn test_transient_failures_are_retried() {
+        let mut config = mock_config();
+        config.transient_failures = 2;
+        let mock_device = start(config).await;
+
+        let (services, _) = DeviceHandler::get_services(&mock_device.device())
+            .await
+            .expect("Services cannot be retrieved after retries");
+
+        assert_eq!(services.len(), 7);
+
+        mock_device.stop().await;
+    }
+
+    /// Every topology is loaded and merged, with each node and link tagged by topology UUID
+    #[tokio::test]
+    async fn test_multiple_topologies() {
+        let mut config = mock_config();
+        split_topology(&mut config);
+        let mock_device = start(config).await;
+
+        let full_context = DeviceHandler::get_full_context(&mock_device.device())
+            .await
+            .expect("Full context cannot be retrieved");
+        assert_eq!(full_context.topology.as_array().unwrap().len(), 2);
+
+        let snapshot = TopologySnapshot::build(full_context, VendorProfile::default()).unwrap();
+        assert_eq!(snapshot.nodes.len(), 38);
+        assert_eq!(snapshot.links.len(), 79);
+        let topology_uuids: HashSet<String> = snapshot
+            .nodes
+            .iter()
+            .map(|node| node.topology_uuid.to_string())
+            .collect();
+        assert_eq!(
+            topology_uuids,
+            HashSet::from([
+                DEVICES_TOPOLOGY_UUID.to_string(),
+                SECOND_TOPOLOGY_UUID.to_string()
+            ])
+        );
+        assert!(snapshot
+            .links
+            .iter()
+            .any(|link| link.topology_uuid.to_string() == SECOND_TOPOLOGY_UUID));
+
+        let context =
+            DeviceHandler::get_service_context(&mock_device.device(), DEVICES_SERVICE_UUID)
+                .await
+                .expect("Service context cannot be retrieved");
+        let topologies = context.topology.as_array().unwrap();
+        assert_eq!(topologies.len(), 2);
+        assert_eq!(
+            topologies
+                .iter()
+                .map(|topology| topology["node"].as_array().unwrap().len())
+                .sum::<usize>(),
+            39
+        );
+        assert_eq!(topologies[1]["uuid"], Value::from(SECOND_TOPOLOGY_UUID));
+
+        mock_device.stop().await;
+    }
+
+    /// The Ciena profile is detected from the modules of the data and reads the connection extensions
+    #[tokio::test]
+    async fn test_vendor_profile_is_detected() {
+        // The Ciena fixtures have no topology, so an empty one is served
+        let mut config = MockDeviceConfig::from_fixtures("sienna_files", token_auth()).unwrap();
+        config
+            .datastore
+            .pointer_mut("/tapi-common:context")
+            .and_then(Value::as_object_mut)
+            .unwrap()
+            .insert(
+                "tapi-topology:topology-context".to_string(),
+                json!({"topology": [{"uuid": SECOND_TOPOLOGY_UUID, "node": [], "link": []}]}),
+            );
+        let mock_device = start(config).await;
+        let device = mock_device.device();
+
+        let full_context = DeviceHandler::get_full_context(&device)
+            .await
+            .expect("Full context cannot be retrieved");
+        let profile =
+            VendorProfiles::global().select(device.vendor.as_deref(), &full_context.modules());
+        assert_eq!(profile.name, "ciena");
+
+        let tapi_connections: Vec<TapiConnection> =
+            parse_tapi(&full_context.connections, "connection").unwrap();
+        let connections = connection_vector_build(&tapi_connections, &profile);
+        assert!(connections
+            .iter()
+            .any(|connection| connection.extensions.contains_key("service_class")));
+
+        let generic = VendorProfiles::global().select(Some("generic"), &full_context.modules());
+        assert!(connection_vector_build(&tapi_connections, &generic)
+            .iter()
+            .all(|connection| connection.extensions.is_empty()));
+
+        mock_device.stop().await;
+    }
+}
@@ -0,0 +1,346 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    // Apply the notifications of mock devices to the cached snapshots and forward them to the browser
+    use actix_api::handlers::notifications::NotificationHub;
+    use actix_api::handlers::repository::DataSourceRepository;
+    use actix_api::handlers::requester::DataSource;
+    use actix_api::handlers::snapshot::{SnapshotCache, TopologySnapshot};
+    use actix_api::models::notification::{
+        NotificationType, ObjectType, TapiNotification, TopologyEvent,
+    };
+    use actix_api::routes::events::events;
+
+    use crate::common::{start_mock_device, start_mock_device_with};
+
+    use actix_web::body::{BoxBody, MessageBody};
+    use actix_web::{test, web, App};
+    use serde_json::{json, Value};
+    use std::future::poll_fn;
+    use std::pin::Pin;
+    use std::sync::Arc;
+    use std::time::Duration;
+    use tapi_model::common::OperationalState;
+
+    const DELETED_SERVICE_UUID: &str = "00000000-0000-0000-0000-000000000008";
+    const CHANGED_SERVICE_UUID: &str = "088d78de-6e67-4695-bbc4-40b84a0d25dc";
+
+    fn service_notification(notification_type: &str, uuid: &str) -> Value {
+        json!({
+            "uuid": "11111111-0000-0000-0000-000000000001",
+            "notification-type": format!("tapi-notification:NOTIFICATION_TYPE_{}", notification_type),
+            "target-object-type": "tapi-connectivity:CONNECTIVITY_OBJECT_TYPE_CONNECTIVITY_SERVICE",
+            "target-object-identifier": uuid,
+            "event-time-stamp": "2025-04-10T10:00:00Z",
+        })
+    }
+
+    /// Reads the next chunk of a streamed response body.
+    async fn next_chunk(body: &mut Pin<Box<BoxBody>>) -> Option<String> {
+        let chunk = tokio::time::timeout(
+            Duration::from_secs(10),
+            poll_fn(|cx| body.as_mut().poll_next(cx)),
+        )
+        .await
+        .expect("No chunk was streamed")?;
+        Some(String::from_utf8(chunk.unwrap().to_vec()).unwrap())
+    }
+
+    /// Events are read whether they come bare or wrapped in an `ietf-restconf:notification`
+    #[tokio::test]
+    async fn test_notification_from_event() {
+        let bare = service_notification("OBJECT_DELETION", DELETED_SERVICE_UUID);
+        let wrapped = json!({
+            "ietf-restconf:notification": {
+                "eventTime": "2025-04-10T10:00:00Z",
+                "tapi-notification:notification": bare.clone(),
+            }
+        });
+
+        for event in [bare, wrapped] {
+            let notification = TapiNotification::from_event(&event).unwrap();
+            assert_eq!(
+                notification.notification_type,
+                NotificationType::ObjectDeletion
+            );
+            assert_eq!(
+                notification.target_object_type,
+                ObjectType::ConnectivityService
+            );
+            assert_eq!(notification.target_object_identifier, DELETED_SERVICE_UUID);
+        }
+
+        let mut unknown = service_notification("OBJECT_DELETION", DELETED_SERVICE_UUID);
+        unknown["notification-type"] = json!("ALARM_EVENT");
+        assert!(TapiNotification::from_event(&unknown).is_err());
+        assert!(TapiNotification::from_event(&json!({ "eventTime": "now" })).is_err());
+    }
+
+    /// Deletions and attribute changes update the cached snapshot, creations take it again
+    #[tokio::test]
+    async fn test_notifications_update_snapshot() {
+        let mock_device = start_mock_device().await;
+        let data_source = DataSource::Device(mock_device.device());
+        let cache = SnapshotCache::new(Duration::from_secs(300));
+
+        // Nothing is updated while no snapshot is cached
+        let deletion = TapiNotification::from_event(&service_notification(
+            "OBJECT_DELETION",
+            DELETED_SERVICE_UUID,
+        ))
+        .unwrap();
+        assert!(cache
+            .apply(&data_source, &deletion)
+            .await
+            .unwrap()
+            .is_none());
+
+        let snapshot = cache.get(&data_source).await.unwrap();
+        assert_eq!(snapshot.connectivity_services.len(), 7);
+
+        let updated = cache.apply(&data_source, &deletion).await.unwrap().unwrap();
+        assert_eq!(updated.connectivity_services.len(), 6);
+        assert!(updated.service(DELETED_SERVICE_UUID).is_none());
+        assert_eq!(updated.nodes.len(), 38);
+        assert_eq!(updated.taken_at, snapshot.taken_at);
+
+        let mut change = service_notification("ATTRIBUTE_VALUE_CHANGE", CHANGED_SERVICE_UUID);
+        change["changed-attributes"] = json!([{
+            "value-name": "operational-state",
+            "old-value": "ENABLED",
+            "new-value": "DISABLED",
+        }]);
+        let change = TapiNotification::from_event(&change).unwrap();
+        let updated = cache.apply(&data_source, &change).await.unwrap().unwrap();
+        assert_eq!(
+            updated
+                .service(CHANGED_SERVICE_UUID)
+                .unwrap()
+                .operational_state,
+            Some(OperationalState::Disabled)
+        );
+        assert_eq!(updated.connectivity_services.len(), 6);
+
+        // The device now holds a new service, the whole snapshot is taken again
+        let created_uuid = "22222222-0000-0000-0000-000000000001";
+        mock_device.update_datastore(|datastore| {
+            let services = datastore
+                .pointer_mut(
+                    "/tapi-common:context/tapi-connectivity:connectivity-context/connectivity-service",
+                )
+                .and_then(Value::as_array_mut)
+                .unwrap();
+            let mut created = services[1].clone();
+            created["uuid"] = json!(created_uuid);
+            services.push(created);
+        });
+        let creation =
+            TapiNotification::from_event(&service_notification("OBJECT_CREATION", created_uuid))
+                .unwrap();
+        let retaken = cache.apply(&data_source, &creation).await.unwrap().unwrap();
+        assert_eq!(retaken.connectivity_services.len(), 8);
+        assert!(retaken.service(created_uuid).is_some());
+        assert!(retaken.taken_at >= updated.taken_at);
+
+        mock_device.stop().await;
+    }
+
+    /// Notifications on topology objects change only the vector holding their target
+    #[tokio::test]
+    async fn test_notifications_patch_topology_objects() {
+        let mock_device = start_mock_device().await;
+        let snapshot = TopologySnapshot::take(&DataSource::Device(mock_device.device()))
+            .await
+            .unwrap();
+        mock_device.stop().await;
+        let notification = |notification_type: &str, object_type: &str, uuid: String| {
+            let mut event = service_notification(notification_type, &uuid);
+            event["target-object-type"] = json!(object_type);
+            event
+        };
+        let apply = |event: &Value| {
+            snapshot
+                .apply(&TapiNotification::from_event(event).unwrap())
+                .unwrap()
+        };
+
+        // A deleted link leaves the nodes, connections and services shared
+        let link = &snapshot.links[0];
+        let updated = apply(&notification(
+            "OBJECT_DELETION",
+            "tapi-topology:TOPOLOGY_OBJECT_TYPE_LINK",
+            link.link_uuid.to_string(),
+        ))
+        .unwrap();
+        assert_eq!(updated.links.len(), snapshot.links.len() - 1);
+        assert!(Arc::ptr_eq(&updated.nodes, &snapshot.nodes));
+        assert!(Arc::ptr_eq(&updated.connections, &snapshot.connections));
+        assert!(Arc::ptr_eq(
+            &updated.connectivity_services,
+            &snapshot.connectivity_services
+        ));
+        let node_edge_point_uuid = link.node_edge_points[0].node_edge_point_uuid;
+        assert!(updated
+            .graph
+            .links_of(node_edge_point_uuid)
+            .all(|other| other.link_uuid != link.link_uuid));
+        assert_eq!(updated.graph.nodes().len(), snapshot.nodes.len());
+
+        // A deleted connection is no longer indexed
+        let connection_uuid = snapshot.connections[0].connection_uuid;
+        let updated = apply(&notification(
+            "OBJECT_DELETION",
+            "tapi-connectivity:CONNECTIVITY_OBJECT_TYPE_CONNECTION",
+            connection_uuid.to_string(),
+        ))
+        .unwrap();
+        assert_eq!(updated.connections.len(), snapshot.connections.len() - 1);
+        assert!(updated
+            .graph
+            .connections_by_uuid(connection_uuid)
+            .next()
+            .is_none());
+        assert!(Arc::ptr_eq(&updated.links, &snapshot.links));
+
+        // A changed state is not read by the snapshot, a changed name needs the context again
+        let node_uuid = snapshot.nodes[0].node_uuid.to_string();
+        let mut state_change = notification(
+            "ATTRIBUTE_VALUE_CHANGE",
+            "tapi-topology:TOPOLOGY_OBJECT_TYPE_NODE",
+            node_uuid.clone(),
+        );
+        state_change["changed-attributes"] = json!([{
+            "value-name": "tapi-common:operational-state",
+            "new-value": "DISABLED",
+        }]);
+        let updated = apply(&state_change).unwrap();
+        assert!(Arc::ptr_eq(&updated.nodes, &snapshot.nodes));
+        assert!(Arc::ptr_eq(&updated.graph, &snapshot.graph));
+        let mut name_change = state_change.clone();
+        name_change["changed-attributes"] = json!([{"value-name": "name", "new-value": []}]);
+        assert!(apply(&name_change).is_none());
+
+        // Unknown targets are not applied
+        let unknown = notification(
+            "OBJECT_DELETION",
+            "tapi-topology:TOPOLOGY_OBJECT_TYPE_NODE",
+            "33333333-0000-0000-0000-000000000001".to_string(),
+        );
+        assert!(apply(&unknown).is_none());
+    }
+
+    /// `/events/{id}` follows the stream of the device and forwards each applied notification
+    #[tokio::test]
+    async fn test_events_route() {
+        let mock_device = start_mock_device().await;
+        let device = mock_device.device();
+        let data_source = DataSource::Device(device.clone());
+        let repository = DataSourceRepository::in_memory();
+        repository.insert(data_source.clone()).await.unwrap();
+        let cache = SnapshotCache::new(Duration::from_secs(300));
+        let hub = NotificationHub::new(cache.clone());
+        cache.get(&data_source).await.unwrap();
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(repository.clone()))
+                .app_data(web::Data::new(hub.clone()))
+                .service(events),
+        )
+        .await;
+
+        let req = test::TestRequest::get().uri("/events/unknown").to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 404);
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/events/{}", device.ip))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert!(resp.status().is_success());
+        assert_eq!(
+            resp.headers().get("content-type").unwrap(),
+            "text/event-stream"
+        );
+        let mut body = Box::pin(resp.into_body());
+        assert_eq!(
+            next_chunk(&mut body).await.as_deref(),
+            Some(": subscribed\n\n")
+        );
+
+        // The hub opens the stream of the device in the background
+        for _ in 0..100 {
+            if mock_device.subscribers() > 0 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+        assert_eq!(
+            mock_device.publish(service_notification(
+                "OBJECT_DELETION",
+                DELETED_SERVICE_UUID
+            )),
+            1
+        );
+
+        let chunk = next_chunk(&mut body).await.unwrap();
+        let event: TopologyEvent =
+            serde_json::from_str(chunk.strip_prefix("data: ").unwrap().trim_end()).unwrap();
+        assert_eq!(
+            event.notification.target_object_identifier,
+            DELETED_SERVICE_UUID
+        );
+        assert!(event.snapshot_timestamp.is_some());
+        let snapshot = cache.get(&data_source).await.unwrap();
+        assert_eq!(snapshot.connectivity_services.len(), 6);
+
+        // Unsubscribing, as deleting the device does, ends the events
+        hub.unsubscribe(&device.ip).await;
+        assert_eq!(next_chunk(&mut body).await, None);
+
+        mock_device.stop().await;
+    }
+
+    /// Devices announcing no stream are subscribed to, and their stream is closed with its
+    /// subscription once nobody follows it
+    #[tokio::test]
+    async fn test_subscription_without_announced_stream() {
+        let mock_device = start_mock_device_with(|config| config.announces_stream = false).await;
+        let device = mock_device.device();
+        let hub = NotificationHub::new(SnapshotCache::new(Duration::from_secs(300)));
+
+        let receiver = hub.subscribe(&device).await;
+        for _ in 0..100 {
+            if mock_device.subscribers() > 0 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+        assert_eq!(mock_device.subscribers(), 1);
+        let mut subscriptions = 0;
+        mock_device.update_datastore(|datastore| {
+            subscriptions = datastore
+                .pointer("/tapi-common:context/tapi-notification:notification-context/notif-subscription")
+                .and_then(Value::as_array)
+                .map_or(0, Vec::len);
+        });
+        assert_eq!(subscriptions, 1);
+        assert!(hub.is_followed(&device.ip).await);
+
+        // The next notification finds nobody to forward it to
+        drop(receiver);
+        mock_device.publish(service_notification(
+            "OBJECT_DELETION",
+            DELETED_SERVICE_UUID,
+        ));
+        for _ in 0..100 {
+            if !hub.is_followed(&device.ip).await {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+        assert!(!hub.is_followed(&device.ip).await);
+
+        mock_device.stop().await;
+    }
+}
@@ -0,0 +1,127 @@
+#[cfg(test)]
+mod tests {
+    // Read a mock device protected with OAuth2, reusing and renewing its tokens
+    use actix_api::handlers::requester::DeviceHandler;
+    use actix_api::mock::{MockDevice, MockDeviceConfig};
+    use actix_api::models::devices::{Auth, GrantType, OAuth2Auth};
+    use actix_api::AppError;
+
+    use std::time::Duration;
+
+    fn oauth2_auth(grant_type: GrantType) -> OAuth2Auth {
+        OAuth2Auth {
+            grant_type,
+            token_uri: "/oauth2/token".to_string(),
+            client_id: Some("tapi-client".to_string()),
+            client_secret: Some("2025_S3cr3t".to_string()),
+            username: Some("tapi".to_string()),
+            password: Some("2025_T3st".to_string()),
+            scope: Some("tapi".to_string()),
+        }
+    }
+
+    fn config(oauth2_auth: OAuth2Auth) -> MockDeviceConfig {
+        MockDeviceConfig::from_fixtures("devices_files", Auth::OAuth2(oauth2_auth)).unwrap()
+    }
+
+    /// The token is requested once and reused while it is valid
+    #[tokio::test]
+    async fn test_token_is_cached() {
+        let mock_device = MockDevice::start(
+            config(oauth2_auth(GrantType::ClientCredentials)),
+            "127.0.0.1:0",
+        )
+        .await
+        .unwrap();
+        let device = mock_device.device();
+
+        assert_eq!(
+            DeviceHandler::get_services(&device).await.unwrap().0.len(),
+            7
+        );
+        assert_eq!(
+            DeviceHandler::get_services(&device).await.unwrap().0.len(),
+            7
+        );
+        assert_eq!(mock_device.token_grants(), vec!["client_credentials"]);
+
+        mock_device.stop().await;
+    }
+
+    /// Tokens about to expire are renewed with their refresh token
+    #[tokio::test]
+    async fn test_expired_token_is_refreshed() {
+        let mut config = config(oauth2_auth(GrantType::ClientCredentials));
+        // Renewed one second after being issued, given the expiry margin
+        config.token_lifetime = Duration::from_secs(11);
+        let mock_device = MockDevice::start(config, "127.0.0.1:0").await.unwrap();
+        let device = mock_device.device();
+
+        assert_eq!(
+            DeviceHandler::get_services(&device).await.unwrap().0.len(),
+            7
+        );
+        tokio::time::sleep(Duration::from_millis(1200)).await;
+        assert_eq!(
+            DeviceHandler::get_services(&device).await.unwrap().0.len(),
+            7
+        );
+        assert_eq!(
+            mock_device.token_grants(),
+            vec!["client_credentials", "refresh_token"]
+        );
+
+        mock_device.stop().await;
+    }
+
+    /// Requests rejected with `401` are retried once with a renewed token
+    #[tokio::test]
+    async fn test_rejected_token_is_renewed() {
+        let mock_device =
+            MockDevice::start(config(oauth2_auth(GrantType::Password)), "127.0.0.1:0")
+                .await
+                .unwrap();
+        let device = mock_device.device();
+
+        assert_eq!(
+            DeviceHandler::get_services(&device).await.unwrap().0.len(),
+            7
+        );
+        mock_device.revoke_tokens();
+        assert_eq!(
+            DeviceHandler::get_services(&device).await.unwrap().0.len(),
+            7
+        );
+        assert_eq!(
+            mock_device.token_grants(),
+            vec!["password", "refresh_token"]
+        );
+
+        mock_device.stop().await;
+    }
+
+    /// Wrong client credentials are reported as an authentication error
+    #[tokio::test]
+    async fn test_wrong_client_secret() {
+        let mock_device = MockDevice::start(
+            config(oauth2_auth(GrantType::ClientCredentials)),
+            "127.0.0.1:0",
+        )
+        .await
+        .unwrap();
+        let mut device = mock_device.device();
+        if let Auth::OAuth2(oauth2_auth) = &mut device.auth {
+            oauth2_auth.client_secret = Some("wrong".to_string());
+        }
+
+        let result = DeviceHandler::get_services(&device).await;
+        assert!(
+            matches!(result, Err(AppError::AuthenticationError(_))),
+            "{:?}",
+            result
+        );
+        assert!(mock_device.token_grants().is_empty());
+
+        mock_device.stop().await;
+    }
+}
@@ -0,0 +1,256 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    // Compute paths between service interface points of mock devices
+    use actix_api::handlers::repository::DataSourceRepository;
+    use actix_api::handlers::requester::DataSource;
+    use actix_api::handlers::snapshot::{SnapshotCache, TopologySnapshot};
+    use actix_api::models::path_computation::{
+        PathCompService, PathComputationResult, PathConstraints, PathRequest, PathSource,
+    };
+    use actix_api::models::provisioning::AvailableSip;
+    use actix_api::routes::path_computation::compute_paths;
+
+    use crate::common::{start_mock_device, start_mock_device_with};
+
+    use actix_web::{test, web, App};
+    use serde_json::json;
+    use std::collections::HashMap;
+    use std::time::Duration;
+    use tapi_model::Uuid;
+
+    /// Two service interface points on the nodes at both ends of a link of the topology
+    fn linked_sips(snapshot: &TopologySnapshot) -> (AvailableSip, AvailableSip) {
+        let sips = snapshot.service_interface_points();
+        let on_node = |node_uuid: Uuid| sips.iter().find(|sip| sip.node_uuid == node_uuid);
+        snapshot
+            .links
+            .iter()
+            .filter_map(|link| match link.node_edge_points.as_slice() {
+                [a, b] if a.node_uuid != b.node_uuid => {
+                    Some((on_node(a.node_uuid)?.clone(), on_node(b.node_uuid)?.clone()))
+                }
+                _ => None,
+            })
+            .next()
+            .expect("No link joins two nodes with service interface points")
+    }
+
+    fn path_request(source: &AvailableSip, destination: &AvailableSip) -> PathRequest {
+        PathRequest {
+            service_interface_points: vec![source.uuid.to_string(), destination.uuid.to_string()],
+            layer_protocol_name: None,
+            constraints: PathConstraints::default(),
+        }
+    }
+
+    /// The RPC input references both service interface points and the constrained nodes, the output is read with or without its wrappers
+    #[tokio::test]
+    async fn test_path_request_input_and_output() {
+        let node_topologies = HashMap::from([("node-1".to_string(), "topology-1".to_string())]);
+        let mut request = PathRequest {
+            service_interface_points: vec!["sip-1".to_string(), "sip-2".to_string()],
+            layer_protocol_name: Some("PHOTONIC_MEDIA".to_string()),
+            constraints: PathConstraints {
+                include_nodes: vec![],
+                exclude_nodes: vec!["node-1".to_string()],
+            },
+        };
+
+        let input = request.input(&node_topologies).unwrap();
+        let input = &input["tapi-path-computation:input"];
+        assert_eq!(input["sep"].as_array().unwrap().len(), 2);
+        assert_eq!(
+            input["sep"][1]["service-interface-point"]["service-interface-point-uuid"],
+            "sip-2"
+        );
+        assert_eq!(input["sep"][0]["layer-protocol-name"], "PHOTONIC_MEDIA");
+        assert_eq!(
+            input["topology-constraint"]["exclude-node"],
+            json!([{ "topology-uuid": "topology-1", "node-uuid": "node-1" }])
+        );
+
+        request.constraints.include_nodes = vec!["node-2".to_string()];
+        assert!(request.input(&node_topologies).is_err());
+        request.service_interface_points = vec!["sip-1".to_string(), "sip-1".to_string()];
+        assert!(request.validate().is_err());
+
+        let service = json!({
+            "uuid": "service-1",
+            "end-point": [
+                { "service-interface-point": { "service-interface-point-uuid": "sip-1" } },
+                { "service-interface-point": { "service-interface-point-uuid": "sip-2" } },
+            ],
+            "path": [
+                { "path-uuid": "path-1" },
+                { "uuid": "path-2", "link": [{ "topology-uuid": "topology-1", "link-uuid": "link-1" }] },
+            ],
+        });
+        let wrapped = json!({ "tapi-path-computation:output": { "service": service } });
+        for output in [&wrapped, &service] {
+            let read = PathCompService::from_output(output).unwrap();
+            assert_eq!(read.uuid.as_deref(), Some("service-1"));
+            assert_eq!(read.path_uuids, vec!["path-1", "path-2"]);
+            assert_eq!(read.paths.len(), 1);
+            assert_eq!(read.paths[0].links[0].link_uuid, "link-1");
+        }
+
+        request.service_interface_points = vec!["sip-2".to_string(), "sip-1".to_string()];
+        assert!(request.matches(&service));
+        request.service_interface_points = vec!["sip-2".to_string(), "sip-3".to_string()];
+        assert!(!request.matches(&service));
+    }
+
+    /// The device computes a path whose schema holds the nodes at both ends, excluded nodes leave no path
+    #[tokio::test]
+    async fn test_compute_paths() {
+        let mock_device = start_mock_device().await;
+        let device = mock_device.device();
+        let data_source = DataSource::Device(device.clone());
+        let repository = DataSourceRepository::in_memory();
+        repository.insert(data_source.clone()).await.unwrap();
+        let cache = SnapshotCache::new(Duration::from_secs(300));
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(repository.clone()))
+                .app_data(web::Data::new(cache.clone()))
+                .service(compute_paths),
+        )
+        .await;
+
+        let snapshot = cache.get(&data_source).await.unwrap();
+        let (source, destination) = linked_sips(&snapshot);
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/paths/{}", device.ip))
+            .set_json(path_request(&source, &destination))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 200);
+        assert!(resp.headers().contains_key("X-Snapshot-Timestamp"));
+        let result: PathComputationResult = test::read_body_json(resp).await;
+        assert_eq!(result.source, PathSource::Computed);
+        assert!(result.service_uuid.is_some());
+        assert_eq!(result.paths.len(), 1);
+        let node_uuids: Vec<&str> = result.paths[0]["nodes"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter_map(|node| node["node_uuid"].as_str())
+            .collect();
+        assert!(node_uuids.contains(&source.node_uuid.to_string().as_str()));
+        assert!(node_uuids.contains(&destination.node_uuid.to_string().as_str()));
+
+        // The destination cannot be reached around itself, nor from the path computed before
+        let mut excluded = path_request(&source, &destination);
+        excluded.constraints.exclude_nodes = vec![destination.node_uuid.to_string()];
+        let req = test::TestRequest::post()
+            .uri(&format!("/paths/{}", device.ip))
+            .set_json(excluded)
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 404);
+
+        let unknown = PathRequest {
+            service_interface_points: vec![source.uuid.to_string(), "unknown-sip".to_string()],
+            ..path_request(&source, &destination)
+        };
+        let req = test::TestRequest::post()
+            .uri(&format!("/paths/{}", device.ip))
+            .set_json(unknown)
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 400);
+
+        mock_device.stop().await;
+    }
+
+    /// Devices without the RPC answer with the paths they already hold between the service interface points
+    #[tokio::test]
+    async fn test_existing_paths() {
+        let mock_device =
+            start_mock_device_with(|config| config.supports_path_computation = false).await;
+        let device = mock_device.device();
+        let data_source = DataSource::Device(device.clone());
+        let repository = DataSourceRepository::in_memory();
+        repository.insert(data_source.clone()).await.unwrap();
+        let cache = SnapshotCache::new(Duration::from_secs(300));
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(repository.clone()))
+                .app_data(web::Data::new(cache.clone()))
+                .service(compute_paths),
+        )
+        .await;
+
+        let snapshot = cache.get(&data_source).await.unwrap();
+        let (source, destination) = linked_sips(&snapshot);
+
+        // Without a path computation context the failure of the RPC is reported
+        let req = test::TestRequest::post()
+            .uri(&format!("/paths/{}", device.ip))
+            .set_json(path_request(&source, &destination))
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 502);
+
+        let link = snapshot
+            .links
+            .iter()
+            .find(|link| {
+                let node_uuids: Vec<Uuid> = link
+                    .node_edge_points
+                    .iter()
+                    .map(|node_edge_point| node_edge_point.node_uuid)
+                    .collect();
+                node_uuids.contains(&source.node_uuid)
+                    && node_uuids.contains(&destination.node_uuid)
+            })
+            .unwrap();
+        let end_points = json!([
+            { "service-interface-point": { "service-interface-point-uuid": source.uuid } },
+            { "service-interface-point": { "service-interface-point-uuid": destination.uuid } },
+        ]);
+        mock_device.update_datastore(|datastore| {
+            datastore["tapi-common:context"]["tapi-path-computation:path-computation-context"] = json!({
+                "path-comp-service": [{
+                    "uuid": "44444444-0000-0000-0000-000000000001",
+                    "end-point": end_points,
+                    "path": [{ "path-uuid": "44444444-0000-0000-0000-000000000002" }],
+                }],
+                "path": [{
+                    "uuid": "44444444-0000-0000-0000-000000000002",
+                    "name": [{ "value-name": "PATH_NAME", "value": "existing" }],
+                    "link": [{
+                        "topology-uuid": link.topology_uuid.to_string(),
+                        "link-uuid": link.link_uuid.to_string(),
+                    }],
+                }],
+            });
+        });
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/paths/{}", device.ip))
+            .set_json(path_request(&source, &destination))
+            .to_request();
+        let result: PathComputationResult = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(result.source, PathSource::Existing);
+        assert_eq!(
+            result.service_uuid.as_deref(),
+            Some("44444444-0000-0000-0000-000000000001")
+        );
+        assert_eq!(result.paths.len(), 1);
+        assert_eq!(result.paths[0]["value_name"], "existing");
+
+        // The existing path does not avoid the destination node
+        let mut excluded = path_request(&source, &destination);
+        excluded.constraints.exclude_nodes = vec![destination.node_uuid.to_string()];
+        let req = test::TestRequest::post()
+            .uri(&format!("/paths/{}", device.ip))
+            .set_json(excluded)
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 404);
+
+        mock_device.stop().await;
+    }
+}
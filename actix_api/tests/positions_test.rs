@@ -0,0 +1,132 @@
+#[cfg(test)]
+mod tests {
+    // Store the positions of the nodes of the network map of each host
+    use actix_api::handlers::repository::DataSourceRepository;
+    use actix_api::handlers::requester::DataSource;
+    use actix_api::models::devices::{Auth, BasicAuth, Device, TlsConfig};
+    use actix_api::models::map_positions::MapPositions;
+    use actix_api::routes::positions::{get_positions, set_positions};
+
+    use actix_web::{test, web, App};
+    use serde_json::json;
+
+    fn basic_device(ip: &str) -> Device {
+        Device {
+            ip: ip.to_string(),
+            port: Some(18010),
+            auth: Auth::Basic(BasicAuth {
+                username: "tapi".to_string(),
+                password: "2025_T3st".to_string(),
+            }),
+            vendor: None,
+            retrieval_strategy: None,
+            tls: TlsConfig::default(),
+        }
+    }
+
+    /// Positions put on `/hosts/{id}/positions` are read back by every client until they are replaced
+    #[actix_web::test]
+    async fn test_positions_route() {
+        let repository = DataSourceRepository::in_memory();
+        repository
+            .insert(DataSource::Device(basic_device("10.95.87.21")))
+            .await
+            .unwrap();
+        repository
+            .insert(DataSource::Device(basic_device("10.95.87.22")))
+            .await
+            .unwrap();
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(repository.clone()))
+                .service(get_positions)
+                .service(set_positions),
+        )
+        .await;
+
+        let req = test::TestRequest::get()
+            .uri("/hosts/10.95.87.21/positions")
+            .to_request();
+        let positions: MapPositions = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(positions, MapPositions::default());
+
+        let req = test::TestRequest::put()
+            .uri("/hosts/10.95.87.21/positions")
+            .set_json(json!({"node-1": [120.5, 80.0], "node-2": [300.0, 410.25]}))
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 200);
+
+        let req = test::TestRequest::get()
+            .uri("/hosts/10.95.87.21/positions")
+            .to_request();
+        let positions: MapPositions = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(positions.0.len(), 2);
+        assert_eq!(positions.0["node-1"], (120.5, 80.0));
+        assert_eq!(positions.0["node-2"], (300.0, 410.25));
+
+        // Positions are kept per host
+        let req = test::TestRequest::get()
+            .uri("/hosts/10.95.87.22/positions")
+            .to_request();
+        let positions: MapPositions = test::call_and_read_body_json(&app, req).await;
+        assert!(positions.0.is_empty());
+
+        // An empty object resets the layout
+        let req = test::TestRequest::put()
+            .uri("/hosts/10.95.87.21/positions")
+            .set_json(json!({}))
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 200);
+        assert!(repository
+            .positions("10.95.87.21")
+            .await
+            .unwrap()
+            .0
+            .is_empty());
+
+        let req = test::TestRequest::put()
+            .uri("/hosts/10.95.87.21/positions")
+            .set_json(json!({"node-1": "left"}))
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 400);
+
+        let req = test::TestRequest::get()
+            .uri("/hosts/10.0.0.1/positions")
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 404);
+        let req = test::TestRequest::put()
+            .uri("/hosts/10.0.0.1/positions")
+            .set_json(json!({}))
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 404);
+    }
+
+    /// Removing a host drops the positions of its map
+    #[tokio::test]
+    async fn test_remove_host_drops_positions() {
+        let repository = DataSourceRepository::in_memory();
+        repository
+            .insert(DataSource::Device(basic_device("10.95.87.21")))
+            .await
+            .unwrap();
+        let positions: MapPositions =
+            serde_json::from_value(json!({"node-1": [10.0, 20.0]})).unwrap();
+        repository
+            .set_positions("10.95.87.21", &positions)
+            .await
+            .unwrap();
+        assert_eq!(
+            repository.positions("10.95.87.21").await.unwrap(),
+            positions
+        );
+
+        repository.remove("10.95.87.21").await.unwrap();
+        assert!(repository
+            .positions("10.95.87.21")
+            .await
+            .unwrap()
+            .0
+            .is_empty());
+    }
+}
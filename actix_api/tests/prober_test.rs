@@ -0,0 +1,119 @@
+#[cfg(test)]
+mod tests {
+    // Probe the TAPI RESTCONF URIs of a mock device and read the stored results
+    use actix_api::handlers::prober::RestconfProber;
+    use actix_api::handlers::repository::DataSourceRepository;
+    use actix_api::handlers::requester::DataSource;
+    use actix_api::mock::{MockDevice, MockDeviceConfig};
+    use actix_api::models::devices::{Auth, BasicAuth};
+    use actix_api::models::restconf_validations::RestconfValidation;
+    use actix_api::models::tapi_uris::TapiUri;
+    use actix_api::routes::get_validations::get_validations;
+    use actix_api::routes::probe::probe;
+
+    use actix_web::{test, web, App};
+
+    fn basic_auth() -> Auth {
+        Auth::Basic(BasicAuth {
+            username: "tapi".to_string(),
+            password: "2025_T3st".to_string(),
+        })
+    }
+
+    fn by_topic<'a>(validations: &'a [RestconfValidation], topic: &str) -> &'a RestconfValidation {
+        validations
+            .iter()
+            .find(|validation| validation.topic == topic)
+            .unwrap_or_else(|| panic!("{} was not probed", topic))
+    }
+
+    /// Per-UUID URIs are filled with the UUIDs found by the `uuid-list` topics
+    #[tokio::test]
+    async fn test_probe_follows_dependencies() {
+        let config = MockDeviceConfig::from_fixtures("devices_files", basic_auth()).unwrap();
+        let mock_device = MockDevice::start(config, "127.0.0.1:0").await.unwrap();
+
+        let validations = RestconfProber::probe(&mock_device.device(), &TapiUri::seeded())
+            .await
+            .unwrap();
+        assert_eq!(validations.len(), TapiUri::seeded().len());
+
+        for topic in [
+            "context",
+            "context.depth=3",
+            "topology.uuid-list",
+            "topology.by-parts",
+            "node.uuid-list",
+            "node.by-parts",
+            "link.by-parts",
+            "node.owned-node-edge-point.by-parts",
+            "connectivity-service.by-parts",
+            "connection.by-parts",
+        ] {
+            let validation = by_topic(&validations, topic);
+            assert!(validation.success, "{} failed: {:?}", topic, validation);
+            assert_eq!(validation.status_code, Some(200));
+            assert_eq!(
+                validation.response_type.as_deref(),
+                Some("application/yang-data+json")
+            );
+        }
+
+        // The mock has no physical context, so the devices cannot be listed nor requested
+        let physical_context = by_topic(&validations, "physical-context");
+        assert_eq!(physical_context.status_code, Some(404));
+        assert!(!physical_context.success);
+        let device = by_topic(&validations, "device.by-parts");
+        assert_eq!(device.status_code, None);
+        assert!(!device.success);
+
+        mock_device.stop().await;
+    }
+
+    /// Devices rejecting `fields` cannot be walked per UUID, which the routes report and store
+    #[actix_web::test]
+    async fn test_probe_routes_without_query_parameters() {
+        let mut config = MockDeviceConfig::from_fixtures("devices_files", basic_auth()).unwrap();
+        config.supports_query_parameters = false;
+        let mock_device = MockDevice::start(config, "127.0.0.1:0").await.unwrap();
+        let device = mock_device.device();
+
+        let repository = DataSourceRepository::in_memory();
+        repository
+            .insert(DataSource::Device(device.clone()))
+            .await
+            .unwrap();
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(repository.clone()))
+                .service(probe)
+                .service(get_validations),
+        )
+        .await;
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/probe/{}", device.ip))
+            .to_request();
+        let probed: Vec<RestconfValidation> = test::call_and_read_body_json(&app, req).await;
+        assert!(by_topic(&probed, "context").success);
+        assert_eq!(
+            by_topic(&probed, "topology.uuid-list").status_code,
+            Some(400)
+        );
+        assert_eq!(by_topic(&probed, "topology.by-parts").status_code, None);
+        assert_eq!(by_topic(&probed, "node.by-parts").status_code, None);
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/get_validations/{}", device.ip))
+            .to_request();
+        let stored: Vec<RestconfValidation> = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(stored, probed);
+
+        let req = test::TestRequest::get()
+            .uri("/get_validations/10.0.0.1")
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 404);
+
+        mock_device.stop().await;
+    }
+}
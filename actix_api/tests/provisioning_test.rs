@@ -0,0 +1,272 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    // Render, create and delete connectivity services on mock devices
+    use actix_api::handlers::provisioning::Provisioner;
+    use actix_api::handlers::repository::DataSourceRepository;
+    use actix_api::handlers::requester::DataSource;
+    use actix_api::handlers::snapshot::SnapshotCache;
+    use actix_api::models::provisioning::{
+        AvailableSip, ProvisioningResult, RequestedCapacity, ServiceConstraints, ServiceRequest,
+        CONNECTIVITY_CONTEXT_PATH,
+    };
+    use actix_api::routes::provisioning::{create_service, delete_service, get_sips};
+
+    use crate::common::start_mock_device;
+
+    use actix_web::{test, web, App};
+    use serde_json::json;
+    use std::collections::HashMap;
+    use std::time::Duration;
+    use tapi_model::Uuid;
+
+    const SERVICE_UUID: &str = "33333333-0000-0000-0000-000000000001";
+    const FIRST_SIP_UUID: &str = "44444444-0000-0000-0000-000000000001";
+    const SECOND_SIP_UUID: &str = "44444444-0000-0000-0000-000000000002";
+
+    fn uuid(uuid: &str) -> Uuid {
+        Uuid::parse_str(uuid).unwrap()
+    }
+
+    fn service_request(service_interface_points: Vec<Uuid>) -> ServiceRequest {
+        ServiceRequest {
+            uuid: Some(uuid(SERVICE_UUID)),
+            name: Some("provisioned".to_string()),
+            service_interface_points,
+            layer_protocol_name: "PHOTONIC_MEDIA".to_string(),
+            layer_protocol_qualifier: None,
+            capacity: Some(RequestedCapacity {
+                value: 100,
+                unit: "GBPS".to_string(),
+            }),
+            constraints: ServiceConstraints::default(),
+        }
+    }
+
+    /// The payload holds an end point per service interface point and references the constrained nodes
+    #[tokio::test]
+    async fn test_service_request_payload() {
+        let node_topologies = HashMap::from([("node-1".to_string(), "topology-1".to_string())]);
+        let mut request = service_request(vec![uuid(FIRST_SIP_UUID), uuid(SECOND_SIP_UUID)]);
+        request.constraints = ServiceConstraints {
+            service_level: Some("GOLD".to_string()),
+            include_nodes: vec!["node-1".to_string()],
+            exclude_nodes: vec![],
+            diverse_from: vec!["service-1".to_string()],
+        };
+
+        let payload = request
+            .payload(uuid(SERVICE_UUID), &node_topologies)
+            .unwrap();
+        let service = &payload["tapi-connectivity:connectivity-service"][0];
+        assert_eq!(service["uuid"], SERVICE_UUID);
+        assert_eq!(service["name"][0]["value"], "provisioned");
+        assert_eq!(service["end-point"].as_array().unwrap().len(), 2);
+        assert_eq!(
+            service["end-point"][1]["service-interface-point"]["service-interface-point-uuid"],
+            SECOND_SIP_UUID
+        );
+        assert_eq!(
+            service["requested-capacity"]["total-size"],
+            json!({ "value": 100, "unit": "GBPS" })
+        );
+        assert_eq!(service["service-level"], "GOLD");
+        assert_eq!(
+            service["topology-constraint"][0]["include-node"],
+            json!([{ "topology-uuid": "topology-1", "node-uuid": "node-1" }])
+        );
+        assert_eq!(
+            service["diversity-exclusion"][0]["connectivity-service-uuid"],
+            "service-1"
+        );
+
+        request.constraints.exclude_nodes = vec!["node-2".to_string()];
+        assert!(request
+            .payload(uuid(SERVICE_UUID), &node_topologies)
+            .is_err());
+
+        let duplicated = service_request(vec![uuid(FIRST_SIP_UUID), uuid(FIRST_SIP_UUID)]);
+        assert!(duplicated
+            .payload(uuid(SERVICE_UUID), &node_topologies)
+            .is_err());
+
+        let mut no_capacity = service_request(vec![uuid(FIRST_SIP_UUID), uuid(SECOND_SIP_UUID)]);
+        no_capacity.capacity = Some(RequestedCapacity {
+            value: 0,
+            unit: "GBPS".to_string(),
+        });
+        assert!(no_capacity
+            .payload(uuid(SERVICE_UUID), &node_topologies)
+            .is_err());
+    }
+
+    /// Dry runs render the request without sending it, invalid requests are rejected
+    #[tokio::test]
+    async fn test_dry_run() {
+        let mock_device = start_mock_device().await;
+        let device = mock_device.device();
+        let data_source = DataSource::Device(device.clone());
+        let repository = DataSourceRepository::in_memory();
+        repository.insert(data_source.clone()).await.unwrap();
+        let cache = SnapshotCache::new(Duration::from_secs(300));
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(repository.clone()))
+                .app_data(web::Data::new(cache.clone()))
+                .app_data(web::Data::new(Provisioner::new(
+                    Duration::from_millis(50),
+                    Duration::from_secs(5),
+                )))
+                .service(get_sips)
+                .service(create_service)
+                .service(delete_service),
+        )
+        .await;
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/sips/{}", device.ip))
+            .to_request();
+        let sips: Vec<AvailableSip> = test::call_and_read_body_json(&app, req).await;
+        assert!(sips.len() >= 2);
+        let sip_uuids: Vec<Uuid> = sips.iter().take(2).map(|sip| sip.uuid).collect();
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/services/{}?dry_run=true", device.ip))
+            .set_json(service_request(sip_uuids.clone()))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 200);
+        let result: ProvisioningResult = test::read_body_json(resp).await;
+        assert!(result.dry_run);
+        assert!(!result.completed);
+        assert_eq!(result.path, CONNECTIVITY_CONTEXT_PATH);
+        assert_eq!(
+            result.payload.unwrap()["tapi-connectivity:connectivity-service"][0]["uuid"],
+            SERVICE_UUID
+        );
+
+        let req = test::TestRequest::delete()
+            .uri(&format!(
+                "/services/{}/{}?dry_run=true",
+                device.ip, SERVICE_UUID
+            ))
+            .to_request();
+        let result: ProvisioningResult = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(result.method, "DELETE");
+        assert!(result.path.ends_with(SERVICE_UUID));
+
+        // Nothing reached the device
+        cache.invalidate(&device.ip).await;
+        let snapshot = cache.get(&data_source).await.unwrap();
+        assert_eq!(snapshot.connectivity_services.len(), 7);
+        assert!(snapshot.service(SERVICE_UUID).is_none());
+
+        let invalid_requests = [
+            json!(service_request(vec![sip_uuids[0]])),
+            json!(service_request(vec![sip_uuids[0], Uuid::new_v4()])),
+            json!({
+                "uuid": "x/../../..",
+                "service_interface_points": sip_uuids,
+                "layer_protocol_name": "PHOTONIC_MEDIA",
+            }),
+        ];
+        for invalid_request in invalid_requests {
+            let req = test::TestRequest::post()
+                .uri(&format!("/services/{}?dry_run=true", device.ip))
+                .set_json(invalid_request)
+                .to_request();
+            assert_eq!(test::call_service(&app, req).await.status(), 400);
+        }
+
+        let req = test::TestRequest::delete()
+            .uri(&format!("/services/{}/not-a-uuid?dry_run=true", device.ip))
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 400);
+
+        let req = test::TestRequest::post()
+            .uri("/services/unknown?dry_run=true")
+            .set_json(service_request(sip_uuids))
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 404);
+
+        mock_device.stop().await;
+    }
+
+    /// Created services are polled until installed, deleted ones until the device no longer finds them
+    #[tokio::test]
+    async fn test_create_and_delete_service() {
+        let mock_device = start_mock_device().await;
+        let device = mock_device.device();
+        let data_source = DataSource::Device(device.clone());
+        let repository = DataSourceRepository::in_memory();
+        repository.insert(data_source.clone()).await.unwrap();
+        let cache = SnapshotCache::new(Duration::from_secs(300));
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(repository.clone()))
+                .app_data(web::Data::new(cache.clone()))
+                .app_data(web::Data::new(Provisioner::new(
+                    Duration::from_millis(50),
+                    Duration::from_secs(5),
+                )))
+                .service(create_service)
+                .service(delete_service),
+        )
+        .await;
+
+        let snapshot = cache.get(&data_source).await.unwrap();
+        let sip_uuids: Vec<Uuid> = snapshot
+            .service_interface_points()
+            .iter()
+            .take(2)
+            .map(|sip| sip.uuid)
+            .collect();
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/services/{}", device.ip))
+            .set_json(service_request(sip_uuids.clone()))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 201);
+        let result: ProvisioningResult = test::read_body_json(resp).await;
+        assert!(result.completed);
+        assert!(!result.dry_run);
+        let state = result.state.unwrap();
+        assert_eq!(state.lifecycle_state.as_deref(), Some("INSTALLED"));
+        assert_eq!(state.operational_state.as_deref(), Some("ENABLED"));
+
+        let snapshot = cache.get(&data_source).await.unwrap();
+        assert_eq!(snapshot.connectivity_services.len(), 8);
+        assert!(snapshot.service(SERVICE_UUID).is_some());
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/services/{}", device.ip))
+            .set_json(service_request(sip_uuids))
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 409);
+
+        let req = test::TestRequest::delete()
+            .uri(&format!("/services/{}/{}", device.ip, SERVICE_UUID))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 200);
+        let result: ProvisioningResult = test::read_body_json(resp).await;
+        assert!(result.completed);
+        assert_eq!(result.state, None);
+
+        let snapshot = cache.get(&data_source).await.unwrap();
+        assert_eq!(snapshot.connectivity_services.len(), 7);
+        assert!(snapshot.service(SERVICE_UUID).is_none());
+
+        // The device no longer holds the service
+        let req = test::TestRequest::delete()
+            .uri(&format!("/services/{}/{}", device.ip, SERVICE_UUID))
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 502);
+
+        mock_device.stop().await;
+    }
+}
@@ -0,0 +1,134 @@
+#[cfg(test)]
+mod tests {
+    // Import the necessary structs and enums from your backend models
+    use actix_api::handlers::repository::DataSourceRepository;
+    use actix_api::handlers::requester::DataSource;
+    use actix_api::models::devices::{Auth, BasicAuth, Device, TlsConfig};
+    use actix_api::models::files_model::{Complete, FilesEnum};
+
+    use std::fs;
+    use std::path::PathBuf;
+
+    fn basic_device(ip: &str) -> Device {
+        Device {
+            ip: ip.to_string(),
+            port: Some(18010),
+            auth: Auth::Basic(BasicAuth {
+                username: "tapi".to_string(),
+                password: "2025_T3st".to_string(),
+            }),
+            vendor: None,
+            retrieval_strategy: None,
+            tls: TlsConfig::default(),
+        }
+    }
+
+    /// Creates an empty directory under the system temp dir for a single test.
+    fn test_data_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("tapi_repository_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).expect("Test data dir cannot be created");
+        dir
+    }
+
+    #[tokio::test]
+    async fn test_insert_get_and_remove_device() {
+        let repository = DataSourceRepository::in_memory();
+
+        let id = repository
+            .insert(DataSource::Device(basic_device("10.95.87.21")))
+            .await
+            .expect("Device cannot be inserted");
+        assert_eq!(id, "10.95.87.21");
+
+        match repository.get("10.95.87.21").await.unwrap() {
+            Some(DataSource::Device(device)) => assert_eq!(device, basic_device("10.95.87.21")),
+            _ => panic!("Expected the inserted Device"),
+        }
+        assert_eq!(repository.list().await.unwrap().len(), 1);
+
+        assert!(repository.remove("10.95.87.21").await.unwrap().is_some());
+        assert!(repository.get("10.95.87.21").await.unwrap().is_none());
+        assert!(repository.remove("10.95.87.21").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_remove_file_set_deletes_files() {
+        let repository = DataSourceRepository::in_memory();
+        let dir = test_data_dir("remove");
+        let context_path = dir.join("test_context.json");
+        fs::write(&context_path, "{}").unwrap();
+
+        repository
+            .insert(DataSource::FilesEnum(FilesEnum::Complete(Complete {
+                id: "test".to_string(),
+                complete_context_path: context_path.to_string_lossy().to_string(),
+            })))
+            .await
+            .unwrap();
+
+        assert!(repository.remove("test").await.unwrap().is_some());
+        assert!(!context_path.exists());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_remove_orphaned_files() {
+        let repository = DataSourceRepository::in_memory();
+        let dir = test_data_dir("orphans");
+        let registered_path = dir.join("registered_context.json");
+        let orphan_path = dir.join("orphan_context.json");
+        let gitkeep_path = dir.join(".gitkeep");
+        for path in [&registered_path, &orphan_path, &gitkeep_path] {
+            fs::write(path, "{}").unwrap();
+        }
+
+        repository
+            .insert(DataSource::FilesEnum(FilesEnum::Complete(Complete {
+                id: "registered".to_string(),
+                complete_context_path: registered_path.to_string_lossy().to_string(),
+            })))
+            .await
+            .unwrap();
+
+        let removed = repository.remove_orphaned_files(&dir).await.unwrap();
+
+        assert_eq!(removed, vec![orphan_path.clone()]);
+        assert!(registered_path.exists());
+        assert!(gitkeep_path.exists());
+        assert!(!orphan_path.exists());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    /// The stored index of a registered file is not an orphan, the index of an orphan is
+    #[tokio::test]
+    async fn test_remove_orphaned_files_keeps_indexes() {
+        let repository = DataSourceRepository::in_memory();
+        let dir = test_data_dir("indexes");
+        let registered_path = dir.join("registered_context.json");
+        let registered_index_path = dir.join("registered_context.json.index");
+        let orphan_index_path = dir.join("orphan_context.json.index");
+        for path in [&registered_path, &registered_index_path, &orphan_index_path] {
+            fs::write(path, "{}").unwrap();
+        }
+
+        repository
+            .insert(DataSource::FilesEnum(FilesEnum::Complete(Complete {
+                id: "registered".to_string(),
+                complete_context_path: registered_path.to_string_lossy().to_string(),
+            })))
+            .await
+            .unwrap();
+
+        let removed = repository.remove_orphaned_files(&dir).await.unwrap();
+
+        assert_eq!(removed, vec![orphan_index_path.clone()]);
+        assert!(registered_path.exists());
+        assert!(registered_index_path.exists());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
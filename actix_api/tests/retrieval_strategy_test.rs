@@ -0,0 +1,156 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    // Read a mock device with each retrieval strategy and remember the one it supports
+    use actix_api::handlers::repository::DataSourceRepository;
+    use actix_api::handlers::requester::{DataSource, DeviceHandler};
+    use actix_api::handlers::snapshot::{SnapshotCache, TopologySnapshot};
+    use actix_api::models::devices::{Device, RetrievalStrategy};
+    use actix_api::models::vendor_profile::VendorProfile;
+    use actix_api::routes::{get_services::connectivity_services, probe::probe, refresh::refresh};
+
+    use crate::common::{start_mock_device, start_mock_device_with};
+
+    use actix_web::{test, web, App};
+    use std::time::Duration;
+
+    async fn stored_device(repository: &DataSourceRepository, ip: &str) -> Device {
+        match repository.get(ip).await.unwrap() {
+            Some(DataSource::Device(device)) => device,
+            other => panic!("{} is not a device: {:?}", ip, other),
+        }
+    }
+
+    /// Every strategy reads the same topology and connectivity
+    #[tokio::test]
+    async fn test_every_strategy_reads_the_context() {
+        let mock_device = start_mock_device().await;
+
+        for strategy in RetrievalStrategy::ALL {
+            let mut device = mock_device.device();
+            device.retrieval_strategy = Some(strategy);
+
+            let full_context = DeviceHandler::get_full_context(&device)
+                .await
+                .unwrap_or_else(|err| panic!("{} failed: {}", strategy, err));
+            assert_eq!(full_context.strategy, Some(strategy));
+
+            let snapshot = TopologySnapshot::build(full_context, VendorProfile::default()).unwrap();
+            assert_eq!(snapshot.nodes.len(), 38, "{}", strategy);
+            assert_eq!(snapshot.links.len(), 79, "{}", strategy);
+            assert_eq!(snapshot.connectivity_services.len(), 7, "{}", strategy);
+            assert_eq!(snapshot.strategy, Some(strategy));
+
+            let (services, read_with) = DeviceHandler::get_services(&device).await.unwrap();
+            assert_eq!(services.len(), 7, "{}", strategy);
+            assert_eq!(read_with, strategy);
+        }
+
+        mock_device.stop().await;
+    }
+
+    /// Devices rejecting `fields` and `depth` are read through the complete context, even when
+    /// another strategy was remembered
+    #[tokio::test]
+    async fn test_strategy_falls_back_without_query_parameters() {
+        let mock_device =
+            start_mock_device_with(|config| config.supports_query_parameters = false).await;
+
+        let full_context = DeviceHandler::get_full_context(&mock_device.device())
+            .await
+            .unwrap();
+        assert_eq!(full_context.strategy, Some(RetrievalStrategy::FullContext));
+
+        let mut device = mock_device.device();
+        device.retrieval_strategy = Some(RetrievalStrategy::PerUuid);
+        let full_context = DeviceHandler::get_full_context(&device).await.unwrap();
+        assert_eq!(full_context.strategy, Some(RetrievalStrategy::FullContext));
+        let (services, read_with) = DeviceHandler::get_services(&device).await.unwrap();
+        assert_eq!(services.len(), 7);
+        assert_eq!(read_with, RetrievalStrategy::FullContext);
+
+        mock_device.stop().await;
+    }
+
+    /// The probe chooses a strategy from the supported URIs, and a snapshot replaces it when the
+    /// responses turn out to be too big
+    #[actix_web::test]
+    async fn test_strategy_is_remembered() {
+        let mock_device =
+            start_mock_device_with(|config| config.max_response_bytes = Some(500_000)).await;
+        let device = mock_device.device();
+
+        let repository = DataSourceRepository::in_memory();
+        repository
+            .insert(DataSource::Device(device.clone()))
+            .await
+            .unwrap();
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(repository.clone()))
+                .app_data(web::Data::new(SnapshotCache::new(Duration::from_secs(300))))
+                .service(probe)
+                .service(refresh),
+        )
+        .await;
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/probe/{}", device.ip))
+            .to_request();
+        assert!(test::call_service(&app, req).await.status().is_success());
+        assert_eq!(
+            stored_device(&repository, &device.ip)
+                .await
+                .retrieval_strategy,
+            Some(RetrievalStrategy::FieldsFiltered)
+        );
+
+        // The complete topology is too big, so only the per-UUID requests succeed
+        let req = test::TestRequest::post()
+            .uri(&format!("/refresh/{}", device.ip))
+            .to_request();
+        assert!(test::call_service(&app, req).await.status().is_success());
+        assert_eq!(
+            stored_device(&repository, &device.ip)
+                .await
+                .retrieval_strategy,
+            Some(RetrievalStrategy::PerUuid)
+        );
+
+        mock_device.stop().await;
+    }
+
+    /// Listing the services remembers the strategy they were read with
+    #[actix_web::test]
+    async fn test_services_remember_the_strategy() {
+        let mock_device =
+            start_mock_device_with(|config| config.supports_query_parameters = false).await;
+        let device = mock_device.device();
+
+        let repository = DataSourceRepository::in_memory();
+        repository
+            .insert(DataSource::Device(device.clone()))
+            .await
+            .unwrap();
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(repository.clone()))
+                .service(connectivity_services),
+        )
+        .await;
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/get_services/{}", device.ip))
+            .to_request();
+        assert!(test::call_service(&app, req).await.status().is_success());
+        assert_eq!(
+            stored_device(&repository, &device.ip)
+                .await
+                .retrieval_strategy,
+            Some(RetrievalStrategy::FullContext)
+        );
+
+        mock_device.stop().await;
+    }
+}
@@ -0,0 +1,246 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    // Import the snapshot cache, the routes that use it and the mock device
+    use actix_api::handlers::repository::DataSourceRepository;
+    use actix_api::handlers::requester::FullContext;
+    use actix_api::handlers::requester::{DataSource, Requester};
+    use actix_api::handlers::snapshot::{SnapshotCache, TopologySnapshot};
+    use actix_api::models::files_model::FilesEnum;
+    use actix_api::models::vendor_profile::VendorProfile;
+    use actix_api::routes::{
+        get_schema::schema_by_service, get_topology::topology_by_id, refresh::refresh,
+    };
+    use actix_api::AppError;
+
+    use crate::common::start_mock_device;
+
+    use actix_web::{test, web, App};
+    use serde_json::{json, Value};
+    use std::collections::HashMap;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    const DEVICES_SERVICE_UUID: &str = "00000000-0000-0000-0000-000000000009";
+
+    fn devices_files() -> DataSource {
+        DataSource::FilesEnum(
+            serde_json::from_value::<FilesEnum>(json!({
+                "id": "devices_files",
+                "topology_path": "devices_files/topology.json",
+                "connections_path": "devices_files/connections.json",
+                "connectivity_services_path": "devices_files/connectivity_services.json"
+            }))
+            .unwrap(),
+        )
+    }
+
+    /// The snapshot of a device is reused until it is refreshed or invalidated
+    #[tokio::test]
+    async fn test_device_snapshot_is_cached() {
+        let mock_device = start_mock_device().await;
+        let data_source = DataSource::Device(mock_device.device());
+        let cache = SnapshotCache::new(Duration::from_secs(300));
+
+        let first = cache.get(&data_source).await.unwrap();
+        assert_eq!(first.nodes.len(), 38);
+        assert_eq!(first.links.len(), 79);
+        assert_eq!(first.connectivity_services.len(), 7);
+        assert!(first.service(DEVICES_SERVICE_UUID).is_some());
+
+        let cached = cache.get(&data_source).await.unwrap();
+        assert!(Arc::ptr_eq(&first, &cached));
+
+        let refreshed = cache.refresh(&data_source).await.unwrap();
+        assert!(!Arc::ptr_eq(&first, &refreshed));
+        assert!(Arc::ptr_eq(
+            &refreshed,
+            &cache.get(&data_source).await.unwrap()
+        ));
+
+        cache.invalidate(data_source.id()).await;
+        assert!(!Arc::ptr_eq(
+            &refreshed,
+            &cache.get(&data_source).await.unwrap()
+        ));
+
+        mock_device.stop().await;
+    }
+
+    #[tokio::test]
+    async fn test_expired_snapshot_is_taken_again() {
+        let data_source = devices_files();
+        let cache = SnapshotCache::new(Duration::ZERO);
+
+        let first = cache.get(&data_source).await.unwrap();
+        let second = cache.get(&data_source).await.unwrap();
+
+        assert!(!Arc::ptr_eq(&first, &second));
+        assert!(second.taken_at >= first.taken_at);
+    }
+
+    #[actix_web::test]
+    async fn test_schema_and_refresh_routes() {
+        let repository = DataSourceRepository::in_memory();
+        repository.insert(devices_files()).await.unwrap();
+        let cache = SnapshotCache::new(Duration::from_secs(300));
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(repository.clone()))
+                .app_data(web::Data::new(cache.clone()))
+                .service(schema_by_service)
+                .service(refresh),
+        )
+        .await;
+
+        let req = test::TestRequest::get()
+            .uri(&format!(
+                "/get_schema/devices_files/{}",
+                DEVICES_SERVICE_UUID
+            ))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert!(resp.status().is_success());
+        let header = resp
+            .headers()
+            .get("X-Snapshot-Timestamp")
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .to_string();
+        let schema: Value = test::read_body_json(resp).await;
+        assert_eq!(schema["snapshot_timestamp"], header);
+        assert_eq!(schema["uuid"], DEVICES_SERVICE_UUID);
+        assert_eq!(schema["value_name"], "2c-MC");
+
+        let req = test::TestRequest::get()
+            .uri("/get_schema/devices_files/missing")
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 404);
+
+        let req = test::TestRequest::post()
+            .uri("/refresh/devices_files")
+            .to_request();
+        let refreshed: Value = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(refreshed["id"], "devices_files");
+        assert_eq!(refreshed["nodes"], 38);
+        assert_eq!(refreshed["links"], 79);
+        assert_eq!(refreshed["connectivity_services"], 7);
+
+        let req = test::TestRequest::post()
+            .uri("/refresh/unknown")
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 404);
+    }
+
+    /// The whole network is returned with the edge points of each node grouped by inventory
+    #[actix_web::test]
+    async fn test_topology_route() {
+        let repository = DataSourceRepository::in_memory();
+        repository.insert(devices_files()).await.unwrap();
+        let cache = SnapshotCache::new(Duration::from_secs(300));
+        let snapshot = cache.get(&devices_files()).await.unwrap();
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(repository.clone()))
+                .app_data(web::Data::new(cache.clone()))
+                .service(topology_by_id),
+        )
+        .await;
+
+        let req = test::TestRequest::get()
+            .uri("/get_topology/devices_files")
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert!(resp.status().is_success());
+        let header = resp
+            .headers()
+            .get("X-Snapshot-Timestamp")
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .to_string();
+        let topology: Value = test::read_body_json(resp).await;
+        assert_eq!(topology["snapshot_timestamp"], header);
+
+        let nodes = topology["nodes"].as_array().unwrap();
+        let links = topology["links"].as_array().unwrap();
+        assert_eq!(nodes.len(), 38);
+        assert_eq!(links.len(), 79);
+
+        // Every owned edge point is listed once, in the inventory it belongs to
+        let mut inventories: HashMap<String, String> = HashMap::new();
+        for node in nodes {
+            let inventory_ids: Vec<&str> = node["inventories"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|inventory| inventory["inventory_id"].as_str().unwrap())
+                .collect();
+            let mut unique_ids = inventory_ids.clone();
+            unique_ids.sort_unstable();
+            unique_ids.dedup();
+            assert_eq!(unique_ids.len(), inventory_ids.len());
+            for inventory in node["inventories"].as_array().unwrap() {
+                for node_edge_point in inventory["node_edge_points"].as_array().unwrap() {
+                    inventories.insert(
+                        node_edge_point["node_edge_point_uuid"]
+                            .as_str()
+                            .unwrap()
+                            .to_string(),
+                        inventory["inventory_id"].as_str().unwrap().to_string(),
+                    );
+                }
+            }
+        }
+        let owned: usize = snapshot
+            .nodes
+            .iter()
+            .map(|node| node.owned_node_edge_points.len())
+            .sum();
+        assert_eq!(inventories.len(), owned);
+
+        // Link ends carry the inventory of their edge point
+        for link in links {
+            for node_edge_point in link["node_edge_points"].as_array().unwrap() {
+                assert!(nodes
+                    .iter()
+                    .any(|node| node["node_uuid"] == node_edge_point["node_uuid"]));
+                if let Some(inventory_id) = node_edge_point["inventory_id"].as_str() {
+                    let uuid = node_edge_point["node_edge_point_uuid"].as_str().unwrap();
+                    assert_eq!(inventories[uuid], inventory_id);
+                }
+            }
+        }
+
+        let req = test::TestRequest::get()
+            .uri("/get_topology/unknown")
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 404);
+    }
+
+    /// Objects that do not match the TAPI model are reported as model errors
+    #[tokio::test]
+    async fn test_invalid_context_is_a_model_error() {
+        let mut wrong_link: FullContext =
+            Requester::get_full_context(&devices_files()).await.unwrap();
+        wrong_link.topology[0]["link"][0]["uuid"] = json!("link-1");
+        match TopologySnapshot::build(wrong_link, VendorProfile::default()) {
+            Err(AppError::ModelError(message)) => assert!(message.contains("topology")),
+            other => panic!("Expected a model error, got {:?}", other.map(|_| ())),
+        }
+
+        let mut wrong_service = Requester::get_full_context(&devices_files()).await.unwrap();
+        wrong_service.connectivity_services[0]
+            .as_object_mut()
+            .unwrap()
+            .remove("uuid");
+        assert!(matches!(
+            TopologySnapshot::build(wrong_service, VendorProfile::default()),
+            Err(AppError::ModelError(_))
+        ));
+    }
+}
@@ -0,0 +1,143 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    // Verify the certificate of a mock device, or not, according to the TLS settings of the device
+    use actix_api::handlers::repository::DataSourceRepository;
+    use actix_api::handlers::requester::DeviceHandler;
+    use actix_api::handlers::snapshot::SnapshotCache;
+    use actix_api::models::devices::{Scheme, TlsConfig};
+    use actix_api::routes::{add_host::add_host, get_hosts::get_hosts};
+
+    use crate::common::{start_mock_device, start_mock_device_with};
+
+    use actix_web::{test, web, App};
+    use serde_json::{json, Value};
+    use std::time::Duration;
+
+    /// Certificates are verified unless the device opts into insecure TLS
+    #[tokio::test]
+    async fn test_untrusted_certificate_is_rejected() {
+        let mock_device = start_mock_device().await;
+
+        let mut device = mock_device.device();
+        device.tls = TlsConfig::default();
+        assert!(DeviceHandler::get_services(&device).await.is_err());
+
+        device.tls.insecure = true;
+        assert_eq!(
+            DeviceHandler::get_services(&device).await.unwrap().0.len(),
+            7
+        );
+
+        // Trusting the CA of the mock is enough without the opt-in
+        assert_eq!(
+            DeviceHandler::get_services(&mock_device.device())
+                .await
+                .unwrap()
+                .0
+                .len(),
+            7
+        );
+
+        mock_device.stop().await;
+    }
+
+    /// Devices registered with the `http` scheme are requested without TLS
+    #[tokio::test]
+    async fn test_http_scheme() {
+        let mock_device = start_mock_device().await;
+
+        let mut device = mock_device.device();
+        device.tls = TlsConfig {
+            scheme: Scheme::Http,
+            ..TlsConfig::default()
+        };
+        assert!(device.get_device_base_url().starts_with("http://"));
+        assert_eq!(
+            DeviceHandler::get_services(&device).await.unwrap().0.len(),
+            7
+        );
+
+        mock_device.stop().await;
+    }
+
+    /// Devices requiring mutual TLS only answer clients presenting their certificate
+    #[tokio::test]
+    async fn test_client_certificate() {
+        let mock_device =
+            start_mock_device_with(|config| config.require_client_certificate = true).await;
+
+        let mut device = mock_device.device();
+        assert!(DeviceHandler::get_services(&device).await.is_err());
+
+        let (client_certificate, client_key) = mock_device.client_identity();
+        device.tls.client_certificate = Some(client_certificate);
+        device.tls.client_key = Some(client_key);
+        assert_eq!(
+            DeviceHandler::get_services(&device).await.unwrap().0.len(),
+            7
+        );
+
+        mock_device.stop().await;
+    }
+
+    /// Unreadable certificates are rejected when the host is added, and the client key is never
+    /// listed back
+    #[actix_web::test]
+    async fn test_add_host_tls() {
+        let mock_device = start_mock_device().await;
+        let (client_certificate, client_key) = mock_device.client_identity();
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(DataSourceRepository::in_memory()))
+                .app_data(web::Data::new(SnapshotCache::new(Duration::from_secs(300))))
+                .service(add_host)
+                .service(get_hosts),
+        )
+        .await;
+
+        let device = |tls: Value| {
+            json!({
+                "ip": "127.0.0.1",
+                "port": mock_device.port(),
+                "auth": {"username": "tapi", "password": "2025_T3st"},
+                "tls": tls,
+            })
+        };
+
+        let req = test::TestRequest::post()
+            .uri("/add_host")
+            .set_json(device(json!({"ca_certificate": "not a certificate"})))
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 400);
+
+        let req = test::TestRequest::post()
+            .uri("/add_host")
+            .set_json(device(json!({"client_certificate": client_certificate})))
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 400);
+
+        let req = test::TestRequest::post()
+            .uri("/add_host")
+            .set_json(device(json!({
+                "ca_certificate": mock_device.ca_certificate(),
+                "client_certificate": client_certificate,
+                "client_key": client_key,
+                "insecure": true,
+            })))
+            .to_request();
+        assert!(test::call_service(&app, req).await.status().is_success());
+
+        let req = test::TestRequest::get().uri("/get_hosts").to_request();
+        let hosts: Value = test::call_and_read_body_json(&app, req).await;
+        let tls = &hosts[0]["tls"];
+        assert_eq!(tls["scheme"], "https");
+        assert_eq!(tls["insecure"], true);
+        assert!(tls["client_certificate"].is_string());
+        assert!(tls.get("client_key").is_none());
+
+        mock_device.stop().await;
+    }
+}
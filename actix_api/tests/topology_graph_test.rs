@@ -0,0 +1,464 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    // Index the topology and connections of a mock device and trace its services over the graph
+    use actix_api::handlers::requester::DataSource;
+    use actix_api::handlers::snapshot::TopologySnapshot;
+    use actix_api::logic::schema_builder::build_schema;
+    use actix_api::models::connectivity_services::Service;
+    use actix_api::models::endpoint::Endpoint;
+    use actix_api::models::schema::{LayerResponse, TraceLayer, TraceWarning, TraceWarningKind};
+    use actix_api::models::topology_graph::TopologyGraph;
+
+    use crate::common::start_mock_device;
+
+    use serde_json::Value;
+    use std::collections::HashMap;
+    use std::sync::Arc;
+    use tapi_model::common::LayerProtocolName;
+
+    async fn take_snapshot() -> TopologySnapshot {
+        let mock_device = start_mock_device().await;
+        let snapshot = TopologySnapshot::take(&DataSource::Device(mock_device.device()))
+            .await
+            .unwrap();
+        mock_device.stop().await;
+        snapshot
+    }
+
+    /// Every index returns what scanning the vectors finds, in the same order
+    #[tokio::test]
+    async fn test_indexes_match_scans() {
+        let snapshot = take_snapshot().await;
+        let graph = &snapshot.graph;
+        assert_eq!(graph.nodes().len(), 38);
+        assert_eq!(graph.links().len(), 79);
+
+        for link in graph.links() {
+            assert!(graph
+                .links_by_uuid(link.link_uuid)
+                .any(|found| found.link_uuid == link.link_uuid));
+            for node_edge_point in &link.node_edge_points {
+                let indexed: Vec<_> = graph
+                    .links_of(node_edge_point.node_edge_point_uuid)
+                    .map(|link| link.link_uuid)
+                    .collect();
+                let scanned: Vec<_> = graph
+                    .links()
+                    .iter()
+                    .filter(|link| {
+                        link.node_edge_points.iter().any(|other| {
+                            other.node_edge_point_uuid == node_edge_point.node_edge_point_uuid
+                        })
+                    })
+                    .map(|link| link.link_uuid)
+                    .collect();
+                assert_eq!(indexed, scanned);
+            }
+        }
+
+        for connection in graph.connections() {
+            for connection_end_point in &connection.connection_end_points {
+                let indexed: Vec<_> = graph
+                    .connections_of(connection_end_point.node_edge_point_uuid)
+                    .map(|connection| connection.connection_uuid)
+                    .collect();
+                assert!(indexed.contains(&connection.connection_uuid));
+            }
+            for lower_connection in &connection.lower_connections {
+                assert!(graph
+                    .upper_connections(lower_connection.connection_uuid)
+                    .any(|upper| upper.connection_uuid == connection.connection_uuid));
+            }
+            assert_eq!(
+                graph
+                    .connections_by_uuid(connection.connection_uuid)
+                    .next()
+                    .map(|found| found.connection_uuid),
+                Some(connection.connection_uuid)
+            );
+        }
+
+        for node in graph.nodes() {
+            assert!(graph
+                .nodes_by_uuid(node.node_uuid)
+                .any(|found| found.node_uuid == node.node_uuid));
+            for owned_node_edge_point in &node.owned_node_edge_points {
+                let found = graph
+                    .owned_node_edge_point(
+                        node.node_uuid,
+                        owned_node_edge_point.node_edge_point_uuid,
+                    )
+                    .unwrap();
+                assert_eq!(found.inventory_id, owned_node_edge_point.inventory_id);
+                for connection_end_point in &owned_node_edge_point.connection_end_points {
+                    let clients = graph
+                        .client_node_edge_points(connection_end_point.connection_end_point_uuid);
+                    assert_eq!(
+                        clients.len(),
+                        connection_end_point.client_node_edge_points.len()
+                    );
+                }
+                if let Some(client) = owned_node_edge_point
+                    .connection_end_points
+                    .first()
+                    .and_then(|connection_end_point| {
+                        connection_end_point.client_node_edge_points.first()
+                    })
+                {
+                    assert!(graph
+                        .inventory_nodes(client.node_uuid, client.node_edge_point_uuid)
+                        .iter()
+                        .any(|parent| parent.node_uuid == node.node_uuid));
+                }
+            }
+        }
+
+        let unknown = "00000000-0000-0000-0000-000000000000".parse().unwrap();
+        assert_eq!(graph.links_by_uuid(unknown).count(), 0);
+        assert_eq!(graph.links_of(unknown).count(), 0);
+        assert_eq!(graph.connections_of(unknown).count(), 0);
+        assert!(graph.client_node_edge_points(unknown).is_empty());
+    }
+
+    /// Services traced over the graph keep every endpoint of their connections
+    #[tokio::test]
+    async fn test_trace_over_graph() {
+        let snapshot = take_snapshot().await;
+        assert_eq!(snapshot.connectivity_services.len(), 7);
+
+        let mut traced_services = 0;
+
+        for connectivity_service in snapshot.connectivity_services.iter() {
+            let service = Service::connectivity_service_build(
+                connectivity_service,
+                &snapshot.connections,
+                &snapshot.profile,
+            );
+            let schema = build_schema(&service, &snapshot.graph).unwrap();
+            let endpoints: Vec<&Value> = schema["nodes"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .flat_map(|node| node["inventories"].as_array().unwrap())
+                .flat_map(|inventory| inventory["endpoints"].as_array().unwrap())
+                .collect();
+            // One of the fixture services traces no endpoint
+            if endpoints.is_empty() {
+                continue;
+            }
+            traced_services += 1;
+
+            // Edge points are traced once each
+            let mut node_edge_points: Vec<&str> = endpoints
+                .iter()
+                .map(|endpoint| endpoint["node_edge_point_uuid"].as_str().unwrap())
+                .collect();
+            let traced = node_edge_points.len();
+            node_edge_points.sort_unstable();
+            node_edge_points.dedup();
+            assert_eq!(node_edge_points.len(), traced);
+
+            // The edge points of the service end points are part of the trace
+            for end_point in &service.end_points {
+                for connection_end_point in &end_point.connection_end_points {
+                    let uuid = connection_end_point.node_edge_point_uuid.to_string();
+                    assert!(node_edge_points.contains(&uuid.as_str()));
+                }
+            }
+        }
+        assert_eq!(traced_services, 6);
+    }
+
+    /// The route is numbered from the first end point of the service, whichever order the trace found the endpoints in
+    #[tokio::test]
+    async fn test_route_order() {
+        let snapshot = take_snapshot().await;
+
+        for connectivity_service in snapshot.connectivity_services.iter() {
+            let mut service = Service::connectivity_service_build(
+                connectivity_service,
+                &snapshot.connections,
+                &snapshot.profile,
+            );
+            let mut traces = vec![];
+            for _ in 0..2 {
+                let schema = build_schema(&service, &snapshot.graph).unwrap();
+                let route = schema["route"].as_array().unwrap();
+                let Some(first) = route.first() else {
+                    continue;
+                };
+
+                // Hops are numbered in order, and the endpoints of the schema carry the same numbers
+                let hops: Vec<i64> = route
+                    .iter()
+                    .map(|hop| hop["hop"].as_i64().unwrap())
+                    .collect();
+                assert_eq!(hops, (1..=route.len() as i64).collect::<Vec<_>>());
+                for node in schema["nodes"].as_array().unwrap() {
+                    for inventory in node["inventories"].as_array().unwrap() {
+                        for endpoint in inventory["endpoints"].as_array().unwrap() {
+                            let hop = &route[endpoint["id"].as_u64().unwrap() as usize - 1];
+                            assert_eq!(
+                                hop["node_edge_point_uuid"],
+                                endpoint["node_edge_point_uuid"]
+                            );
+                            assert_eq!(hop["direction"], endpoint["direction"]);
+                        }
+                    }
+                }
+
+                // The route enters the network at the A-end
+                let a_end = service.end_points[0].connection_end_points[0]
+                    .node_edge_point_uuid
+                    .to_string();
+                assert_eq!(first["node_edge_point_uuid"], a_end.as_str());
+                assert_eq!(first["direction"], "ingress");
+                assert_eq!(schema["nodes"][0]["node_uuid"], first["node_uuid"]);
+
+                // Tracing again gives the same schema
+                assert_eq!(build_schema(&service, &snapshot.graph).unwrap(), schema);
+
+                // Starting from the other end visits the same edge points
+                let mut node_edge_points: Vec<String> = route
+                    .iter()
+                    .map(|hop| hop["node_edge_point_uuid"].to_string())
+                    .collect();
+                node_edge_points.sort();
+                traces.push(node_edge_points);
+                service.end_points.reverse();
+            }
+            if let [forward, backward] = traces.as_slice() {
+                assert_eq!(forward, backward);
+            }
+        }
+    }
+
+    /// Each layer lists its connections once, carried by connections of the same or a lower layer
+    #[tokio::test]
+    async fn test_layers() {
+        let snapshot = take_snapshot().await;
+
+        let mut layer_names = vec![];
+        for connectivity_service in snapshot.connectivity_services.iter() {
+            let service = Service::connectivity_service_build(
+                connectivity_service,
+                &snapshot.connections,
+                &snapshot.profile,
+            );
+            let schema = build_schema(&service, &snapshot.graph).unwrap();
+            let Some(layers) = schema["layers"].as_array() else {
+                continue;
+            };
+            let layers: Vec<LayerResponse> = layers
+                .iter()
+                .map(|layer| serde_json::from_value(layer.clone()).unwrap())
+                .collect();
+
+            // Layers come from the client layer down, once each
+            assert!(layers.windows(2).all(|pair| pair[0].layer < pair[1].layer));
+
+            let mut connection_layers = HashMap::new();
+            for layer in &layers {
+                for connection in &layer.connections {
+                    assert!(connection_layers
+                        .insert(connection.connection_uuid, layer.layer)
+                        .is_none());
+                }
+            }
+            for layer in &layers {
+                for connection in &layer.connections {
+                    for lower_connection in &connection.lower_connections {
+                        assert!(connection_layers[lower_connection] >= layer.layer);
+                    }
+                    for connection_end_point in &connection.connection_end_points {
+                        if let Some(hop) = connection_end_point.hop {
+                            assert_eq!(
+                                schema["route"][hop as usize - 1]["node_edge_point_uuid"],
+                                connection_end_point.node_edge_point_uuid.to_string()
+                            );
+                        }
+                    }
+                }
+            }
+            if service.name == "ODU silver" {
+                layer_names = layers.iter().map(|layer| layer.layer).collect();
+            }
+        }
+        assert_eq!(
+            layer_names,
+            vec![
+                TraceLayer::Dsr,
+                TraceLayer::Odu,
+                TraceLayer::Otsi,
+                TraceLayer::Mc,
+                TraceLayer::OmsOts
+            ]
+        );
+
+        assert_eq!(
+            TraceLayer::of(
+                Some(LayerProtocolName::PhotonicMedia),
+                "tapi-photonic-media:PHOTONIC_LAYER_QUALIFIER_OTSiMC"
+            ),
+            TraceLayer::Mc
+        );
+        assert_eq!(
+            TraceLayer::of(
+                Some(LayerProtocolName::PhotonicMedia),
+                "tapi-common:LAYER_PROTOCOL_QUALIFIER_UNSPECIFIED"
+            ),
+            TraceLayer::OmsOts
+        );
+        assert_eq!(TraceLayer::of(None, ""), TraceLayer::Unknown);
+    }
+
+    /// Anomalies of the topology are returned with the schema, on the edge point they were found on
+    #[tokio::test]
+    async fn test_trace_warnings() {
+        let snapshot = take_snapshot().await;
+        let services: HashMap<String, _> = snapshot
+            .connectivity_services
+            .iter()
+            .map(|connectivity_service| {
+                let service = Service::connectivity_service_build(
+                    connectivity_service,
+                    &snapshot.connections,
+                    &snapshot.profile,
+                );
+                (service.name.clone(), service)
+            })
+            .collect();
+        let trace = |graph: &TopologyGraph, name: &str| -> (Vec<Endpoint>, Vec<TraceWarning>) {
+            let schema = build_schema(&services[name], graph).unwrap();
+            let endpoints = schema["nodes"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .flat_map(|node| node["inventories"].as_array().unwrap())
+                .flat_map(|inventory| inventory["endpoints"].as_array().unwrap())
+                .map(|endpoint| serde_json::from_value(endpoint.clone()).unwrap())
+                .collect();
+            let warnings = schema
+                .get("warnings")
+                .map(|warnings| serde_json::from_value(warnings.clone()).unwrap())
+                .unwrap_or_default();
+            (endpoints, warnings)
+        };
+        let corrupted = |edit: &dyn Fn(&mut Vec<_>, &mut Vec<_>, &mut Vec<_>)| {
+            let mut nodes = snapshot.graph.nodes().to_vec();
+            let mut links = snapshot.graph.links().to_vec();
+            let mut connections = snapshot.graph.connections().to_vec();
+            edit(&mut nodes, &mut links, &mut connections);
+            TopologyGraph::new(Arc::new(nodes), Arc::new(links), Arc::new(connections))
+        };
+
+        // A consistent trace has no warnings
+        let (endpoints, warnings) = trace(&snapshot.graph, "ODU silver");
+        assert!(warnings.is_empty());
+
+        // The optical channels share a connection with each other, which is left out of the trace
+        let (_, warnings) = trace(&snapshot.graph, "OCH-UC9-1_UC9-2-192.1-74");
+        assert_eq!(warnings.len(), 2);
+        assert!(warnings.iter().all(|warning| {
+            warning.kind == TraceWarningKind::ForeignConnection && warning.connection_uuid.is_some()
+        }));
+
+        // An edge point without inventory name
+        let a_end = endpoints.iter().find(|endpoint| endpoint.id == 1).unwrap();
+        let graph = corrupted(&|nodes, _, _| {
+            for node in nodes.iter_mut() {
+                for owned_node_edge_point in &mut node.owned_node_edge_points {
+                    if owned_node_edge_point.node_edge_point_uuid == a_end.node_edge_point_uuid {
+                        owned_node_edge_point.inventory_id = "UNKNOWN".to_string();
+                    }
+                }
+            }
+        });
+        let (_, warnings) = trace(&graph, "ODU silver");
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].kind, TraceWarningKind::MissingInventory);
+        assert_eq!(warnings[0].node_edge_point_uuid, a_end.node_edge_point_uuid);
+
+        // A connection end point serving two clients
+        let served = endpoints
+            .iter()
+            .find(|endpoint| endpoint.client_node_edge_point_uuid.is_some())
+            .unwrap();
+        let graph = corrupted(&|nodes, _, _| {
+            for node in nodes.iter_mut() {
+                for owned_node_edge_point in &mut node.owned_node_edge_points {
+                    for connection_end_point in &mut owned_node_edge_point.connection_end_points {
+                        if Some(connection_end_point.connection_end_point_uuid)
+                            == served.connection_end_point_uuid
+                        {
+                            let client = connection_end_point.client_node_edge_points[0].clone();
+                            connection_end_point.client_node_edge_points.push(client);
+                        }
+                    }
+                }
+            }
+        });
+        let (_, warnings) = trace(&graph, "ODU silver");
+        assert!(warnings.iter().any(|warning| {
+            warning.kind == TraceWarningKind::MultipleClientNodeEdgePoints
+                && warning.node_edge_point_uuid == served.node_edge_point_uuid
+        }));
+
+        // A connection ending on an edge point no node owns
+        let dangling = "00000000-0000-0000-0000-000000000001".parse().unwrap();
+        let graph = corrupted(&|_, _, connections| {
+            for connection in connections.iter_mut() {
+                if Some(connection.connection_uuid) == a_end.connection_uuid {
+                    let mut connection_end_point = connection.connection_end_points[0].clone();
+                    connection_end_point.node_edge_point_uuid = dangling;
+                    connection_end_point.connection_end_point_uuid = dangling;
+                    connection.connection_end_points.push(connection_end_point);
+                }
+            }
+        });
+        let (endpoints_with_dangling, warnings) = trace(&graph, "ODU silver");
+        assert!(warnings.iter().any(|warning| {
+            warning.kind == TraceWarningKind::DanglingNodeEdgePoint
+                && warning.node_edge_point_uuid == dangling
+        }));
+        assert!(endpoints_with_dangling
+            .iter()
+            .any(|endpoint| endpoint.node_edge_point_uuid == dangling));
+
+        // A link ending on an edge point missing from its node, and a link with a single end
+        let linked = endpoints
+            .iter()
+            .filter(|endpoint| endpoint.link_uuid.is_some())
+            .min_by_key(|endpoint| endpoint.id)
+            .unwrap();
+        let graph = corrupted(&|nodes, _, _| {
+            for node in nodes.iter_mut() {
+                node.owned_node_edge_points.retain(|owned_node_edge_point| {
+                    owned_node_edge_point.node_edge_point_uuid != linked.node_edge_point_uuid
+                });
+            }
+        });
+        let (_, warnings) = trace(&graph, "ODU silver");
+        assert!(warnings.iter().any(|warning| {
+            warning.kind == TraceWarningKind::LinkEndMissing
+                && warning.node_edge_point_uuid == linked.node_edge_point_uuid
+                && warning.link_uuid == linked.link_uuid
+        }));
+        let graph = corrupted(&|_, links, _| {
+            for link in links.iter_mut() {
+                if Some(link.link_uuid) == linked.link_uuid {
+                    link.node_edge_points.retain(|node_edge_point| {
+                        node_edge_point.node_edge_point_uuid == linked.node_edge_point_uuid
+                    });
+                }
+            }
+        });
+        let (_, warnings) = trace(&graph, "ODU silver");
+        assert!(warnings.iter().any(|warning| {
+            warning.kind == TraceWarningKind::LinkEndMissing
+                && warning.node_edge_point_uuid == linked.node_edge_point_uuid
+        }));
+    }
+}
@@ -0,0 +1,403 @@
+#[cfg(test)]
+mod tests {
+    // Upload file sets through /upload_services and check the validation report
+    use actix_api::handlers::repository::DataSourceRepository;
+    use actix_api::handlers::requester::{DataSource, Requester};
+    use actix_api::handlers::snapshot::{SnapshotCache, TopologySnapshot};
+    use actix_api::mock::restconf::to_xml;
+    use actix_api::models::files_model::{ByPart, FilesEnum};
+    use actix_api::models::vendor_profile::VendorProfile;
+    use actix_api::routes::by_files::upload_services;
+
+    use actix_web::{test, web, App};
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+    use serde_json::{json, Value};
+    use std::fs;
+    use std::io::{Cursor, Write};
+    use std::path::Path;
+    use std::time::Duration;
+    use zip::write::SimpleFileOptions;
+
+    const BOUNDARY: &str = "tapi-upload-boundary";
+
+    /// Builds a multipart body with the metadata and the given files.
+    fn multipart_body(id: &str, files: &[(&str, Vec<u8>)]) -> Vec<u8> {
+        let mut body = Vec::new();
+        body.extend_from_slice(
+            format!(
+                "--{}\r\nContent-Disposition: form-data; name=\"json\"\r\nContent-Type: application/json\r\n\r\n{}\r\n",
+                BOUNDARY,
+                json!({"id": id})
+            )
+            .as_bytes(),
+        );
+        for (name, content) in files {
+            body.extend_from_slice(
+                format!(
+                    "--{}\r\nContent-Disposition: form-data; name=\"{}\"; filename=\"{}.json\"\r\nContent-Type: application/json\r\n\r\n",
+                    BOUNDARY, name, name
+                )
+                .as_bytes(),
+            );
+            body.extend_from_slice(content);
+            body.extend_from_slice(b"\r\n");
+        }
+        body.extend_from_slice(format!("--{}--\r\n", BOUNDARY).as_bytes());
+        body
+    }
+
+    async fn upload(
+        repository: &DataSourceRepository,
+        id: &str,
+        files: &[(&str, Vec<u8>)],
+    ) -> (u16, Value) {
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(repository.clone()))
+                .app_data(web::Data::new(SnapshotCache::new(Duration::from_secs(300))))
+                .service(upload_services),
+        )
+        .await;
+        let req = test::TestRequest::post()
+            .uri("/upload_services")
+            .insert_header((
+                "Content-Type",
+                format!("multipart/form-data; boundary={}", BOUNDARY),
+            ))
+            .set_payload(multipart_body(id, files))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        let status = resp.status().as_u16();
+        (status, test::read_body_json(resp).await)
+    }
+
+    fn fixture(name: &str) -> Vec<u8> {
+        fs::read(format!("devices_files/{}", name)).unwrap()
+    }
+
+    fn gzip(content: &[u8]) -> Vec<u8> {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(content).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    fn zip(members: &[(&str, Vec<u8>)]) -> Vec<u8> {
+        let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, content) in members {
+            writer
+                .start_file(*name, SimpleFileOptions::default())
+                .unwrap();
+            writer.write_all(content).unwrap();
+        }
+        writer.finish().unwrap().into_inner()
+    }
+
+    fn tar_gz(members: &[(&str, Vec<u8>)]) -> Vec<u8> {
+        let mut builder = tar::Builder::new(GzEncoder::new(Vec::new(), Compression::default()));
+        for (name, content) in members {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(content.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder
+                .append_data(&mut header, name, content.as_slice())
+                .unwrap();
+        }
+        builder.into_inner().unwrap().finish().unwrap()
+    }
+
+    #[actix_web::test]
+    async fn test_upload_by_part_report() {
+        let repository = DataSourceRepository::in_memory();
+        let (status, body) = upload(
+            &repository,
+            "upload_by_part",
+            &[
+                ("topology_file", fixture("topology.json")),
+                ("connections_file", fixture("connections.json")),
+                (
+                    "connectivity_services_file",
+                    fixture("connectivity_services.json"),
+                ),
+            ],
+        )
+        .await;
+
+        assert_eq!(status, 200);
+        let report = &body["report"];
+        assert_eq!(report["valid"], true);
+        assert_eq!(report["counts"]["nodes"], 38);
+        assert_eq!(report["counts"]["links"], 79);
+        assert_eq!(report["counts"]["connectivity_services"], 7);
+        assert_eq!(report["vendor_profile"], "generic");
+        // Some links of the fixtures end in nodes of other topologies
+        let dangling = report["dangling_references"].as_array().unwrap();
+        assert!(!dangling.is_empty());
+        assert!(dangling
+            .iter()
+            .all(|reference| reference["source"] == "link"
+                && reference["target"] == "node-edge-point"));
+        assert!(repository.get("upload_by_part").await.unwrap().is_some());
+
+        repository.remove("upload_by_part").await.unwrap();
+    }
+
+    #[actix_web::test]
+    async fn test_upload_missing_files_and_sections() {
+        let repository = DataSourceRepository::in_memory();
+
+        let (status, report) = upload(
+            &repository,
+            "upload_missing_files",
+            &[("topology_file", fixture("topology.json"))],
+        )
+        .await;
+        assert_eq!(status, 422);
+        assert_eq!(
+            report["missing_sections"],
+            json!(["connections_file", "connectivity_services_file"])
+        );
+
+        let context = json!({
+            "tapi-common:context": {
+                "tapi-topology:topology-context": {
+                    "topology": serde_json::from_slice::<Value>(&fixture("topology.json")).unwrap()
+                }
+            }
+        });
+        let (status, report) = upload(
+            &repository,
+            "upload_missing_sections",
+            &[("complete_context_file", context.to_string().into_bytes())],
+        )
+        .await;
+        assert_eq!(status, 422);
+        assert_eq!(report["valid"], false);
+        assert_eq!(
+            report["missing_sections"],
+            json!([
+                "/tapi-common:context/tapi-connectivity:connectivity-context/connectivity-service",
+                "/tapi-common:context/tapi-connectivity:connectivity-context/connection"
+            ])
+        );
+        assert!(!Path::new("data/upload_missing_sections_context.json").exists());
+
+        let (status, report) = upload(
+            &repository,
+            "upload_invalid_json",
+            &[(
+                "complete_context_file",
+                b"{\"tapi-common:context\": ".to_vec(),
+            )],
+        )
+        .await;
+        assert_eq!(status, 422);
+        assert!(report["errors"][0]
+            .as_str()
+            .unwrap()
+            .starts_with("context file"));
+
+        assert!(repository.list().await.unwrap().is_empty());
+    }
+
+    #[actix_web::test]
+    async fn test_rejected_upload_keeps_registered_files() {
+        let repository = DataSourceRepository::in_memory();
+        let section = |name: &str| serde_json::from_slice::<Value>(&fixture(name)).unwrap();
+        let context = json!({
+            "tapi-common:context": {
+                "tapi-topology:topology-context": {"topology": section("topology.json")},
+                "tapi-connectivity:connectivity-context": {
+                    "connectivity-service": section("connectivity_services.json"),
+                    "connection": section("connections.json")
+                }
+            }
+        });
+        let (status, _) = upload(
+            &repository,
+            "upload_replaced",
+            &[("complete_context_file", context.to_string().into_bytes())],
+        )
+        .await;
+        assert_eq!(status, 200);
+        assert!(Path::new("data/upload_replaced_context.json.index").exists());
+
+        // The same id uploaded again without its connectivity context is rejected
+        let partial_context = json!({
+            "tapi-common:context": {
+                "tapi-topology:topology-context": {"topology": section("topology.json")}
+            }
+        });
+        let (status, report) = upload(
+            &repository,
+            "upload_replaced",
+            &[(
+                "complete_context_file",
+                partial_context.to_string().into_bytes(),
+            )],
+        )
+        .await;
+        assert_eq!(status, 422);
+        assert_eq!(report["valid"], false);
+        assert!(!Path::new("data/upload_replaced_context.json.staged").exists());
+
+        // The files of the first upload are still registered and readable
+        let data_source = repository.get("upload_replaced").await.unwrap().unwrap();
+        let full_context = Requester::get_full_context(&data_source).await.unwrap();
+        let snapshot = TopologySnapshot::build(full_context, VendorProfile::default()).unwrap();
+        assert_eq!(snapshot.nodes.len(), 38);
+        assert!(Path::new("data/upload_replaced_context.json.index").exists());
+
+        repository.remove("upload_replaced").await.unwrap();
+    }
+
+    #[actix_web::test]
+    async fn test_upload_compressed_and_archived_files() {
+        let repository = DataSourceRepository::in_memory();
+
+        // Gzipped parts are decompressed
+        let (status, body) = upload(
+            &repository,
+            "upload_gzip",
+            &[
+                ("topology_file", gzip(&fixture("topology.json"))),
+                ("connections_file", fixture("connections.json")),
+                (
+                    "connectivity_services_file",
+                    gzip(&fixture("connectivity_services.json")),
+                ),
+            ],
+        )
+        .await;
+        assert_eq!(status, 200);
+        assert_eq!(body["report"]["counts"]["nodes"], 38);
+        repository.remove("upload_gzip").await.unwrap();
+
+        // Zip members are detected by content, whatever their name
+        let archive = zip(&[
+            ("export/a.json", fixture("connectivity_services.json")),
+            ("export/b.json.gz", gzip(&fixture("topology.json"))),
+            ("export/c.json", fixture("connections.json")),
+            ("export/README.txt", b"TAPI export".to_vec()),
+        ]);
+        let (status, body) = upload(
+            &repository,
+            "upload_zip",
+            &[("complete_context_file", archive)],
+        )
+        .await;
+        assert_eq!(status, 200);
+        assert_eq!(body["report"]["counts"]["connectivity_services"], 7);
+        assert!(Path::new("data/upload_zip_topology.json").exists());
+        assert!(!Path::new("data/upload_zip_member.json").exists());
+        repository.remove("upload_zip").await.unwrap();
+
+        // A tar.gz with a complete context is stored as a complete file set
+        let context = json!({
+            "tapi-common:context": {
+                "tapi-topology:topology-context": {
+                    "topology": serde_json::from_slice::<Value>(&fixture("topology.json")).unwrap()
+                },
+                "tapi-connectivity:connectivity-context": {
+                    "connectivity-service": serde_json::from_slice::<Value>(&fixture("connectivity_services.json")).unwrap(),
+                    "connection": serde_json::from_slice::<Value>(&fixture("connections.json")).unwrap()
+                }
+            }
+        });
+        let archive = tar_gz(&[("context.json", context.to_string().into_bytes())]);
+        let (status, body) = upload(
+            &repository,
+            "upload_tar",
+            &[("complete_context_file", archive)],
+        )
+        .await;
+        assert_eq!(status, 200);
+        assert_eq!(body["report"]["counts"]["links"], 79);
+        assert!(Path::new("data/upload_tar_context.json").exists());
+        repository.remove("upload_tar").await.unwrap();
+
+        // Archives without every part are rejected
+        let archive = zip(&[("topology.json", fixture("topology.json"))]);
+        let (status, report) = upload(
+            &repository,
+            "upload_zip_missing",
+            &[("complete_context_file", archive)],
+        )
+        .await;
+        assert_eq!(status, 422);
+        assert_eq!(
+            report["errors"][0],
+            "Archive is missing connections, connectivity_services"
+        );
+        assert!(!Path::new("data/upload_zip_missing_topology.json").exists());
+    }
+
+    #[actix_web::test]
+    async fn test_upload_xml_files() {
+        let repository = DataSourceRepository::in_memory();
+        let as_xml = |name: &str, file: &str| {
+            to_xml(
+                name,
+                &serde_json::from_slice::<Value>(&fixture(file)).unwrap(),
+            )
+            .into_bytes()
+        };
+
+        let (status, body) = upload(
+            &repository,
+            "upload_xml",
+            &[
+                (
+                    "topology_file",
+                    as_xml("tapi-topology:topology", "topology.json"),
+                ),
+                (
+                    "connections_file",
+                    as_xml("tapi-connectivity:connection", "connections.json"),
+                ),
+                (
+                    "connectivity_services_file",
+                    as_xml(
+                        "tapi-connectivity:connectivity-service",
+                        "connectivity_services.json",
+                    ),
+                ),
+            ],
+        )
+        .await;
+        assert_eq!(status, 200);
+        assert_eq!(body["report"]["counts"]["nodes"], 38);
+        assert_eq!(body["report"]["counts"]["links"], 79);
+        assert_eq!(body["report"]["counts"]["connectivity_services"], 7);
+
+        // The builders see the same structures as with the JSON files
+        let json_files = DataSource::FilesEnum(FilesEnum::ByPart(ByPart {
+            id: "devices_files".to_string(),
+            topology_path: "devices_files/topology.json".to_string(),
+            connections_path: "devices_files/connections.json".to_string(),
+            connectivity_services_path: "devices_files/connectivity_services.json".to_string(),
+        }));
+        let xml_files = repository.get("upload_xml").await.unwrap().unwrap();
+        let snapshot = |data_source: DataSource| async move {
+            let full_context = Requester::get_full_context(&data_source).await.unwrap();
+            TopologySnapshot::build(full_context, VendorProfile::default()).unwrap()
+        };
+        let json_snapshot = snapshot(json_files).await;
+        let xml_snapshot = snapshot(xml_files).await;
+        assert_eq!(
+            format!("{:?}", xml_snapshot.nodes),
+            format!("{:?}", json_snapshot.nodes)
+        );
+        assert_eq!(
+            format!("{:?}", xml_snapshot.links),
+            format!("{:?}", json_snapshot.links)
+        );
+        assert_eq!(
+            format!("{:?}", xml_snapshot.connections),
+            format!("{:?}", json_snapshot.connections)
+        );
+
+        repository.remove("upload_xml").await.unwrap();
+    }
+}
@@ -1,11 +1,12 @@
-use gloo_net::http::Request;
+use gloo_net::http::{Request, Response};
 use gloo_net::Error;
 use lazy_static::lazy_static;
 use serde::{Deserialize, Serialize};
 use serde_json::{from_str, json, Value};
 use std::option_env;
-use wasm_bindgen::JsValue;
-use web_sys::{Blob, BlobPropertyBag, File, FormData};
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::{Blob, BlobPropertyBag, EventSource, File, FormData, MessageEvent};
 
 lazy_static! {
     static ref API_URL: &'static str = option_env!("API_URL").unwrap_or("/api");
@@ -17,16 +18,43 @@ pub struct Device {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub port: Option<i64>, // Optional port number
     pub auth: Auth, // Authentication method (enum)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vendor: Option<String>, // Vendor profile, detected by the server if missing
+    #[serde(default)]
+    pub tls: TlsConfig, // Scheme and certificates used to reach the device
 }
 
-#[allow(clippy::enum_variant_names)]
-/// Enum representing the different authentication methods
+/// Scheme the device is reached with
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum Scheme {
+    #[default]
+    Https,
+    Http,
+}
+
+/// TLS settings of a device, applied by the server to every request sent to it
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq, Clone)]
+pub struct TlsConfig {
+    #[serde(default)]
+    pub scheme: Scheme,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ca_certificate: Option<String>, // PEM bundle of the trusted CA certificates
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_certificate: Option<String>, // PEM certificate for mutual TLS
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_key: Option<String>, // PKCS#8 PEM key of the client certificate
+    #[serde(default)]
+    pub insecure: bool, // Accept invalid certificates
+}
+
+/// Enum representing the different authentication methods, as the server models them
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
 #[serde(untagged)]
 pub enum Auth {
-    Oauth2(Oauth2),       // OAuth2 Authentication
-    BasicAuth(BasicAuth), // Basic Authentication
-    Custom(CustomAuth),   // Custom Authentication
+    OAuth2(OAuth2Auth), // OAuth2 Authentication
+    Basic(BasicAuth),   // Basic Authentication
+    Token(TokenAuth),   // Token Authentication
 }
 
 /// Represents Basic Authentication with username and password
@@ -36,20 +64,38 @@ pub struct BasicAuth {
     pub password: String, // Password for authentication
 }
 
-/// Represents OAuth2 Authentication with additional fields for grant type and authentication URL
+/// OAuth2 grant used to obtain the access tokens of a device
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum GrantType {
+    ClientCredentials,
+    Password,
+}
+
+/// Represents OAuth2 Authentication with the grant type and the token endpoint
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
-pub struct Oauth2 {
-    pub username: String,   // Username for OAuth2 authentication
-    pub password: String,   // Password for OAuth2 authentication
-    pub grant_type: String, // Grant type for OAuth2 (e.g., client_credentials, password)
-    pub auth_sufix: String, // URL to request OAuth2 token
+pub struct OAuth2Auth {
+    pub grant_type: GrantType, // Grant type for OAuth2 (client_credentials or password)
+    #[serde(alias = "auth_sufix")]
+    pub token_uri: String, // URI to request OAuth2 tokens
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_secret: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>, // Resource owner, only for the password grant
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
 }
 
-/// Represents Custom Authentication with an arbitrary body and authentication URL
+/// Represents Token Authentication with an arbitrary body and authentication URI
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
-pub struct CustomAuth {
-    pub auth_body: Value,   // A JSON object containing custom authentication data
-    pub auth_sufix: String, // URL for custom authentication
+pub struct TokenAuth {
+    pub auth_body: Value, // A JSON object containing token authentication data
+    #[serde(alias = "auth_sufix")]
+    pub auth_uri: String, // URI for token authentication
 }
 
 /// Fetches JSON schema from the server for a given IP address.
@@ -74,6 +120,24 @@ pub async fn get_schema(ip: String, service_uuid: String) -> Result<Value, Error
     Ok(json)
 }
 
+/// Fetches the whole network of a device from the server: its nodes, their edge points grouped by inventory, and its links.
+///
+/// # Arguments
+///
+/// * `ip` - The IP address of the device whose network is to be fetched.
+///
+/// # Returns
+///
+/// * `Result<Value, Error>` - Returns the network graph as a `Value` if successful, or an error if the request fails.
+pub async fn get_topology(ip: String) -> Result<Value, Error> {
+    let response = Request::get(&format!("{}/get_topology/{}", *API_URL, &ip))
+        .send()
+        .await?;
+    let text = response.text().await?;
+    let json: Value = from_str(&text)?;
+    Ok(json)
+}
+
 /// Fetches JSON schema from the server for a given IP address.
 ///
 /// # Arguments
@@ -92,6 +156,240 @@ pub async fn get_services(ip: String) -> Result<Value, Error> {
     Ok(json)
 }
 
+/// Takes a new topology snapshot of a data source on the server.
+///
+/// # Arguments
+///
+/// * `id` - The ID of the data source to be refreshed.
+///
+/// # Returns
+///
+/// * `Result<Value, Error>` - Returns the timestamp and size of the new snapshot as a `Value` if successful, or an error if the request fails.
+pub async fn refresh_snapshot(id: &str) -> Result<Value, Error> {
+    let response = Request::post(&format!("{}/refresh/{}", *API_URL, id))
+        .send()
+        .await?;
+    let text = response.text().await?;
+    let json: Value = from_str(&text)?;
+    Ok(json)
+}
+
+/// Requests every TAPI RESTCONF URI to a device and stores the results on the server.
+///
+/// # Arguments
+///
+/// * `ip` - The IP address of the device to be probed.
+///
+/// # Returns
+///
+/// * `Result<Value, Error>` - Returns the result of each URI as a `Value` if successful, or an error if the request fails.
+pub async fn probe_device(ip: &str) -> Result<Value, Error> {
+    let response = Request::post(&format!("{}/probe/{}", *API_URL, ip))
+        .send()
+        .await?;
+    let text = response.text().await?;
+    let json: Value = from_str(&text)?;
+    Ok(json)
+}
+
+/// Fetches the last probe result of each TAPI RESTCONF URI for a device.
+///
+/// # Arguments
+///
+/// * `ip` - The IP address of the device.
+///
+/// # Returns
+///
+/// * `Result<Value, Error>` - Returns the results as a `Value` if successful, or an error if the request fails.
+pub async fn get_validations(ip: &str) -> Result<Value, Error> {
+    let response = Request::get(&format!("{}/get_validations/{}", *API_URL, ip))
+        .send()
+        .await?;
+    let text = response.text().await?;
+    let json: Value = from_str(&text)?;
+    Ok(json)
+}
+
+/// Fetches the last health checks of a device, the most recent first.
+///
+/// # Arguments
+///
+/// * `ip` - The IP address of the device.
+/// * `limit` - The maximum number of checks returned.
+///
+/// # Returns
+///
+/// * `Result<Value, Error>` - Returns the checks as a `Value` if successful, or an error if the request fails.
+pub async fn get_health(ip: &str, limit: usize) -> Result<Value, Error> {
+    let response = Request::get(&format!("{}/hosts/{}/health?limit={}", *API_URL, ip, limit))
+        .send()
+        .await?;
+    let text = response.text().await?;
+    let json: Value = from_str(&text)?;
+    Ok(json)
+}
+
+/// Fetches the positions of the nodes moved on the network map of a host, by node UUID.
+///
+/// # Arguments
+///
+/// * `ip` - The IP address of the device.
+///
+/// # Returns
+///
+/// * `Result<Value, Error>` - Returns the `[x, y]` position of each moved node as a `Value` if successful, or an error if the request fails.
+pub async fn get_positions(ip: &str) -> Result<Value, Error> {
+    let response = Request::get(&format!("{}/hosts/{}/positions", *API_URL, ip))
+        .send()
+        .await?;
+    let text = response.text().await?;
+    let json: Value = from_str(&text)?;
+    Ok(json)
+}
+
+/// Stores the positions of the nodes moved on the network map of a host, replacing the previous
+/// ones. An empty object resets the layout of the map.
+///
+/// # Arguments
+///
+/// * `ip` - The IP address of the device.
+/// * `positions` - The `[x, y]` position of each moved node, by node UUID.
+///
+/// # Returns
+///
+/// * `Result<Value, Error>` - Returns the stored positions as a `Value` if successful, or an error if the request fails.
+pub async fn set_positions(ip: &str, positions: &Value) -> Result<Value, Error> {
+    let response = Request::put(&format!("{}/hosts/{}/positions", *API_URL, ip))
+        .json(positions)?
+        .send()
+        .await?;
+    if !response.ok() {
+        return Err(Error::GlooError(format!(
+            "Positions not stored: {}",
+            response.status()
+        )));
+    }
+    let text = response.text().await?;
+    let json: Value = from_str(&text)?;
+    Ok(json)
+}
+
+/// Fetches the service interface points new connectivity services can join on a device.
+///
+/// # Arguments
+///
+/// * `ip` - The IP address of the device.
+///
+/// # Returns
+///
+/// * `Result<Value, Error>` - Returns the service interface points and their nodes as a `Value` if successful, or an error if the request fails.
+pub async fn get_sips(ip: &str) -> Result<Value, Error> {
+    let response = Request::get(&format!("{}/sips/{}", *API_URL, ip))
+        .send()
+        .await?;
+    let text = response.text().await?;
+    let json: Value = from_str(&text)?;
+    Ok(json)
+}
+
+/// Reads the result of a provisioning request, or the reason given by the server to reject it.
+async fn provisioning_result(response: Result<Response, Error>) -> Result<Value, String> {
+    let response =
+        response.map_err(|_| String::from("Request failed. The API might not be available."))?;
+    let is_ok = response.ok();
+    let text = response
+        .text()
+        .await
+        .map_err(|_| String::from("Empty API response. The API might not be available."))?;
+    if !is_ok {
+        return Err(text);
+    }
+    from_str(&text).map_err(|_| String::from("Failed to parse response JSON."))
+}
+
+/// Creates a connectivity service on a device, waiting for the device to install it.
+///
+/// # Arguments
+///
+/// * `ip` - The IP address of the device.
+/// * `request` - The service interface points, layer protocol, capacity and constraints of the service.
+/// * `dry_run` - When `true` the payload is only rendered, without sending it to the device.
+///
+/// # Returns
+///
+/// * `Result<Value, String>` - Returns the provisioning result as a `Value` if successful, or the error message of the server.
+pub async fn create_service(ip: &str, request: &Value, dry_run: bool) -> Result<Value, String> {
+    let builder = Request::post(&format!("{}/services/{}?dry_run={}", *API_URL, ip, dry_run))
+        .header("Accept", "application/json")
+        .json(request)
+        .map_err(|_| String::from("Failed to create request. Cannot create the service."))?;
+    provisioning_result(builder.send().await).await
+}
+
+/// Deletes a connectivity service from a device, waiting for the device to remove it.
+///
+/// # Arguments
+///
+/// * `ip` - The IP address of the device.
+/// * `uuid` - The UUID of the service to be deleted.
+/// * `dry_run` - When `true` the request is only rendered, without sending it to the device.
+///
+/// # Returns
+///
+/// * `Result<Value, String>` - Returns the provisioning result as a `Value` if successful, or the error message of the server.
+pub async fn delete_service(ip: &str, uuid: &str, dry_run: bool) -> Result<Value, String> {
+    let response = Request::delete(&format!(
+        "{}/services/{}/{}?dry_run={}",
+        *API_URL, ip, uuid, dry_run
+    ))
+    .send()
+    .await;
+    provisioning_result(response).await
+}
+
+/// Changes of a device pushed by the server, received until the value is dropped
+pub struct DeviceEvents {
+    source: EventSource,
+    _on_message: Closure<dyn FnMut(MessageEvent)>,
+}
+
+impl Drop for DeviceEvents {
+    fn drop(&mut self) {
+        self.source.close();
+    }
+}
+
+/// Follows the changes of a device, applied by the server to its topology snapshot.
+///
+/// # Arguments
+///
+/// * `ip` - The IP address of the device to be followed.
+/// * `on_event` - Called with each `TopologyEvent`, holding the notification and the timestamp of the updated snapshot.
+///
+/// # Returns
+///
+/// * `Result<DeviceEvents, JsValue>` - Returns the open event stream, or an error if the browser cannot open it.
+pub fn follow_events(
+    ip: &str,
+    on_event: impl Fn(Value) + 'static,
+) -> Result<DeviceEvents, JsValue> {
+    let source = EventSource::new(&format!("{}/events/{}", *API_URL, ip))?;
+    let on_message = Closure::<dyn FnMut(MessageEvent)>::new(move |event: MessageEvent| {
+        if let Some(event) = event
+            .data()
+            .as_string()
+            .and_then(|data| from_str::<Value>(&data).ok())
+        {
+            on_event(event);
+        }
+    });
+    source.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+    Ok(DeviceEvents {
+        source,
+        _on_message: on_message,
+    })
+}
+
 /// Adds a new device to the server.
 ///
 /// # Arguments
@@ -235,11 +533,17 @@ pub async fn upload_connectivity_files(
 
     // Enviar la solicitud y obtener la respuesta
     if let Ok(response) = builder.send().await {
+        let status = response.status();
         // Leer el texto de la respuesta
         if let Ok(text) = response.text().await {
             // Parsear la respuesta como JSON
-            if let Ok(json) = serde_json::from_str(&text) {
-                Ok(json)
+            if let Ok(json) = serde_json::from_str::<Value>(&text) {
+                // 422: el backend rechaza los archivos y devuelve el informe de validación
+                if status == 422 {
+                    Err(upload_report_summary(&json))
+                } else {
+                    Ok(json)
+                }
             } else {
                 Err(String::from("Error al parsear la respuesta JSON."))
             }
@@ -250,3 +554,29 @@ pub async fn upload_connectivity_files(
         Err(String::from("Error al enviar la solicitud."))
     }
 }
+
+/// Resume el informe de validación de una subida rechazada.
+fn upload_report_summary(report: &Value) -> String {
+    let join = |key: &str| {
+        report[key]
+            .as_array()
+            .map(|items| {
+                items
+                    .iter()
+                    .filter_map(Value::as_str)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            })
+            .unwrap_or_default()
+    };
+    let mut summary = vec![];
+    let missing_sections = join("missing_sections");
+    if !missing_sections.is_empty() {
+        summary.push(format!("Faltan: {}", missing_sections));
+    }
+    let errors = join("errors");
+    if !errors.is_empty() {
+        summary.push(format!("Errores: {}", errors));
+    }
+    format!("Archivos no válidos. {}", summary.join(". "))
+}
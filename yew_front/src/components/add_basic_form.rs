@@ -2,14 +2,14 @@ use web_sys::HtmlInputElement;
 use yew::{platform::spawn_local, prelude::*};
 use yew_router::prelude::*;
 
-use crate::api::connection::{add_device, Auth, BasicAuth, Device};
-use crate::components::{alert::Alert, button::Button, input::Input};
+use crate::api::connection::{add_device, Auth, BasicAuth, Device, TlsConfig};
+use crate::components::{alert::Alert, button::Button, input::Input, tls_fields::TlsFields};
 use crate::Route;
 
 /// A functional Yew component that renders a form to add a new device.
 ///
-/// This component provides a form with input fields for the host, port, user, and password of the device.
-/// It handles form submission by making an asynchronous request to add the device to the server.
+/// This component provides a form with input fields for the host, port, vendor, user, and password of the device,
+/// and its TLS settings. It handles form submission by making an asynchronous request to add the device to the server.
 /// On success, it redirects to the `Devices` route; on failure, it displays an error message.
 #[function_component(AddBasicForm)]
 pub fn add_device_form() -> Html {
@@ -19,12 +19,16 @@ pub fn add_device_form() -> Html {
     let error_message_handle = use_state(String::default);
     let host_handle = use_state(String::default);
     let port_handle: UseStateHandle<Option<i64>> = use_state(|| None);
+    let vendor_handle = use_state(String::default);
+    let tls_handle = use_state(TlsConfig::default);
     let user_handle = use_state(String::default);
     let password_handle = use_state(String::default);
 
     // Cloning state values for use in async tasks and callbacks
     let host = (*host_handle).clone();
     let port = *port_handle;
+    let vendor = (*vendor_handle).clone();
+    let tls = (*tls_handle).clone();
     let user = (*user_handle).clone();
     let password = (*password_handle).clone();
     let error_message = (*error_message_handle).clone();
@@ -48,6 +52,15 @@ pub fn add_device_form() -> Html {
         })
     };
 
+    let on_change_vendor = {
+        Callback::from(move |e: Event| {
+            let target = e.target_dyn_into::<HtmlInputElement>();
+            if let Some(input) = target {
+                vendor_handle.set(input.value());
+            }
+        })
+    };
+
     let on_change_user = {
         Callback::from(move |e: Event| {
             let target = e.target_dyn_into::<HtmlInputElement>();
@@ -66,9 +79,13 @@ pub fn add_device_form() -> Html {
         })
     };
 
+    let on_change_tls = { Callback::from(move |tls: TlsConfig| tls_handle.set(tls)) };
+
     // Cloning values for use in async code
     let cloned_host = host.clone();
     let cloned_port = port;
+    let cloned_vendor = vendor.clone();
+    let cloned_tls = tls.clone();
     let cloned_user = user.clone();
     let cloned_password = password.clone();
 
@@ -80,6 +97,8 @@ pub fn add_device_form() -> Html {
         let cloned_navigator = navigator.clone();
         let cloned_host = cloned_host.clone();
         let cloned_port = cloned_port;
+        let cloned_vendor = cloned_vendor.clone();
+        let cloned_tls = cloned_tls.clone();
         let cloned_user = cloned_user.clone();
         let cloned_password = cloned_password.clone();
         let cloned_error_message_handle = error_message_handle.clone();
@@ -90,7 +109,9 @@ pub fn add_device_form() -> Html {
             let result = add_device(Device {
                 ip: cloned_host,
                 port: cloned_port,
-                auth: Auth::BasicAuth(BasicAuth {
+                vendor: (!cloned_vendor.is_empty()).then_some(cloned_vendor),
+                tls: cloned_tls,
+                auth: Auth::Basic(BasicAuth {
                     username: cloned_user,
                     password: cloned_password,
                 }),
@@ -137,6 +158,16 @@ pub fn add_device_form() -> Html {
                     onchange={on_change_port}
                 />
             </div>
+            <div class="input-group">
+                <Input
+                    input_type="text"
+                    name="vendor"
+                    label="Vendor"
+                    placeholder="auto"
+                    value={vendor}
+                    onchange={on_change_vendor}
+                />
+            </div>
             <div class="input-group">
                 <Input
                     input_type="user"
@@ -157,6 +188,7 @@ pub fn add_device_form() -> Html {
                     onchange={on_change_password}
                 />
             </div>
+            <TlsFields tls={tls} onchange={on_change_tls}/>
             <Button btn_type="submit" class="primary" onclick={None::<Callback<MouseEvent>>} message="Save"/>
         </form>
     }
@@ -3,14 +3,14 @@ use web_sys::HtmlInputElement;
 use yew::{platform::spawn_local, prelude::*};
 use yew_router::prelude::*;
 
-use crate::api::connection::{add_device, Auth, CustomAuth, Device};
-use crate::components::{alert::Alert, button::Button, input::Input};
+use crate::api::connection::{add_device, Auth, Device, TlsConfig, TokenAuth};
+use crate::components::{alert::Alert, button::Button, input::Input, tls_fields::TlsFields};
 use crate::Route;
 
 /// A functional Yew component that renders a form to add a new device.
 ///
-/// This component provides a form with input fields for the host, port, user, and password of the device.
-/// It handles form submission by making an asynchronous request to add the device to the server.
+/// This component provides a form with input fields for the host, port, vendor, user, and password of the device,
+/// and its TLS settings. It handles form submission by making an asynchronous request to add the device to the server.
 /// On success, it redirects to the `Devices` route; on failure, it displays an error message.
 #[function_component(AddCustomForm)]
 pub fn add_device_form() -> Html {
@@ -20,12 +20,16 @@ pub fn add_device_form() -> Html {
     let error_message_handle = use_state(String::default);
     let host_handle = use_state(String::default);
     let port_handle: UseStateHandle<Option<i64>> = use_state(|| None);
+    let vendor_handle = use_state(String::default);
+    let tls_handle = use_state(TlsConfig::default);
     let auth_body_handle = use_state(String::default);
     let auth_sufix_handle = use_state(String::default);
 
     // Cloning state values for use in async tasks and callbacks
     let host = (*host_handle).clone();
     let port = *port_handle;
+    let vendor = (*vendor_handle).clone();
+    let tls = (*tls_handle).clone();
     let auth_body = (*auth_body_handle).clone();
     let auth_sufix = (*auth_sufix_handle).clone();
     let error_message = (*error_message_handle).clone();
@@ -49,6 +53,15 @@ pub fn add_device_form() -> Html {
         })
     };
 
+    let on_change_vendor = {
+        Callback::from(move |e: Event| {
+            let target = e.target_dyn_into::<HtmlInputElement>();
+            if let Some(input) = target {
+                vendor_handle.set(input.value());
+            }
+        })
+    };
+
     let on_change_auth_body = {
         Callback::from(move |e: Event| {
             let target = e.target_dyn_into::<HtmlInputElement>();
@@ -67,9 +80,13 @@ pub fn add_device_form() -> Html {
         })
     };
 
+    let on_change_tls = { Callback::from(move |tls: TlsConfig| tls_handle.set(tls)) };
+
     // Cloning values for use in async code
     let cloned_host = host.clone();
     let cloned_port = port;
+    let cloned_vendor = vendor.clone();
+    let cloned_tls = tls.clone();
     let cloned_auth_sufix = auth_sufix.clone();
     let cloned_auth_body = auth_body.clone();
 
@@ -81,6 +98,8 @@ pub fn add_device_form() -> Html {
         let cloned_navigator = navigator.clone();
         let cloned_host = cloned_host.clone();
         let cloned_port = cloned_port;
+        let cloned_vendor = cloned_vendor.clone();
+        let cloned_tls = cloned_tls.clone();
         let cloned_auth_sufix = cloned_auth_sufix.clone();
         let cloned_auth_body = cloned_auth_body.clone();
         let cloned_error_message_handle = error_message_handle.clone();
@@ -91,9 +110,11 @@ pub fn add_device_form() -> Html {
             let result = add_device(Device {
                 ip: cloned_host,
                 port: cloned_port,
-                auth: Auth::Custom(CustomAuth {
+                vendor: (!cloned_vendor.is_empty()).then_some(cloned_vendor),
+                tls: cloned_tls,
+                auth: Auth::Token(TokenAuth {
                     auth_body: serde_json::from_str::<Value>(cloned_auth_body.as_str()).unwrap(),
-                    auth_sufix: cloned_auth_sufix,
+                    auth_uri: cloned_auth_sufix,
                 }),
             })
             .await;
@@ -138,6 +159,16 @@ pub fn add_device_form() -> Html {
                     onchange={on_change_port}
                 />
             </div>
+            <div class="input-group">
+                <Input
+                    input_type="text"
+                    name="vendor"
+                    label="Vendor"
+                    placeholder="auto"
+                    value={vendor}
+                    onchange={on_change_vendor}
+                />
+            </div>
             <div class="input-group">
                 <Input
                     input_type="auth_body"
@@ -158,6 +189,7 @@ pub fn add_device_form() -> Html {
                     onchange={on_change_auth_sufix}
                 />
             </div>
+            <TlsFields tls={tls} onchange={on_change_tls}/>
             <Button btn_type="submit" class="primary" onclick={None::<Callback<MouseEvent>>} message="Save"/>
         </form>
     }
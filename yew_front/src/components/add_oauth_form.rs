@@ -1,15 +1,18 @@
-use web_sys::HtmlInputElement;
+use web_sys::{HtmlInputElement, HtmlSelectElement};
 use yew::{platform::spawn_local, prelude::*};
 use yew_router::prelude::*;
 
-use crate::api::connection::{add_device, Auth, Device, Oauth2};
-use crate::components::{alert::Alert, button::Button, input::Input};
+use crate::api::connection::{add_device, Auth, Device, GrantType, OAuth2Auth, TlsConfig};
+use crate::components::{
+    alert::Alert, button::Button, input::Input, select::Select, tls_fields::TlsFields,
+};
 use crate::Route;
 
 /// A functional Yew component that renders a form to add a new device.
 ///
-/// This component provides a form with input fields for the host, port, user, and password of the device.
-/// It handles form submission by making an asynchronous request to add the device to the server.
+/// This component provides a form with input fields for the host, port and vendor of the device, the OAuth2 grant
+/// type, the token endpoint, the client credentials and the TLS settings. The user and password are only asked for the password grant.
+/// Empty optional fields are not sent. It handles form submission by making an asynchronous request to add the device to the server.
 /// On success, it redirects to the `Devices` route; on failure, it displays an error message.
 #[function_component(AddOauthForm)]
 pub fn add_device_form() -> Html {
@@ -19,18 +22,28 @@ pub fn add_device_form() -> Html {
     let error_message_handle = use_state(String::default);
     let host_handle = use_state(String::default);
     let port_handle: UseStateHandle<Option<i64>> = use_state(|| None);
+    let vendor_handle = use_state(String::default);
+    let tls_handle = use_state(TlsConfig::default);
     let user_handle = use_state(String::default);
     let password_handle = use_state(String::default);
-    let grant_type_handle = use_state(String::default);
-    let auth_sufix_handle = use_state(String::default);
+    let grant_type_handle = use_state(|| "client_credentials".to_string());
+    let token_uri_handle = use_state(String::default);
+    let client_id_handle = use_state(String::default);
+    let client_secret_handle = use_state(String::default);
+    let scope_handle = use_state(String::default);
 
     // Cloning state values for use in async tasks and callbacks
     let host = (*host_handle).clone();
     let port = *port_handle;
+    let vendor = (*vendor_handle).clone();
+    let tls = (*tls_handle).clone();
     let user = (*user_handle).clone();
     let password = (*password_handle).clone();
     let grant_type = (*grant_type_handle).clone();
-    let auth_sufix = (*auth_sufix_handle).clone();
+    let token_uri = (*token_uri_handle).clone();
+    let client_id = (*client_id_handle).clone();
+    let client_secret = (*client_secret_handle).clone();
+    let scope = (*scope_handle).clone();
     let error_message = (*error_message_handle).clone();
 
     // Callbacks to handle input change
@@ -52,6 +65,15 @@ pub fn add_device_form() -> Html {
         })
     };
 
+    let on_change_vendor = {
+        Callback::from(move |e: Event| {
+            let target = e.target_dyn_into::<HtmlInputElement>();
+            if let Some(input) = target {
+                vendor_handle.set(input.value());
+            }
+        })
+    };
+
     let on_change_user = {
         Callback::from(move |e: Event| {
             let target = e.target_dyn_into::<HtmlInputElement>();
@@ -71,30 +93,64 @@ pub fn add_device_form() -> Html {
     };
 
     let on_change_grant_type = {
+        Callback::from(move |e: Event| {
+            let target = e.target_dyn_into::<HtmlSelectElement>();
+            if let Some(select) = target {
+                grant_type_handle.set(select.value());
+            }
+        })
+    };
+
+    let on_change_token_uri = {
         Callback::from(move |e: Event| {
             let target = e.target_dyn_into::<HtmlInputElement>();
             if let Some(input) = target {
-                grant_type_handle.set(input.value());
+                token_uri_handle.set(input.value());
             }
         })
     };
 
-    let on_change_auth_sufix = {
+    let on_change_client_id = {
         Callback::from(move |e: Event| {
             let target = e.target_dyn_into::<HtmlInputElement>();
             if let Some(input) = target {
-                auth_sufix_handle.set(input.value());
+                client_id_handle.set(input.value());
             }
         })
     };
 
+    let on_change_client_secret = {
+        Callback::from(move |e: Event| {
+            let target = e.target_dyn_into::<HtmlInputElement>();
+            if let Some(input) = target {
+                client_secret_handle.set(input.value());
+            }
+        })
+    };
+
+    let on_change_scope = {
+        Callback::from(move |e: Event| {
+            let target = e.target_dyn_into::<HtmlInputElement>();
+            if let Some(input) = target {
+                scope_handle.set(input.value());
+            }
+        })
+    };
+
+    let on_change_tls = { Callback::from(move |tls: TlsConfig| tls_handle.set(tls)) };
+
     // Cloning values for use in async code
     let cloned_host = host.clone();
     let cloned_port = port;
+    let cloned_vendor = vendor.clone();
+    let cloned_tls = tls.clone();
     let cloned_user = user.clone();
     let cloned_password = password.clone();
-    let cloned_auth_sufix = auth_sufix.clone();
     let cloned_grant_type = grant_type.clone();
+    let cloned_token_uri = token_uri.clone();
+    let cloned_client_id = client_id.clone();
+    let cloned_client_secret = client_secret.clone();
+    let cloned_scope = scope.clone();
 
     // Callback for form submission
     let on_submit = Callback::from(move |e: SubmitEvent| {
@@ -104,24 +160,48 @@ pub fn add_device_form() -> Html {
         let cloned_navigator = navigator.clone();
         let cloned_host = cloned_host.clone();
         let cloned_port = cloned_port;
+        let cloned_vendor = cloned_vendor.clone();
+        let cloned_tls = cloned_tls.clone();
         let cloned_user = cloned_user.clone();
         let cloned_password = cloned_password.clone();
-        let cloned_auth_sufix = cloned_auth_sufix.clone();
         let cloned_grant_type = cloned_grant_type.clone();
+        let cloned_token_uri = cloned_token_uri.clone();
+        let cloned_client_id = cloned_client_id.clone();
+        let cloned_client_secret = cloned_client_secret.clone();
+        let cloned_scope = cloned_scope.clone();
         let cloned_error_message_handle = error_message_handle.clone();
 
+        // Empty optional fields are left out of the request
+        let optional = |value: String| (!value.is_empty()).then_some(value);
+        let grant_type = if cloned_grant_type == "password" {
+            GrantType::Password
+        } else {
+            GrantType::ClientCredentials
+        };
+        // The resource owner credentials are only sent with the password grant
+        let (username, password) = match grant_type {
+            GrantType::Password => (optional(cloned_user), optional(cloned_password)),
+            GrantType::ClientCredentials => (None, None),
+        };
+        let auth = Auth::OAuth2(OAuth2Auth {
+            grant_type,
+            token_uri: cloned_token_uri,
+            client_id: optional(cloned_client_id),
+            client_secret: optional(cloned_client_secret),
+            username,
+            password,
+            scope: optional(cloned_scope),
+        });
+
         // Asynchronous task to handle form submission
         spawn_local(async move {
             // Call the API to add the device
             let result = add_device(Device {
                 ip: cloned_host,
                 port: cloned_port,
-                auth: Auth::Oauth2(Oauth2 {
-                    username: cloned_user,
-                    password: cloned_password,
-                    grant_type: cloned_grant_type,
-                    auth_sufix: cloned_auth_sufix,
-                }),
+                vendor: (!cloned_vendor.is_empty()).then_some(cloned_vendor),
+                tls: cloned_tls,
+                auth,
             })
             .await;
 
@@ -167,44 +247,89 @@ pub fn add_device_form() -> Html {
             </div>
             <div class="input-group">
                 <Input
-                    input_type="user"
-                    name="user"
-                    label="User"
-                    placeholder="my_username"
-                    value={user}
-                    onchange={on_change_user}
+                    input_type="text"
+                    name="vendor"
+                    label="Vendor"
+                    placeholder="auto"
+                    value={vendor}
+                    onchange={on_change_vendor}
+                />
+            </div>
+            <div class="input-group">
+                <Select
+                    label="Grant Type"
+                    name="grant_type"
+                    options={vec![
+                        (AttrValue::from("client_credentials"), AttrValue::from("Client Credentials")),
+                        (AttrValue::from("password"), AttrValue::from("Password")),
+                    ]}
+                    value={grant_type.clone()}
+                    onchange={on_change_grant_type}
+                />
+            </div>
+            <div class="input-group">
+                <Input
+                    input_type="text"
+                    name="token_uri"
+                    label="Token Uri"
+                    placeholder="/oauth2/token"
+                    value={token_uri}
+                    onchange={on_change_token_uri}
                 />
             </div>
             <div class="input-group">
                 <Input
-                    input_type="password"
-                    name="password"
-                    label="Password"
-                    placeholder="my_password"
-                    value={password}
-                    onchange={on_change_password}
+                    input_type="text"
+                    name="client_id"
+                    label="Client Id"
+                    placeholder="my_client"
+                    value={client_id}
+                    onchange={on_change_client_id}
                 />
             </div>
             <div class="input-group">
                 <Input
-                    input_type="grant_type"
-                    name="grant_type"
-                    label="Grant Type"
-                    placeholder="client_credentials"
-                    value={grant_type}
-                    onchange={on_change_grant_type}
+                    input_type="password"
+                    name="client_secret"
+                    label="Client Secret"
+                    placeholder="my_client_secret"
+                    value={client_secret}
+                    onchange={on_change_client_secret}
                 />
             </div>
+            if grant_type == "password" {
+                <div class="input-group">
+                    <Input
+                        input_type="user"
+                        name="user"
+                        label="User"
+                        placeholder="my_username"
+                        value={user}
+                        onchange={on_change_user}
+                    />
+                </div>
+                <div class="input-group">
+                    <Input
+                        input_type="password"
+                        name="password"
+                        label="Password"
+                        placeholder="my_password"
+                        value={password}
+                        onchange={on_change_password}
+                    />
+                </div>
+            }
             <div class="input-group">
                 <Input
-                    input_type="auth_sufix"
-                    name="auth_sufix"
-                    label="Auth Url Sufix"
-                    placeholder="/tron/api/v1/tokens"
-                    value={auth_sufix}
-                    onchange={on_change_auth_sufix}
+                    input_type="text"
+                    name="scope"
+                    label="Scope"
+                    placeholder="optional"
+                    value={scope}
+                    onchange={on_change_scope}
                 />
             </div>
+            <TlsFields tls={tls} onchange={on_change_tls}/>
             <Button btn_type="submit" class="primary" onclick={None::<Callback<MouseEvent>>} message="Save"/>
         </form>
     }
@@ -0,0 +1,59 @@
+use serde_json::Value;
+use yew::{platform::spawn_local, prelude::*};
+
+use crate::api::connection::get_health;
+
+/// Properties for the `HealthBadge` component.
+#[derive(Properties, PartialEq)]
+pub struct Props {
+    /// The IP address of the device whose last health check is displayed.
+    pub ip: String,
+}
+
+/// A badge with the status of the last health check of a device.
+///
+/// Hovering the badge shows when the device was checked, how long the RESTCONF request took and
+/// the failure of unhealthy devices.
+#[function_component(HealthBadge)]
+pub fn health_badge(props: &Props) -> Html {
+    let health = use_state(|| None::<Value>);
+
+    // Fetch the last check on component mount
+    {
+        let health = health.clone();
+        let ip = props.ip.clone();
+        use_effect_with((), move |_| {
+            spawn_local(async move {
+                if let Ok(Value::Array(checks)) = get_health(&ip, 1).await {
+                    health.set(checks.into_iter().next());
+                }
+            });
+            || ()
+        });
+    }
+
+    let Some(health) = (*health).clone() else {
+        return html! { <span class="health-badge health-unknown">{"Not checked"}</span> };
+    };
+
+    let status = health["status"].as_str().unwrap_or("unknown").to_string();
+    let mut title = format!(
+        "Checked at {}",
+        health["checked_at"]
+            .as_str()
+            .unwrap_or("?")
+            .replace('T', " ")
+    );
+    if let Some(latency_ms) = health["latency_ms"].as_i64() {
+        title.push_str(&format!("\nRESTCONF answered in {} ms", latency_ms));
+    }
+    if let Some(error) = health["error"].as_str() {
+        title.push_str(&format!("\n{}", error));
+    }
+
+    html! {
+        <span class={classes!("health-badge", format!("health-{}", status))} title={title}>
+            { status }
+        </span>
+    }
+}
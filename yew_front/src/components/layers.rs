@@ -0,0 +1,192 @@
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use yew::{platform::spawn_local, prelude::*};
+
+use crate::api::connection::{follow_events, get_schema};
+
+/// Properties for the `Layers` component.
+#[derive(Properties, PartialEq)]
+pub struct LayersProps {
+    /// The IP address of the device associated with this schema.
+    pub device_ip: String,
+
+    /// The UUID of the service being displayed in this schema.
+    pub service_uuid: String,
+}
+
+/// First group of a UUID, enough to tell the objects of a service apart.
+fn short_uuid(uuid: &str) -> &str {
+    uuid.split('-').next().unwrap_or(uuid)
+}
+
+/// A Yew functional component that stacks the layers of the trace of a service.
+///
+/// Each row is a layer, from the client layer down to the fibre, with the connections of the layer
+/// and their connection end points in route order. Clicking a connection highlights the lower
+/// connections carrying it and the upper connections it carries.
+#[function_component(Layers)]
+pub fn layers(props: &LayersProps) -> Html {
+    let json_data = use_state(|| None::<Value>);
+    let selected_connection = use_state(|| None::<String>);
+
+    {
+        let json_clone = json_data.clone();
+        let ip = props.device_ip.clone();
+        let service_uuid = props.service_uuid.clone();
+        use_effect_with((), move |_| {
+            spawn_local(async move {
+                match get_schema(ip, service_uuid).await {
+                    Ok(fetched_json) => json_clone.set(Some(fetched_json)),
+                    Err(_) => {
+                        json_clone.set(Some(serde_json::json!({"error": "Failed to fetch JSON"})))
+                    }
+                }
+            });
+            || ()
+        });
+    }
+
+    // Fetch the schema again each time the device reports a change
+    {
+        let json_clone = json_data.clone();
+        let ip = props.device_ip.clone();
+        let service_uuid = props.service_uuid.clone();
+        use_effect_with((), move |_| {
+            let events = follow_events(&ip.clone(), move |_| {
+                let json_clone = json_clone.clone();
+                let ip = ip.clone();
+                let service_uuid = service_uuid.clone();
+                spawn_local(async move {
+                    if let Ok(fetched_json) = get_schema(ip, service_uuid).await {
+                        json_clone.set(Some(fetched_json));
+                    }
+                });
+            })
+            .ok();
+            move || drop(events)
+        });
+    }
+
+    let Some(schema) = (*json_data).clone() else {
+        return html! { <p>{"Loading..."}</p> };
+    };
+    let empty = vec![];
+    let layers = schema["layers"].as_array().unwrap_or(&empty);
+    if layers.is_empty() {
+        return html! { <p class="layers-empty">{"The trace of this service has no connections"}</p> };
+    }
+
+    let node_names: HashMap<&str, &str> = schema["nodes"]
+        .as_array()
+        .unwrap_or(&empty)
+        .iter()
+        .filter_map(|node| Some((node["node_uuid"].as_str()?, node["value_name"].as_str()?)))
+        .collect();
+
+    // Connections related to the selected one: those carrying it and those it carries
+    let mut lower_connections: HashSet<&str> = HashSet::new();
+    let mut upper_connections: HashSet<&str> = HashSet::new();
+    if let Some(selected) = &*selected_connection {
+        for connection in layers
+            .iter()
+            .flat_map(|layer| layer["connections"].as_array().unwrap_or(&empty))
+        {
+            let uuid = connection["connection_uuid"].as_str().unwrap_or_default();
+            let lowers: Vec<&str> = connection["lower_connections"]
+                .as_array()
+                .unwrap_or(&empty)
+                .iter()
+                .filter_map(Value::as_str)
+                .collect();
+            if uuid == selected {
+                lower_connections.extend(lowers);
+            } else if lowers.contains(&selected.as_str()) {
+                upper_connections.insert(uuid);
+            }
+        }
+    }
+
+    html! {
+        <div class="layers-view">
+            { for layers.iter().map(|layer| {
+                let connections = layer["connections"].as_array().unwrap_or(&empty);
+                let link_count = layer["links"].as_array().map_or(0, Vec::len);
+                html! {
+                    <div class="layer-row">
+                        <div class="layer-name">
+                            { layer["layer"].as_str().unwrap_or_default() }
+                            if link_count > 0 {
+                                <span class="layer-links">{ format!("{} links", link_count) }</span>
+                            }
+                        </div>
+                        <div class="layer-connections">
+                            { for connections.iter().map(|connection| {
+                                let uuid = connection["connection_uuid"].as_str().unwrap_or_default().to_string();
+                                let class = if selected_connection.as_deref() == Some(uuid.as_str()) {
+                                    "selected"
+                                } else if lower_connections.contains(uuid.as_str()) {
+                                    "lower-highlighted"
+                                } else if upper_connections.contains(uuid.as_str()) {
+                                    "upper-highlighted"
+                                } else {
+                                    ""
+                                };
+                                let lowers: Vec<&str> = connection["lower_connections"]
+                                    .as_array()
+                                    .unwrap_or(&empty)
+                                    .iter()
+                                    .filter_map(Value::as_str)
+                                    .map(short_uuid)
+                                    .collect();
+                                let on_click = {
+                                    let selected_connection = selected_connection.clone();
+                                    let uuid = uuid.clone();
+                                    Callback::from(move |_: MouseEvent| {
+                                        if selected_connection.as_deref() == Some(uuid.as_str()) {
+                                            selected_connection.set(None);
+                                        } else {
+                                            selected_connection.set(Some(uuid.clone()));
+                                        }
+                                    })
+                                };
+                                html! {
+                                    <div class={classes!("layer-connection", class)} title={uuid.clone()} onclick={on_click}>
+                                        <div class="layer-connection-uuid">{ short_uuid(&uuid) }</div>
+                                        <div class="layer-connection-end-points">
+                                            { for connection["connection_end_points"].as_array().unwrap_or(&empty).iter().map(|cep| {
+                                                let node_uuid = cep["node_uuid"].as_str().unwrap_or_default();
+                                                let node_edge_point_uuid = cep["node_edge_point_uuid"].as_str().unwrap_or_default();
+                                                let servers: Vec<&str> = cep["server_node_edge_points"]
+                                                    .as_array()
+                                                    .unwrap_or(&empty)
+                                                    .iter()
+                                                    .filter_map(Value::as_str)
+                                                    .collect();
+                                                let mut title = format!("Node edge point {}", node_edge_point_uuid);
+                                                if !servers.is_empty() {
+                                                    title.push_str(&format!("\nServed by {}", servers.join(", ")));
+                                                }
+                                                html! {
+                                                    <div class="layer-cep" title={title}>
+                                                        if let Some(hop) = cep["hop"].as_i64() {
+                                                            <span class="layer-cep-hop">{ hop }</span>
+                                                        }
+                                                        { node_names.get(node_uuid).copied().unwrap_or(short_uuid(node_uuid)) }
+                                                        <span class="layer-cep-nep">{ short_uuid(node_edge_point_uuid) }</span>
+                                                    </div>
+                                                }
+                                            }) }
+                                        </div>
+                                        if !lowers.is_empty() {
+                                            <div class="layer-served-by">{ format!("over {}", lowers.join(", ")) }</div>
+                                        }
+                                    </div>
+                                }
+                            }) }
+                        </div>
+                    </div>
+                }
+            }) }
+        </div>
+    }
+}
@@ -4,8 +4,13 @@ pub mod add_oauth_form;
 pub mod alert;
 pub mod button;
 pub mod footer_legend;
+pub mod health_badge;
 pub mod input;
+pub mod layers;
+pub mod network_map;
 pub mod nodes;
 pub mod select;
+pub mod service_form;
 pub mod sidebar;
+pub mod tls_fields;
 pub mod upload_form;
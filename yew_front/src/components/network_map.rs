@@ -0,0 +1,321 @@
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use web_sys::Element;
+use yew::{platform::spawn_local, prelude::*};
+
+use crate::api::connection::{follow_events, get_positions, get_topology, set_positions};
+
+/// Size of the drawing area of the map, in SVG units.
+const MAP_WIDTH: f64 = 1200.0;
+const MAP_HEIGHT: f64 = 800.0;
+
+/// Radius of the circle drawn for each node.
+const NODE_RADIUS: f64 = 18.0;
+
+/// Positions of the nodes of a map, by node UUID.
+type Positions = HashMap<String, (f64, f64)>;
+
+/// Properties for the `NetworkMap` component.
+#[derive(Properties, PartialEq)]
+pub struct NetworkMapProps {
+    /// The IP address of the device whose network is drawn.
+    pub device_ip: String,
+}
+
+/// Positions of all the nodes: the stored ones, and the others on a circle around the centre of the map.
+fn node_positions(nodes: &[Value], stored: &Positions) -> Positions {
+    let radius = MAP_WIDTH.min(MAP_HEIGHT) / 2.0 - 3.0 * NODE_RADIUS;
+    let count = nodes.len().max(1) as f64;
+    nodes
+        .iter()
+        .enumerate()
+        .filter_map(|(index, node)| {
+            let node_uuid = node["node_uuid"].as_str()?.to_string();
+            let position = stored.get(&node_uuid).copied().unwrap_or_else(|| {
+                let angle = 2.0 * std::f64::consts::PI * index as f64 / count;
+                (
+                    MAP_WIDTH / 2.0 + radius * angle.cos(),
+                    MAP_HEIGHT / 2.0 + radius * angle.sin(),
+                )
+            });
+            Some((node_uuid, position))
+        })
+        .collect()
+}
+
+/// Position of the mouse in the coordinates of the map.
+fn map_point(svg: &NodeRef, event: &MouseEvent) -> Option<(f64, f64)> {
+    let rect = svg.cast::<Element>()?.get_bounding_client_rect();
+    if rect.width() == 0.0 || rect.height() == 0.0 {
+        return None;
+    }
+    Some((
+        (event.client_x() as f64 - rect.left()) * MAP_WIDTH / rect.width(),
+        (event.client_y() as f64 - rect.top()) * MAP_HEIGHT / rect.height(),
+    ))
+}
+
+/// A Yew functional component that draws the whole network of a device as a map.
+///
+/// Nodes are drawn as circles and the links between them as lines, one line per pair of nodes with
+/// the number of links on it. Clicking a node lists its inventories, with their edge points, and
+/// the nodes it is linked to.
+///
+/// Nodes can be dragged. Their positions are stored on the server under the host when a drag ends,
+/// and loaded from there before the map is drawn, so every client sees the same layout.
+#[function_component(NetworkMap)]
+pub fn network_map(props: &NetworkMapProps) -> Html {
+    let topology = use_state(|| None::<Value>);
+    // Positions of the nodes moved on this host, the others are laid out on a circle
+    let positions = use_state(Positions::new);
+    let dragging = use_state(|| None::<String>);
+    let selected_node = use_state(|| None::<String>);
+    let svg_ref = use_node_ref();
+
+    {
+        let topology = topology.clone();
+        let positions = positions.clone();
+        let ip = props.device_ip.clone();
+        use_effect_with((), move |_| {
+            spawn_local(async move {
+                // The stored layout is loaded first, the map is drawn once the network arrives
+                match get_positions(&ip)
+                    .await
+                    .map(serde_json::from_value::<Positions>)
+                {
+                    Ok(Ok(stored)) => positions.set(stored),
+                    _ => web_sys::console::warn_1(&"Cannot load the positions of the map".into()),
+                }
+                match get_topology(ip).await {
+                    Ok(fetched_json) => topology.set(Some(fetched_json)),
+                    Err(_) => {
+                        topology.set(Some(serde_json::json!({"error": "Failed to fetch JSON"})))
+                    }
+                }
+            });
+            || ()
+        });
+    }
+
+    // Fetch the network again each time the device reports a change, keeping the layout
+    {
+        let topology = topology.clone();
+        let ip = props.device_ip.clone();
+        use_effect_with((), move |_| {
+            let events = follow_events(&ip.clone(), move |_| {
+                let topology = topology.clone();
+                let ip = ip.clone();
+                spawn_local(async move {
+                    if let Ok(fetched_json) = get_topology(ip).await {
+                        topology.set(Some(fetched_json));
+                    }
+                });
+            })
+            .ok();
+            move || drop(events)
+        });
+    }
+
+    let on_mouse_move = {
+        let positions = positions.clone();
+        let dragging = dragging.clone();
+        let svg_ref = svg_ref.clone();
+        Callback::from(move |event: MouseEvent| {
+            if let (Some(node_uuid), Some(point)) = (&*dragging, map_point(&svg_ref, &event)) {
+                let mut moved = (*positions).clone();
+                moved.insert(node_uuid.clone(), point);
+                positions.set(moved);
+            }
+        })
+    };
+
+    let on_mouse_up = {
+        let positions = positions.clone();
+        let dragging = dragging.clone();
+        let ip = props.device_ip.clone();
+        Callback::from(move |_: MouseEvent| {
+            if dragging.is_some() {
+                dragging.set(None);
+                let ip = ip.clone();
+                let moved = serde_json::json!(*positions);
+                spawn_local(async move {
+                    if set_positions(&ip, &moved).await.is_err() {
+                        web_sys::console::warn_1(&"Cannot store the positions of the map".into());
+                    }
+                });
+            }
+        })
+    };
+
+    let on_reset_layout = {
+        let positions = positions.clone();
+        let ip = props.device_ip.clone();
+        Callback::from(move |_: MouseEvent| {
+            positions.set(Positions::new());
+            let ip = ip.clone();
+            spawn_local(async move {
+                if set_positions(&ip, &serde_json::json!({})).await.is_err() {
+                    web_sys::console::warn_1(&"Cannot reset the positions of the map".into());
+                }
+            });
+        })
+    };
+
+    let Some(topology_value) = (*topology).clone() else {
+        return html! { <p>{"Loading..."}</p> };
+    };
+    if let Some(error) = topology_value["error"].as_str() {
+        return html! { <p class="network-map-error">{ error }</p> };
+    }
+    let empty = vec![];
+    let nodes = topology_value["nodes"].as_array().unwrap_or(&empty);
+    let links = topology_value["links"].as_array().unwrap_or(&empty);
+    let layout = node_positions(nodes, &positions);
+
+    // Links grouped by the pair of nodes they join, in a stable order
+    let mut node_pairs: BTreeMap<(String, String), Vec<String>> = BTreeMap::new();
+    for link in links {
+        let ends: Vec<&str> = link["node_edge_points"]
+            .as_array()
+            .unwrap_or(&empty)
+            .iter()
+            .filter_map(|end| end["node_uuid"].as_str())
+            .collect();
+        if let [first, second, ..] = ends.as_slice() {
+            let pair = if first <= second {
+                (first.to_string(), second.to_string())
+            } else {
+                (second.to_string(), first.to_string())
+            };
+            node_pairs
+                .entry(pair)
+                .or_default()
+                .push(link["link_uuid"].as_str().unwrap_or_default().to_string());
+        }
+    }
+
+    let node_names: HashMap<&str, &str> = nodes
+        .iter()
+        .filter_map(|node| Some((node["node_uuid"].as_str()?, node["value_name"].as_str()?)))
+        .collect();
+    let selected = (*selected_node).clone();
+    let details = selected.as_ref().and_then(|selected| {
+        nodes
+            .iter()
+            .find(|node| node["node_uuid"].as_str() == Some(selected.as_str()))
+    });
+    let neighbours: Vec<(&str, usize)> = selected
+        .as_ref()
+        .map(|selected| {
+            node_pairs
+                .iter()
+                .filter_map(|((first, second), link_uuids)| {
+                    if first == selected {
+                        Some((second.as_str(), link_uuids.len()))
+                    } else if second == selected {
+                        Some((first.as_str(), link_uuids.len()))
+                    } else {
+                        None
+                    }
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    html! {
+        <div class="network-map">
+            <div class="network-map-toolbar">
+                <span>{ format!("{} nodes, {} links", nodes.len(), links.len()) }</span>
+                <button class="network-map-reset" onclick={on_reset_layout}>{"Reset layout"}</button>
+            </div>
+            <div class="network-map-body">
+                <svg
+                    ref={svg_ref}
+                    class="network-map-canvas"
+                    viewBox={format!("0 0 {} {}", MAP_WIDTH, MAP_HEIGHT)}
+                    onmousemove={on_mouse_move}
+                    onmouseup={on_mouse_up.clone()}
+                    onmouseleave={on_mouse_up}
+                >
+                    { for node_pairs.iter().filter_map(|((first, second), link_uuids)| {
+                        let (x1, y1) = layout.get(first)?;
+                        let (x2, y2) = layout.get(second)?;
+                        let highlighted = selected.as_ref().is_some_and(|selected| selected == first || selected == second);
+                        Some(html! {
+                            <g class={classes!("network-map-link", highlighted.then_some("highlighted"))}>
+                                <title>{ link_uuids.join("\n") }</title>
+                                <line
+                                    x1={x1.to_string()} y1={y1.to_string()}
+                                    x2={x2.to_string()} y2={y2.to_string()}
+                                    stroke-width={(1 + link_uuids.len().min(6)).to_string()}
+                                />
+                                if link_uuids.len() > 1 {
+                                    <text x={((x1 + x2) / 2.0).to_string()} y={((y1 + y2) / 2.0).to_string()}>
+                                        { link_uuids.len() }
+                                    </text>
+                                }
+                            </g>
+                        })
+                    }) }
+                    { for nodes.iter().filter_map(|node| {
+                        let node_uuid = node["node_uuid"].as_str()?.to_string();
+                        let (x, y) = *layout.get(&node_uuid)?;
+                        let name = node["value_name"].as_str().unwrap_or_default();
+                        let on_mouse_down = {
+                            let dragging = dragging.clone();
+                            let selected_node = selected_node.clone();
+                            let node_uuid = node_uuid.clone();
+                            Callback::from(move |event: MouseEvent| {
+                                event.prevent_default();
+                                dragging.set(Some(node_uuid.clone()));
+                                selected_node.set(Some(node_uuid.clone()));
+                            })
+                        };
+                        let is_selected = selected.as_deref() == Some(node_uuid.as_str());
+                        Some(html! {
+                            <g class={classes!("network-map-node", is_selected.then_some("selected"))} onmousedown={on_mouse_down}>
+                                <title>{ &node_uuid }</title>
+                                <circle cx={x.to_string()} cy={y.to_string()} r={NODE_RADIUS.to_string()} />
+                                <text x={x.to_string()} y={(y + NODE_RADIUS + 14.0).to_string()}>{ name }</text>
+                            </g>
+                        })
+                    }) }
+                </svg>
+                if let Some(node) = details {
+                    <div class="network-map-details">
+                        <h3>{ node["value_name"].as_str().unwrap_or_default() }</h3>
+                        <p class="network-map-uuid">{ node["node_uuid"].as_str().unwrap_or_default() }</p>
+                        <h4>{"Inventories"}</h4>
+                        <ul>
+                            { for node["inventories"].as_array().unwrap_or(&empty).iter().map(|inventory| {
+                                let node_edge_points = inventory["node_edge_points"].as_array().unwrap_or(&empty);
+                                html! {
+                                    <li>
+                                        <details>
+                                            <summary>{ format!("{} ({})", inventory["inventory_id"].as_str().unwrap_or_default(), node_edge_points.len()) }</summary>
+                                            <ul>
+                                                { for node_edge_points.iter().map(|node_edge_point| html! {
+                                                    <li class="network-map-uuid">
+                                                        { node_edge_point["node_edge_point_uuid"].as_str().unwrap_or_default() }
+                                                        { format!(" {}", node_edge_point["layer_protocol_qualifier"].as_str().unwrap_or_default().rsplit(':').next().unwrap_or_default()) }
+                                                    </li>
+                                                }) }
+                                            </ul>
+                                        </details>
+                                    </li>
+                                }
+                            }) }
+                        </ul>
+                        <h4>{"Linked to"}</h4>
+                        <ul>
+                            { for neighbours.iter().map(|(node_uuid, count)| {
+                                let name = node_names.get(node_uuid).copied().unwrap_or(node_uuid);
+                                html! { <li>{ format!("{} ({} links)", name, count) }</li> }
+                            }) }
+                        </ul>
+                    </div>
+                }
+            </div>
+        </div>
+    }
+}
@@ -3,9 +3,11 @@ use serde_json::Value;
 use std::collections::HashMap;
 use std::collections::HashSet;
 use wasm_bindgen::JsCast;
+use web_sys::HtmlSelectElement;
 use yew::{platform::spawn_local, prelude::*};
 
-use crate::api::connection::get_schema;
+use crate::api::connection::{follow_events, get_schema};
+use crate::components::select::Select;
 
 fn get_position(id: &str) -> Option<(f64, f64)> {
     let window = web_sys::window()?;
@@ -83,29 +85,29 @@ fn highlight_class(
 #[function_component(Nodes)]
 pub fn nodes(props: &NodesProps) -> Html {
     let layer_protocol_qualifier_types: Vec<&str> = vec![
-        "_10_GigE_WAN\"",
-        "_10_GigE_LAN\"",
-        "_DSR\"",
-        "_ODU\"",
-        "_ODU0\"",
-        "_ODU1\"",
-        "_ODU2\"",
-        "_ODU2E\"",
-        "_ODU3\"",
-        "_ODU4\"",
-        "_ODU_CN\"",
-        "_OTU\"",
-        "_OTU_0\"",
-        "_OTU_1\"",
-        "_OTU_2\"",
-        "_OTU_3\"",
-        "_OTU_4\"",
-        "_OTSI\"",
-        "_OTSIMC\"",
-        "_MC\"",
-        "_UNSPECIFIED\"",
-        "_OMS\"",
-        "_OTS\"",
+        "_10_GigE_WAN",
+        "_10_GigE_LAN",
+        "_DSR",
+        "_ODU",
+        "_ODU0",
+        "_ODU1",
+        "_ODU2",
+        "_ODU2E",
+        "_ODU3",
+        "_ODU4",
+        "_ODU_CN",
+        "_OTU",
+        "_OTU_0",
+        "_OTU_1",
+        "_OTU_2",
+        "_OTU_3",
+        "_OTU_4",
+        "_OTSI",
+        "_OTSIMC",
+        "_MC",
+        "_UNSPECIFIED",
+        "_OMS",
+        "_OTS",
     ];
 
     let json_data = use_state(|| None);
@@ -116,7 +118,7 @@ pub fn nodes(props: &NodesProps) -> Html {
         use_effect_with((), move |_| {
             let json_clone = json_clone.clone();
             spawn_local(async move {
-                match get_schema(ip.clone(), service_uuid.clone().replace("\"", "")).await {
+                match get_schema(ip.clone(), service_uuid.clone()).await {
                     Ok(fetched_json) => json_clone.set(Some(fetched_json)),
                     Err(_) => {
                         json_clone.set(Some(serde_json::json!({"error": "Failed to fetch JSON"})))
@@ -127,6 +129,27 @@ pub fn nodes(props: &NodesProps) -> Html {
         });
     }
 
+    // Fetch the schema again each time the device reports a change
+    {
+        let json_clone = json_data.clone();
+        let ip = props.device_ip.clone();
+        let service_uuid = props.service_uuid.clone();
+        use_effect_with((), move |_| {
+            let events = follow_events(&ip.clone(), move |_| {
+                let json_clone = json_clone.clone();
+                let ip = ip.clone();
+                let service_uuid = service_uuid.clone();
+                spawn_local(async move {
+                    if let Ok(fetched_json) = get_schema(ip, service_uuid).await {
+                        json_clone.set(Some(fetched_json));
+                    }
+                });
+            })
+            .ok();
+            move || drop(events)
+        });
+    }
+
     // State hooks for managing hover data, selected endpoint, and highlighted endpoints
     let hover_data = use_state(|| "Hover over an endpoint to see details".to_string());
     let is_modal_open = use_state(|| false);
@@ -142,6 +165,17 @@ pub fn nodes(props: &NodesProps) -> Html {
 
     let positions_pairs_vec = use_state(Vec::<((f64, f64), (f64, f64), String)>::new);
 
+    // Topology whose nodes are shown, all of them when empty
+    let topology_filter = use_state(String::new);
+    let on_change_topology = {
+        let topology_filter = topology_filter.clone();
+        Callback::from(move |e: Event| {
+            if let Some(select) = e.target_dyn_into::<HtmlSelectElement>() {
+                topology_filter.set(select.value());
+            }
+        })
+    };
+
     // Callback to handle right-click (context menu) events and update hover data
     let oncontextmenu = {
         let hover_data = hover_data.clone();
@@ -357,7 +391,46 @@ pub fn nodes(props: &NodesProps) -> Html {
     let json_clone = json_data.clone();
     let json_data_value = (*json_clone).clone().unwrap_or(empty_value.clone()); // Clonar el valor para mantenerlo vivo
     let nodes_response = json_data_value.get("nodes").unwrap_or(&empty_value);
-    let nodes: &Vec<Value> = nodes_response.as_array().unwrap_or(&empty_array);
+    let all_nodes: &Vec<Value> = nodes_response.as_array().unwrap_or(&empty_array);
+
+    // Topologies of the nodes, in order of appearance
+    let mut topology_options: Vec<(AttrValue, AttrValue)> = Vec::new();
+    for node in all_nodes {
+        let topology_uuid = node["topology_uuid"].as_str().unwrap_or_default();
+        if !topology_uuid.is_empty()
+            && !topology_options
+                .iter()
+                .any(|(value, _)| value.as_str() == topology_uuid)
+        {
+            topology_options.push((
+                AttrValue::from(topology_uuid.to_string()),
+                AttrValue::from(topology_uuid.to_string()),
+            ));
+        }
+    }
+    // Warnings of the trace by node edge point, to mark the broken endpoints
+    let warnings: &Vec<Value> = json_data_value
+        .get("warnings")
+        .and_then(Value::as_array)
+        .unwrap_or(&empty_array);
+    let mut warnings_by_node_edge_point: HashMap<&str, Vec<&str>> = HashMap::new();
+    for warning in warnings {
+        if let Some(node_edge_point_uuid) = warning["node_edge_point_uuid"].as_str() {
+            warnings_by_node_edge_point
+                .entry(node_edge_point_uuid)
+                .or_default()
+                .push(warning["message"].as_str().unwrap_or_default());
+        }
+    }
+
+    let nodes: Vec<Value> = all_nodes
+        .iter()
+        .filter(|node| {
+            topology_filter.is_empty()
+                || node["topology_uuid"].as_str() == Some(topology_filter.as_str())
+        })
+        .cloned()
+        .collect();
 
     // Function to format JSON values as pretty-printed strings
     fn format_json(value: &Value) -> String {
@@ -476,7 +549,7 @@ pub fn nodes(props: &NodesProps) -> Html {
                                             html! {
                                                 <div class="inventory-item-good">
                                                 <h3>
-                                                    { format!("{}\n", inventory_id.replace("/", "\n").replace('"', "")) }
+                                                    { format!("{}\n", inventory_id.replace("/", "\n")) }
                                                 </h3>
                                                     <div class="endpoints-container-good">
                                                         {
@@ -575,7 +648,7 @@ pub fn nodes(props: &NodesProps) -> Html {
 
                                                                         let last_nepu: &str = {
                                                                                 if let Some(node_edge_point_uuid) = ep["node_edge_point_uuid"].as_str() {
-                                                                                    &node_edge_point_uuid[node_edge_point_uuid.len()-5..node_edge_point_uuid.len()-1]
+                                                                                    &node_edge_point_uuid[node_edge_point_uuid.len()-4..]
                                                                             } else {
                                                                                 ""
                                                                             }
@@ -584,9 +657,9 @@ pub fn nodes(props: &NodesProps) -> Html {
                                                                         let qualifier: &str = {
                                                                             if let Some(protocol_qualifier) = ep["layer_protocol_qualifier"].as_str() {
                                                                                 if let Some(qualifier_index) = protocol_qualifier.find("QUALIFIER_") {
-                                                                                    &protocol_qualifier[qualifier_index + "QUALIFIER_".len()..protocol_qualifier.len()-1]
+                                                                                    &protocol_qualifier[qualifier_index + "QUALIFIER_".len()..]
                                                                                 } else if let Some(qualifier_index) = protocol_qualifier.find("TYPE_") {
-                                                                                    &protocol_qualifier[qualifier_index + "TYPE_".len()..protocol_qualifier.len()-1]
+                                                                                    &protocol_qualifier[qualifier_index + "TYPE_".len()..]
                                                                                 } else {
                                                                                     ""
                                                                                 }
@@ -598,6 +671,8 @@ pub fn nodes(props: &NodesProps) -> Html {
                                                                         let id: &str = {
                                                                             ep["node_edge_point_uuid"].as_str().unwrap_or_default()
                                                                         };
+                                                                        let endpoint_warnings = warnings_by_node_edge_point.get(id).map(|messages| messages.join("\n"));
+                                                                        let has_warnings = endpoint_warnings.is_some();
 
                                                                         not_empty_endpoint.push(html! {
                                                                             <div class="endpoint-wrapper">
@@ -615,15 +690,17 @@ pub fn nodes(props: &NodesProps) -> Html {
                                                                                             is_connection_highlighted,
                                                                                             is_link_highlighted,
                                                                                         ),
-                                                                                        if service_flag { "first" } else { "second" }
+                                                                                        if service_flag { "first" } else { "second" },
+                                                                                        has_warnings.then_some("warning-highlighted")
                                                                                     )}
+                                                                                    title={endpoint_warnings}
                                                                                     oncontextmenu={prevent_default_context_menu.clone()}
                                                                                     oncontextmenu={oncontextmenu.clone()}
                                                                                     onclick={on_click}
                                                                                 >
                                                                                     {""}
                                                                                 </div>
-                                                                                <div class="endpoint-details">{format!("{} / {}", last_nepu, qualifier)}</div>
+                                                                                <div class="endpoint-details">{format!("{}. {} / {}", ep["id"], last_nepu, qualifier)}</div>
                                                                             </div>
                                                                         });
                                                                     }
@@ -658,6 +735,30 @@ pub fn nodes(props: &NodesProps) -> Html {
 
     html! {
         <div class="simple-div">
+            if topology_options.len() > 1 {
+                <div class="topology-filter">
+                    <Select
+                        label="Topology"
+                        name="topology"
+                        options={topology_options}
+                        value={AttrValue::from((*topology_filter).clone())}
+                        onchange={on_change_topology}
+                    />
+                </div>
+            }
+            if !warnings.is_empty() {
+                <div class="trace-warnings">
+                    <h3>{ format!("{} warnings found while tracing the service", warnings.len()) }</h3>
+                    <ul>
+                        { for warnings.iter().map(|warning| html! {
+                            <li class="trace-warning">
+                                <span class="trace-warning-kind">{ warning["kind"].as_str().unwrap_or_default().replace('_', " ") }</span>
+                                { warning["message"].as_str().unwrap_or_default() }
+                            </li>
+                        }) }
+                    </ul>
+                </div>
+            }
             { content }
             // Modal (pop-up) que aparece con el clic derecho
             if *is_modal_open {
@@ -0,0 +1,329 @@
+use serde_json::{json, Value};
+use web_sys::{HtmlInputElement, HtmlSelectElement};
+use yew::{platform::spawn_local, prelude::*};
+
+use crate::api::connection::{create_service, get_sips};
+use crate::components::{alert::Alert, button::Button, input::Input, select::Select};
+
+/// Layer protocols offered for new services.
+const LAYER_PROTOCOLS: [&str; 4] = ["PHOTONIC_MEDIA", "ODU", "DSR", "ETH"];
+
+/// Units offered for the requested capacity.
+const CAPACITY_UNITS: [&str; 3] = ["GBPS", "MBPS", "GHz"];
+
+/// Properties for the `ServiceForm` component.
+#[derive(Properties, PartialEq)]
+pub struct Props {
+    /// The IP address of the device the services are created on.
+    pub device_ip: String,
+    /// Callback invoked once a service is created on the device.
+    pub on_created: Callback<()>,
+}
+
+/// Splits a comma separated list of UUIDs, skipping empty entries.
+fn split_list(list: &str) -> Vec<String> {
+    list.split(',')
+        .map(str::trim)
+        .filter(|uuid| !uuid.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Builds the callback of a text input, storing its value in `handle`.
+fn on_change_text(handle: &UseStateHandle<String>) -> Callback<Event> {
+    let handle = handle.clone();
+    Callback::from(move |e: Event| {
+        if let Some(input) = e.target_dyn_into::<HtmlInputElement>() {
+            handle.set(input.value());
+        }
+    })
+}
+
+/// A functional Yew component that renders a form to create a connectivity service on a device.
+///
+/// The user selects the service interface points offered by the device, the layer protocol and
+/// its qualifier, the capacity and the routing constraints. With "Dry run" checked the server only
+/// renders the TAPI payload, which is shown below the form; otherwise the service is created and
+/// the resulting lifecycle and operational states are shown.
+#[function_component(ServiceForm)]
+pub fn service_form(props: &Props) -> Html {
+    let ip = props.device_ip.clone();
+
+    // State hooks to manage form input values, the result and error messages
+    let sips_handle = use_state(Vec::<Value>::new);
+    let selected_sips_handle = use_state(Vec::<String>::new);
+    let name_handle = use_state(String::default);
+    let layer_handle = use_state(|| LAYER_PROTOCOLS[0].to_string());
+    let qualifier_handle = use_state(String::default);
+    let capacity_handle = use_state(String::default);
+    let unit_handle = use_state(|| CAPACITY_UNITS[0].to_string());
+    let service_level_handle = use_state(String::default);
+    let include_nodes_handle = use_state(String::default);
+    let exclude_nodes_handle = use_state(String::default);
+    let dry_run_handle = use_state(|| true);
+    let result_handle = use_state(|| None::<Value>);
+    let error_message_handle = use_state(String::default);
+    let is_sending_handle = use_state(|| false);
+
+    // Fetch the service interface points on component mount
+    {
+        let sips_handle = sips_handle.clone();
+        let error_message_handle = error_message_handle.clone();
+        use_effect_with(ip.clone(), move |ip| {
+            let ip = ip.clone();
+            spawn_local(async move {
+                match get_sips(&ip).await {
+                    Ok(Value::Array(sips)) => sips_handle.set(sips),
+                    _ => error_message_handle
+                        .set("Failed to fetch the service interface points".to_string()),
+                }
+            });
+            || ()
+        });
+    }
+
+    let on_toggle_sip = {
+        let selected_sips_handle = selected_sips_handle.clone();
+        Callback::from(move |e: Event| {
+            if let Some(input) = e.target_dyn_into::<HtmlInputElement>() {
+                let mut selected_sips = (*selected_sips_handle).clone();
+                selected_sips.retain(|uuid| *uuid != input.value());
+                if input.checked() {
+                    selected_sips.push(input.value());
+                }
+                selected_sips_handle.set(selected_sips);
+            }
+        })
+    };
+
+    let on_change_select = |handle: &UseStateHandle<String>| {
+        let handle = handle.clone();
+        Callback::from(move |e: Event| {
+            if let Some(select) = e.target_dyn_into::<HtmlSelectElement>() {
+                handle.set(select.value());
+            }
+        })
+    };
+    let on_change_layer = on_change_select(&layer_handle);
+    let on_change_unit = on_change_select(&unit_handle);
+
+    let on_change_dry_run = {
+        let dry_run_handle = dry_run_handle.clone();
+        Callback::from(move |e: Event| {
+            if let Some(input) = e.target_dyn_into::<HtmlInputElement>() {
+                dry_run_handle.set(input.checked());
+            }
+        })
+    };
+
+    // Callback for form submission
+    let on_submit = {
+        let ip = ip.clone();
+        let selected_sips = (*selected_sips_handle).clone();
+        let name = (*name_handle).clone();
+        let layer = (*layer_handle).clone();
+        let qualifier = (*qualifier_handle).clone();
+        let capacity = (*capacity_handle).clone();
+        let unit = (*unit_handle).clone();
+        let service_level = (*service_level_handle).clone();
+        let include_nodes = (*include_nodes_handle).clone();
+        let exclude_nodes = (*exclude_nodes_handle).clone();
+        let dry_run = *dry_run_handle;
+        let result_handle = result_handle.clone();
+        let error_message_handle = error_message_handle.clone();
+        let is_sending_handle = is_sending_handle.clone();
+        let on_created = props.on_created.clone();
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default(); // Prevent default form submission behavior
+
+            let capacity = match capacity.trim() {
+                "" => None,
+                value => match value.parse::<u64>() {
+                    Ok(value) => Some(json!({ "value": value, "unit": unit })),
+                    Err(_) => {
+                        error_message_handle.set("The capacity must be a number".to_string());
+                        return;
+                    }
+                },
+            };
+            let request = json!({
+                "name": (!name.is_empty()).then_some(&name),
+                "service_interface_points": selected_sips,
+                "layer_protocol_name": layer,
+                "layer_protocol_qualifier": (!qualifier.is_empty()).then_some(&qualifier),
+                "capacity": capacity,
+                "constraints": {
+                    "service_level": (!service_level.is_empty()).then_some(&service_level),
+                    "include_nodes": split_list(&include_nodes),
+                    "exclude_nodes": split_list(&exclude_nodes),
+                },
+            });
+
+            let ip = ip.clone();
+            let result_handle = result_handle.clone();
+            let error_message_handle = error_message_handle.clone();
+            let is_sending_handle = is_sending_handle.clone();
+            let on_created = on_created.clone();
+            is_sending_handle.set(true);
+            error_message_handle.set(String::new());
+            spawn_local(async move {
+                match create_service(&ip, &request, dry_run).await {
+                    Ok(result) => {
+                        if !dry_run {
+                            on_created.emit(());
+                        }
+                        result_handle.set(Some(result));
+                    }
+                    Err(message) => {
+                        result_handle.set(None);
+                        error_message_handle.set(message);
+                    }
+                }
+                is_sending_handle.set(false);
+            });
+        })
+    };
+
+    let options = |values: &[&str]| {
+        values
+            .iter()
+            .map(|value| {
+                (
+                    AttrValue::from(value.to_string()),
+                    AttrValue::from(value.to_string()),
+                )
+            })
+            .collect::<Vec<_>>()
+    };
+
+    // Render the form
+    html! {
+        <form class="form-container service-form" onsubmit={on_submit}>
+            <h3>{"Create Connectivity Service"}</h3>
+            if !error_message_handle.is_empty() {
+                <Alert alert_type={"danger"} message={(*error_message_handle).clone()}/>
+            }
+            <div class="input-group">
+                <Input
+                    input_type="text"
+                    name="service_name"
+                    label="Name"
+                    placeholder="optional"
+                    value={(*name_handle).clone()}
+                    onchange={on_change_text(&name_handle)}
+                />
+            </div>
+            <div class="input-group">
+                <label>{"Service Interface Points"}</label>
+                <div class="sip-list">
+                    { for sips_handle.iter().map(|sip| {
+                        let uuid = sip["uuid"].as_str().unwrap_or_default().to_string();
+                        let label = format!(
+                            "{} ({}, {})",
+                            uuid,
+                            sip["node_name"].as_str().unwrap_or("?"),
+                            sip["layer_protocol_name"].as_str().unwrap_or("?"),
+                        );
+                        html! {
+                            <label class="sip-option">
+                                <input
+                                    type="checkbox"
+                                    value={uuid.clone()}
+                                    checked={selected_sips_handle.contains(&uuid)}
+                                    onchange={on_toggle_sip.clone()}
+                                />
+                                { label }
+                            </label>
+                        }
+                    }) }
+                </div>
+            </div>
+            <div class="input-group">
+                <Select
+                    label="Layer Protocol"
+                    name="layer_protocol_name"
+                    options={options(&LAYER_PROTOCOLS)}
+                    value={(*layer_handle).clone()}
+                    onchange={on_change_layer}
+                />
+            </div>
+            <div class="input-group">
+                <Input
+                    input_type="text"
+                    name="layer_protocol_qualifier"
+                    label="Layer Qualifier"
+                    placeholder="tapi-photonic-media:PHOTONIC_LAYER_QUALIFIER_MC"
+                    value={(*qualifier_handle).clone()}
+                    onchange={on_change_text(&qualifier_handle)}
+                />
+            </div>
+            <div class="input-group">
+                <Input
+                    input_type="number"
+                    name="capacity"
+                    label="Capacity"
+                    placeholder="100"
+                    value={(*capacity_handle).clone()}
+                    onchange={on_change_text(&capacity_handle)}
+                />
+                <Select
+                    label="Unit"
+                    name="capacity_unit"
+                    options={options(&CAPACITY_UNITS)}
+                    value={(*unit_handle).clone()}
+                    onchange={on_change_unit}
+                />
+            </div>
+            <div class="input-group">
+                <Input
+                    input_type="text"
+                    name="service_level"
+                    label="Service Level"
+                    placeholder="GOLD"
+                    value={(*service_level_handle).clone()}
+                    onchange={on_change_text(&service_level_handle)}
+                />
+            </div>
+            <div class="input-group">
+                <Input
+                    input_type="text"
+                    name="include_nodes"
+                    label="Include Nodes"
+                    placeholder="node UUIDs, comma separated"
+                    value={(*include_nodes_handle).clone()}
+                    onchange={on_change_text(&include_nodes_handle)}
+                />
+            </div>
+            <div class="input-group">
+                <Input
+                    input_type="text"
+                    name="exclude_nodes"
+                    label="Exclude Nodes"
+                    placeholder="node UUIDs, comma separated"
+                    value={(*exclude_nodes_handle).clone()}
+                    onchange={on_change_text(&exclude_nodes_handle)}
+                />
+            </div>
+            <div class="input-group">
+                <label for="edit-dry_run">{"Dry run"}</label>
+                <input
+                    id="edit-dry_run"
+                    type="checkbox"
+                    checked={*dry_run_handle}
+                    onchange={on_change_dry_run}
+                />
+            </div>
+            <Button
+                btn_type="submit"
+                class="primary"
+                onclick={None::<Callback<MouseEvent>>}
+                message={if *is_sending_handle { "Sending..." } else if *dry_run_handle { "Render Payload" } else { "Create Service" }}
+            />
+            if let Some(result) = (*result_handle).clone() {
+                <pre class="provisioning-result">
+                    { serde_json::to_string_pretty(&result).unwrap_or_default() }
+                </pre>
+            }
+        </form>
+    }
+}
@@ -0,0 +1,149 @@
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{HtmlInputElement, HtmlSelectElement};
+use yew::{platform::spawn_local, prelude::*};
+
+use crate::api::connection::{Scheme, TlsConfig};
+use crate::components::select::Select;
+
+/// Properties for the `TlsFields` component.
+#[derive(Properties, PartialEq)]
+pub struct Props {
+    /// The TLS settings currently entered.
+    pub tls: TlsConfig,
+    /// Callback receiving the TLS settings after every change.
+    pub onchange: Callback<TlsConfig>,
+}
+
+/// Reads the first file of a file input as text.
+async fn read_file(input: HtmlInputElement) -> Option<String> {
+    let file = input.files()?.get(0)?;
+    JsFuture::from(file.text()).await.ok()?.as_string()
+}
+
+/// Builds the callback of a file input, storing the content of the file with `set`.
+fn on_change_file(
+    tls: &TlsConfig,
+    onchange: &Callback<TlsConfig>,
+    set: fn(&mut TlsConfig, Option<String>),
+) -> Callback<Event> {
+    let tls = tls.clone();
+    let onchange = onchange.clone();
+    Callback::from(move |e: Event| {
+        if let Some(input) = e.target_dyn_into::<HtmlInputElement>() {
+            let mut tls = tls.clone();
+            let onchange = onchange.clone();
+            spawn_local(async move {
+                set(&mut tls, read_file(input).await);
+                onchange.emit(tls);
+            });
+        }
+    })
+}
+
+/// A functional Yew component with the TLS fields shared by the forms adding a device.
+///
+/// It lets the user choose the scheme, upload a CA bundle and a client certificate and key for
+/// mutual TLS, and opt into accepting invalid certificates, which is shown as a warning.
+#[function_component(TlsFields)]
+pub fn tls_fields(props: &Props) -> Html {
+    let tls = props.tls.clone();
+
+    let on_change_scheme = {
+        let tls = tls.clone();
+        let onchange = props.onchange.clone();
+        Callback::from(move |e: Event| {
+            if let Some(select) = e.target_dyn_into::<HtmlSelectElement>() {
+                let mut tls = tls.clone();
+                tls.scheme = if select.value() == "http" {
+                    Scheme::Http
+                } else {
+                    Scheme::Https
+                };
+                onchange.emit(tls);
+            }
+        })
+    };
+
+    let on_change_insecure = {
+        let tls = tls.clone();
+        let onchange = props.onchange.clone();
+        Callback::from(move |e: Event| {
+            if let Some(input) = e.target_dyn_into::<HtmlInputElement>() {
+                let mut tls = tls.clone();
+                tls.insecure = input.checked();
+                onchange.emit(tls);
+            }
+        })
+    };
+
+    let on_change_ca_certificate = on_change_file(&tls, &props.onchange, |tls, pem| {
+        tls.ca_certificate = pem;
+    });
+    let on_change_client_certificate = on_change_file(&tls, &props.onchange, |tls, pem| {
+        tls.client_certificate = pem;
+    });
+    let on_change_client_key = on_change_file(&tls, &props.onchange, |tls, pem| {
+        tls.client_key = pem;
+    });
+
+    html! {
+        <>
+            <div class="input-group">
+                <Select
+                    label="Scheme"
+                    name="scheme"
+                    options={vec![
+                        (AttrValue::from("https"), AttrValue::from("HTTPS")),
+                        (AttrValue::from("http"), AttrValue::from("HTTP")),
+                    ]}
+                    value={if tls.scheme == Scheme::Http { "http" } else { "https" }}
+                    onchange={on_change_scheme}
+                />
+            </div>
+            if tls.scheme == Scheme::Https {
+                <div class="input-group">
+                    <label for="edit-ca_certificate">{"CA Bundle"}</label>
+                    <input
+                        id="edit-ca_certificate"
+                        class="form-control"
+                        type="file"
+                        accept=".pem,.crt,.cer"
+                        onchange={on_change_ca_certificate}
+                    />
+                </div>
+                <div class="input-group">
+                    <label for="edit-client_certificate">{"Client Certificate"}</label>
+                    <input
+                        id="edit-client_certificate"
+                        class="form-control"
+                        type="file"
+                        accept=".pem,.crt,.cer"
+                        onchange={on_change_client_certificate}
+                    />
+                </div>
+                <div class="input-group">
+                    <label for="edit-client_key">{"Client Key"}</label>
+                    <input
+                        id="edit-client_key"
+                        class="form-control"
+                        type="file"
+                        accept=".pem,.key"
+                        onchange={on_change_client_key}
+                    />
+                </div>
+                <div class="input-group">
+                    <label for="edit-insecure">{"Insecure TLS"}</label>
+                    <input
+                        id="edit-insecure"
+                        type="checkbox"
+                        checked={tls.insecure}
+                        onchange={on_change_insecure}
+                    />
+                    if tls.insecure {
+                        <span class="tls-warning">{"⚠ Certificates will not be verified"}</span>
+                    }
+                </div>
+            }
+        </>
+    }
+}
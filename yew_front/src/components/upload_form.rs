@@ -221,7 +221,7 @@ pub fn upload_form() -> Html {
                     <input
                         id="complete-context-upload-id"
                         type="file"
-                        accept="application/json"
+                        accept=".json,.xml,.gz,.zip,.tgz"
                         onchange={on_change_complete_context}
                     />
                 </div>
@@ -233,7 +233,7 @@ pub fn upload_form() -> Html {
                     <input
                         id="topology-upload-id"
                         type="file"
-                        accept="application/json"
+                        accept=".json,.xml,.gz"
                         onchange={on_change_topology}
                     />
                 </div>
@@ -242,7 +242,7 @@ pub fn upload_form() -> Html {
                     <input
                         id="connections-upload-id"
                         type="file"
-                        accept="application/json"
+                        accept=".json,.xml,.gz"
                         onchange={on_change_connections}
                     />
                 </div>
@@ -251,7 +251,7 @@ pub fn upload_form() -> Html {
                     <input
                         id="connectivity-services-upload-id"
                         type="file"
-                        accept="application/json"
+                        accept=".json,.xml,.gz"
                         onchange={on_change_connectivity_services}
                     />
                 </div>
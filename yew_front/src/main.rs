@@ -17,6 +17,10 @@ enum Route {
     UploadFiles,
     #[at("/service_schema/:ip")]
     ServiceSchema { ip: String },
+    #[at("/compliance/:ip")]
+    Compliance { ip: String },
+    #[at("/network_schema/:ip")]
+    NetworkSchema { ip: String },
     #[at("/node_schema/:ip/:uuid/:name")]
     NodeSchema {
         ip: String,
@@ -38,6 +42,12 @@ fn switch(route: Route) -> Html {
         Route::NodeSchema { ip, uuid, name } => {
             html! { <pages::nodes_schema::NodeSchema device_ip={ip} service_uuid={uuid} name={name}/> }
         }
+        Route::Compliance { ip } => {
+            html! { <pages::compliance::Compliance device_ip={ip}/> }
+        }
+        Route::NetworkSchema { ip } => {
+            html! { <pages::network_schema::NetworkSchema device_ip={ip}/> }
+        }
         Route::Devices => html! { <pages::devices::Devices/>},
         Route::AddDevices => html! { <pages::add_devices::AddDevices/>},
         Route::UploadFiles => html! {<pages::upload_files::UploadFiles/>},
@@ -0,0 +1,226 @@
+use serde_json::Value;
+use yew::{platform::spawn_local, prelude::*};
+
+use crate::api::connection::{get_validations, probe_device};
+use crate::components::sidebar::SideBar;
+
+/// Retrieval strategies shown as the columns of the compliance matrix.
+const STRATEGIES: [&str; 4] = ["Full", "Depth", "Fields", "Per UUID"];
+
+/// Properties for the `Compliance` component.
+#[derive(PartialEq, Properties)]
+pub struct Props {
+    /// The IP address of the device whose probe results are displayed.
+    pub device_ip: String,
+}
+
+/// Retrieval strategy exercised by a `tapi_uris` topic, e.g. `Per UUID` for `node.by-parts`.
+fn strategy(topic: &str) -> &'static str {
+    if topic.contains("by-parts") {
+        "Per UUID"
+    } else if topic.contains("depth=") {
+        "Depth"
+    } else if topic.contains("uuid-list") || topic.contains(';') {
+        "Fields"
+    } else {
+        "Full"
+    }
+}
+
+/// Resource a topic belongs to, e.g. `node` for `node.owned-node-edge-point.uuid-list`.
+fn resource(topic: &str) -> &str {
+    topic.split('.').next().unwrap_or(topic)
+}
+
+/// A component showing which TAPI RESTCONF URIs a device supports.
+///
+/// The matrix groups the last probe results by resource and retrieval strategy: a cell is green
+/// when every URI succeeded, yellow when only some did and red when none did. The table below
+/// lists the status code and content type of each URI. "Probe Device" requests every URI again.
+#[function_component(Compliance)]
+pub fn compliance(props: &Props) -> Html {
+    let ip = props.device_ip.clone();
+    let validations = use_state(|| None::<Vec<Value>>);
+    let probing = use_state(|| false);
+    let error = use_state(|| None::<String>);
+
+    // Fetch the stored results on component mount
+    {
+        let validations = validations.clone();
+        let error = error.clone();
+        let ip = ip.clone();
+        use_effect_with((), move |_| {
+            spawn_local(async move {
+                match get_validations(&ip).await {
+                    Ok(Value::Array(fetched)) => validations.set(Some(fetched)),
+                    _ => {
+                        validations.set(Some(Vec::new()));
+                        error.set(Some("Failed to fetch the probe results".to_string()));
+                    }
+                }
+            });
+            || ()
+        });
+    }
+
+    let on_probe = {
+        let ip = ip.clone();
+        let validations = validations.clone();
+        let probing = probing.clone();
+        let error = error.clone();
+        Callback::from(move |_: MouseEvent| {
+            let ip = ip.clone();
+            let validations = validations.clone();
+            let probing = probing.clone();
+            let error = error.clone();
+            probing.set(true);
+            spawn_local(async move {
+                match probe_device(&ip).await {
+                    Ok(Value::Array(probed)) => {
+                        error.set(None);
+                        validations.set(Some(probed));
+                    }
+                    _ => error.set(Some("The device cannot be probed".to_string())),
+                }
+                probing.set(false);
+            });
+        })
+    };
+
+    let content = match (*validations).clone() {
+        None => html! {
+            <div class="loading-section">
+                <div class="lds-grid">
+                    <div></div>
+                    <div></div>
+                    <div></div>
+                    <div></div>
+                    <div></div>
+                    <div></div>
+                    <div></div>
+                    <div></div>
+                    <div></div>
+                </div>
+            </div>
+        },
+        Some(validations) if validations.is_empty() => html! {
+            <p class="compliance-empty">{"The device has not been probed yet."}</p>
+        },
+        Some(validations) => {
+            let topic =
+                |validation: &Value| validation["topic"].as_str().unwrap_or("?").to_string();
+            let mut resources: Vec<String> = Vec::new();
+            for validation in &validations {
+                let resource = resource(&topic(validation)).to_string();
+                if !resources.contains(&resource) {
+                    resources.push(resource);
+                }
+            }
+
+            let cell = |resource_name: &str, strategy_name: &str| {
+                let group: Vec<&Value> = validations
+                    .iter()
+                    .filter(|validation| {
+                        let topic = topic(validation);
+                        resource(&topic) == resource_name && strategy(&topic) == strategy_name
+                    })
+                    .collect();
+                let succeeded = group
+                    .iter()
+                    .filter(|validation| validation["success"].as_bool().unwrap_or(false))
+                    .count();
+                let title = group
+                    .iter()
+                    .map(|validation| {
+                        format!(
+                            "{}: {}",
+                            topic(validation),
+                            validation["status_code"]
+                                .as_i64()
+                                .map(|status_code| status_code.to_string())
+                                .unwrap_or("not requested".to_string())
+                        )
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                let (class, symbol) = match (group.len(), succeeded) {
+                    (0, _) => ("compliance-none", "—"),
+                    (total, succeeded) if total == succeeded => ("compliance-ok", "✔"),
+                    (_, 0) => ("compliance-failed", "✘"),
+                    _ => ("compliance-partial", "◐"),
+                };
+                html! { <td class={class} title={title}>{ symbol }</td> }
+            };
+
+            html! {
+                <>
+                    <div class="table-container">
+                        <table class="compliance-matrix">
+                            <thead>
+                                <tr>
+                                    <th>{"Resource"}</th>
+                                    { for STRATEGIES.iter().map(|strategy_name| html! { <th>{ *strategy_name }</th> }) }
+                                </tr>
+                            </thead>
+                            <tbody>
+                                { for resources.iter().map(|resource_name| html! {
+                                    <tr>
+                                        <td>{ resource_name.clone() }</td>
+                                        { for STRATEGIES.iter().map(|strategy_name| cell(resource_name, strategy_name)) }
+                                    </tr>
+                                }) }
+                            </tbody>
+                        </table>
+                    </div>
+                    <div class="table-container">
+                        <table>
+                            <thead>
+                                <tr>
+                                    <th>{"Topic"}</th>
+                                    <th>{"URI"}</th>
+                                    <th>{"Status"}</th>
+                                    <th>{"Response Type"}</th>
+                                    <th>{"Result"}</th>
+                                </tr>
+                            </thead>
+                            <tbody>
+                                { for validations.iter().map(|validation| {
+                                    let success = validation["success"].as_bool().unwrap_or(false);
+                                    html! {
+                                        <tr>
+                                            <td>{ topic(validation) }</td>
+                                            <td class="compliance-uri">{ validation["uri"].as_str().unwrap_or("?") }</td>
+                                            <td>{ validation["status_code"].as_i64().map(|status_code| status_code.to_string()).unwrap_or("—".to_string()) }</td>
+                                            <td>{ validation["response_type"].as_str().unwrap_or("—") }</td>
+                                            <td class={if success { "compliance-ok" } else { "compliance-failed" }}>
+                                                { if success { "✔" } else { "✘" } }
+                                            </td>
+                                        </tr>
+                                    }
+                                }) }
+                            </tbody>
+                        </table>
+                    </div>
+                </>
+            }
+        }
+    };
+
+    html! {
+        <div class="services-page">
+            <div class="main-services-container">
+                <SideBar />
+                <div class="search-container">
+                    <span class="compliance-title">{ format!("RESTCONF compliance of {}", ip) }</span>
+                    <button class="refresh-button" onclick={on_probe} disabled={*probing}>
+                        { if *probing { "Probing..." } else { "Probe Device" } }
+                    </button>
+                    if let Some(error) = (*error).clone() {
+                        <span class="snapshot-timestamp">{ error }</span>
+                    }
+                </div>
+                { content }
+            </div>
+        </div>
+    }
+}
@@ -2,6 +2,7 @@ use yew::{platform::spawn_local, prelude::*};
 use yew_router::prelude::*;
 
 use crate::api::connection::{delete_device, get_devices};
+use crate::components::health_badge::HealthBadge;
 use crate::components::sidebar::SideBar;
 use crate::Route;
 
@@ -71,6 +72,9 @@ pub fn devices() -> Html {
                                             <button class="check-api-button">
                                                 <Link<Route> to={Route::ServiceSchema{ip: id.clone()}} classes="check-api-text">{"Check Services List"}</Link<Route>>
                                             </button>
+                                            <button class="check-api-button">
+                                                <Link<Route> to={Route::NetworkSchema{ip: id.clone()}} classes="check-api-text">{"Check Network Map"}</Link<Route>>
+                                            </button>
                                             <button onclick={
                                                 Callback::from(move |e: MouseEvent| {
                                                     e.prevent_default();
@@ -100,6 +104,7 @@ pub fn devices() -> Html {
                                                 <span class="device-icon">{"📱"}</span>
                                                 <div class="device-info">
                                                     <p>{ format!("IP: {}", ip) }</p>
+                                                    <HealthBadge ip={ip.clone()} />
                                                     {
                                                         if let Some(port) = device.get("port") {
                                                             html!(<p>{ format!("Port: {}", port.as_i64().unwrap_or(00000)) }</p>)
@@ -107,12 +112,34 @@ pub fn devices() -> Html {
                                                             html!()
                                                         }
                                                     }
+                                                    {
+                                                        if let Some(strategy) = device.get("retrieval_strategy").and_then(|strategy| strategy.as_str()) {
+                                                            html!(<p>{ format!("Retrieval: {}", strategy.replace('_', " ")) }</p>)
+                                                        } else {
+                                                            html!()
+                                                        }
+                                                    }
+                                                    {
+                                                        if device["tls"]["insecure"].as_bool().unwrap_or(false) {
+                                                            html!(<p class="tls-warning">{"⚠ Insecure TLS: certificates are not verified"}</p>)
+                                                        } else if device["tls"]["scheme"].as_str() == Some("http") {
+                                                            html!(<p class="tls-warning">{"⚠ Plain HTTP"}</p>)
+                                                        } else {
+                                                            html!()
+                                                        }
+                                                    }
                                                 </div>
                                             </div>
                                             <div class="device-actions">
                                                 <button class="check-api-button">
                                                     <Link<Route> to={Route::ServiceSchema{ip: ip.clone()}} classes="check-api-text">{"Check tAPI Schema"}</Link<Route>>
                                                 </button>
+                                                <button class="check-api-button">
+                                                    <Link<Route> to={Route::Compliance{ip: ip.clone()}} classes="check-api-text">{"Check Compliance"}</Link<Route>>
+                                                </button>
+                                                <button class="check-api-button">
+                                                    <Link<Route> to={Route::NetworkSchema{ip: ip.clone()}} classes="check-api-text">{"Check Network Map"}</Link<Route>>
+                                                </button>
                                                 <button onclick={
                                                     Callback::from(move |e: MouseEvent| {
                                                         e.prevent_default();
@@ -1,8 +1,10 @@
 pub mod add_devices;
+pub mod compliance;
 pub mod devices;
 pub mod home;
 pub mod info;
 pub mod login;
+pub mod network_schema;
 pub mod nodes_schema;
 pub mod services_schema;
 pub mod upload_files;
@@ -0,0 +1,26 @@
+use yew::prelude::*;
+
+use crate::components::{network_map::NetworkMap, sidebar::SideBar};
+
+/// Properties for the `NetworkSchema` component.
+#[derive(PartialEq, Properties)]
+pub struct Props {
+    /// The IP address of the device whose network is displayed.
+    pub device_ip: String,
+}
+
+/// A page drawing the whole network of a device, every node and link, not only those of a service.
+#[function_component(NetworkSchema)]
+pub fn network_schema(props: &Props) -> Html {
+    html! {
+        <div class="services-page">
+            <div class="main-services-container">
+                <SideBar />
+                <div class="search-container">
+                    <span class="network-map-title">{ format!("Network of {}", props.device_ip) }</span>
+                </div>
+                <NetworkMap device_ip={props.device_ip.clone()} />
+            </div>
+        </div>
+    }
+}
@@ -1,6 +1,8 @@
 use yew::prelude::*;
 
-use crate::components::{footer_legend::FooterLegend, nodes::Nodes, sidebar::SideBar};
+use crate::components::{
+    footer_legend::FooterLegend, layers::Layers, nodes::Nodes, sidebar::SideBar,
+};
 
 /// Properties for the `NodeSchema` component.
 #[derive(PartialEq, Properties)]
@@ -17,6 +19,13 @@ pub struct Props {
 
 #[function_component(NodeSchema)]
 pub fn node_schema(props: &Props) -> Html {
+    // Whether the layers of the trace are shown instead of the nodes
+    let show_layers = use_state(|| false);
+    let on_toggle_view = {
+        let show_layers = show_layers.clone();
+        Callback::from(move |_: MouseEvent| show_layers.set(!*show_layers))
+    };
+
     html! {
         <div class="node-page">
             // Render the sidebar component for navigation
@@ -31,9 +40,19 @@ pub fn node_schema(props: &Props) -> Html {
                 </div>
             </div>
 
-            <Nodes device_ip={props.device_ip.clone()} service_uuid={props.service_uuid.clone()} />
+            <div class="view-toggle">
+                <button class="view-toggle-button" onclick={on_toggle_view}>
+                    { if *show_layers { "Nodes view" } else { "Layers view" } }
+                </button>
+            </div>
+
+            if *show_layers {
+                <Layers device_ip={props.device_ip.clone()} service_uuid={props.service_uuid.clone()} />
+            } else {
+                <Nodes device_ip={props.device_ip.clone()} service_uuid={props.service_uuid.clone()} />
 
-            <FooterLegend />
+                <FooterLegend />
+            }
         </div>
     }
 }
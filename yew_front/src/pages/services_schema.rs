@@ -2,7 +2,9 @@ use serde_json::Value;
 use yew::{platform::spawn_local, prelude::*};
 use yew_router::prelude::*;
 
-use crate::api::connection::get_services;
+use crate::api::connection::{delete_service, follow_events, get_services, refresh_snapshot};
+use crate::components::alert::Alert;
+use crate::components::service_form::ServiceForm;
 use crate::components::sidebar::SideBar;
 use crate::Route;
 
@@ -18,6 +20,9 @@ pub fn schema(props: &Props) -> Html {
     let ip = props.device_ip.clone();
     let json_data = use_state(|| None);
     let search_query = use_state(String::new);
+    let snapshot_timestamp = use_state(|| None::<String>);
+    let show_form = use_state(|| false);
+    let error_message = use_state(String::default);
 
     // Fetch JSON data on component mount
     {
@@ -37,6 +42,106 @@ pub fn schema(props: &Props) -> Html {
         });
     }
 
+    // Fetch the services again each time the device reports a change
+    {
+        let json_data = json_data.clone();
+        let snapshot_timestamp = snapshot_timestamp.clone();
+        use_effect_with(ip.clone(), move |ip| {
+            let ip = ip.clone();
+            let events = follow_events(&ip.clone(), move |event| {
+                if let Some(timestamp) = event.get("snapshot_timestamp").and_then(Value::as_str) {
+                    snapshot_timestamp.set(Some(timestamp.to_string()));
+                }
+                let json_data = json_data.clone();
+                let ip = ip.clone();
+                spawn_local(async move {
+                    if let Ok(fetched_json) = get_services(ip).await {
+                        json_data.set(Some(fetched_json));
+                    }
+                });
+            })
+            .ok();
+            move || drop(events)
+        });
+    }
+
+    // Take a new topology snapshot, so the next schemas show the current state of the device
+    let on_refresh = {
+        let ip = ip.clone();
+        let snapshot_timestamp = snapshot_timestamp.clone();
+        Callback::from(move |_: MouseEvent| {
+            let ip = ip.clone();
+            let snapshot_timestamp = snapshot_timestamp.clone();
+            spawn_local(async move {
+                match refresh_snapshot(&ip).await {
+                    Ok(response) => snapshot_timestamp.set(
+                        response
+                            .get("snapshot_timestamp")
+                            .and_then(Value::as_str)
+                            .map(str::to_string),
+                    ),
+                    Err(_) => snapshot_timestamp.set(Some("Failed to refresh".to_string())),
+                }
+            });
+        })
+    };
+
+    // Fetch the services again once one is created or deleted
+    let reload_services = {
+        let json_data = json_data.clone();
+        let ip = ip.clone();
+        Callback::from(move |_: ()| {
+            let json_data = json_data.clone();
+            let ip = ip.clone();
+            spawn_local(async move {
+                if let Ok(fetched_json) = get_services(ip).await {
+                    json_data.set(Some(fetched_json));
+                }
+            });
+        })
+    };
+
+    let on_toggle_form = {
+        let show_form = show_form.clone();
+        Callback::from(move |_: MouseEvent| show_form.set(!*show_form))
+    };
+
+    // Delete a service from the device after confirmation
+    let on_delete = {
+        let ip = ip.clone();
+        let error_message = error_message.clone();
+        let reload_services = reload_services.clone();
+        move |uuid: String| {
+            let ip = ip.clone();
+            let error_message = error_message.clone();
+            let reload_services = reload_services.clone();
+            Callback::from(move |e: MouseEvent| {
+                e.prevent_default();
+                let is_confirmed = web_sys::window()
+                    .and_then(|window| {
+                        window
+                            .confirm_with_message(&format!("Delete the service {}?", uuid))
+                            .ok()
+                    })
+                    .unwrap_or(false);
+                if !is_confirmed {
+                    return;
+                }
+                let ip = ip.clone();
+                let uuid = uuid.clone();
+                let error_message = error_message.clone();
+                let reload_services = reload_services.clone();
+                spawn_local(async move {
+                    match delete_service(&ip, &uuid, false).await {
+                        Ok(_) => error_message.set(String::new()),
+                        Err(message) => error_message.set(message),
+                    }
+                    reload_services.emit(());
+                });
+            })
+        }
+    };
+
     // Filtrar los servicios por el valor de búsqueda
     let filtered_services = {
         if let Some(services) = (*json_data).clone() {
@@ -117,6 +222,9 @@ pub fn schema(props: &Props) -> Html {
                                                 {"Check Nodes Schema"}
                                             </Link<Route>>
                                         </button>
+                                        <button class="delete-service-button" onclick={on_delete(uuid.clone())}>
+                                            {"Delete"}
+                                        </button>
                                     </td>
                                 </tr>
                             }
@@ -141,7 +249,20 @@ pub fn schema(props: &Props) -> Html {
                             search_query.set(value);
                         })}
                     />
+                    <button class="refresh-button" onclick={on_refresh}>{"Refresh Topology"}</button>
+                    <button class="refresh-button" onclick={on_toggle_form}>
+                        { if *show_form { "Hide Form" } else { "New Service" } }
+                    </button>
+                    if let Some(timestamp) = (*snapshot_timestamp).clone() {
+                        <span class="snapshot-timestamp">{ format!("Snapshot: {}", timestamp) }</span>
+                    }
                 </div>
+                if !error_message.is_empty() {
+                    <Alert alert_type={"danger"} message={(*error_message).clone()}/>
+                }
+                if *show_form {
+                    <ServiceForm device_ip={ip.clone()} on_created={reload_services.clone()} />
+                }
                 { content }
             </div>
         </div>